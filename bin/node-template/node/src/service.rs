@@ -11,7 +11,8 @@ pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use sc_consensus_aura::{ImportQueueParams, StartAuraParams, SlotProportion};
 use sc_finality_grandpa::SharedVoterState;
-use sc_keystore::LocalKeystore;
+use sc_keystore::RemoteKeystore;
+use sp_core::crypto::{ExposeSecret, SecretString};
 use sc_telemetry::TelemetrySpan;
 
 // Our native executor instance.
@@ -40,10 +41,6 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
 	)
 >, ServiceError> {
-	if config.keystore_remote.is_some() {
-		return Err(ServiceError::Other(
-			format!("Remote Keystores are not supported.")))
-	}
 	let inherent_data_providers = InherentDataProviders::new();
 
 	let (client, backend, keystore_container, task_manager) =
@@ -95,11 +92,15 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	})
 }
 
-fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
-	// FIXME: here would the concrete keystore be built,
-	//        must return a concrete type (NOT `LocalKeystore`) that
-	//        implements `CryptoStore` and `SyncCryptoStore`
-	Err("Remote Keystore not supported.")
+fn remote_keystore(
+	url: &String,
+	token: &Option<SecretString>,
+) -> Result<Arc<RemoteKeystore>, sc_keystore::Error> {
+	let remote = RemoteKeystore::open(url)?;
+	Ok(Arc::new(match token {
+		Some(token) => remote.with_token(token.expose_secret().clone()),
+		None => remote,
+	}))
 }
 
 /// Builds a new service for a full client.
@@ -117,11 +118,14 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	} = new_partial(&config)?;
 
 	if let Some(url) = &config.keystore_remote {
-		match remote_keystore(url) {
+		match remote_keystore(url, &config.keystore_remote_token) {
 			Ok(k) => keystore_container.set_remote_keystore(k),
 			Err(e) => {
-				return Err(ServiceError::Other(
-					format!("Error hooking up remote keystore for {}: {}", url, e)))
+				return Err(ServiceError::Other(format!(
+					"Error hooking up remote keystore for {}: {}",
+					sc_keystore::redact_token(url),
+					e,
+				)))
 			}
 		};
 	}
@@ -137,6 +141,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			import_queue,
 			on_demand: None,
 			block_announce_validator_builder: None,
+			warp_sync: None,
 		})?;
 
 	if config.offchain_worker.enabled {
@@ -319,6 +324,7 @@ pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			import_queue,
 			on_demand: Some(on_demand.clone()),
 			block_announce_validator_builder: None,
+			warp_sync: None,
 		})?;
 
 	if config.offchain_worker.enabled {
@@ -81,4 +81,7 @@ pub enum Subcommand {
 
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
+
+	/// Database maintenance utilities.
+	Db(sc_cli::DbSubcommand),
 }
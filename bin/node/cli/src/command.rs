@@ -20,7 +20,7 @@ use crate::{chain_spec, service, Cli, Subcommand};
 use node_executor::Executor;
 use node_runtime::{Block, RuntimeApi};
 use sc_cli::{Result, SubstrateCli, RuntimeVersion, Role, ChainSpec};
-use sc_service::PartialComponents;
+use sc_service::{PartialComponents, chain_ops::AuxKeys};
 use crate::service::new_partial;
 
 impl SubstrateCli for Cli {
@@ -141,6 +141,10 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.database))
 		},
+		Some(Subcommand::Db(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(config, &aux_keys()))
+		},
 		Some(Subcommand::Revert(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -166,3 +170,17 @@ pub fn run() -> Result<()> {
 		}
 	}
 }
+
+/// The data the consensus engines of the node keep in the aux storage, copied by `db migrate`.
+fn aux_keys() -> AuxKeys<node_primitives::Hash> {
+	AuxKeys {
+		keys: grandpa::aux_keys()
+			.into_iter()
+			.chain(sc_consensus_babe::aux_schema::aux_keys())
+			.collect(),
+		block_keys: vec![
+			(|hash: &_| sc_consensus_babe::aux_schema::block_weight_key(hash))
+				as fn(&node_primitives::Hash) -> Vec<u8>,
+		],
+	}
+}
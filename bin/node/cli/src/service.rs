@@ -35,6 +35,7 @@ use sc_client_api::{ExecutorProvider, RemoteBackend};
 use node_executor::Executor;
 use sc_telemetry::{TelemetryConnectionNotifier, TelemetrySpan};
 use sc_consensus_babe::SlotProportion;
+use sp_core::crypto::ExposeSecret;
 
 type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
@@ -60,10 +61,24 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		grandpa::SharedVoterState,
 	)
 >, ServiceError> {
-	let (client, backend, keystore_container, task_manager) =
+	let (client, backend, mut keystore_container, task_manager) =
 		sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
 	let client = Arc::new(client);
 
+	if let Some(url) = &config.keystore_remote {
+		let mut remote = sc_keystore::RemoteKeystore::open(url).map_err(|e| ServiceError::Other(
+			format!(
+				"Error hooking up remote keystore for {}: {}",
+				sc_keystore::redact_token(url),
+				e,
+			)
+		))?;
+		if let Some(token) = &config.keystore_remote_token {
+			remote = remote.with_token(token.expose_secret().clone());
+		}
+		keystore_container.set_remote_keystore(Arc::new(remote));
+	}
+
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
 	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
@@ -86,6 +101,9 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 	)?;
 
 	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
+	inherent_data_providers
+		.register_provider(sp_transaction_storage_proof::InherentDataProvider::new(client.clone()))
+		.map_err(|e| sc_service::Error::Other(e.into_string()))?;
 
 	let import_queue = sc_consensus_babe::import_queue(
 		babe_link.clone(),
@@ -197,14 +215,13 @@ pub fn new_full_base(
 	config.network.extra_sets.push(grandpa::grandpa_peers_set_config());
 
 	#[cfg(feature = "cli")]
-	config.network.request_response_protocols.push(
-		sc_finality_grandpa_warp_sync::request_response_config_for_chain(
-			&config,
-			task_manager.spawn_handle(),
+	let warp_sync: Option<Arc<dyn sc_network::warp_request_handler::WarpSyncProvider<Block>>> =
+		Some(Arc::new(sc_finality_grandpa_warp_sync::NetworkProvider::new(
 			backend.clone(),
 			import_setup.1.shared_authority_set().clone(),
-		)
-	);
+		)));
+	#[cfg(not(feature = "cli"))]
+	let warp_sync = None;
 
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -215,6 +232,7 @@ pub fn new_full_base(
 			import_queue,
 			on_demand: None,
 			block_announce_validator_builder: None,
+			warp_sync,
 		})?;
 
 	if config.offchain_worker.enabled {
@@ -425,6 +443,7 @@ pub fn new_light_base(mut config: Configuration) -> Result<(
 			import_queue,
 			on_demand: Some(on_demand.clone()),
 			block_announce_validator_builder: None,
+			warp_sync: None,
 		})?;
 	network_starter.start_network();
 
@@ -667,7 +686,7 @@ mod tests {
 				let check_era = frame_system::CheckEra::from(Era::Immortal);
 				let check_nonce = frame_system::CheckNonce::from(index);
 				let check_weight = frame_system::CheckWeight::new();
-				let payment = pallet_transaction_payment::ChargeTransactionPayment::from(0);
+				let payment = pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None);
 				let extra = (
 					check_spec_version,
 					check_tx_version,
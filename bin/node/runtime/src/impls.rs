@@ -17,8 +17,11 @@
 
 //! Some configurable implementations as associated type for the substrate runtime.
 
-use frame_support::traits::{OnUnbalanced, Currency};
-use crate::{Balances, Authorship, NegativeImbalance};
+use frame_support::traits::{OnUnbalanced, Currency, tokens::fungibles::{Balanced, CreditOf}};
+use sp_runtime::traits::{Convert, SaturatedConversion};
+use pallet_asset_tx_payment::HandleCredit;
+use node_primitives::{AccountId, Balance};
+use crate::{Assets, Balances, Authorship, NegativeImbalance};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -27,6 +30,24 @@ impl OnUnbalanced<NegativeImbalance> for Author {
 	}
 }
 
+/// A `HandleCredit` implementation that transfers the fees paid in assets to the block author.
+pub struct CreditToBlockAuthor;
+impl HandleCredit<AccountId, Assets> for CreditToBlockAuthor {
+	fn handle_credit(credit: CreditOf<AccountId, Assets>) {
+		// Dropping the credit if the author can not receive it burns the fee.
+		let _ = <Assets as Balanced<AccountId>>::resolve(&Authorship::author(), credit);
+	}
+}
+
+/// Converts a native balance into the `u64` balance used by the assets, saturating if it
+/// does not fit.
+pub struct NativeToAssetBalance;
+impl Convert<Balance, u64> for NativeToAssetBalance {
+	fn convert(balance: Balance) -> u64 {
+		balance.saturated_into()
+	}
+}
+
 #[cfg(test)]
 mod multiplier_tests {
 	use sp_runtime::{assert_eq_error_rate, FixedPointNumber, traits::Convert};
@@ -0,0 +1,134 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reference signer for the remote keystore.
+//!
+//! Serves the keys of a local keystore to nodes started with `--keystore-uri`. Clients connecting
+//! over TCP have to authenticate with the token read from `--token-file`.
+
+use std::{fs, io, net::TcpListener, path::PathBuf, sync::Arc, thread};
+
+use structopt::StructOpt;
+
+use sc_keystore::{LocalKeystore, RemoteSigner};
+use sp_core::crypto::SecretString;
+
+/// Serve the keys of a keystore to remote keystore clients.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+struct Opt {
+	/// Address to listen on, `tcp://host:port`, `host:port` or `unix:///path/to/socket`.
+	///
+	/// Only listen on a public interface if the network between the node and the signer is
+	/// trusted, requests and signatures are not encrypted.
+	#[structopt(long, default_value = "127.0.0.1:9955")]
+	listen: String,
+	/// File that contains the token clients have to authenticate with.
+	///
+	/// Required when listening on TCP. Nodes read the token from their own
+	/// `--keystore-token-file`. TCP is not encrypted, so the token can be sniffed and reused by
+	/// anyone on the path between the node and the signer.
+	#[structopt(long, parse(from_os_str))]
+	token_file: Option<PathBuf>,
+	/// Path of the keystore directory holding the keys.
+	#[structopt(long, parse(from_os_str))]
+	keystore_path: PathBuf,
+	/// Password used by the keystore.
+	#[structopt(long, conflicts_with = "password-filename")]
+	password: Option<String>,
+	/// File that contains the password used by the keystore.
+	#[structopt(long, parse(from_os_str))]
+	password_filename: Option<PathBuf>,
+}
+
+fn main() -> Result<(), String> {
+	let opt = Opt::from_args();
+
+	let password = match (opt.password, opt.password_filename) {
+		(Some(password), _) => Some(password),
+		(None, Some(path)) => Some(
+			fs::read_to_string(&path)
+				.map_err(|e| format!("Failed to read password file {}: {}", path.display(), e))?
+				.trim_end()
+				.to_string()
+		),
+		(None, None) => None,
+	};
+	let keystore = LocalKeystore::open(&opt.keystore_path, password.map(SecretString::new))
+		.map_err(|e| format!("Failed to open keystore: {}", e))?;
+	let token = opt.token_file.map(|path| {
+		fs::read_to_string(&path)
+			.map(|token| token.trim().to_string())
+			.map_err(|e| format!("Failed to read token file {}: {}", path.display(), e))
+	}).transpose()?;
+	if token.as_ref().map_or(false, |token| token.is_empty() || token.contains('@')) {
+		return Err("The token must not be empty or contain `@`".into());
+	}
+	let tcp = !opt.listen.starts_with("unix://");
+	if tcp && token.is_none() {
+		return Err("A token file is required when listening on TCP".into());
+	}
+	let mut signer = RemoteSigner::new(Arc::new(keystore));
+	if let Some(token) = token {
+		signer = signer.with_token(token);
+	}
+	let signer = Arc::new(signer);
+
+	if let Some(path) = opt.listen.strip_prefix("unix://") {
+		return serve_unix(signer, path);
+	}
+	let address = opt.listen.strip_prefix("tcp://").unwrap_or(&opt.listen);
+	let listener = TcpListener::bind(address)
+		.map_err(|e| format!("Failed to listen on {}: {}", address, e))?;
+	eprintln!("Remote signer listening on {}", address);
+	for stream in listener.incoming() {
+		spawn_connection(signer.clone(), stream);
+	}
+	Ok(())
+}
+
+#[cfg(unix)]
+fn serve_unix(signer: Arc<RemoteSigner>, path: &str) -> Result<(), String> {
+	let listener = std::os::unix::net::UnixListener::bind(path)
+		.map_err(|e| format!("Failed to listen on {}: {}", path, e))?;
+	eprintln!("Remote signer listening on {}", path);
+	for stream in listener.incoming() {
+		spawn_connection(signer.clone(), stream);
+	}
+	Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_unix(_signer: Arc<RemoteSigner>, _path: &str) -> Result<(), String> {
+	Err("Unix sockets are not supported on this platform".into())
+}
+
+fn spawn_connection<S>(signer: Arc<RemoteSigner>, stream: io::Result<S>)
+	where S: io::Read + io::Write + Send + 'static
+{
+	match stream {
+		Ok(stream) => {
+			thread::spawn(move || {
+				if let Err(e) = signer.serve(stream) {
+					eprintln!("Connection closed: {}", e);
+				}
+			});
+		},
+		Err(e) => eprintln!("Failed to accept connection: {}", e),
+	}
+}
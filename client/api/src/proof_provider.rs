@@ -70,4 +70,30 @@ pub trait ProofProvider<Block: BlockT> {
 		storage_key: Option<&PrefixedStorageKey>,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<ChangesProof<Block::Header>>;
+
+	/// Given a `BlockId` and a start key, return a proof for a range of the main state trie,
+	/// or of the given child trie, starting at `start_key` (inclusive). The key-value data
+	/// covered by the proof is limited to roughly `size_limit` bytes.
+	///
+	/// Returns the proof and the number of entries it covers.
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)>;
+
+	/// Verify a range proof for the main state trie, or for the given child trie, generated by
+	/// `read_proof_collection`, against the given state root.
+	///
+	/// Returns the entries covered by the proof, starting at `start_key`, and a flag that
+	/// is set when the proof reaches the end of the trie.
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
 }
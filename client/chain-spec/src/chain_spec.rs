@@ -160,6 +160,8 @@ struct ClientSpec<E> {
 	#[serde(skip_serializing)]
 	genesis: serde::de::IgnoredAny,
 	light_sync_state: Option<SerializableLightSyncState>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	wasm_stack_height_limit: Option<u32>,
 }
 
 /// A type denoting empty extensions.
@@ -225,6 +227,18 @@ impl<G, E> ChainSpec<G, E> {
 		&self.client_spec.extensions
 	}
 
+	/// Deterministic stack height limit enforced on the Wasm runtime, if any.
+	pub fn wasm_stack_height_limit(&self) -> Option<u32> {
+		self.client_spec.wasm_stack_height_limit
+	}
+
+	/// Set the deterministic stack height limit enforced on the Wasm runtime.
+	///
+	/// Every node of the chain must enforce the same limit, which is why it is part of the spec.
+	pub fn set_wasm_stack_height_limit(&mut self, limit: Option<u32>) {
+		self.client_spec.wasm_stack_height_limit = limit;
+	}
+
 	/// Create hardcoded spec.
 	pub fn from_genesis<F: Fn() -> G + 'static + Send + Sync>(
 		name: &str,
@@ -249,6 +263,7 @@ impl<G, E> ChainSpec<G, E> {
 			consensus_engine: (),
 			genesis: Default::default(),
 			light_sync_state: None,
+			wasm_stack_height_limit: None,
 		};
 
 		ChainSpec {
@@ -376,6 +391,10 @@ where
 		ChainSpec::extensions(self) as &dyn GetExtension
 	}
 
+	fn wasm_stack_height_limit(&self) -> Option<u32> {
+		ChainSpec::wasm_stack_height_limit(self)
+	}
+
 	fn as_json(&self, raw: bool) -> Result<String, String> {
 		ChainSpec::as_json(self, raw)
 	}
@@ -481,7 +500,8 @@ mod tests {
 		).unwrap();
 
 		assert_eq!(spec1.as_json(false), spec2.as_json(false));
-		assert_eq!(spec2.chain_type(), ChainType::Live)
+		assert_eq!(spec2.chain_type(), ChainType::Live);
+		assert_eq!(spec2.wasm_stack_height_limit(), None);
 	}
 
 	#[derive(Debug, Serialize, Deserialize)]
@@ -499,5 +519,6 @@ mod tests {
 		)).unwrap();
 
 		assert_eq!(spec.extensions().my_property, "Test Extension");
+		assert_eq!(spec.wasm_stack_height_limit(), Some(16384));
 	}
 }
@@ -147,6 +147,11 @@ pub trait ChainSpec: BuildStorage + Send + Sync {
 	fn properties(&self) -> Properties;
 	/// Returns a reference to defined chain spec extensions.
 	fn extensions(&self) -> &dyn GetExtension;
+	/// Deterministic stack height limit enforced on the Wasm runtime, if any.
+	///
+	/// Calls exceeding it fail the same way on every node, so it must not differ between the nodes
+	/// of a chain. Native execution doesn't enforce it.
+	fn wasm_stack_height_limit(&self) -> Option<u32>;
 	/// Add a bootnode to the list.
 	fn add_boot_node(&mut self, addr: MultiaddrWithPeerId);
 	/// Return spec as JSON.
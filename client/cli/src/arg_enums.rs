@@ -29,6 +29,8 @@ arg_enum! {
 		Interpreted,
 		// Uses a compiled runtime.
 		Compiled,
+		// Uses a compiled runtime with a pool of instances reset between calls.
+		CompiledPooled,
 	}
 }
 
@@ -38,7 +40,7 @@ impl WasmExecutionMethod {
 		Self::variants()
 			.iter()
 			.cloned()
-			.filter(|&name| cfg!(feature = "wasmtime") || name != "Compiled")
+			.filter(|&name| cfg!(feature = "wasmtime") || name == "Interpreted")
 			.collect()
 	}
 }
@@ -51,8 +53,11 @@ impl Into<sc_service::config::WasmExecutionMethod> for WasmExecutionMethod {
 			}
 			#[cfg(feature = "wasmtime")]
 			WasmExecutionMethod::Compiled => sc_service::config::WasmExecutionMethod::Compiled,
+			#[cfg(feature = "wasmtime")]
+			WasmExecutionMethod::CompiledPooled =>
+				sc_service::config::WasmExecutionMethod::CompiledPooled,
 			#[cfg(not(feature = "wasmtime"))]
-			WasmExecutionMethod::Compiled => panic!(
+			WasmExecutionMethod::Compiled | WasmExecutionMethod::CompiledPooled => panic!(
 				"Substrate must be compiled with \"wasmtime\" feature for compiled Wasm execution"
 			),
 		}
@@ -206,6 +211,30 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// Syncing mode.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy)]
+	pub enum SyncMode {
+		// Full sync. Download and verify all blocks.
+		Full,
+		// Download blocks without executing them. Download latest state with proofs.
+		Fast,
+		// Prove finality and download the latest state.
+		Warp,
+	}
+}
+
+impl Into<sc_network::config::SyncMode> for SyncMode {
+	fn into(self) -> sc_network::config::SyncMode {
+		match self {
+			SyncMode::Full => sc_network::config::SyncMode::Full,
+			SyncMode::Fast => sc_network::config::SyncMode::Fast,
+			SyncMode::Warp => sc_network::config::SyncMode::Warp,
+		}
+	}
+}
+
 /// Default value for the `--execution-syncing` parameter.
 pub const DEFAULT_EXECUTION_SYNCING: ExecutionStrategy = ExecutionStrategy::NativeElseWasm;
 /// Default value for the `--execution-import-block` parameter.
@@ -0,0 +1,64 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Implementation of the `change-password` subcommand
+
+use crate::{Error, KeystoreParams, SharedParams, utils, SubstrateCli, secret_string_from_str};
+use structopt::StructOpt;
+use sp_core::crypto::{ExposeSecret, SecretString};
+
+/// The `change-password` command
+#[derive(Debug, StructOpt)]
+#[structopt(
+	name = "change-password",
+	about = "Encrypt all keys in the keystore of a node with a new password."
+)]
+pub struct ChangePasswordCmd {
+	/// The new password.
+	/// If not given, you will be prompted for the password.
+	#[structopt(long, parse(try_from_str = secret_string_from_str))]
+	new_password: Option<SecretString>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+impl ChangePasswordCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		let keystore = utils::open_local_keystore(cli, &self.shared_params, &self.keystore_params)?;
+		let password = match &self.new_password {
+			Some(password) => password.clone(),
+			None => {
+				let password = utils::read_password(None, "New keystore password: ")?;
+				let confirmation = utils::read_password(None, "Repeat the new password: ")?;
+				if password.expose_secret() != confirmation.expose_secret() {
+					return Err(Error::Input("The passwords don't match".into()));
+				}
+				password
+			},
+		};
+		keystore.change_password(password)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,89 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Database maintenance CLI utilities
+
+use crate::{CliConfiguration, error, params::{DatabaseParams, PruningParams, SharedParams}};
+use sc_service::{Configuration, chain_ops::AuxKeys};
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+use super::{
+	db_stats_cmd::DbStatsCmd,
+	db_prune_cmd::DbPruneCmd,
+	db_migrate_cmd::DbMigrateCmd,
+};
+
+/// Database maintenance utilities for the cli.
+///
+/// These commands open the database of the node exclusively, so the node must not be running.
+#[derive(Debug, StructOpt)]
+pub enum DbSubcommand {
+	/// Print the size of the database columns and the number of retained block bodies and states.
+	Stats(DbStatsCmd),
+
+	/// Apply the given block and state pruning to the database.
+	Prune(DbPruneCmd),
+
+	/// Copy the database to a new database of another backend.
+	Migrate(DbMigrateCmd),
+}
+
+impl DbSubcommand {
+	/// Run the db subcommands
+	///
+	/// `aux_keys` names the data of the consensus engines, which is only needed to migrate a
+	/// database that can not enumerate its keys.
+	pub fn run<B: BlockT>(
+		&self,
+		config: Configuration,
+		aux_keys: &AuxKeys<B::Hash>,
+	) -> error::Result<()> {
+		match self {
+			DbSubcommand::Stats(cmd) => cmd.run::<B>(config),
+			DbSubcommand::Prune(cmd) => cmd.run::<B>(config),
+			DbSubcommand::Migrate(cmd) => cmd.run::<B>(config, aux_keys),
+		}
+	}
+}
+
+impl CliConfiguration for DbSubcommand {
+	fn shared_params(&self) -> &SharedParams {
+		match self {
+			DbSubcommand::Stats(cmd) => cmd.shared_params(),
+			DbSubcommand::Prune(cmd) => cmd.shared_params(),
+			DbSubcommand::Migrate(cmd) => cmd.shared_params(),
+		}
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		match self {
+			DbSubcommand::Stats(cmd) => cmd.database_params(),
+			DbSubcommand::Prune(cmd) => cmd.database_params(),
+			DbSubcommand::Migrate(cmd) => cmd.database_params(),
+		}
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		match self {
+			DbSubcommand::Stats(cmd) => cmd.pruning_params(),
+			DbSubcommand::Prune(cmd) => cmd.pruning_params(),
+			DbSubcommand::Migrate(cmd) => cmd.pruning_params(),
+		}
+	}
+}
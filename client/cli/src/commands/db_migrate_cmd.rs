@@ -0,0 +1,108 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::arg_enums::Database;
+use crate::error;
+use crate::params::{DatabaseParams, SharedParams};
+use crate::CliConfiguration;
+use sc_service::{Configuration, chain_ops::{AuxKeys, migrate_database}};
+use sp_runtime::traits::Block as BlockT;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// The `db migrate` command used to move a database to another backend.
+///
+/// The database selected with `--database` is copied, it is not modified or removed. A ParityDb
+/// database can not enumerate its keys, so only its blocks up to the last finalized one and the
+/// state of that block are copied.
+#[derive(Debug, StructOpt)]
+pub struct DbMigrateCmd {
+	/// Database backend to copy the database to.
+	#[structopt(
+		long,
+		value_name = "DB",
+		case_insensitive = true,
+		possible_values = &Database::variants(),
+	)]
+	pub target_database: Database,
+
+	/// Path of the new database.
+	///
+	/// Defaults to where the node looks for a database of the target backend.
+	#[structopt(long, parse(from_os_str))]
+	pub target_path: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DbMigrateCmd {
+	/// Run the migrate command
+	///
+	/// `aux_keys` names the data of the consensus engines to copy out of a ParityDb database.
+	pub fn run<B: BlockT>(
+		&self,
+		config: Configuration,
+		aux_keys: &AuxKeys<B::Hash>,
+	) -> error::Result<()> {
+		let config_dir = config.database.path()
+			.and_then(|path| path.parent())
+			.map(PathBuf::from)
+			.ok_or_else(||
+				error::Error::Input("Cannot migrate custom database implementation".into())
+			)?;
+		let mut target = self.database_config(
+			&config_dir,
+			self.database_cache_size()?.unwrap_or(128),
+			self.target_database,
+		)?;
+		if let Some(path) = &self.target_path {
+			target = match target {
+				sc_service::DatabaseConfig::RocksDb { cache_size, .. } =>
+					sc_service::DatabaseConfig::RocksDb { path: path.clone(), cache_size },
+				sc_service::DatabaseConfig::ParityDb { .. } =>
+					sc_service::DatabaseConfig::ParityDb { path: path.clone() },
+				custom => custom,
+			};
+		}
+		if target.path() == config.database.path() {
+			return Err(error::Error::Input("The database can not be migrated to itself".into()));
+		}
+
+		let target_path = target.path().map(|path| path.display().to_string()).unwrap_or_default();
+		migrate_database::<B>(&config, target, aux_keys)?;
+		println!("Copied the {} database to {}.", config.database, target_path);
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbMigrateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,67 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use sc_service::{Configuration, chain_ops::prune_database};
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `db prune` command used to prune an existing database.
+///
+/// When the state pruning mode changes, only the state of the last finalized block is kept, so
+/// all blocks must be finalized. The pruning mode of a ParityDb database can not be changed.
+#[derive(Debug, StructOpt)]
+pub struct DbPruneCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl DbPruneCmd {
+	/// Run the prune command
+	pub fn run<B: BlockT>(&self, config: Configuration) -> error::Result<()> {
+		let summary = prune_database::<B>(&config)?;
+		println!("Removed {} block bodies and {} states.", summary.bodies, summary.states);
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbPruneCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
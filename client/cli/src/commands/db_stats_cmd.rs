@@ -0,0 +1,70 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, SharedParams};
+use crate::CliConfiguration;
+use sc_service::{Configuration, chain_ops::database_stats};
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `db stats` command used to inspect the database.
+#[derive(Debug, StructOpt)]
+pub struct DbStatsCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DbStatsCmd {
+	/// Run the stats command
+	pub fn run<B: BlockT>(&self, config: Configuration) -> error::Result<()> {
+		let stats = database_stats::<B>(&config)?;
+
+		println!("Database: {}", config.database);
+		match stats.columns {
+			Some(columns) => {
+				println!("{:<16}{:>16}{:>16}", "Column", "Keys", "Size (KiB)");
+				for column in columns {
+					println!("{:<16}{:>16}{:>16}", column.name, column.keys, column.size / 1024);
+				}
+			},
+			None => println!("Column sizes are not available for this database"),
+		}
+		println!("Best block: #{}", stats.best_number);
+		println!("Finalized block: #{}", stats.finalized_number);
+		println!("Canonical blocks with a body: {}", stats.bodies);
+		println!("Canonical blocks with a state: {}", stats.states);
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbStatsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Implementation of the `export` subcommand
+
+use crate::{Error, KeystoreParams, SharedParams, utils, SubstrateCli, secret_string_from_str};
+use std::{convert::TryFrom, fs, path::PathBuf};
+use structopt::StructOpt;
+use sp_core::crypto::{KeyTypeId, SecretString};
+
+/// The `export` command
+#[derive(Debug, StructOpt)]
+#[structopt(
+	name = "export",
+	about = "Export a key from the keystore of a node, encrypted with an export password."
+)]
+pub struct ExportKeyCmd {
+	/// Key type, examples: "gran", or "imon"
+	#[structopt(long)]
+	key_type: String,
+
+	/// Hex encoded public key of the key to export.
+	#[structopt(long)]
+	public: String,
+
+	/// File to write the exported key to.
+	/// If not given, the exported key is printed to stdout.
+	#[structopt(long, parse(from_os_str))]
+	output: Option<PathBuf>,
+
+	/// Password the exported key is encrypted with.
+	/// If not given, you will be prompted for the password.
+	#[structopt(long, parse(try_from_str = secret_string_from_str))]
+	export_password: Option<SecretString>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+impl ExportKeyCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		let key_type = KeyTypeId::try_from(self.key_type.as_str()).map_err(|_| Error::KeyTypeInvalid)?;
+		let public = utils::decode_hex(&self.public)?;
+		let keystore = utils::open_local_keystore(cli, &self.shared_params, &self.keystore_params)?;
+		let password = utils::read_password(self.export_password.as_ref(), "Export password: ")?;
+
+		let exported = keystore.export_key(key_type, &public, &password)?
+			.ok_or_else(|| Error::Input("Key not found in keystore".into()))?;
+
+		match &self.output {
+			Some(path) => fs::write(path, exported)?,
+			None => println!("{}", exported),
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,66 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Implementation of the `import` subcommand
+
+use crate::{Error, KeystoreParams, SharedParams, utils, SubstrateCli, secret_string_from_str};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+use sp_core::crypto::SecretString;
+
+/// The `import` command
+#[derive(Debug, StructOpt)]
+#[structopt(
+	name = "import",
+	about = "Import a key exported with `key export` into the keystore of a node."
+)]
+pub struct ImportKeyCmd {
+	/// File holding the exported key.
+	#[structopt(long, parse(from_os_str))]
+	input: PathBuf,
+
+	/// Password the exported key is encrypted with.
+	/// If not given, you will be prompted for the password.
+	#[structopt(long, parse(try_from_str = secret_string_from_str))]
+	export_password: Option<SecretString>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+impl ImportKeyCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		let exported = fs::read_to_string(&self.input)?;
+		let keystore = utils::open_local_keystore(cli, &self.shared_params, &self.keystore_params)?;
+		let password = utils::read_password(self.export_password.as_ref(), "Export password: ")?;
+
+		let (key_type, public) = keystore.import_key(&exported, &password)?;
+		eprintln!(
+			"Imported key of type {} with public key 0x{}",
+			String::from_utf8_lossy(&key_type.0),
+			hex::encode(public),
+		);
+		Ok(())
+	}
+}
@@ -22,6 +22,9 @@ use structopt::StructOpt;
 
 use super::{
 	insert_key::InsertKeyCmd,
+	change_password::ChangePasswordCmd,
+	export_key::ExportKeyCmd,
+	import_key::ImportKeyCmd,
 	inspect_key::InspectKeyCmd,
 	generate::GenerateCmd,
 	inspect_node_key::InspectNodeKeyCmd,
@@ -46,6 +49,15 @@ pub enum KeySubcommand {
 
 	/// Insert a key to the keystore of a node.
 	Insert(InsertKeyCmd),
+
+	/// Encrypt all keys in the keystore of a node with a new password.
+	ChangePassword(ChangePasswordCmd),
+
+	/// Export a key from the keystore of a node.
+	Export(ExportKeyCmd),
+
+	/// Import an exported key into the keystore of a node.
+	Import(ImportKeyCmd),
 }
 
 impl KeySubcommand {
@@ -57,6 +69,9 @@ impl KeySubcommand {
 			KeySubcommand::InspectKey(cmd) => cmd.run(),
 			KeySubcommand::Insert(cmd) => cmd.run(cli),
 			KeySubcommand::InspectNodeKey(cmd) => cmd.run(),
+			KeySubcommand::ChangePassword(cmd) => cmd.run(cli),
+			KeySubcommand::Export(cmd) => cmd.run(cli),
+			KeySubcommand::Import(cmd) => cmd.run(cli),
 		}
 	}
 }
@@ -29,9 +29,16 @@ mod run_cmd;
 mod generate_node_key;
 mod generate;
 mod insert_key;
+mod change_password;
+mod export_key;
+mod import_key;
 mod inspect_node_key;
 mod inspect_key;
 mod key;
+mod db;
+mod db_stats_cmd;
+mod db_prune_cmd;
+mod db_migrate_cmd;
 pub mod utils;
 
 pub use self::{
@@ -44,10 +51,17 @@ pub use self::{
 	sign::SignCmd,
 	generate::GenerateCmd,
 	insert_key::InsertKeyCmd,
+	change_password::ChangePasswordCmd,
+	export_key::ExportKeyCmd,
+	import_key::ImportKeyCmd,
 	inspect_key::InspectKeyCmd,
 	generate_node_key::GenerateNodeKeyCmd,
 	inspect_node_key::InspectNodeKeyCmd,
 	key::KeySubcommand,
+	db::DbSubcommand,
+	db_stats_cmd::DbStatsCmd,
+	db_prune_cmd::DbPruneCmd,
+	db_migrate_cmd::DbMigrateCmd,
 	vanity::VanityCmd,
 	verify::VerifyCmd,
 	revert_cmd::RevertCmd,
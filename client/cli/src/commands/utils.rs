@@ -23,7 +23,9 @@ use sp_core::{
 	crypto::{Ss58Codec, Ss58AddressFormat},
 };
 use sp_runtime::{MultiSigner, traits::IdentifyAccount};
-use crate::{OutputType, error::{self, Error}};
+use crate::{OutputType, KeystoreParams, SharedParams, SubstrateCli, error::{self, Error}};
+use sc_keystore::LocalKeystore;
+use sc_service::config::{BasePath, KeystoreConfig};
 use serde_json::json;
 use sp_core::crypto::{SecretString, Zeroize, ExposeSecret};
 
@@ -266,6 +268,39 @@ pub fn read_message(msg: Option<&String>, should_decode: bool) -> Result<Vec<u8>
 	Ok(message)
 }
 
+/// Open the local keystore of the chain selected by `shared_params`.
+pub fn open_local_keystore<C: SubstrateCli>(
+	cli: &C,
+	shared_params: &SharedParams,
+	keystore_params: &KeystoreParams,
+) -> Result<LocalKeystore, Error> {
+	let base_path = shared_params
+		.base_path()
+		.unwrap_or_else(|| BasePath::from_project("", "", &C::executable_name()));
+	let chain_id = shared_params.chain_id(shared_params.is_dev());
+	let chain_spec = cli.load_spec(&chain_id)?;
+	let config_dir = base_path.config_dir(chain_spec.id());
+
+	match keystore_params.keystore_config(&config_dir)? {
+		(Some(uri), _) => Err(Error::Input(format!(
+			"Keys of the remote keystore at {} can only be managed by the remote signer",
+			uri,
+		))),
+		(None, KeystoreConfig::Path { path, password }) => Ok(LocalKeystore::open(path, password)?),
+		(None, KeystoreConfig::InMemory) => Err(Error::Input(
+			"The keys of an in-memory keystore can not be managed".into()
+		)),
+	}
+}
+
+/// Returns the given password or asks for it on the terminal, using `prompt`.
+pub fn read_password(password: Option<&SecretString>, prompt: &str) -> Result<SecretString, Error> {
+	match password {
+		Some(password) => Ok(password.clone()),
+		None => Ok(SecretString::new(rpassword::read_password_from_tty(Some(prompt))?)),
+	}
+}
+
 
 /// Allows for calling $method with appropriate crypto impl.
 #[macro_export]
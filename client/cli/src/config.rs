@@ -35,6 +35,7 @@ use sc_service::config::{
 use sc_service::{ChainSpec, TracingReceiver, KeepBlocks, TransactionStorageMode};
 use sc_telemetry::TelemetryHandle;
 use sc_tracing::logging::LoggerBuilder;
+use sp_core::crypto::SecretString;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
@@ -196,6 +197,15 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			.unwrap_or_else(|| Ok((None, KeystoreConfig::InMemory)))
 	}
 
+	/// Get the token to authenticate with at the remote keystore.
+	///
+	/// By default this is retrieved from `KeystoreParams` if it is available. Otherwise its `None`.
+	fn keystore_remote_token(&self) -> Result<Option<SecretString>> {
+		self.keystore_params()
+			.map(|x| x.keystore_remote_token())
+			.unwrap_or(Ok(None))
+	}
+
 	/// Get the database cache size.
 	///
 	/// By default this is retrieved from `DatabaseParams` if it is available. Otherwise its `None`.
@@ -515,6 +525,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				DCV::p2p_listen_port(),
 			)?,
 			keystore_remote,
+			keystore_remote_token: self.keystore_remote_token()?,
 			keystore,
 			database: self.database_config(&config_dir, database_cache_size, database)?,
 			state_cache_size: self.state_cache_size()?,
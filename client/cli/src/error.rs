@@ -68,7 +68,7 @@ pub enum Error {
 	#[error("Key store operation failed")]
 	KeyStoreOperation,
 
-	#[error("Key storage issue encountered")]
+	#[error("Key storage issue encountered: {0}")]
 	KeyStorage(#[from] sc_keystore::Error),
 
 	#[error("Invalid hexadecimal string data")]
@@ -42,7 +42,8 @@ pub struct DatabaseParams {
 	/// This changes the storage format for blocks bodies.
 	/// If this is enabled, each transaction is stored separately in the
 	/// transaction database column and is only referenced by hash
-	/// in the block body column.
+	/// in the block body column. Block authors of a chain with the transaction storage
+	/// pallet need this to build the storage proofs.
 	#[structopt(long)]
 	pub storage_chain: bool,
 }
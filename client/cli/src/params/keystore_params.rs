@@ -29,10 +29,29 @@ const DEFAULT_KEYSTORE_CONFIG_PATH: &'static str = "keystore";
 /// Parameters of the keystore
 #[derive(Debug, StructOpt)]
 pub struct KeystoreParams {
-	/// Specify custom URIs to connect to for keystore-services
+	/// Specify custom URIs to connect to for keystore-services.
+	///
+	/// Keys are then held by an external signer reachable at `tcp://host:port` or
+	/// `unix:///path/to/socket`, see the `remote-signer` utility.
+	///
+	/// TCP connections are not encrypted, so anyone who can observe the traffic to the signer
+	/// can reuse the token to sign with its keys. Only connect over TCP through the loopback
+	/// interface or an otherwise secured link.
 	#[structopt(long = "keystore-uri")]
 	pub keystore_uri: Option<String>,
 
+	/// File that contains the token the remote signer requires.
+	///
+	/// Takes precedence over a token given as the user of the URI, e.g. `tcp://token@host:port`,
+	/// which is visible to other users of the machine in the process list.
+	#[structopt(
+		long = "keystore-token-file",
+		value_name = "PATH",
+		parse(from_os_str),
+		requires = "keystore-uri"
+	)]
+	pub keystore_token_file: Option<PathBuf>,
+
 	/// Specify custom keystore path.
 	#[structopt(long = "keystore-path", value_name = "PATH", parse(from_os_str))]
 	pub keystore_path: Option<PathBuf>,
@@ -96,6 +115,21 @@ impl KeystoreParams {
 		Ok((self.keystore_uri.clone(), KeystoreConfig::Path { path, password }))
 	}
 
+	/// Get the token to authenticate with at the remote keystore, if any.
+	pub fn keystore_remote_token(&self) -> Result<Option<SecretString>> {
+		let path = match self.keystore_token_file {
+			Some(ref path) => path,
+			None => return Ok(None),
+		};
+		let token = fs::read_to_string(path)
+			.map_err(|e| format!("Failed to read token file {}: {}", path.display(), e))?;
+		let token = token.trim();
+		if token.is_empty() {
+			return Err(format!("Token file {} is empty", path.display()).into());
+		}
+		Ok(Some(SecretString::new(token.into())))
+	}
+
 	/// helper method to fetch password from `KeyParams` or read from stdin
 	pub fn read_password(&self) -> error::Result<Option<SecretString>> {
 		let (password_interactive, password) = (self.password_interactive, self.password.clone());
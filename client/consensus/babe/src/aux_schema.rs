@@ -34,10 +34,16 @@ const BABE_EPOCH_CHANGES_VERSION: &[u8] = b"babe_epoch_changes_version";
 const BABE_EPOCH_CHANGES_KEY: &[u8] = b"babe_epoch_changes";
 const BABE_EPOCH_CHANGES_CURRENT_VERSION: u32 = 2;
 
-fn block_weight_key<H: Encode>(block_hash: H) -> Vec<u8> {
+/// The aux storage key under which the weight of the given block is stored.
+pub fn block_weight_key<H: Encode>(block_hash: H) -> Vec<u8> {
 	(b"block_weight", block_hash).encode()
 }
 
+/// The aux storage keys which aren't tied to a specific block.
+pub fn aux_keys() -> Vec<Vec<u8>> {
+	vec![BABE_EPOCH_CHANGES_VERSION.to_vec(), BABE_EPOCH_CHANGES_KEY.to_vec()]
+}
+
 fn load_decode<B, T>(backend: &B, key: &[u8]) -> ClientResult<Option<T>>
 	where
 		B: AuxStore,
@@ -196,6 +196,25 @@ impl Epoch {
 			},
 		}
 	}
+
+	/// Create an epoch from the description returned by the runtime. The genesis configuration
+	/// is used if the runtime reports that the configuration was never changed.
+	pub fn from_runtime(
+		epoch: sp_consensus_babe::Epoch,
+		genesis_config: &BabeGenesisConfiguration,
+	) -> Epoch {
+		Epoch {
+			epoch_index: epoch.epoch_index,
+			start_slot: epoch.start_slot,
+			duration: epoch.duration,
+			authorities: epoch.authorities,
+			randomness: epoch.randomness,
+			config: epoch.config.unwrap_or_else(|| BabeEpochConfiguration {
+				c: genesis_config.c,
+				allowed_slots: genesis_config.allowed_slots,
+			}),
+		}
+	}
 }
 
 /// Errors encountered by the babe authorship task.
@@ -322,7 +341,7 @@ impl Config {
 				&b, |v| v == 1,
 			)?;
 			let has_api_v2 = a.has_api_with::<dyn BabeApi<B>, _>(
-				&b, |v| v == 2,
+				&b, |v| v >= 2,
 			)?;
 
 			if has_api_v1 {
@@ -517,6 +536,7 @@ async fn answer_requests<B: BlockT, C>(
 						duration: viable_epoch.as_ref().duration,
 						authorities: viable_epoch.as_ref().authorities.clone(),
 						randomness: viable_epoch.as_ref().randomness,
+						config: Some(viable_epoch.as_ref().config.clone()),
 					})
 				};
 
@@ -1242,6 +1262,79 @@ impl<Block: BlockT, I: Clone, Client> Clone for BabeBlockImport<Block, Client, I
 	}
 }
 
+impl<Block, Client, Inner> BabeBlockImport<Block, Client, Inner> where
+	Block: BlockT,
+	Inner: BlockImport<Block, Transaction = sp_api::TransactionFor<Client, Block>> + Send + Sync,
+	Inner::Error: Into<ConsensusError>,
+	Client: HeaderBackend<Block> + HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ AuxStore + ProvideRuntimeApi<Block> + ProvideCache<Block> + Send + Sync,
+	Client::Api: BabeApi<Block> + ApiExt<Block>,
+{
+	/// Import a block along with its complete state, as downloaded by warp sync.
+	///
+	/// The parent of the block is not available, so nothing can be verified against it. The
+	/// block becomes the new best block, and the epoch changes tree is reset to the epochs
+	/// read from the imported state.
+	fn import_state(
+		&mut self,
+		mut block: BlockImportParams<Block, sp_api::TransactionFor<Client, Block>>,
+		new_cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, ConsensusError> {
+		let hash = block.post_hash();
+		let number = *block.header.number();
+
+		block.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+
+		// the chain weight is counted from the imported block onwards.
+		aux_schema::write_block_weight(
+			hash,
+			0,
+			|values| block.auxiliary.extend(
+				values.iter().map(|(k, v)| (k.to_vec(), Some(v.to_vec())))
+			),
+		);
+
+		let imported_aux = match self.inner.import_block(block, new_cache).map_err(Into::into)? {
+			ImportResult::Imported(aux) => aux,
+			r => return Ok(r),
+		};
+
+		let block_id = BlockId::Hash(hash);
+		let runtime_api = self.client.runtime_api();
+		let runtime_api_err = |e| ConsensusError::ClientImport(
+			babe_err(Error::<Block>::RuntimeApi(e)).into()
+		);
+
+		// older runtimes don't report the configuration of the epochs.
+		if !runtime_api.has_api_with::<dyn BabeApi<Block>, _>(&block_id, |v| v >= 3)
+			.map_err(runtime_api_err)?
+		{
+			return Err(ConsensusError::ClientImport(
+				"The runtime doesn't report the BABE epoch configuration".into()
+			));
+		}
+
+		let (current_epoch, next_epoch) = runtime_api.current_epoch(&block_id)
+			.and_then(|current| runtime_api.next_epoch(&block_id).map(|next| (current, next)))
+			.map_err(runtime_api_err)?;
+
+		let mut epoch_changes = self.epoch_changes.lock();
+		epoch_changes.reset(
+			hash,
+			number,
+			Epoch::from_runtime(current_epoch, &self.config),
+			Epoch::from_runtime(next_epoch, &self.config),
+		);
+
+		aux_schema::write_epoch_changes::<Block, _, _>(
+			&*epoch_changes,
+			|insert| self.client.insert_aux(insert, &[]),
+		).map_err(|e| ConsensusError::ClientImport(e.to_string()))?;
+
+		Ok(ImportResult::Imported(imported_aux))
+	}
+}
+
 impl<Block: BlockT, Client, I> BabeBlockImport<Block, Client, I> {
 	fn new(
 		client: Arc<Client>,
@@ -1280,7 +1373,17 @@ impl<Block, Client, Inner> BlockImport<Block> for BabeBlockImport<Block, Client,
 		// early exit if block already in chain, otherwise the check for
 		// epoch changes will error when trying to re-import an epoch change
 		match self.client.status(BlockId::Hash(hash)) {
+			Ok(sp_blockchain::BlockStatus::InChain) if block.import_existing => {
+				// the epoch changes have been recorded when the block was first imported,
+				// so it is passed on as is, e.g. to import its state.
+				let _ = block.take_intermediate::<BabeIntermediate<Block>>(INTERMEDIATE_KEY);
+				block.fork_choice = Some(ForkChoiceStrategy::Custom(false));
+				return self.inner.import_block(block, new_cache).map_err(Into::into);
+			},
 			Ok(sp_blockchain::BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
+			Ok(sp_blockchain::BlockStatus::Unknown) if block.imported_state.is_some() => {
+				return self.import_state(block, new_cache);
+			},
 			Ok(sp_blockchain::BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
 		}
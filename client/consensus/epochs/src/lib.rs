@@ -638,6 +638,26 @@ impl<Hash, Number, E: Epoch> EpochChanges<Hash, Number, E> where
 		}
 	}
 
+	/// Reset to a specified pair of epochs, as if they were announced at the given block.
+	///
+	/// This is used when the state of a block has been imported without its ancestry, e.g. by
+	/// warp sync. The epochs are stored in a single node, so that none of the missing
+	/// ancestors are ever looked up.
+	pub fn reset(&mut self, hash: Hash, number: Number, current: E, next: E) {
+		self.inner = ForkTree::new();
+		self.epochs.clear();
+
+		let persisted = PersistedEpoch::Genesis(current, next);
+		let header = PersistedEpochHeader::from(&persisted);
+		let _res = self.inner.import(
+			hash,
+			number,
+			header,
+			&|_, _| Ok(false) as Result<bool, fork_tree::Error<ClientError>>,
+		);
+		self.epochs.insert((hash, number), persisted);
+	}
+
 	/// Return the inner fork tree.
 	pub fn tree(&self) -> &ForkTree<Hash, Number, PersistedEpochHeader<E>> {
 		&self.inner
@@ -30,6 +30,7 @@
 
 pub mod light;
 pub mod offchain;
+pub mod maintenance;
 
 #[cfg(any(feature = "with-kvdb-rocksdb", test))]
 pub mod bench;
@@ -49,6 +50,7 @@ use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use std::io;
 use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
 use parking_lot::{Mutex, RwLock};
 use linked_hash_map::LinkedHashMap;
 use log::{trace, debug, warn};
@@ -66,7 +68,7 @@ use codec::{Decode, Encode};
 use hash_db::Prefix;
 use sp_trie::{MemoryDB, PrefixedMemoryDB, prefixed_key};
 use sp_database::Transaction;
-use sp_core::{Hasher, ChangesTrieConfiguration};
+use sp_core::ChangesTrieConfiguration;
 use sp_core::offchain::OffchainOverlayedChange;
 use sp_core::storage::{well_known_keys, ChildInfo};
 use sp_arithmetic::traits::Saturating;
@@ -77,7 +79,7 @@ use sp_runtime::traits::{
 use sp_state_machine::{
 	DBValue, ChangesTrieTransaction, ChangesTrieCacheAction, UsageInfo as StateUsageInfo,
 	StorageCollection, ChildStorageCollection, OffchainChangesCollection,
-	backend::Backend as StateBackend, StateMachineStats,
+	backend::Backend as StateBackend, StateMachineStats, IndexOperation,
 };
 use crate::utils::{DatabaseType, Meta, meta_keys, read_db, read_meta};
 use crate::changes_tries_storage::{DbChangesTrieStorage, DbChangesTrieStorageTransaction};
@@ -288,11 +290,33 @@ pub enum KeepBlocks {
 pub enum TransactionStorageMode {
 	/// Store block body as an encoded list of full transactions in the BODY column
 	BlockBody,
-	/// Store a list of hashes in the BODY column and each transaction individually
-	/// in the TRANSACTION column.
+	/// Store indexed transaction data individually in the TRANSACTION column, reference
+	/// counted by content hash. The BODY column keeps the list of extrinsics with the
+	/// indexed data stripped.
 	StorageChain,
 }
 
+/// Extrinsic as stored in the BODY column in `StorageChain` mode.
+#[derive(Debug, Encode, Decode)]
+enum DbExtrinsic<B: BlockT> {
+	/// Extrinsic that contains indexed data.
+	Indexed {
+		/// Hash of the indexed part.
+		hash: DbHash,
+		/// Extrinsic header.
+		header: Vec<u8>,
+	},
+	/// Extrinsic that renews previously indexed data.
+	Renewed {
+		/// Hash of the renewed data.
+		hash: DbHash,
+		/// Complete extrinsic data.
+		extrinsic: B::Extrinsic,
+	},
+	/// Complete extrinsic data.
+	Full(B::Extrinsic),
+}
+
 /// Where to find the database..
 #[derive(Debug, Clone)]
 pub enum DatabaseSettingsSrc {
@@ -515,12 +539,29 @@ impl<Block: BlockT> sc_client_api::blockchain::Backend<Block> for BlockchainDb<B
 						)),
 					},
 					TransactionStorageMode::StorageChain => {
-						match Vec::<Block::Hash>::decode(&mut &body[..]) {
-							Ok(hashes) => {
-								let extrinsics: ClientResult<Vec<Block::Extrinsic>> = hashes.into_iter().map(
-									|h| self.extrinsic(&h).and_then(|maybe_ex| maybe_ex.ok_or_else(
-										|| sp_blockchain::Error::Backend(
-											format!("Missing transaction: {}", h))))
+						match Vec::<DbExtrinsic<Block>>::decode(&mut &body[..]) {
+							Ok(index) => {
+								let extrinsics: ClientResult<Vec<Block::Extrinsic>> = index.into_iter().map(
+									| ex | match ex {
+										DbExtrinsic::Indexed { hash, header } => {
+											match self.db.get(columns::TRANSACTION, hash.as_ref()) {
+												Some(t) => {
+													let mut input = header;
+													input.extend(t);
+													Block::Extrinsic::decode(&mut &input[..]).map_err(|err|
+														sp_blockchain::Error::Backend(
+															format!("Error decoding indexed extrinsic: {}", err)
+														)
+													)
+												},
+												None => Err(sp_blockchain::Error::Backend(
+													format!("Missing indexed transaction {:?}", hash))
+												),
+											}
+										},
+										DbExtrinsic::Renewed { extrinsic, .. } => Ok(extrinsic),
+										DbExtrinsic::Full(ex) => Ok(ex),
+									}
 								).collect();
 								Ok(Some(extrinsics?))
 							}
@@ -563,23 +604,47 @@ impl<Block: BlockT> sc_client_api::blockchain::Backend<Block> for BlockchainDb<B
 		children::read_children(&*self.db, columns::META, meta_keys::CHILDREN_PREFIX, parent_hash)
 	}
 
-	fn extrinsic(&self, hash: &Block::Hash) -> ClientResult<Option<Block::Extrinsic>> {
-		match self.db.get(columns::TRANSACTION, hash.as_ref()) {
-			Some(ex) => {
-				match Decode::decode(&mut &ex[..]) {
-					Ok(ex) => Ok(Some(ex)),
+	fn indexed_transaction(&self, hash: &Block::Hash) -> ClientResult<Option<Vec<u8>>> {
+		Ok(self.db.get(columns::TRANSACTION, hash.as_ref()))
+	}
+
+	fn has_indexed_transaction(&self, hash: &Block::Hash) -> ClientResult<bool> {
+		Ok(self.db.contains(columns::TRANSACTION, hash.as_ref()))
+	}
+
+	fn block_indexed_body(&self, id: BlockId<Block>) -> ClientResult<Option<Vec<Vec<u8>>>> {
+		match self.transaction_storage {
+			TransactionStorageMode::BlockBody => Ok(None),
+			TransactionStorageMode::StorageChain => {
+				let body = match read_db(&*self.db, columns::KEY_LOOKUP, columns::BODY, id)? {
+					Some(body) => body,
+					None => return Ok(None),
+				};
+				match Vec::<DbExtrinsic<Block>>::decode(&mut &body[..]) {
+					Ok(index) => {
+						let mut transactions = Vec::new();
+						for ex in index.into_iter() {
+							match ex {
+								DbExtrinsic::Indexed { hash, .. } | DbExtrinsic::Renewed { hash, .. } => {
+									match self.db.get(columns::TRANSACTION, hash.as_ref()) {
+										Some(t) => transactions.push(t),
+										None => return Err(sp_blockchain::Error::Backend(
+											format!("Missing indexed transaction {:?}", hash))
+										),
+									}
+								},
+								DbExtrinsic::Full(_) => {},
+							}
+						}
+						Ok(Some(transactions))
+					}
 					Err(err) => Err(sp_blockchain::Error::Backend(
-							format!("Error decoding extrinsic {}: {}", hash, err)
+						format!("Error decoding body list: {}", err)
 					)),
 				}
-			},
-			None => Ok(None),
+			}
 		}
 	}
-
-	fn have_extrinsic(&self, hash: &Block::Hash) -> ClientResult<bool> {
-		Ok(self.db.contains(columns::TRANSACTION, hash.as_ref()))
-	}
 }
 
 impl<Block: BlockT> sc_client_api::blockchain::ProvideCache<Block> for BlockchainDb<Block> {
@@ -680,6 +745,8 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	reset_storage: bool,
+	index_ops: Vec<IndexOperation>,
 }
 
 impl<Block: BlockT> BlockImportOperation<Block> {
@@ -771,6 +838,7 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		self.db_updates = transaction;
 		self.changes_trie_config_update = Some(changes_trie_config);
 		self.commit_state = true;
+		self.reset_storage = true;
 		Ok(root)
 	}
 
@@ -822,6 +890,11 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		self.set_head = Some(block);
 		Ok(())
 	}
+
+	fn update_transaction_index(&mut self, index_ops: Vec<IndexOperation>) -> ClientResult<()> {
+		self.index_ops = index_ops;
+		Ok(())
+	}
 }
 
 struct StorageDb<Block: BlockT> {
@@ -1039,8 +1112,13 @@ impl<Block: BlockT> Backend<Block> {
 
 		let meta = self.blockchain.meta.read();
 
-		// cannot find tree route with empty DB.
-		if meta.best_hash != Default::default() {
+		// cannot find tree route with empty DB, nor to a block that was imported along with
+		// its state but without its ancestry (e.g. by warp sync).
+		let route_to_known = sc_client_api::blockchain::HeaderBackend::header(
+			&self.blockchain,
+			BlockId::Hash(route_to),
+		)?.is_some();
+		if meta.best_hash != Default::default() && route_to_known {
 			let tree_route = sp_blockchain::tree_route(
 				&self.blockchain,
 				meta.best_hash,
@@ -1163,6 +1241,11 @@ impl<Block: BlockT> Backend<Block> {
 						implies existence of blocks with all numbers before it; qed")
 			};
 
+			// blocks imported without state are not known to the state db.
+			if !sc_client_api::Backend::have_state_at(self, &hash, new_canonical.saturated_into()) {
+				return Ok(())
+			}
+
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
 			let commit = self.storage.state_db.canonicalize_block(&hash)
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
@@ -1183,7 +1266,10 @@ impl<Block: BlockT> Backend<Block> {
 		operation.apply_offchain(&mut transaction);
 
 		let mut meta_updates = Vec::with_capacity(operation.finalized_blocks.len());
-		let mut last_finalized_hash = self.blockchain.meta.read().finalized_hash;
+		let (mut last_finalized_hash, last_finalized_num) = {
+			let meta = self.blockchain.meta.read();
+			(meta.finalized_hash, meta.finalized_number)
+		};
 
 		let mut changes_trie_cache_ops = None;
 		for (block, justification) in operation.finalized_blocks {
@@ -1210,6 +1296,12 @@ impl<Block: BlockT> Backend<Block> {
 			// blocks are keyed by number + hash.
 			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
 
+			// the header is already known when the block is re-imported, e.g. with its state.
+			let existing_header = sc_client_api::blockchain::HeaderBackend::header(
+				&self.blockchain,
+				BlockId::Hash(hash),
+			)?.is_some();
+
 			let (enacted, retracted) = if pending_block.leaf_state.is_best() {
 				self.set_head_with_transaction(&mut transaction, parent_hash, (number, hash))?
 			} else {
@@ -1224,20 +1316,14 @@ impl<Block: BlockT> Backend<Block> {
 			)?;
 
 			transaction.set_from_vec(columns::HEADER, &lookup_key, pending_block.header.encode());
-			if let Some(body) = &pending_block.body {
+			if let Some(body) = pending_block.body {
 				match self.transaction_storage {
 					TransactionStorageMode::BlockBody => {
 						transaction.set_from_vec(columns::BODY, &lookup_key, body.encode());
 					},
 					TransactionStorageMode::StorageChain => {
-						let mut hashes = Vec::with_capacity(body.len());
-						for extrinsic in body {
-							let extrinsic = extrinsic.encode();
-							let hash = HashFor::<Block>::hash(&extrinsic);
-							transaction.set(columns::TRANSACTION, &hash.as_ref(), &extrinsic);
-							hashes.push(hash);
-						}
-						transaction.set_from_vec(columns::BODY, &lookup_key, hashes.encode());
+						let body = apply_index_ops::<Block>(&mut transaction, body, operation.index_ops);
+						transaction.set_from_vec(columns::BODY, &lookup_key, body);
 					},
 				}
 			}
@@ -1304,14 +1390,27 @@ impl<Block: BlockT> Backend<Block> {
 				}
 				self.state_usage.tally_writes(ops, bytes);
 				let number_u64 = number.saturated_into::<u64>();
-				let commit = self.storage.state_db.insert_block(
-					&hash,
-					number_u64,
-					&pending_block.header.parent_hash(),
-					changeset,
-				).map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+				let commit = if operation.reset_storage && number_u64 > 0 {
+					// The complete state of an already finalized block has been imported.
+					self.storage.state_db.import_state(&hash, number_u64, changeset)
+				} else {
+					self.storage.state_db.insert_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					)
+				}.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
 				apply_state_commit(&mut transaction, commit);
 
+				if !operation.reset_storage && number_u64 > 0 && number <= last_finalized_num {
+					// The block has been finalized before its state was available and is now
+					// re-imported with state, so it can be canonicalized right away.
+					let commit = self.storage.state_db.canonicalize_block(&hash)
+						.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+					apply_state_commit(&mut transaction, commit);
+				}
+
 				// Check if need to finalize. Genesis is always finalized instantly.
 				let finalized = number_u64 == 0 || pending_block.leaf_state.is_final();
 				finalized
@@ -1341,8 +1440,15 @@ impl<Block: BlockT> Backend<Block> {
 			let cache = operation.old_state.into_cache_changes();
 
 			if finalized {
-				// TODO: ensure best chain contains this block.
-				self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
+				if operation.reset_storage && !number.is_zero() {
+					// The complete state of the block has been imported, its ancestry doesn't
+					// have to be known and the state db already treats it as canonical.
+					let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+					transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
+				} else {
+					// TODO: ensure best chain contains this block.
+					self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
+				}
 				self.note_finalized(
 					&mut transaction,
 					true,
@@ -1358,7 +1464,9 @@ impl<Block: BlockT> Backend<Block> {
 
 			debug!(target: "db", "DB Commit {:?} ({}), best = {}", hash, number, is_best);
 
-			let displaced_leaf = {
+			let displaced_leaf = if existing_header {
+				None
+			} else {
 				let mut leaves = self.blockchain.leaves.write();
 				let displaced_leaf = leaves.import(hash, number, parent_hash);
 				leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
@@ -1366,20 +1474,22 @@ impl<Block: BlockT> Backend<Block> {
 				displaced_leaf
 			};
 
-			let mut children = children::read_children(
-				&*self.storage.db,
-				columns::META,
-				meta_keys::CHILDREN_PREFIX,
-				parent_hash,
-			)?;
-			children.push(hash);
-			children::write_children(
-				&mut transaction,
-				columns::META,
-				meta_keys::CHILDREN_PREFIX,
-				parent_hash,
-				children,
-			);
+			if !existing_header {
+				let mut children = children::read_children(
+					&*self.storage.db,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent_hash,
+				)?;
+				children.push(hash);
+				children::write_children(
+					&mut transaction,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent_hash,
+					children,
+				);
+			}
 
 			meta_updates.push((hash, number, pending_block.leaf_state.is_best(), finalized));
 
@@ -1473,9 +1583,12 @@ impl<Block: BlockT> Backend<Block> {
 			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
 			transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
 
-			let commit = self.storage.state_db.canonicalize_block(&f_hash)
-				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
-			apply_state_commit(transaction, commit);
+			// blocks imported without state (e.g. during state sync) are not known to the state db.
+			if sc_client_api::Backend::have_state_at(self, &f_hash, f_num) {
+				let commit = self.storage.state_db.canonicalize_block(&f_hash)
+					.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+				apply_state_commit(transaction, commit);
+			}
 
 			if !f_num.is_zero() {
 				let new_changes_trie_cache_ops = self.changes_tries_storage.finalize(
@@ -1512,37 +1625,110 @@ impl<Block: BlockT> Backend<Block> {
 				return Ok(())
 			}
 			let number = finalized.saturating_sub(keep.into());
-			match read_db(&*self.storage.db, columns::KEY_LOOKUP, columns::BODY, BlockId::<Block>::number(number))? {
-				Some(body) => {
-					debug!(target: "db", "Removing block #{}", number);
-					utils::remove_from_db(
-						transaction,
-						&*self.storage.db,
-						columns::KEY_LOOKUP,
-						columns::BODY,
-						BlockId::<Block>::number(number),
-					)?;
-					match self.transaction_storage {
-						TransactionStorageMode::BlockBody => {},
-						TransactionStorageMode::StorageChain => {
-							match Vec::<Block::Hash>::decode(&mut &body[..]) {
-								Ok(hashes) => {
-									for h in hashes {
-										transaction.remove(columns::TRANSACTION, h.as_ref());
-									}
-								}
-								Err(err) => return Err(sp_blockchain::Error::Backend(
-									format!("Error decoding body list: {}", err)
-								)),
-							}
+			prune_block_body::<Block>(transaction, &*self.storage.db, self.transaction_storage, number)?;
+		}
+		Ok(())
+	}
+}
+
+/// Remove the body of the canonical block `number` and release the indexed transactions it
+/// references.
+///
+/// Returns `false` if the body was already removed.
+fn prune_block_body<Block: BlockT>(
+	transaction: &mut Transaction<DbHash>,
+	db: &dyn Database<DbHash>,
+	transaction_storage: TransactionStorageMode,
+	number: NumberFor<Block>,
+) -> ClientResult<bool> {
+	let body = match read_db(db, columns::KEY_LOOKUP, columns::BODY, BlockId::<Block>::number(number))? {
+		Some(body) => body,
+		None => return Ok(false),
+	};
+	debug!(target: "db", "Removing block #{}", number);
+	utils::remove_from_db(
+		transaction,
+		db,
+		columns::KEY_LOOKUP,
+		columns::BODY,
+		BlockId::<Block>::number(number),
+	)?;
+	match transaction_storage {
+		TransactionStorageMode::BlockBody => {},
+		TransactionStorageMode::StorageChain => {
+			match Vec::<DbExtrinsic<Block>>::decode(&mut &body[..]) {
+				Ok(body) => {
+					for ex in body {
+						match ex {
+							DbExtrinsic::Indexed { hash, .. } |
+							DbExtrinsic::Renewed { hash, .. } =>
+								transaction.release(columns::TRANSACTION, hash),
+							DbExtrinsic::Full(_) => {},
 						}
 					}
 				}
-				None => return Ok(()),
+				Err(err) => return Err(sp_blockchain::Error::Backend(
+					format!("Error decoding body list: {}", err)
+				)),
 			}
 		}
-		Ok(())
 	}
+	Ok(true)
+}
+
+fn apply_index_ops<Block: BlockT>(
+	transaction: &mut Transaction<DbHash>,
+	body: Vec<Block::Extrinsic>,
+	ops: Vec<IndexOperation>,
+) -> Vec<u8> {
+	let mut extrinsic_index: Vec<DbExtrinsic<Block>> = Vec::with_capacity(body.len());
+	let mut index_map = HashMap::new();
+	let mut renewed_map = HashMap::new();
+	for op in ops {
+		match op {
+			IndexOperation::Insert { extrinsic, hash, size } => {
+				index_map.insert(extrinsic, (hash, size));
+			}
+			IndexOperation::Renew { extrinsic, hash } => {
+				renewed_map.insert(extrinsic, hash);
+			}
+		}
+	}
+	for (index, extrinsic) in body.into_iter().enumerate() {
+		let index = index as u32;
+		let db_extrinsic = if let Some(hash) = renewed_map.get(&index).and_then(|h| to_db_hash(h)) {
+			// Bump ref counter
+			transaction.reference(columns::TRANSACTION, hash);
+			DbExtrinsic::Renewed { hash, extrinsic }
+		} else {
+			match index_map.get(&index).and_then(|(h, size)| to_db_hash(h).map(|h| (h, *size))) {
+				Some((hash, size)) => {
+					let encoded = extrinsic.encode();
+					if size as usize <= encoded.len() {
+						let offset = encoded.len() - size as usize;
+						transaction.store(columns::TRANSACTION, hash, encoded[offset..].to_vec());
+						DbExtrinsic::Indexed { hash, header: encoded[..offset].to_vec() }
+					} else {
+						// Invalid indexed slice. Just store full data and don't index anything.
+						DbExtrinsic::Full(extrinsic)
+					}
+				}
+				_ => DbExtrinsic::Full(extrinsic),
+			}
+		};
+		extrinsic_index.push(db_extrinsic);
+	}
+	debug!(
+		target: "db",
+		"DB transaction index: {} inserted, {} renewed",
+		index_map.len(),
+		renewed_map.len(),
+	);
+	extrinsic_index.encode()
+}
+
+fn to_db_hash(hash: &[u8]) -> Option<DbHash> {
+	hash.try_into().ok()
 }
 
 fn apply_state_commit(transaction: &mut Transaction<DbHash>, commit: sc_state_db::CommitSet<Vec<u8>>) {
@@ -1608,6 +1794,8 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			reset_storage: false,
+			index_ops: Default::default(),
 		})
 	}
 
@@ -1949,7 +2137,7 @@ pub(crate) mod tests {
 		changes: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 		extrinsics_root: H256,
 	) -> H256 {
-		insert_block(backend, number, parent_hash, changes, extrinsics_root, Vec::new())
+		insert_block(backend, number, parent_hash, changes, extrinsics_root, Vec::new(), None)
 	}
 
 	pub fn insert_block(
@@ -1959,6 +2147,7 @@ pub(crate) mod tests {
 		changes: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 		extrinsics_root: H256,
 		body: Vec<ExtrinsicWrapper<u64>>,
+		transaction_index: Option<Vec<IndexOperation>>,
 	) -> H256 {
 		use sp_runtime::testing::Digest;
 
@@ -1987,6 +2176,9 @@ pub(crate) mod tests {
 		backend.begin_state_operation(&mut op, block_id).unwrap();
 		op.set_block_data(header, Some(body), None, NewBlockState::Best).unwrap();
 		op.update_changes_trie((changes_trie_update, ChangesTrieCacheAction::Clear)).unwrap();
+		if let Some(index) = transaction_index {
+			op.update_transaction_index(index).unwrap();
+		}
 		backend.commit_operation(op).unwrap();
 
 		header_hash
@@ -2592,7 +2784,7 @@ pub(crate) mod tests {
 			let mut blocks = Vec::new();
 			let mut prev_hash = Default::default();
 			for i in 0 .. 5 {
-				let hash = insert_block(&backend, i, prev_hash, None, Default::default(), vec![i.into()]);
+				let hash = insert_block(&backend, i, prev_hash, None, Default::default(), vec![i.into()], None);
 				blocks.push(hash);
 				prev_hash = hash;
 			}
@@ -2613,4 +2805,152 @@ pub(crate) mod tests {
 			assert_eq!(Some(vec![4.into()]), bc.body(BlockId::hash(blocks[4])).unwrap());
 		}
 	}
+
+	#[test]
+	fn maintenance_prunes_and_migrates() {
+		let db = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		let settings = |db: &Arc<dyn Database<DbHash>>, keep_blocks| DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			state_pruning: PruningMode::ArchiveAll,
+			source: DatabaseSettingsSrc::Custom(db.clone()),
+			keep_blocks,
+			transaction_storage: TransactionStorageMode::BlockBody,
+		};
+
+		let mut blocks = Vec::new();
+		{
+			let backend = Backend::<Block>::new(settings(&db, KeepBlocks::All), 0).unwrap();
+			let mut prev_hash = Default::default();
+			for i in 0 .. 5 {
+				let hash = insert_block(&backend, i, prev_hash, None, Default::default(), vec![i.into()], None);
+				blocks.push(hash);
+				prev_hash = hash;
+			}
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(blocks[4])).unwrap();
+			for i in 1 .. 5 {
+				op.mark_finalized(BlockId::Hash(blocks[i]), None).unwrap();
+			}
+			backend.commit_operation(op).unwrap();
+		}
+
+		let stats = maintenance::stats::<Block>(&settings(&db, KeepBlocks::All)).unwrap();
+		assert_eq!((stats.best_number, stats.finalized_number, stats.bodies), (4, 4, 5));
+		assert!(stats.columns.is_some());
+
+		let summary = maintenance::prune::<Block>(&settings(&db, KeepBlocks::Some(2))).unwrap();
+		assert_eq!(summary.bodies, 3);
+		let stats = maintenance::stats::<Block>(&settings(&db, KeepBlocks::All)).unwrap();
+		assert_eq!(stats.bodies, 2);
+
+		let target = sp_database::as_database(kvdb_memorydb::create(crate::utils::NUM_COLUMNS));
+		maintenance::migrate::<Block>(
+			&settings(&db, KeepBlocks::All),
+			DatabaseSettingsSrc::Custom(target.clone()),
+			&Default::default(),
+		).unwrap();
+		assert!(maintenance::migrate::<Block>(
+			&settings(&db, KeepBlocks::All),
+			DatabaseSettingsSrc::Custom(target.clone()),
+			&Default::default(),
+		).is_err());
+
+		let backend = Backend::<Block>::new(settings(&target, KeepBlocks::All), 0).unwrap();
+		let bc = backend.blockchain();
+		assert_eq!(bc.info().finalized_hash, blocks[4]);
+		assert_eq!(None, bc.body(BlockId::hash(blocks[2])).unwrap());
+		assert_eq!(Some(vec![3.into()]), bc.body(BlockId::hash(blocks[3])).unwrap());
+	}
+
+	#[test]
+	fn indexed_data_block_body() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(1, 10, TransactionStorageMode::StorageChain);
+
+		let x0 = ExtrinsicWrapper::from(0u64).encode();
+		let x1 = ExtrinsicWrapper::from(1u64).encode();
+		let x0_hash = <HashFor<Block> as sp_core::Hasher>::hash(&x0[1..]);
+		let x1_hash = <HashFor<Block> as sp_core::Hasher>::hash(&x1[1..]);
+		let index = vec![
+			IndexOperation::Insert {
+				extrinsic: 0,
+				hash: x0_hash.as_ref().to_vec(),
+				size: (x0.len() - 1) as u32,
+			},
+			IndexOperation::Insert {
+				extrinsic: 1,
+				hash: x1_hash.as_ref().to_vec(),
+				size: (x1.len() - 1) as u32,
+			},
+		];
+		let hash = insert_block(
+			&backend,
+			0,
+			Default::default(),
+			None,
+			Default::default(),
+			vec![0u64.into(), 1u64.into()],
+			Some(index),
+		);
+		let bc = backend.blockchain();
+		assert_eq!(bc.indexed_transaction(&x0_hash).unwrap().unwrap(), &x0[1..]);
+		assert_eq!(bc.indexed_transaction(&x1_hash).unwrap().unwrap(), &x1[1..]);
+		assert_eq!(
+			bc.block_indexed_body(BlockId::hash(hash)).unwrap().unwrap(),
+			vec![x0[1..].to_vec(), x1[1..].to_vec()],
+		);
+		assert_eq!(
+			bc.body(BlockId::hash(hash)).unwrap().unwrap(),
+			vec![0u64.into(), 1u64.into()],
+		);
+	}
+
+	#[test]
+	fn renew_transaction_storage() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(2, 10, TransactionStorageMode::StorageChain);
+		let mut blocks = Vec::new();
+		let mut prev_hash = Default::default();
+		let x1 = ExtrinsicWrapper::from(0u64).encode();
+		let x1_hash = <HashFor<Block> as sp_core::Hasher>::hash(&x1[1..]);
+		for i in 0 .. 10 {
+			let mut index = Vec::new();
+			if i == 0 {
+				index.push(IndexOperation::Insert {
+					extrinsic: 0,
+					hash: x1_hash.as_ref().to_vec(),
+					size: (x1.len() - 1) as u32,
+				});
+			} else if i < 5 {
+				// keep renewing 1st
+				index.push(IndexOperation::Renew {
+					extrinsic: 0,
+					hash: x1_hash.as_ref().to_vec(),
+				});
+			} // else stop renewing
+			let hash = insert_block(
+				&backend,
+				i,
+				prev_hash,
+				None,
+				Default::default(),
+				vec![i.into()],
+				Some(index),
+			);
+			blocks.push(hash);
+			prev_hash = hash;
+		}
+
+		for i in 1 .. 10 {
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(blocks[4])).unwrap();
+			op.mark_finalized(BlockId::Hash(blocks[i]), None).unwrap();
+			backend.commit_operation(op).unwrap();
+			let bc = backend.blockchain();
+			if i < 6 {
+				assert!(bc.indexed_transaction(&x1_hash).unwrap().is_some());
+			} else {
+				assert!(bc.indexed_transaction(&x1_hash).unwrap().is_none());
+			}
+		}
+	}
 }
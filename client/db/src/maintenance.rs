@@ -0,0 +1,844 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Offline maintenance of the database of a full node.
+//!
+//! The database is opened exclusively by these functions, so the node must not be running.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+use std::io;
+use codec::Decode;
+use hash_db::{HashDBRef, Hasher, Prefix};
+use log::info;
+use sc_client_api::leaves::LeafSet;
+use sp_blockchain::{Result as ClientResult, Error as ClientError};
+use sp_core::storage::{well_known_keys, ChildInfo};
+use sp_database::{Database, Transaction};
+use sp_runtime::generic::{BlockId, DigestItem};
+use sp_runtime::traits::{
+	Block as BlockT, Header as HeaderT, HashFor, NumberFor, SaturatedConversion,
+};
+use sp_trie::{DBValue, KeySpacedDB, Trie, prefixed_key, trie_types::TrieDB};
+use sc_state_db::{PruningMode, StateDb};
+use crate::utils::{self, DatabaseType, Meta, meta_keys};
+use crate::{
+	children, columns, apply_state_commit, prune_block_body, DatabaseSettings,
+	DatabaseSettingsSrc, DbExtrinsic, DbHash, KeepBlocks, StateMetaDb, TransactionStorageMode,
+	DB_HASH_LEN,
+};
+
+/// All columns of the database and their names.
+const COLUMNS: &[(u32, &str)] = &[
+	(columns::META, "meta"),
+	(columns::STATE, "state"),
+	(columns::STATE_META, "state_meta"),
+	(columns::KEY_LOOKUP, "key_lookup"),
+	(columns::HEADER, "header"),
+	(columns::BODY, "body"),
+	(columns::JUSTIFICATION, "justification"),
+	(columns::CHANGES_TRIE, "changes_trie"),
+	(columns::AUX, "aux"),
+	(columns::OFFCHAIN, "offchain"),
+	(columns::CACHE, "cache"),
+	(columns::TRANSACTION, "transaction"),
+];
+
+/// Number of blocks whose bodies are removed in a single database transaction.
+const PRUNE_BATCH_BLOCKS: u64 = 1024;
+
+/// Size of the values copied in a single database transaction when migrating.
+const MIGRATE_BATCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// Number of state nodes removed in a single database transaction when changing the pruning
+/// mode.
+const PRUNE_BATCH_KEYS: usize = 1024 * 1024;
+
+/// Size of a database column.
+#[derive(Debug, Clone)]
+pub struct ColumnStats {
+	/// Name of the column.
+	pub name: &'static str,
+	/// Number of keys in the column.
+	pub keys: u64,
+	/// Total size of the keys and values in the column, in bytes.
+	pub size: u64,
+}
+
+/// Contents of a database.
+#[derive(Debug, Clone)]
+pub struct DatabaseStats {
+	/// Size of each column, if the database is able to enumerate its keys.
+	pub columns: Option<Vec<ColumnStats>>,
+	/// Number of the best block.
+	pub best_number: u64,
+	/// Number of the last finalized block.
+	pub finalized_number: u64,
+	/// Number of canonical blocks with a body.
+	pub bodies: u64,
+	/// Number of distinct states of canonical blocks.
+	pub states: u64,
+}
+
+/// Keys of the auxiliary data to copy out of a database that can not enumerate its keys.
+///
+/// The consensus engines store their data in the auxiliary column, under keys only they know.
+pub struct AuxKeys<H> {
+	/// Keys of the data that is not tied to a block.
+	pub keys: Vec<Vec<u8>>,
+	/// Functions returning the key of the data stored for a block, given its hash.
+	pub block_keys: Vec<fn(&H) -> Vec<u8>>,
+}
+
+impl<H> Default for AuxKeys<H> {
+	fn default() -> Self {
+		AuxKeys { keys: Vec::new(), block_keys: Vec::new() }
+	}
+}
+
+/// What was removed by [`prune`].
+#[derive(Debug, Clone, Default)]
+pub struct PruneSummary {
+	/// Number of removed block bodies.
+	pub bodies: u64,
+	/// Number of removed block states.
+	pub states: u64,
+}
+
+/// Collect the statistics of the database.
+///
+/// Walks the whole canonical chain, so this takes a while for long chains.
+pub fn stats<Block: BlockT>(settings: &DatabaseSettings) -> ClientResult<DatabaseStats> {
+	let db = utils::open_database::<Block>(settings, DatabaseType::Full)?;
+	let meta = utils::read_meta::<Block>(&*db, columns::HEADER)?;
+
+	let mut column_stats = Vec::with_capacity(COLUMNS.len());
+	for &(column, name) in COLUMNS {
+		let mut stats = ColumnStats { name, keys: 0, size: 0 };
+		let result = db.iter(column, &mut |key, value| {
+			stats.keys += 1;
+			stats.size += (key.len() + value.len()) as u64;
+		});
+		match result {
+			Ok(()) => column_stats.push(stats),
+			Err(e) => {
+				info!("Column sizes are not available: {}", e);
+				break;
+			},
+		}
+	}
+	let columns = if column_stats.len() == COLUMNS.len() { Some(column_stats) } else { None };
+
+	let mut bodies = 0;
+	let mut states = 0;
+	let mut state_roots = HashSet::new();
+	let best_number: u64 = meta.best_number.saturated_into();
+	for number in 0..=best_number {
+		let id = BlockId::<Block>::number(number.saturated_into());
+		let header = match utils::read_header::<Block>(&*db, columns::KEY_LOOKUP, columns::HEADER, id)? {
+			Some(header) => header,
+			None => continue,
+		};
+		if utils::read_db(&*db, columns::KEY_LOOKUP, columns::BODY, id)?.is_some() {
+			bodies += 1;
+		}
+		// Root nodes are stored under their hash, with or without key prefixes. Blocks which
+		// don't change the state share it with their parent, so each root is counted once.
+		let state_root = *header.state_root();
+		if state_roots.insert(state_root) && db.contains(columns::STATE, state_root.as_ref()) {
+			states += 1;
+		}
+	}
+
+	Ok(DatabaseStats {
+		columns,
+		best_number,
+		finalized_number: meta.finalized_number.saturated_into(),
+		bodies,
+		states,
+	})
+}
+
+/// Apply the block and state pruning of `settings` to the database.
+///
+/// Removes the bodies of all finalized blocks but the last `keep_blocks` ones and the states
+/// outside of the state pruning window.
+///
+/// When the state pruning mode changes, only the state of the last finalized block is kept and
+/// the new pruning window starts at this block. This requires all blocks to be finalized and a
+/// database which can enumerate its keys.
+pub fn prune<Block: BlockT>(settings: &DatabaseSettings) -> ClientResult<PruneSummary> {
+	let db = utils::open_database::<Block>(settings, DatabaseType::Full)?;
+	let meta = utils::read_meta::<Block>(&*db, columns::HEADER)?;
+	let mut summary = PruneSummary::default();
+
+	let state_db = StateDb::<Block::Hash, Vec<u8>>::new(
+		settings.state_pruning.clone(),
+		!settings.source.supports_ref_counting(),
+		&StateMetaDb(&*db),
+	);
+	summary.states = match state_db {
+		Ok(state_db) => {
+			let first_available = state_db.first_available();
+			let mut transaction = Transaction::new();
+			apply_state_commit(&mut transaction, state_db.prune());
+			db.commit(transaction)?;
+			state_db.apply_pending();
+			state_db.first_available() - first_available
+		},
+		Err(sc_state_db::Error::InvalidPruningMode(mode)) =>
+			change_state_pruning::<Block>(settings, &*db, &meta, &mode)?,
+		Err(e) => return Err(ClientError::from_state_db(e)),
+	};
+	info!("Pruned {} states", summary.states);
+
+	if let KeepBlocks::Some(keep_blocks) = settings.keep_blocks {
+		// Always keep the last finalized block
+		let keep = std::cmp::max(keep_blocks, 1) as u64;
+		let finalized: u64 = meta.finalized_number.saturated_into();
+		let mut transaction = Transaction::new();
+		let mut pending = 0;
+		for number in 0..finalized.saturating_sub(keep - 1) {
+			let number: NumberFor<Block> = number.saturated_into();
+			if prune_block_body::<Block>(&mut transaction, &*db, settings.transaction_storage, number)? {
+				summary.bodies += 1;
+				pending += 1;
+			}
+			// Released transactions are reference counted, which only works across commits.
+			let commit = match settings.transaction_storage {
+				TransactionStorageMode::BlockBody => pending >= PRUNE_BATCH_BLOCKS,
+				TransactionStorageMode::StorageChain => pending > 0,
+			};
+			if commit {
+				db.commit(std::mem::take(&mut transaction))?;
+				pending = 0;
+			}
+		}
+		db.commit(transaction)?;
+		info!("Pruned {} block bodies", summary.bodies);
+	}
+
+	Ok(summary)
+}
+
+/// Switch the state database to the pruning mode of `settings`, from the mode `db_mode`.
+///
+/// The journals of the previous mode can't be used to prune the states later, so all states but
+/// the one of the last finalized block are removed. Returns the number of removed canonical
+/// states.
+fn change_state_pruning<Block: BlockT>(
+	settings: &DatabaseSettings,
+	db: &dyn Database<DbHash>,
+	meta: &Meta<NumberFor<Block>, Block::Hash>,
+	db_mode: &str,
+) -> ClientResult<u64> {
+	if settings.source.supports_ref_counting() {
+		return Err(ClientError::Backend(format!(
+			"The state pruning mode of a {} database can not be changed",
+			settings.source,
+		)));
+	}
+	if meta.best_number != meta.finalized_number {
+		return Err(ClientError::Backend(format!(
+			"The state pruning mode can only be changed when all blocks are finalized, \
+			revert the blocks after #{} first",
+			meta.finalized_number,
+		)));
+	}
+	info!("Changing the state pruning mode from {} to {:?}", db_mode, settings.state_pruning);
+
+	let previous_mode = match db_mode.as_bytes() {
+		mode if mode == PruningMode::ArchiveAll.id() => PruningMode::ArchiveAll,
+		mode if mode == PruningMode::ArchiveCanonical.id() => PruningMode::ArchiveCanonical,
+		_ => PruningMode::default(),
+	};
+	let previous = StateDb::<Block::Hash, Vec<u8>>::new(previous_mode, true, &StateMetaDb(db))
+		.map_err(ClientError::from_state_db)?;
+	let first_available = previous.first_available();
+
+	let header = utils::require_header::<Block>(
+		db,
+		columns::KEY_LOOKUP,
+		columns::HEADER,
+		BlockId::Hash(meta.finalized_hash),
+	)?;
+	let mut keep = HashSet::new();
+	walk_state::<Block>(db, true, *header.state_root(), &mut |key, _| {
+		keep.insert(key.to_vec());
+	})?;
+
+	let mut result = Ok(());
+	let mut transaction = Transaction::new();
+	let mut pending = 0;
+	db.iter(columns::STATE, &mut |key, _| {
+		if result.is_ok() && !keep.contains(key) {
+			transaction.remove(columns::STATE, key);
+			pending += 1;
+			if pending >= PRUNE_BATCH_KEYS {
+				result = db.commit(std::mem::take(&mut transaction));
+				pending = 0;
+			}
+		}
+	})?;
+	result?;
+	db.iter(columns::STATE_META, &mut |key, _| transaction.remove(columns::STATE_META, key))?;
+	db.commit(transaction)?;
+
+	let state_db = StateDb::<Block::Hash, Vec<u8>>::new(
+		settings.state_pruning.clone(),
+		true,
+		&StateMetaDb(db),
+	).map_err(ClientError::from_state_db)?;
+	let finalized_number: u64 = meta.finalized_number.saturated_into();
+	let commit = state_db.import_state(&meta.finalized_hash, finalized_number, Default::default())
+		.map_err(|e: sc_state_db::Error<io::Error>| ClientError::from_state_db(e))?;
+	let mut transaction = Transaction::new();
+	apply_state_commit(&mut transaction, commit);
+	db.commit(transaction)?;
+
+	Ok(finalized_number.saturating_sub(first_available))
+}
+
+/// Copy the database to the empty database `target`.
+///
+/// Moving a RocksDB database to ParityDb converts the state to the reference counted format
+/// ParityDb uses. ParityDb can not enumerate its keys, so only the canonical chain up to the
+/// last finalized block and the state of that block are copied out of it, together with the
+/// auxiliary data named in `aux_keys`. Changes tries and the offchain storage are not copied.
+pub fn migrate<Block: BlockT>(
+	settings: &DatabaseSettings,
+	target: DatabaseSettingsSrc,
+	aux_keys: &AuxKeys<Block::Hash>,
+) -> ClientResult<()> {
+	let db = utils::open_database::<Block>(settings, DatabaseType::Full)?;
+	let target_settings = DatabaseSettings {
+		state_cache_size: settings.state_cache_size,
+		state_cache_child_ratio: settings.state_cache_child_ratio,
+		state_pruning: settings.state_pruning.clone(),
+		source: target,
+		keep_blocks: settings.keep_blocks,
+		transaction_storage: settings.transaction_storage,
+	};
+	let target_db = utils::open_database::<Block>(&target_settings, DatabaseType::Full)?;
+	if target_db.contains(columns::META, meta_keys::GENESIS_HASH) {
+		return Err(ClientError::Backend(
+			format!("The target {} database is not empty", target_settings.source)
+		));
+	}
+
+	copy::<Block>(
+		&*db,
+		settings.source.supports_ref_counting(),
+		&*target_db,
+		target_settings.source.supports_ref_counting(),
+		settings,
+		aux_keys,
+	)
+}
+
+/// Copy `db` to the empty `target_db`, converting the state if only one of them counts the
+/// references of the state nodes.
+fn copy<Block: BlockT>(
+	db: &dyn Database<DbHash>,
+	ref_counted: bool,
+	target_db: &dyn Database<DbHash>,
+	target_ref_counted: bool,
+	settings: &DatabaseSettings,
+	aux_keys: &AuxKeys<Block::Hash>,
+) -> ClientResult<()> {
+	if ref_counted {
+		// Only ParityDb counts references, and it can not enumerate its keys.
+		return copy_finalized_chain::<Block>(db, target_db, target_ref_counted, settings, aux_keys);
+	}
+
+	let convert_state = target_ref_counted;
+	for &(column, name) in COLUMNS {
+		let mut transaction = Transaction::new();
+		let mut values = RefCountedValues::default();
+		let mut pending = 0;
+		let mut keys = 0u64;
+		let mut result = Ok(());
+		db.iter(column, &mut |key, value| {
+			if result.is_err() {
+				return;
+			}
+			keys += 1;
+			pending += key.len() + value.len();
+			match column {
+				columns::STATE if convert_state => {
+					// Identical nodes stored under different prefixes are referenced once each.
+					if let Some(hash) = node_hash(key) {
+						values.add(hash, value, 1);
+					}
+				},
+				columns::TRANSACTION if convert_state => {
+					// Counters are stored next to the values, with a `0` suffix.
+					let hash: Option<DbHash> = key.try_into().ok();
+					if let Some(hash) = hash {
+						let mut counter_key = key.to_vec();
+						counter_key.push(0);
+						let counter = db.get(columns::TRANSACTION, &counter_key)
+							.and_then(|counter| counter.as_slice().try_into().ok())
+							.map_or(1, u32::from_le_bytes);
+						values.add(hash, value, counter);
+					}
+				},
+				_ => transaction.set(column, key, value),
+			}
+			if pending >= MIGRATE_BATCH_BYTES {
+				result = values.commit(target_db, column, std::mem::take(&mut transaction));
+				pending = 0;
+			}
+		})?;
+		result?;
+		values.commit(target_db, column, transaction)?;
+		info!("Copied {} keys of the {} column", keys, name);
+	}
+
+	if convert_state {
+		let journals = sc_state_db::rewrite_journal_keys::<Block::Hash, Vec<u8>, _>(
+			&StateMetaDb(db),
+			|key| key[key.len().saturating_sub(DB_HASH_LEN)..].to_vec(),
+		).map_err(ClientError::from_state_db)?;
+		let mut transaction = Transaction::new();
+		for (key, value) in journals.inserted {
+			transaction.set_from_vec(columns::STATE_META, &key, value);
+		}
+		target_db.commit(transaction)?;
+	}
+
+	Ok(())
+}
+
+/// Copy the canonical chain up to the last finalized block and the state of that block.
+///
+/// This is all that can be found in a database that can not enumerate its keys. The auxiliary
+/// data is copied for the keys in `aux_keys` only.
+fn copy_finalized_chain<Block: BlockT>(
+	db: &dyn Database<DbHash>,
+	target_db: &dyn Database<DbHash>,
+	target_ref_counted: bool,
+	settings: &DatabaseSettings,
+	aux_keys: &AuxKeys<Block::Hash>,
+) -> ClientResult<()> {
+	let meta = utils::read_meta::<Block>(db, columns::HEADER)?;
+	let finalized_number: u64 = meta.finalized_number.saturated_into();
+
+	let mut transaction = Transaction::new();
+	let mut transactions = RefCountedValues::default();
+	let mut pending = 0;
+	for key in &[&meta_keys::TYPE[..], &meta_keys::GENESIS_HASH[..]] {
+		if let Some(value) = db.get(columns::META, key) {
+			transaction.set_from_vec(columns::META, key, value);
+		}
+	}
+	for key in &aux_keys.keys {
+		if let Some(value) = db.get(columns::AUX, key) {
+			transaction.set_from_vec(columns::AUX, key, value);
+		}
+	}
+
+	let mut parent_hash = None;
+	for number in 0..=finalized_number {
+		let header = utils::require_header::<Block>(
+			db,
+			columns::KEY_LOOKUP,
+			columns::HEADER,
+			BlockId::number(number.saturated_into()),
+		)?;
+		if header.digest().log(DigestItem::as_changes_trie_root).is_some() {
+			return Err(ClientError::Backend(format!(
+				"Changes tries can not be copied out of a {} database",
+				settings.source,
+			)));
+		}
+		let hash = header.hash();
+		let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
+		utils::insert_number_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+		utils::insert_hash_to_key_mapping(&mut transaction, columns::KEY_LOOKUP, number, hash)?;
+		for &column in &[columns::HEADER, columns::BODY, columns::JUSTIFICATION] {
+			let value = match db.get(column, &lookup_key) {
+				Some(value) => value,
+				None => continue,
+			};
+			if let (columns::BODY, TransactionStorageMode::StorageChain) =
+				(column, settings.transaction_storage)
+			{
+				for hash in indexed_transactions::<Block>(&value)? {
+					let indexed = db.get(columns::TRANSACTION, &hash).ok_or_else(||
+						ClientError::Backend(format!("Missing indexed transaction {:?}", hash))
+					)?;
+					pending += indexed.len();
+					transactions.add(hash, &indexed, 1);
+				}
+			}
+			pending += value.len();
+			transaction.set_from_vec(column, &lookup_key, value);
+		}
+		for block_key in &aux_keys.block_keys {
+			let key = block_key(&hash);
+			if let Some(value) = db.get(columns::AUX, &key) {
+				transaction.set_from_vec(columns::AUX, &key, value);
+			}
+		}
+		if let Some(parent_hash) = parent_hash {
+			children::write_children(
+				&mut transaction,
+				columns::META,
+				meta_keys::CHILDREN_PREFIX,
+				parent_hash,
+				vec![hash],
+			);
+		}
+		parent_hash = Some(hash);
+		if pending >= MIGRATE_BATCH_BYTES {
+			transactions.commit(target_db, columns::TRANSACTION, std::mem::take(&mut transaction))?;
+			pending = 0;
+		}
+	}
+
+	// Blocks after the last finalized one are not copied, it becomes the only leaf.
+	let header = utils::require_header::<Block>(
+		db,
+		columns::KEY_LOOKUP,
+		columns::HEADER,
+		BlockId::Hash(meta.finalized_hash),
+	)?;
+	let finalized_key =
+		utils::number_and_hash_to_lookup_key(finalized_number, meta.finalized_hash)?;
+	transaction.set_from_vec(columns::META, meta_keys::BEST_BLOCK, finalized_key.clone());
+	transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, finalized_key);
+	let mut leaves = LeafSet::new();
+	leaves.import(meta.finalized_hash, meta.finalized_number, *header.parent_hash());
+	leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
+	transactions.commit(target_db, columns::TRANSACTION, transaction)?;
+	info!("Copied {} blocks", finalized_number + 1);
+
+	let mut transaction = Transaction::new();
+	let mut nodes = RefCountedValues::default();
+	let mut pending = 0;
+	let mut keys = 0u64;
+	let mut result = Ok(());
+	walk_state::<Block>(db, false, *header.state_root(), &mut |key, value| {
+		if result.is_err() {
+			return;
+		}
+		keys += 1;
+		pending += key.len() + value.len();
+		if !target_ref_counted {
+			transaction.set(columns::STATE, key, value);
+		} else if let Some(hash) = node_hash(key) {
+			nodes.add(hash, value, 1);
+		}
+		if pending >= MIGRATE_BATCH_BYTES {
+			result = nodes.commit(target_db, columns::STATE, std::mem::take(&mut transaction));
+			pending = 0;
+		}
+	})?;
+	result?;
+	nodes.commit(target_db, columns::STATE, transaction)?;
+	info!("Copied {} state nodes of block #{}", keys, finalized_number);
+
+	let state_db = StateDb::<Block::Hash, Vec<u8>>::new(
+		settings.state_pruning.clone(),
+		!target_ref_counted,
+		&StateMetaDb(target_db),
+	).map_err(ClientError::from_state_db)?;
+	let commit = state_db.import_state(&meta.finalized_hash, finalized_number, Default::default())
+		.map_err(|e: sc_state_db::Error<io::Error>| ClientError::from_state_db(e))?;
+	let mut transaction = Transaction::new();
+	apply_state_commit(&mut transaction, commit);
+	target_db.commit(transaction)?;
+
+	Ok(())
+}
+
+/// Hashes of the indexed transactions referenced by an encoded block body.
+fn indexed_transactions<Block: BlockT>(body: &[u8]) -> ClientResult<Vec<DbHash>> {
+	let body = Vec::<DbExtrinsic<Block>>::decode(&mut &body[..]).map_err(|err|
+		ClientError::Backend(format!("Error decoding body list: {}", err))
+	)?;
+	Ok(body.into_iter().filter_map(|ex| match ex {
+		DbExtrinsic::Indexed { hash, .. } | DbExtrinsic::Renewed { hash, .. } => Some(hash),
+		DbExtrinsic::Full(_) => None,
+	}).collect())
+}
+
+/// The hash a state node stored under `key` is reference counted by.
+fn node_hash(key: &[u8]) -> Option<DbHash> {
+	key[key.len().saturating_sub(DB_HASH_LEN)..].try_into().ok()
+}
+
+/// Reference counted values to store in a database.
+///
+/// A database doesn't see the changes of the transaction it commits, so storing a value twice in
+/// one transaction would count a single reference. Each value is stored once and its other
+/// references are added by the following transactions.
+#[derive(Default)]
+struct RefCountedValues {
+	values: HashMap<DbHash, (u32, Vec<u8>)>,
+}
+
+impl RefCountedValues {
+	/// Queue `references` references to `value`.
+	fn add(&mut self, hash: DbHash, value: &[u8], references: u32) {
+		self.values.entry(hash).or_insert_with(|| (0, value.to_vec())).0 += references;
+	}
+
+	/// Commit `transaction` with the queued values of `column`.
+	fn commit(
+		&mut self,
+		db: &dyn Database<DbHash>,
+		column: u32,
+		mut transaction: Transaction<DbHash>,
+	) -> ClientResult<()> {
+		let mut references = Vec::new();
+		for (hash, (count, value)) in self.values.drain() {
+			transaction.store(column, hash, value);
+			if count > 1 {
+				references.push((hash, count - 1));
+			}
+		}
+		db.commit(transaction)?;
+
+		while !references.is_empty() {
+			let mut transaction = Transaction::new();
+			for (hash, count) in references.iter_mut() {
+				transaction.reference(column, *hash);
+				*count -= 1;
+			}
+			references.retain(|(_, count)| *count > 0);
+			db.commit(transaction)?;
+		}
+		Ok(())
+	}
+}
+
+/// Walk all nodes of the state with the given root, including the nodes of its child tries.
+///
+/// `f` is called with the prefixed key and the value of each node.
+fn walk_state<Block: BlockT>(
+	db: &dyn Database<DbHash>,
+	prefix_keys: bool,
+	root: Block::Hash,
+	f: &mut dyn FnMut(&[u8], &[u8]),
+) -> ClientResult<()> {
+	let walker = StateWalker::<Block> {
+		db,
+		prefix_keys,
+		empty: HashFor::<Block>::hash(&[0u8]),
+		f: RefCell::new(f),
+	};
+	let trie_error = |e| ClientError::Backend(format!("Error walking the state: {}", e));
+
+	let mut child_tries = Vec::new();
+	{
+		let trie = TrieDB::<HashFor<Block>>::new(&walker, &root).map_err(trie_error)?;
+		for item in trie.iter().map_err(trie_error)? {
+			let (key, value) = item.map_err(trie_error)?;
+			let prefix = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+			if key.starts_with(prefix) {
+				let child_root = Block::Hash::decode(&mut &value[..]).map_err(|err|
+					ClientError::Backend(format!("Error decoding child trie root: {}", err))
+				)?;
+				child_tries.push((ChildInfo::new_default(&key[prefix.len()..]), child_root));
+			}
+		}
+	}
+
+	for (child_info, child_root) in child_tries {
+		let child_db = KeySpacedDB::<_, HashFor<Block>>::new(&walker, child_info.keyspace());
+		let trie = TrieDB::<HashFor<Block>>::new(&child_db, &child_root).map_err(trie_error)?;
+		for item in trie.iter().map_err(trie_error)? {
+			item.map_err(trie_error)?;
+		}
+	}
+	Ok(())
+}
+
+/// Reads the nodes of a state, passing each of them to a callback.
+struct StateWalker<'a, Block: BlockT> {
+	db: &'a dyn Database<DbHash>,
+	prefix_keys: bool,
+	empty: Block::Hash,
+	f: RefCell<&'a mut dyn FnMut(&[u8], &[u8])>,
+}
+
+impl<'a, Block: BlockT> StateWalker<'a, Block> {
+	fn read(&self, key: &Block::Hash, prefix: Prefix) -> Option<(Vec<u8>, DBValue)> {
+		let prefixed = prefixed_key::<HashFor<Block>>(key, prefix);
+		let value = if self.prefix_keys {
+			self.db.get(columns::STATE, &prefixed)
+		} else {
+			self.db.get(columns::STATE, key.as_ref())
+		}?;
+		Some((prefixed, value))
+	}
+}
+
+impl<'a, Block: BlockT> HashDBRef<HashFor<Block>, DBValue> for StateWalker<'a, Block> {
+	fn get(&self, key: &Block::Hash, prefix: Prefix) -> Option<DBValue> {
+		if *key == self.empty {
+			return Some([0u8].to_vec());
+		}
+		let (prefixed, value) = self.read(key, prefix)?;
+		let mut f = self.f.borrow_mut();
+		(*f)(&prefixed, &value);
+		Some(value)
+	}
+
+	fn contains(&self, key: &Block::Hash, prefix: Prefix) -> bool {
+		*key == self.empty || self.read(key, prefix).is_some()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use sc_client_api::backend::{AuxStore, Backend as _, BlockImportOperation, NewBlockState};
+	use sc_client_api::blockchain::Backend as _;
+	use sp_runtime::Storage;
+	use sp_runtime::testing::Header;
+	use sp_core::storage::StorageChild;
+	use sp_state_machine::Backend as _;
+	use sp_blockchain::HeaderBackend;
+	use crate::Backend;
+	use crate::tests::Block;
+
+	fn settings(db: &Arc<dyn Database<DbHash>>, state_pruning: PruningMode) -> DatabaseSettings {
+		DatabaseSettings {
+			state_cache_size: 16777216,
+			state_cache_child_ratio: Some((50, 100)),
+			state_pruning,
+			source: DatabaseSettingsSrc::Custom(db.clone()),
+			keep_blocks: KeepBlocks::All,
+			transaction_storage: TransactionStorageMode::BlockBody,
+		}
+	}
+
+	// Two identical child tries, so their nodes are stored under different prefixes with the
+	// same hash.
+	fn insert_genesis(db: &Arc<dyn Database<DbHash>>) -> <Block as BlockT>::Hash {
+		let backend = Backend::<Block>::new(settings(db, PruningMode::ArchiveAll), 0).unwrap();
+		let data: std::collections::BTreeMap<_, _> = vec![
+			(b"key".to_vec(), b"value".to_vec()),
+			(b"long".to_vec(), vec![42; 40]),
+		].into_iter().collect();
+		let storage = Storage {
+			top: vec![(b"top".to_vec(), b"value".to_vec())].into_iter().collect(),
+			children_default: vec![&b"first"[..], &b"second"[..]].into_iter()
+				.map(|name| (name.to_vec(), StorageChild {
+					data: data.clone(),
+					child_info: ChildInfo::new_default(name),
+				}))
+				.collect(),
+		};
+
+		let mut op = backend.begin_operation().unwrap();
+		backend.begin_state_operation(&mut op, BlockId::Hash(Default::default())).unwrap();
+		let state_root = op.reset_storage(storage).unwrap();
+		let header = Header {
+			number: 0,
+			parent_hash: Default::default(),
+			state_root,
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		let hash = header.hash();
+		op.set_block_data(header, Some(vec![42u64.into()]), None, NewBlockState::Final).unwrap();
+		backend.commit_operation(op).unwrap();
+		backend.insert_aux(&[(&b"consensus"[..], &b"data"[..])], &[]).unwrap();
+		hash
+	}
+
+	fn assert_state(backend: &Backend<Block>, hash: <Block as BlockT>::Hash) {
+		let state = backend.state_at(BlockId::Hash(hash)).unwrap();
+		assert_eq!(state.storage(b"top").unwrap(), Some(b"value".to_vec()));
+		for name in &[&b"first"[..], &b"second"[..]] {
+			let child_info = ChildInfo::new_default(name);
+			assert_eq!(state.child_storage(&child_info, b"long").unwrap(), Some(vec![42; 40]));
+		}
+	}
+
+	#[test]
+	fn migration_round_trip_keeps_all_references() {
+		let source = sp_database::as_database(kvdb_memorydb::create(utils::NUM_COLUMNS));
+		let genesis = insert_genesis(&source);
+		let child_root = {
+			let settings = settings(&source, PruningMode::ArchiveAll);
+			let backend = Backend::<Block>::new(settings, 0).unwrap();
+			let state = backend.state_at(BlockId::Hash(genesis)).unwrap();
+			let key = ChildInfo::new_default(b"first").prefixed_storage_key();
+			let root = state.storage(key.as_slice()).unwrap().unwrap();
+			let root: DbHash = root.as_slice().try_into().unwrap();
+			root
+		};
+
+		let ref_counted: Arc<dyn Database<DbHash>> = Arc::new(sp_database::MemDb::new());
+		copy::<Block>(
+			&*source,
+			false,
+			&*ref_counted,
+			true,
+			&settings(&source, PruningMode::ArchiveAll),
+			&Default::default(),
+		).unwrap();
+
+		// The root of the child tries is referenced once per child trie.
+		let mut transaction = Transaction::new();
+		transaction.release(columns::STATE, child_root);
+		ref_counted.commit(transaction).unwrap();
+		assert!(ref_counted.get(columns::STATE, &child_root).is_some());
+		let mut transaction = Transaction::new();
+		transaction.reference(columns::STATE, child_root);
+		ref_counted.commit(transaction).unwrap();
+
+		let target = sp_database::as_database(kvdb_memorydb::create(utils::NUM_COLUMNS));
+		let aux_keys = AuxKeys { keys: vec![b"consensus".to_vec()], block_keys: Vec::new() };
+		copy::<Block>(
+			&*ref_counted,
+			true,
+			&*target,
+			false,
+			&settings(&ref_counted, PruningMode::ArchiveAll),
+			&aux_keys,
+		).unwrap();
+
+		let backend = Backend::<Block>::new(settings(&target, PruningMode::ArchiveAll), 0).unwrap();
+		assert_eq!(backend.blockchain().info().finalized_hash, genesis);
+		assert_eq!(
+			backend.blockchain().body(BlockId::Hash(genesis)).unwrap(),
+			Some(vec![42u64.into()]),
+		);
+		assert_eq!(backend.get_aux(b"consensus").unwrap(), Some(b"data".to_vec()));
+		assert_state(&backend, genesis);
+	}
+
+	#[test]
+	fn pruning_mode_can_be_changed() {
+		let db = sp_database::as_database(kvdb_memorydb::create(utils::NUM_COLUMNS));
+		let genesis = insert_genesis(&db);
+
+		prune::<Block>(&settings(&db, PruningMode::keep_blocks(1))).unwrap();
+		assert!(Backend::<Block>::new(settings(&db, PruningMode::ArchiveAll), 0).is_err());
+		let backend = Backend::<Block>::new(settings(&db, PruningMode::keep_blocks(1)), 0).unwrap();
+		assert_state(&backend, genesis);
+	}
+}
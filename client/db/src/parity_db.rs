@@ -33,7 +33,7 @@ fn handle_err<T>(result: parity_db::Result<T>) -> T {
 }
 
 /// Wrap parity-db database into a trait object that implements `sp_database::Database`
-pub fn open<H: Clone>(path: &std::path::Path, db_type: DatabaseType)
+pub fn open<H: Clone + AsRef<[u8]>>(path: &std::path::Path, db_type: DatabaseType)
 	-> parity_db::Result<std::sync::Arc<dyn Database<H>>>
 {
 	let mut config = parity_db::Options::with_columns(path, NUM_COLUMNS as u8);
@@ -43,18 +43,29 @@ pub fn open<H: Clone>(path: &std::path::Path, db_type: DatabaseType)
 		state_col.ref_counted = true;
 		state_col.preimage = true;
 		state_col.uniform = true;
+
+		let mut tx_col = &mut config.columns[columns::TRANSACTION as usize];
+		tx_col.ref_counted = true;
+		tx_col.preimage = true;
+		tx_col.uniform = true;
 	}
 	let db = parity_db::Db::open(&config)?;
 	Ok(std::sync::Arc::new(DbAdapter(db)))
 }
 
-impl<H: Clone> Database<H> for DbAdapter {
+impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 	fn commit(&self, transaction: Transaction<H>) -> Result<(), DatabaseError> {
 		handle_err(self.0.commit(transaction.0.into_iter().map(|change|
 			match change {
 				Change::Set(col, key, value) => (col as u8, key, Some(value)),
 				Change::Remove(col, key) => (col as u8, key, None),
-				_ => unimplemented!(),
+				Change::Store(col, key, value) => (col as u8, key.as_ref().to_vec(), Some(value)),
+				// Re-inserting the existing value into a ref counted column bumps its counter.
+				Change::Reference(col, key) => {
+					let value = <Self as Database<H>>::get(self, col, key.as_ref());
+					(col as u8, key.as_ref().to_vec(), value)
+				},
+				Change::Release(col, key) => (col as u8, key.as_ref().to_vec(), None),
 			}))
 		);
 
@@ -64,8 +75,4 @@ impl<H: Clone> Database<H> for DbAdapter {
 	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
 		handle_err(self.0.get(col as u8, key))
 	}
-
-	fn lookup(&self, _hash: &H) -> Option<Vec<u8>> {
-		unimplemented!();
-	}
 }
@@ -64,6 +64,9 @@ pub enum Error {
 	#[error("Runtime panicked: {0}")]
 	RuntimePanicked(String),
 
+	#[error("Runtime exhausted the stack height limit")]
+	StackExhausted,
+
 	#[error("Invalid memory reference")]
 	InvalidMemoryReference,
 
@@ -23,5 +23,6 @@
 
 pub mod error;
 pub mod sandbox;
+pub mod stack_height;
 pub mod util;
 pub mod wasm_runtime;
@@ -0,0 +1,96 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deterministic stack height metering.
+//!
+//! How much native stack a runtime uses depends on the execution backend, the compiler and the
+//! machine, so a deeply recursing runtime may succeed on one node and fail on another. Metering
+//! makes the stack limit a property of the runtime code instead: every call accounts the logical
+//! stack height of the callee, as derived from its Wasm code, in a global and traps once the
+//! limit is exceeded.
+//!
+//! Such traps are indistinguishable from any other `unreachable`, so the instrumented code also
+//! exports a function named [`STACK_EXHAUSTED_EXPORT`] that returns `1` if the limit was exceeded.
+//! Backends call it after a trap to report [`Error::StackExhausted`](crate::error::Error).
+//!
+//! The limit must be low enough for the native stack to never overflow first, otherwise the
+//! execution is not deterministic anymore.
+
+use crate::error::WasmError;
+use parity_wasm::elements::{
+	self, ExportEntry, Func, FuncBody, FunctionType, ImportCountType, Instruction, Instructions,
+	Internal, Type, ValueType,
+};
+
+/// Name of the exported function that returns `1` if the stack height limit was exceeded and `0`
+/// otherwise.
+pub const STACK_EXHAUSTED_EXPORT: &str = "__stack_exhausted";
+
+/// Inject stack height metering with the given `limit` into `code`.
+///
+/// The limit is in units of logical stack height, i.e. the number of locals, arguments and operand
+/// stack values of all the functions on the call stack.
+///
+/// Fails if `code` already exports something named [`STACK_EXHAUSTED_EXPORT`].
+pub fn instrument(code: &[u8], limit: u32) -> Result<Vec<u8>, WasmError> {
+	let module: elements::Module = elements::deserialize_buffer(code)
+		.map_err(|_| WasmError::CantDeserializeWasm)?;
+	let exports = module.export_section().map_or(&[][..], |section| section.entries());
+	if exports.iter().any(|export| export.field() == STACK_EXHAUSTED_EXPORT) {
+		return Err(WasmError::Other(format!(
+			"cannot inject stack height metering: `{}` is already exported",
+			STACK_EXHAUSTED_EXPORT,
+		)));
+	}
+	let mut module = pwasm_utils::stack_height::inject_limiter(module, limit)
+		.map_err(|e| WasmError::Other(format!("cannot inject stack height metering: {:?}", e)))?;
+
+	// The limiter appends the global holding the stack height to the global section.
+	let stack_height_global = module.import_count(ImportCountType::Global) as u32 +
+		module.global_section().map_or(0, |section| section.entries().len() as u32) - 1;
+
+	let type_index = {
+		let types = module.type_section_mut()
+			.ok_or_else(|| WasmError::Other("module has no type section".into()))?
+			.types_mut();
+		types.push(Type::Function(FunctionType::new(Vec::new(), Some(ValueType::I32))));
+		types.len() as u32 - 1
+	};
+
+	let function_index = module.import_count(ImportCountType::Function) as u32 +
+		module.function_section().map_or(0, |section| section.entries().len() as u32);
+	module.function_section_mut()
+		.ok_or_else(|| WasmError::Other("module has no function section".into()))?
+		.entries_mut()
+		.push(Func::new(type_index));
+	module.code_section_mut()
+		.ok_or_else(|| WasmError::Other("module has no code section".into()))?
+		.bodies_mut()
+		.push(FuncBody::new(Vec::new(), Instructions::new(vec![
+			Instruction::GetGlobal(stack_height_global),
+			Instruction::I32Const(limit as i32),
+			Instruction::I32GtU,
+			Instruction::End,
+		])));
+	module.export_section_mut()
+		.ok_or_else(|| WasmError::Other("module has no export section".into()))?
+		.entries_mut()
+		.push(ExportEntry::new(STACK_EXHAUSTED_EXPORT.into(), Internal::Function(function_index)));
+
+	elements::serialize(module).map_err(|e| WasmError::Other(format!("cannot encode module: {}", e)))
+}
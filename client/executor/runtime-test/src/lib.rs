@@ -338,8 +338,28 @@ sp_core::wasm_export_functions! {
 	fn test_panic_in_spawned() {
 		sp_tasks::spawn(tasks::panicker, vec![]).join();
 	}
+
+	fn test_recursion(depth: u32) -> u32 {
+		recurse(depth)
+	}
  }
 
+/// Recurse `depth` times.
+///
+/// The recursive call goes through a function pointer the compiler cannot see through, so the
+/// recursion is not turned into a loop.
+#[cfg(not(feature = "std"))]
+#[inline(never)]
+fn recurse(depth: u32) -> u32 {
+	static mut RECURSE: fn(u32) -> u32 = recurse;
+
+	if depth == 0 {
+		return 0;
+	}
+	let recurse = unsafe { sp_std::ptr::read_volatile(&RECURSE) };
+	recurse(depth - 1) + 1
+}
+
  #[cfg(not(feature = "std"))]
  mod tasks {
 	use sp_std::prelude::*;
@@ -51,6 +51,27 @@ macro_rules! test_wasm_execution {
 			fn [<$method_name _compiled>]() {
 				$method_name(WasmExecutionMethod::Compiled);
 			}
+
+			#[test]
+			#[cfg(feature = "wasmtime")]
+			fn [<$method_name _compiled_pooled>]() {
+				$method_name(WasmExecutionMethod::CompiledPooled);
+			}
+		}
+	};
+
+	(resetting_memory $method_name:ident) => {
+		paste::item! {
+			#[test]
+			fn [<$method_name _interpreted>]() {
+				$method_name(WasmExecutionMethod::Interpreted);
+			}
+
+			#[test]
+			#[cfg(feature = "wasmtime")]
+			fn [<$method_name _compiled_pooled>]() {
+				$method_name(WasmExecutionMethod::CompiledPooled);
+			}
 		}
 	};
 
@@ -120,7 +141,7 @@ fn call_not_existing_function(wasm_method: WasmExecutionMethod) {
 					"\"Trap: Trap { kind: Host(Other(\\\"Function `missing_external` is only a stub. Calling a stub is not allowed.\\\")) }\""
 				),
 				#[cfg(feature = "wasmtime")]
-				WasmExecutionMethod::Compiled => assert!(
+				WasmExecutionMethod::Compiled | WasmExecutionMethod::CompiledPooled => assert!(
 					format!("{:?}", e).contains("Wasm execution trapped: call to a missing function env:missing_external")
 				),
 			}
@@ -147,7 +168,7 @@ fn call_yet_another_not_existing_function(wasm_method: WasmExecutionMethod) {
 					"\"Trap: Trap { kind: Host(Other(\\\"Function `yet_another_missing_external` is only a stub. Calling a stub is not allowed.\\\")) }\""
 				),
 				#[cfg(feature = "wasmtime")]
-				WasmExecutionMethod::Compiled => assert!(
+				WasmExecutionMethod::Compiled | WasmExecutionMethod::CompiledPooled => assert!(
 					format!("{:?}", e).contains("Wasm execution trapped: call to a missing function env:yet_another_missing_external")
 				),
 			}
@@ -561,6 +582,7 @@ fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 		HostFunctions::host_functions(),
 		true,
 		None,
+		None,
 	).expect("Creates runtime");
 
 	let instance = runtime.new_instance().unwrap();
@@ -595,6 +617,7 @@ fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 		HostFunctions::host_functions(),
 		true,
 		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -607,7 +630,7 @@ fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 	assert!(res.is_ok());
 }
 
-test_wasm_execution!(interpreted_only heap_is_reset_between_calls);
+test_wasm_execution!(resetting_memory heap_is_reset_between_calls);
 fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
 		wasm_method,
@@ -616,6 +639,7 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 		HostFunctions::host_functions(),
 		true,
 		None,
+		None,
 	).expect("Creates runtime");
 	let instance = runtime.new_instance().unwrap();
 
@@ -632,6 +656,68 @@ fn heap_is_reset_between_calls(wasm_method: WasmExecutionMethod) {
 	instance.call_export("check_and_set_in_heap", &params).unwrap();
 }
 
+#[test]
+#[cfg(feature = "wasmtime")]
+fn compiled_runtime_is_stored_as_artifact() {
+	let cache_dir = tempfile::tempdir().unwrap();
+	let create_runtime = || crate::wasm_runtime::create_wasm_runtime_with_code(
+		WasmExecutionMethod::CompiledPooled,
+		1024,
+		&wasm_binary_unwrap()[..],
+		HostFunctions::host_functions(),
+		true,
+		None,
+		Some(cache_dir.path()),
+	).expect("Creates runtime");
+
+	let call = |runtime: std::sync::Arc<dyn sc_executor_common::wasm_runtime::WasmModule>| {
+		let res = runtime.new_instance().unwrap().call_export("returns_mutable_static", &[0]).unwrap();
+		assert_eq!(33, u64::decode(&mut &res[..]).unwrap());
+	};
+
+	call(create_runtime());
+	let artifacts = std::fs::read_dir(cache_dir.path().join("wasmtime"))
+		.unwrap()
+		.map(|entry| entry.unwrap().path())
+		.collect::<Vec<_>>();
+	assert_eq!(artifacts.len(), 1);
+	assert_eq!(artifacts[0].extension().unwrap(), "cwasm");
+
+	// The second runtime is loaded from the artifact.
+	call(create_runtime());
+
+	// A corrupted artifact is replaced.
+	std::fs::write(&artifacts[0], b"garbage").unwrap();
+	call(create_runtime());
+	assert_ne!(std::fs::read(&artifacts[0]).unwrap(), b"garbage");
+}
+
+test_wasm_execution!(stack_height_limit_is_enforced);
+fn stack_height_limit_is_enforced(wasm_method: WasmExecutionMethod) {
+	let runtime = crate::wasm_runtime::create_wasm_runtime_with_code(
+		wasm_method,
+		1024,
+		&wasm_binary_unwrap()[..],
+		HostFunctions::host_functions(),
+		true,
+		Some(4096),
+		None,
+	).expect("Creates runtime");
+	let instance = runtime.new_instance().unwrap();
+
+	let res = instance.call_export("test_recursion", &10u32.encode()).unwrap();
+	assert_eq!(10, u32::decode(&mut &res[..]).unwrap());
+
+	assert!(matches!(
+		instance.call_export("test_recursion", &100_000u32.encode()),
+		Err(crate::error::Error::StackExhausted)
+	));
+
+	// The stack height is reset for the next call.
+	let res = instance.call_export("test_recursion", &10u32.encode()).unwrap();
+	assert_eq!(10, u32::decode(&mut &res[..]).unwrap());
+}
+
 test_wasm_execution!(parallel_execution);
 fn parallel_execution(wasm_method: WasmExecutionMethod) {
 	let executor = std::sync::Arc::new(crate::WasmExecutor::new(
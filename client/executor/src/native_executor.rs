@@ -106,6 +106,8 @@ pub struct WasmExecutor {
 	/// The path to a directory which the executor can leverage for a file cache, e.g. put there
 	/// compiled artifacts.
 	cache_path: Option<PathBuf>,
+	/// The deterministic stack height limit enforced on the Wasm code, if any.
+	stack_height_limit: Option<u32>,
 }
 
 impl WasmExecutor {
@@ -140,9 +142,21 @@ impl WasmExecutor {
 			cache: Arc::new(RuntimeCache::new(max_runtime_instances, cache_path.clone())),
 			max_runtime_instances,
 			cache_path,
+			stack_height_limit: None,
 		}
 	}
 
+	/// Enforce a deterministic stack height limit on the executed Wasm code.
+	///
+	/// The code is instrumented to account the logical stack height of every call, and calls that
+	/// exceed `stack_height_limit` fail with [`Error::StackExhausted`] with every execution method.
+	/// The limit must be low enough for the native stack to never overflow first, and the same on
+	/// every node of a chain, or they may disagree on which blocks are valid.
+	pub fn with_stack_height_limit(mut self, stack_height_limit: Option<u32>) -> Self {
+		self.stack_height_limit = stack_height_limit;
+		self
+	}
+
 	/// Execute the given closure `f` with the latest runtime (based on `runtime_code`).
 	///
 	/// The closure `f` is expected to return `Err(_)` when there happened a `panic!` in native code
@@ -177,6 +191,7 @@ impl WasmExecutor {
 			self.default_heap_pages,
 			&*self.host_functions,
 			allow_missing_host_functions,
+			self.stack_height_limit,
 			|module, instance, version, ext| {
 				let module = AssertUnwindSafe(module);
 				let instance = AssertUnwindSafe(instance);
@@ -225,6 +240,7 @@ impl sp_core::traits::CallInWasm for WasmExecutor {
 				&wasm_code,
 				self.host_functions.to_vec(),
 				allow_missing_host_functions,
+				self.stack_height_limit,
 				self.cache_path.as_deref(),
 			)
 				.map_err(|e| format!("Failed to create module: {:?}", e))?;
@@ -273,6 +289,19 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 		fallback_method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		max_runtime_instances: usize,
+	) -> Self {
+		Self::new_with_cache_path(fallback_method, default_heap_pages, max_runtime_instances, None)
+	}
+
+	/// Create new instance that stores files for caching, e.g. precompiled runtimes, in
+	/// `cache_path`.
+	///
+	/// See [`NativeExecutor::new`] for the other parameters.
+	pub fn new_with_cache_path(
+		fallback_method: WasmExecutionMethod,
+		default_heap_pages: Option<u64>,
+		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
 	) -> Self {
 		let mut host_functions = D::ExtendHostFunctions::host_functions();
 
@@ -283,7 +312,7 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			default_heap_pages,
 			host_functions,
 			max_runtime_instances,
-			None,
+			cache_path,
 		);
 
 		NativeExecutor {
@@ -292,6 +321,15 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			wasm: wasm_executor,
 		}
 	}
+
+	/// Enforce a deterministic stack height limit on the executed Wasm code.
+	///
+	/// See [`WasmExecutor::with_stack_height_limit`]. Native execution doesn't enforce the limit,
+	/// so nodes executing natively may accept calls which exceed it.
+	pub fn with_stack_height_limit(mut self, stack_height_limit: Option<u32>) -> Self {
+		self.wasm = self.wasm.with_stack_height_limit(stack_height_limit);
+		self
+	}
 }
 
 impl<D: NativeExecutionDispatch> RuntimeInfo for NativeExecutor<D> {
@@ -41,6 +41,10 @@ pub enum WasmExecutionMethod {
 	/// Uses the Wasmtime compiled runtime.
 	#[cfg(feature = "wasmtime")]
 	Compiled,
+	/// Uses the Wasmtime compiled runtime with a pool of pre-instantiated instances, which are
+	/// reset to their initial state before every call.
+	#[cfg(feature = "wasmtime")]
+	CompiledPooled,
 }
 
 impl Default for WasmExecutionMethod {
@@ -59,6 +63,8 @@ struct VersionedRuntime {
 	module: Arc<dyn WasmModule>,
 	/// The number of WebAssembly heap pages this instance was created with.
 	heap_pages: u64,
+	/// The stack height limit the runtime code was instrumented with, if any.
+	stack_height_limit: Option<u32>,
 	/// Runtime version according to `Core_version` if any.
 	version: Option<RuntimeVersion>,
 	/// Cached instance pool.
@@ -189,6 +195,8 @@ impl RuntimeCache {
 	///
 	/// `allow_missing_func_imports` - Ignore missing function imports.
 	///
+	/// `stack_height_limit` - The deterministic stack height limit to enforce, if any.
+	///
 	/// `max_runtime_instances` - The size of the instances cache.
 	///
 	/// `f` - Function to execute.
@@ -208,6 +216,7 @@ impl RuntimeCache {
 		default_heap_pages: u64,
 		host_functions: &[&'static dyn Function],
 		allow_missing_func_imports: bool,
+		stack_height_limit: Option<u32>,
 		f: F,
 	) -> Result<Result<R, Error>, Error>
 		where F: FnOnce(
@@ -225,7 +234,8 @@ impl RuntimeCache {
 			false,
 			|r| r.wasm_method == wasm_method &&
 				r.code_hash == *code_hash &&
-				r.heap_pages == heap_pages
+				r.heap_pages == heap_pages &&
+				r.stack_height_limit == stack_height_limit
 		));
 
 		let runtime = match pos {
@@ -243,6 +253,7 @@ impl RuntimeCache {
 					heap_pages,
 					host_functions.into(),
 					allow_missing_func_imports,
+					stack_height_limit,
 					self.max_runtime_instances,
 					self.cache_path.as_deref(),
 				);
@@ -275,14 +286,26 @@ impl RuntimeCache {
 }
 
 /// Create a wasm runtime with the given `code`.
+///
+/// If `stack_height_limit` is given, the code is instrumented to enforce it deterministically.
 pub fn create_wasm_runtime_with_code(
 	wasm_method: WasmExecutionMethod,
 	heap_pages: u64,
 	code: &[u8],
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	stack_height_limit: Option<u32>,
 	cache_path: Option<&Path>,
 ) -> Result<Arc<dyn WasmModule>, WasmError> {
+	let instrumented_code;
+	let code = match stack_height_limit {
+		Some(limit) => {
+			instrumented_code = sc_executor_common::stack_height::instrument(code, limit)?;
+			&instrumented_code[..]
+		},
+		None => code,
+	};
+
 	match wasm_method {
 		WasmExecutionMethod::Interpreted => {
 			// Wasmi doesn't have any need in a cache directory.
@@ -306,6 +329,17 @@ pub fn create_wasm_runtime_with_code(
 				heap_pages,
 				host_functions,
 				allow_missing_func_imports,
+				sc_executor_wasmtime::InstantiationStrategy::LegacyInstanceReuse,
+				cache_path,
+			).map(|runtime| -> Arc<dyn WasmModule> { Arc::new(runtime) }),
+		#[cfg(feature = "wasmtime")]
+		WasmExecutionMethod::CompiledPooled =>
+			sc_executor_wasmtime::create_runtime(
+				code,
+				heap_pages,
+				host_functions,
+				allow_missing_func_imports,
+				sc_executor_wasmtime::InstantiationStrategy::Pooling,
 				cache_path,
 			).map(|runtime| -> Arc<dyn WasmModule> { Arc::new(runtime) }),
 	}
@@ -338,6 +372,7 @@ fn create_versioned_wasm_runtime(
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	stack_height_limit: Option<u32>,
 	max_instances: usize,
 	cache_path: Option<&Path>,
 ) -> Result<VersionedRuntime, WasmError> {
@@ -349,6 +384,7 @@ fn create_versioned_wasm_runtime(
 		&code,
 		host_functions,
 		allow_missing_func_imports,
+		stack_height_limit,
 		cache_path,
 	)?;
 
@@ -378,6 +414,17 @@ fn create_versioned_wasm_runtime(
 	);
 
 	let mut instances = Vec::with_capacity(max_instances);
+	#[cfg(feature = "wasmtime")]
+	{
+		if wasm_method == WasmExecutionMethod::CompiledPooled {
+			// Instantiate the whole pool upfront, so that no call pays for instantiation.
+			for _ in 0..max_instances {
+				let instance = runtime.new_instance()
+					.map_err(|e| WasmError::Instantiation(e.to_string()))?;
+				instances.push(Mutex::new(Some(instance)));
+			}
+		}
+	}
 	instances.resize_with(max_instances, || Mutex::new(None));
 
 	Ok(VersionedRuntime {
@@ -385,6 +432,7 @@ fn create_versioned_wasm_runtime(
 		module: runtime,
 		version,
 		heap_pages,
+		stack_height_limit,
 		wasm_method,
 		instances,
 	})
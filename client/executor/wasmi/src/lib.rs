@@ -35,6 +35,7 @@ use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance, InvokeMethod};
 use sc_executor_common::{
 	error::{Error, WasmError},
 	sandbox,
+	stack_height::STACK_EXHAUSTED_EXPORT,
 };
 use sc_executor_common::util::{DataSegmentsSnapshot, WasmModuleInfo};
 
@@ -497,6 +498,7 @@ fn call_in_wasm_module(
 			let (ptr, length) = unpack_ptr_and_len(r as u64);
 			memory.get(ptr.into(), length as usize).map_err(|_| Error::Runtime)
 		},
+		Err(_) if is_stack_exhausted(module_instance) => Err(Error::StackExhausted),
 		Err(e) => {
 			trace!(
 				target: "wasm-executor",
@@ -509,6 +511,15 @@ fn call_in_wasm_module(
 	}
 }
 
+/// Returns whether a call trapped because it exceeded the stack height limit the runtime was
+/// instrumented with.
+fn is_stack_exhausted(module_instance: &ModuleRef) -> bool {
+	matches!(
+		module_instance.invoke_export(STACK_EXHAUSTED_EXPORT, &[], &mut wasmi::NopExternals),
+		Ok(Some(I32(1)))
+	)
+}
+
 /// Prepare module instance
 fn instantiate_module(
 	heap_pages: usize,
@@ -0,0 +1,47 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{env, fs, path::PathBuf};
+
+/// Exposes the exact version of the `wasmtime` dependency as `SUBSTRATE_WASMTIME_VERSION`.
+///
+/// The version is taken from the `Cargo.lock` of the workspace being built. It is not set if
+/// there is no lock file, in which case runtime artifacts are not cached.
+fn main() {
+	if let Some(version) = wasmtime_version() {
+		println!("cargo:rustc-env=SUBSTRATE_WASMTIME_VERSION={}", version);
+	}
+}
+
+fn wasmtime_version() -> Option<String> {
+	let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").ok()?);
+	let lock_file = manifest_dir.ancestors()
+		.map(|dir| dir.join("Cargo.lock"))
+		.find(|path| path.exists())?;
+	println!("cargo:rerun-if-changed={}", lock_file.display());
+
+	let lock = fs::read_to_string(lock_file).ok()?;
+	let mut lines = lock.lines();
+	while let Some(line) = lines.next() {
+		if line == "name = \"wasmtime\"" {
+			let version = lines.next()?.strip_prefix("version = \"")?.strip_suffix('"')?;
+			return Some(version.into());
+		}
+	}
+	None
+}
@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Ahead-of-time compiled runtime artifacts.
+//!
+//! The machine code produced for a runtime is stored in the executor cache directory, so that
+//! later runs of the node load it instead of compiling the runtime again. An artifact is keyed by
+//! the hash of the runtime code and by a fingerprint of everything that influences the generated
+//! code, hence an upgrade of the compiler or a change of its settings never picks up a stale
+//! artifact.
+
+use std::{
+	fs::{self, File},
+	io::Write,
+	path::{Path, PathBuf},
+};
+use sp_core::{hashing::blake2_256, hexdisplay::HexDisplay};
+use wasmtime::{Config, Engine, Module, OptLevel};
+
+/// Version of the artifact format.
+///
+/// Must be bumped whenever the runtime code is prepared differently before compilation, e.g.
+/// when the instrumentation applied to it changes.
+const ARTIFACT_VERSION: u32 = 1;
+
+/// Exact version of wasmtime producing the artifacts, found by the build script.
+const WASMTIME_VERSION: Option<&str> = option_env!("SUBSTRATE_WASMTIME_VERSION");
+
+/// File extension of the artifacts.
+const ARTIFACT_EXTENSION: &str = "cwasm";
+
+/// Returns the configuration of the engine compiling runtimes.
+///
+/// Any change to it must be reflected in [`fingerprint`].
+pub fn engine_config() -> Config {
+	let mut config = Config::new();
+	config.cranelift_opt_level(OptLevel::SpeedAndSize);
+	config
+}
+
+/// A fingerprint of the compiler, its configuration and the target.
+///
+/// Returns `None` if the version of wasmtime is not known.
+fn fingerprint() -> Option<[u8; 32]> {
+	let fingerprint = format!(
+		"{}:wasmtime-{}:speed_and_size:{}-{}:{}",
+		ARTIFACT_VERSION,
+		WASMTIME_VERSION?,
+		std::env::consts::ARCH,
+		std::env::consts::OS,
+		cpu_features().join(","),
+	);
+	Some(blake2_256(fingerprint.as_bytes()))
+}
+
+/// The features of the host CPU the compiler generates code for.
+///
+/// Cranelift targets the native CPU, so code compiled on one machine may use instructions that
+/// another machine sharing the cache directory doesn't support.
+fn cpu_features() -> Vec<&'static str> {
+	#[allow(unused_mut)]
+	let mut features = Vec::new();
+	#[cfg(target_arch = "x86_64")]
+	{
+		macro_rules! detect {
+			($($feature:tt),*) => {
+				$(if std::is_x86_feature_detected!($feature) {
+					features.push($feature);
+				})*
+			};
+		}
+		detect!(
+			"sse3", "ssse3", "sse4.1", "sse4.2", "popcnt", "avx", "avx2", "bmi1", "bmi2", "lzcnt",
+			"avx512f", "avx512dq", "avx512vl"
+		);
+	}
+	features
+}
+
+/// Returns the path of the artifact of `code` in `dir`.
+///
+/// Returns `None` if artifacts can't be cached, because the version of the compiler is unknown.
+pub fn path(dir: &Path, code: &[u8]) -> Option<PathBuf> {
+	let fingerprint = fingerprint()?;
+	Some(dir.join(format!(
+		"{}-{}.{}",
+		HexDisplay::from(&blake2_256(code)),
+		HexDisplay::from(&&fingerprint[..8]),
+		ARTIFACT_EXTENSION,
+	)))
+}
+
+/// Load the artifact at `path`.
+///
+/// Returns `None` if there is no artifact or it cannot be used with `engine`.
+///
+/// The artifact is executable code that is not verified in any way, so the cache directory must
+/// only be writable by the node.
+pub fn load(engine: &Engine, path: &Path) -> Option<Module> {
+	let serialized = match fs::read(path) {
+		Ok(serialized) => serialized,
+		Err(_) => return None,
+	};
+
+	match Module::deserialize(engine, &serialized) {
+		Ok(module) => {
+			log::debug!(
+				target: "wasm-runtime",
+				"Loaded precompiled runtime from {}",
+				path.display(),
+			);
+			Some(module)
+		},
+		Err(e) => {
+			log::warn!(
+				target: "wasm-runtime",
+				"Ignoring unusable precompiled runtime {}: {}",
+				path.display(),
+				e,
+			);
+			None
+		},
+	}
+}
+
+/// Store the compiled `module` at `path`.
+///
+/// The artifact is written next to its destination first, so a concurrently starting node never
+/// loads a partially written artifact.
+pub fn store(module: &Module, path: &Path) -> Result<(), String> {
+	let serialized = module.serialize()
+		.map_err(|e| format!("cannot serialize the module: {}", e))?;
+
+	let mut tmp_path = PathBuf::from(path);
+	tmp_path.set_extension("tmp");
+	let mut file = File::create(&tmp_path)
+		.map_err(|e| format!("cannot create {}: {}", tmp_path.display(), e))?;
+	file.write_all(&serialized)
+		.and_then(|_| file.sync_all())
+		.map_err(|e| format!("cannot write {}: {}", tmp_path.display(), e))?;
+	fs::rename(&tmp_path, path)
+		.map_err(|e| format!("cannot move the artifact to {}: {}", path.display(), e))?;
+
+	Ok(())
+}
@@ -19,12 +19,14 @@
 //! Defines data and logic needed for interaction with an WebAssembly instance of a substrate
 //! runtime module.
 
+use crate::artifact;
 use crate::util;
 use crate::imports::Imports;
 
-use std::{slice, marker};
+use std::{slice, marker, path::Path};
 use sc_executor_common::{
 	error::{Error, Result},
+	stack_height::STACK_EXHAUSTED_EXPORT,
 	util::{WasmModuleInfo, DataSegmentsSnapshot},
 	wasm_runtime::InvokeMethod,
 };
@@ -42,15 +44,28 @@ pub struct ModuleWrapper {
 }
 
 impl ModuleWrapper {
-	pub fn new(engine: &Engine, code: &[u8]) -> Result<Self> {
-		let mut raw_module: elements::Module = elements::deserialize_buffer(code)
-			.map_err(|e| Error::from(format!("cannot decode module: {}", e)))?;
-		pwasm_utils::export_mutable_globals(&mut raw_module, "exported_internal_global");
-		let instrumented_code = elements::serialize(raw_module)
-			.map_err(|e| Error::from(format!("cannot encode module: {}", e)))?;
-
-		let module = Module::new(engine, &instrumented_code)
-			.map_err(|e| Error::from(format!("cannot create module: {}", e)))?;
+	/// Compile the given `code`.
+	///
+	/// If `artifacts_dir` is given, a precompiled artifact of `code` is loaded from there instead,
+	/// or stored there after compilation if there is none yet.
+	pub fn new(engine: &Engine, code: &[u8], artifacts_dir: Option<&Path>) -> Result<Self> {
+		let artifact_path = artifacts_dir.and_then(|dir| artifact::path(dir, code));
+		let module = match artifact_path.as_ref().and_then(|path| artifact::load(engine, path)) {
+			Some(module) => module,
+			None => {
+				let module = Self::compile(engine, code)?;
+				if let Some(path) = &artifact_path {
+					if let Err(e) = artifact::store(&module, path) {
+						log::warn!(
+							target: "wasm-runtime",
+							"Failed to store the precompiled runtime: {}",
+							e,
+						);
+					}
+				}
+				module
+			},
+		};
 
 		let module_info = WasmModuleInfo::new(code)
 			.ok_or_else(|| Error::from("cannot deserialize module".to_string()))?;
@@ -64,6 +79,17 @@ impl ModuleWrapper {
 		})
 	}
 
+	fn compile(engine: &Engine, code: &[u8]) -> Result<Module> {
+		let mut raw_module: elements::Module = elements::deserialize_buffer(code)
+			.map_err(|e| Error::from(format!("cannot decode module: {}", e)))?;
+		pwasm_utils::export_mutable_globals(&mut raw_module, "exported_internal_global");
+		let instrumented_code = elements::serialize(raw_module)
+			.map_err(|e| Error::from(format!("cannot encode module: {}", e)))?;
+
+		Module::new(engine, &instrumented_code)
+			.map_err(|e| Error::from(format!("cannot create module: {}", e)))
+	}
+
 	pub fn module(&self) -> &Module {
 		&self.module
 	}
@@ -286,6 +312,15 @@ impl InstanceWrapper {
 		})
 	}
 
+	/// Returns whether a call trapped because it exceeded the stack height limit the runtime was
+	/// instrumented with.
+	pub fn is_stack_exhausted(&self) -> bool {
+		self.instance
+			.get_func(STACK_EXHAUSTED_EXPORT)
+			.and_then(|func| func.call(&[]).ok())
+			.map_or(false, |results| results.first().and_then(Val::i32) == Some(1))
+	}
+
 	/// Returns an indirect function table of this instance.
 	pub fn table(&self) -> Option<&Table> {
 		self.table.as_ref()
@@ -296,6 +331,32 @@ impl InstanceWrapper {
 		self.memory.data_size() as u32
 	}
 
+	/// Reset the linear memory to zeroes.
+	///
+	/// On Linux the pages are handed back to the kernel, which maps them copy-on-write to the
+	/// shared zero page until they are written again. The cost of a reset is thus proportional to
+	/// the memory touched since the last reset rather than to the size of the memory.
+	pub fn decommit_memory(&self) {
+		let len = self.memory.data_size();
+		if len == 0 {
+			return;
+		}
+
+		#[cfg(target_os = "linux")]
+		unsafe {
+			// This should be safe since the memory is a page aligned anonymous mapping owned by
+			// wasmtime, and no references into it are held while resetting it.
+			if libc::madvise(self.memory.data_ptr() as _, len, libc::MADV_DONTNEED) == 0 {
+				return;
+			}
+		}
+
+		unsafe {
+			// This should be safe for the same reasons as `memory_as_slice_mut`.
+			std::ptr::write_bytes(self.memory.data_ptr(), 0, len);
+		}
+	}
+
 	/// Reads `__heap_base: i32` global variable and returns it.
 	///
 	/// If it doesn't exist, not a global or of not i32 type returns an error.
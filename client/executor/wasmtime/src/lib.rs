@@ -18,6 +18,7 @@
 
 ///! Defines a `WasmRuntime` that uses the Wasmtime JIT to execute.
 
+mod artifact;
 mod host;
 mod runtime;
 mod state_holder;
@@ -25,4 +26,4 @@ mod imports;
 mod instance_wrapper;
 mod util;
 
-pub use runtime::create_runtime;
+pub use runtime::{create_runtime, InstantiationStrategy};
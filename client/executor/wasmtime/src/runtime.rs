@@ -18,35 +18,53 @@
 
 //! Defines the compiled Wasm runtime that uses Wasmtime internally.
 
+use crate::artifact;
 use crate::host::HostState;
 use crate::imports::{Imports, resolve_imports};
 use crate::instance_wrapper::{ModuleWrapper, InstanceWrapper, GlobalsSnapshot, EntryPoint};
 use crate::state_holder;
 
+use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use sc_executor_common::{
-	error::{Result, WasmError},
+	error::{Error, Result, WasmError},
 	wasm_runtime::{WasmModule, WasmInstance, InvokeMethod},
 };
 use sp_allocator::FreeingBumpHeapAllocator;
 use sp_runtime_interface::unpack_ptr_and_len;
 use sp_wasm_interface::{Function, Pointer, WordSize, Value};
-use wasmtime::{Config, Engine, Store};
+use wasmtime::{Engine, Store};
+
+/// The way instances of a runtime are prepared for calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstantiationStrategy {
+	/// Reuse an instance, restoring its data segments and mutable globals before every call.
+	///
+	/// The remaining memory keeps whatever previous calls left there.
+	LegacyInstanceReuse,
+	/// Reset an instance to its freshly instantiated state before every call.
+	///
+	/// The linear memory is reset to zero pages copy-on-write before the data segments and the
+	/// mutable globals are restored, so the instance can be kept in a pool and reused by calls
+	/// without any of them observing the memory of another. An instance whose memory was grown
+	/// by a call is replaced by a new one.
+	Pooling,
+}
 
-/// A `WasmModule` implementation using wasmtime to compile the runtime module to machine code
-/// and execute the compiled code.
-pub struct WasmtimeRuntime {
-	module_wrapper: Arc<ModuleWrapper>,
+/// Everything needed to instantiate a compiled runtime.
+struct InstanceCreator {
+	module_wrapper: ModuleWrapper,
 	heap_pages: u32,
 	allow_missing_func_imports: bool,
 	host_functions: Vec<&'static dyn Function>,
+	instantiation_strategy: InstantiationStrategy,
 	engine: Engine,
 }
 
-impl WasmModule for WasmtimeRuntime {
-	fn new_instance(&self) -> Result<Box<dyn WasmInstance>> {
+impl InstanceCreator {
+	fn instantiate(&self) -> Result<InstanceState> {
 		let store = Store::new(&self.engine);
 
 		// Scan all imports, find the matching host functions, and create stubs that adapt arguments
@@ -63,134 +81,171 @@ impl WasmModule for WasmtimeRuntime {
 			InstanceWrapper::new(&store, &self.module_wrapper, &imports, self.heap_pages)?;
 		let heap_base = instance_wrapper.extract_heap_base()?;
 		let globals_snapshot = GlobalsSnapshot::take(&instance_wrapper)?;
+		let memory_size = instance_wrapper.memory_size();
 
-		Ok(Box::new(WasmtimeInstance {
+		Ok(InstanceState {
 			store,
 			instance_wrapper: Rc::new(instance_wrapper),
-			module_wrapper: Arc::clone(&self.module_wrapper),
 			imports,
 			globals_snapshot,
-			heap_pages: self.heap_pages,
 			heap_base,
+			memory_size,
+		})
+	}
+}
+
+/// A `WasmModule` implementation using wasmtime to compile the runtime module to machine code
+/// and execute the compiled code.
+pub struct WasmtimeRuntime {
+	instance_creator: Arc<InstanceCreator>,
+}
+
+impl WasmModule for WasmtimeRuntime {
+	fn new_instance(&self) -> Result<Box<dyn WasmInstance>> {
+		let state = self.instance_creator.instantiate()?;
+
+		Ok(Box::new(WasmtimeInstance {
+			instance_creator: Arc::clone(&self.instance_creator),
+			state: RefCell::new(state),
 		}))
 	}
 }
 
-/// A `WasmInstance` implementation that reuses compiled module and spawns instances
-/// to execute the compiled code.
-pub struct WasmtimeInstance {
+/// An instantiated runtime.
+struct InstanceState {
 	store: Store,
-	module_wrapper: Arc<ModuleWrapper>,
 	instance_wrapper: Rc<InstanceWrapper>,
 	globals_snapshot: GlobalsSnapshot,
 	imports: Imports,
-	heap_pages: u32,
 	heap_base: u32,
+	/// The size of the linear memory right after instantiation.
+	memory_size: u32,
+}
+
+/// A `WasmInstance` implementation that reuses compiled module and spawns instances
+/// to execute the compiled code.
+pub struct WasmtimeInstance {
+	instance_creator: Arc<InstanceCreator>,
+	state: RefCell<InstanceState>,
 }
 
 // This is safe because `WasmtimeInstance` does not leak reference to `self.imports`
 // and all imports don't reference any anything, other than host functions and memory
 unsafe impl Send for WasmtimeInstance {}
 
-impl WasmInstance for WasmtimeInstance {
-	fn call(&self, method: InvokeMethod, data: &[u8]) -> Result<Vec<u8>> {
-		let entrypoint = self.instance_wrapper.resolve_entrypoint(method)?;
-		let allocator = FreeingBumpHeapAllocator::new(self.heap_base);
-
-		self.module_wrapper
+impl WasmtimeInstance {
+	/// Restore the state of the instance according to the instantiation strategy.
+	fn prepare_for_call(&self) -> Result<()> {
+		let module_wrapper = &self.instance_creator.module_wrapper;
+		let state = match self.instance_creator.instantiation_strategy {
+			InstantiationStrategy::LegacyInstanceReuse => self.state.borrow(),
+			InstantiationStrategy::Pooling => {
+				let grown = {
+					let state = self.state.borrow();
+					state.instance_wrapper.memory_size() != state.memory_size
+				};
+				if grown {
+					// Linear memory cannot shrink, so a fresh instance is the only way back.
+					*self.state.borrow_mut() = self.instance_creator.instantiate()?;
+					return Ok(());
+				}
+
+				let state = self.state.borrow();
+				state.instance_wrapper.decommit_memory();
+				state
+			},
+		};
+
+		module_wrapper
 			.data_segments_snapshot()
 			.apply(|offset, contents| {
-				self.instance_wrapper
+				state.instance_wrapper
 					.write_memory_from(Pointer::new(offset), contents)
 			})?;
 
-		self.globals_snapshot.apply(&*self.instance_wrapper)?;
+		state.globals_snapshot.apply(&*state.instance_wrapper)
+	}
+}
+
+impl WasmInstance for WasmtimeInstance {
+	fn call(&self, method: InvokeMethod, data: &[u8]) -> Result<Vec<u8>> {
+		self.prepare_for_call()?;
+
+		let (instance_wrapper, heap_base) = {
+			let state = self.state.borrow();
+			(Rc::clone(&state.instance_wrapper), state.heap_base)
+		};
+		let entrypoint = instance_wrapper.resolve_entrypoint(method)?;
+		let allocator = FreeingBumpHeapAllocator::new(heap_base);
 
 		perform_call(
 			data,
-			Rc::clone(&self.instance_wrapper),
+			instance_wrapper,
 			entrypoint,
 			allocator,
 		)
 	}
 
 	fn get_global_const(&self, name: &str) -> Result<Option<Value>> {
-		let instance = InstanceWrapper::new(&self.store, &self.module_wrapper, &self.imports, self.heap_pages)?;
+		let state = self.state.borrow();
+		let instance = InstanceWrapper::new(
+			&state.store,
+			&self.instance_creator.module_wrapper,
+			&state.imports,
+			self.instance_creator.heap_pages,
+		)?;
 		instance.get_global_val(name)
 	}
 }
 
-/// Prepare a directory structure and a config file to enable wasmtime caching.
-///
-/// In case of an error the caching will not be enabled.
-fn setup_wasmtime_caching(
-	cache_path: &Path,
-	config: &mut Config,
-) -> std::result::Result<(), String> {
-	use std::fs;
-
-	let wasmtime_cache_root = cache_path.join("wasmtime");
-	fs::create_dir_all(&wasmtime_cache_root)
+/// Prepare the directory holding the precompiled runtimes.
+fn setup_artifacts_dir(cache_path: &Path) -> std::result::Result<PathBuf, String> {
+	let artifacts_dir = cache_path.join("wasmtime");
+	std::fs::create_dir_all(&artifacts_dir)
 		.map_err(|err| format!("cannot create the dirs to cache: {:?}", err))?;
 
-	// Canonicalize the path after creating the directories.
-	let wasmtime_cache_root = wasmtime_cache_root
-		.canonicalize()
-		.map_err(|err| format!("failed to canonicalize the path: {:?}", err))?;
-
-	// Write the cache config file
-	let cache_config_path = wasmtime_cache_root.join("cache-config.toml");
-	let config_content = format!(
-		"\
-[cache]
-enabled = true
-directory = \"{cache_dir}\"
-",
-		cache_dir = wasmtime_cache_root.display()
-	);
-	fs::write(&cache_config_path, config_content)
-		.map_err(|err| format!("cannot write the cache config: {:?}", err))?;
-
-	config
-		.cache_config_load(cache_config_path)
-		.map_err(|err| format!("failed to parse the config: {:?}", err))?;
-
-	Ok(())
+	Ok(artifacts_dir)
 }
 
 /// Create a new `WasmtimeRuntime` given the code. This function performs translation from Wasm to
 /// machine code, which can be computationally heavy.
 ///
 /// The `cache_path` designates where this executor implementation can put compiled artifacts.
+/// The runtime is compiled ahead of time once and the artifact is reused by every later call
+/// with the same code, until the compiler or its configuration changes.
+///
+/// The `instantiation_strategy` determines how instances of the runtime are reused between calls.
 pub fn create_runtime(
 	code: &[u8],
 	heap_pages: u64,
 	host_functions: Vec<&'static dyn Function>,
 	allow_missing_func_imports: bool,
+	instantiation_strategy: InstantiationStrategy,
 	cache_path: Option<&Path>,
 ) -> std::result::Result<WasmtimeRuntime, WasmError> {
-	// Create the engine, store and finally the module from the given code.
-	let mut config = Config::new();
-	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
-	if let Some(cache_path) = cache_path {
-		if let Err(reason) = setup_wasmtime_caching(cache_path, &mut config) {
-			log::warn!(
+	let artifacts_dir = cache_path.and_then(|cache_path| {
+		setup_artifacts_dir(cache_path)
+			.map_err(|reason| log::warn!(
 				"failed to setup wasmtime cache. Performance may degrade significantly: {}.",
 				reason,
-			);
-		}
-	}
+			))
+			.ok()
+	});
 
-	let engine = Engine::new(&config);
-	let module_wrapper = ModuleWrapper::new(&engine, code)
+	// Create the engine and finally the module from the given code.
+	let engine = Engine::new(&artifact::engine_config());
+	let module_wrapper = ModuleWrapper::new(&engine, code, artifacts_dir.as_deref())
 		.map_err(|e| WasmError::Other(format!("cannot create module: {}", e)))?;
 
 	Ok(WasmtimeRuntime {
-		module_wrapper: Arc::new(module_wrapper),
-		heap_pages: heap_pages as u32,
-		allow_missing_func_imports,
-		host_functions,
-		engine,
+		instance_creator: Arc::new(InstanceCreator {
+			module_wrapper,
+			heap_pages: heap_pages as u32,
+			allow_missing_func_imports,
+			host_functions,
+			instantiation_strategy,
+			engine,
+		}),
 	})
 }
 
@@ -206,7 +261,13 @@ fn perform_call(
 	let ret = state_holder::with_initialized_state(&host_state, || -> Result<_> {
 		Ok(unpack_ptr_and_len(entrypoint.call(data_ptr, data_len)?))
 	});
-	let (output_ptr, output_len) = ret?;
+	let (output_ptr, output_len) = ret.map_err(|e| {
+		if instance_wrapper.is_stack_exhausted() {
+			Error::StackExhausted
+		} else {
+			e
+		}
+	})?;
 	let output = extract_output_data(&instance_wrapper, output_ptr, output_len)?;
 
 	Ok(output)
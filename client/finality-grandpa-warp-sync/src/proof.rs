@@ -16,17 +16,18 @@
 
 use codec::{Decode, Encode};
 
+use sc_client_api::Backend as ClientBackend;
 use sc_finality_grandpa::{
 	find_scheduled_change, AuthoritySetChanges, BlockNumberOps, GrandpaJustification,
 };
-use sp_blockchain::Backend as BlockchainBackend;
+use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
 use sp_finality_grandpa::{AuthorityList, SetId};
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, NumberFor},
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
 };
 
-use crate::HandleRequestError;
+use crate::Error;
 
 /// The maximum number of authority set change proofs to include in a single warp sync proof.
 const MAX_CHANGES_PER_WARP_SYNC_PROOF: usize = 256;
@@ -45,42 +46,44 @@ pub struct AuthoritySetChangeProof<Block: BlockT> {
 /// An accumulated proof of multiple authority set changes.
 #[derive(Decode, Encode)]
 pub struct WarpSyncProof<Block: BlockT> {
-	proofs: Vec<AuthoritySetChangeProof<Block>>,
-	is_finished: bool,
+	pub(crate) proofs: Vec<AuthoritySetChangeProof<Block>>,
+	pub(crate) is_finished: bool,
 }
 
 impl<Block: BlockT> WarpSyncProof<Block> {
 	/// Generates a warp sync proof starting at the given block. It will generate authority set
 	/// change proofs for all changes that happened from `begin` until the current authority set
-	/// (capped by MAX_CHANGES_PER_WARP_SYNC_PROOF).
+	/// (capped by MAX_CHANGES_PER_WARP_SYNC_PROOF). A complete proof ends with the latest
+	/// justification of the current authority set, if it is more recent than the last change.
 	pub fn generate<Backend>(
 		backend: &Backend,
 		begin: Block::Hash,
 		set_changes: &AuthoritySetChanges<NumberFor<Block>>,
-	) -> Result<WarpSyncProof<Block>, HandleRequestError>
+	) -> Result<WarpSyncProof<Block>, Error>
 	where
-		Backend: BlockchainBackend<Block>,
+		Backend: ClientBackend<Block>,
 	{
 		// TODO: cache best response (i.e. the one with lowest begin_number)
+		let blockchain = backend.blockchain();
 
-		let begin_number = backend
+		let begin_number = blockchain
 			.block_number_from_id(&BlockId::Hash(begin))?
-			.ok_or_else(|| HandleRequestError::InvalidRequest("Missing start block".to_string()))?;
+			.ok_or_else(|| Error::InvalidRequest("Missing start block".to_string()))?;
 
-		if begin_number > backend.info().finalized_number {
-			return Err(HandleRequestError::InvalidRequest(
+		if begin_number > blockchain.info().finalized_number {
+			return Err(Error::InvalidRequest(
 				"Start block is not finalized".to_string(),
 			));
 		}
 
-		let canon_hash = backend.hash(begin_number)?.expect(
+		let canon_hash = blockchain.hash(begin_number)?.expect(
 			"begin number is lower than finalized number; \
 			 all blocks below finalized number must have been imported; \
 			 qed.",
 		);
 
 		if canon_hash != begin {
-			return Err(HandleRequestError::InvalidRequest(
+			return Err(Error::InvalidRequest(
 				"Start block is not in the finalized chain".to_string(),
 			));
 		}
@@ -95,7 +98,7 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 				break;
 			}
 
-			let header = backend.header(BlockId::Number(*last_block))?.expect(
+			let header = blockchain.header(BlockId::Number(*last_block))?.expect(
 				"header number comes from previously applied set changes; must exist in db; qed.",
 			);
 
@@ -108,7 +111,7 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 				break;
 			}
 
-			let justification = backend.justification(BlockId::Number(*last_block))?.expect(
+			let justification = blockchain.justification(BlockId::Number(*last_block))?.expect(
 				"header is last in set and contains standard change signal; \
 				 must have justification; \
 				 qed.",
@@ -122,9 +125,33 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 			});
 		}
 
+		let is_finished = if proof_limit_reached {
+			false
+		} else {
+			// the latest justification must be for a block above the last authority set change,
+			// or at least at the block the proof was requested from.
+			let limit = proofs
+				.last()
+				.map(|proof| *proof.header.number() + One::one())
+				.unwrap_or(begin_number);
+
+			let latest_justification = sc_finality_grandpa::best_justification::<_, Block>(backend)?
+				.filter(|justification| justification.target().0 >= limit);
+
+			if let Some(justification) = latest_justification {
+				let header = blockchain.header(BlockId::Hash(justification.target().1))?.expect(
+					"header hash corresponds to a justification stored in the db; \
+					 the header must exist in db as well; qed.",
+				);
+				proofs.push(AuthoritySetChangeProof { header, justification });
+			}
+
+			true
+		};
+
 		Ok(WarpSyncProof {
 			proofs,
-			is_finished: !proof_limit_reached,
+			is_finished,
 		})
 	}
 
@@ -134,27 +161,35 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 		&self,
 		set_id: SetId,
 		authorities: AuthorityList,
-	) -> Result<(SetId, AuthorityList), HandleRequestError>
+	) -> Result<(SetId, AuthorityList), Error>
 	where
 		NumberFor<Block>: BlockNumberOps,
 	{
 		let mut current_set_id = set_id;
 		let mut current_authorities = authorities;
 
-		for proof in &self.proofs {
+		for (fragment_num, proof) in self.proofs.iter().enumerate() {
 			proof
 				.justification
 				.verify(current_set_id, &current_authorities)
-				.map_err(|err| HandleRequestError::InvalidProof(err.to_string()))?;
+				.map_err(|err| Error::InvalidProof(err.to_string()))?;
 
-			let scheduled_change = find_scheduled_change::<Block>(&proof.header).ok_or(
-				HandleRequestError::InvalidProof(
-					"Header is missing authority set change digest".to_string(),
-				),
-			)?;
+			if proof.justification.target().1 != proof.header.hash() {
+				return Err(Error::InvalidProof(
+					"Mismatch between header and justification".to_string(),
+				));
+			}
 
-			current_authorities = scheduled_change.next_authorities;
-			current_set_id += 1;
+			if let Some(scheduled_change) = find_scheduled_change::<Block>(&proof.header) {
+				current_authorities = scheduled_change.next_authorities;
+				current_set_id += 1;
+			} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
+				// only the last fragment of a finished proof may be a justification of the
+				// current set, without an authority set change.
+				return Err(Error::InvalidProof(
+					"Header is missing authority set change digest".to_string(),
+				));
+			}
 		}
 
 		Ok((current_set_id, current_authorities))
@@ -163,31 +198,80 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 
 #[cfg(test)]
 mod tests {
-	use crate::WarpSyncProof;
+	use crate::{Error, WarpSyncProof};
 	use codec::Encode;
 	use rand::prelude::*;
 	use sc_block_builder::BlockBuilderProvider;
-	use sc_client_api::Backend;
+	use sc_client_api::AuxStore;
 	use sc_finality_grandpa::{AuthoritySetChanges, GrandpaJustification};
 	use sp_blockchain::HeaderBackend;
 	use sp_consensus::BlockOrigin;
+	use sp_finality_grandpa::{AuthorityList, SetId};
 	use sp_keyring::Ed25519Keyring;
 	use sp_runtime::{generic::BlockId, traits::Header as _};
 	use std::sync::Arc;
 	use substrate_test_runtime_client::{
-		ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt, TestClientBuilder,
-		TestClientBuilderExt,
+		runtime::{Block, Hash},
+		ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt, TestClient,
+		TestClientBuilder, TestClientBuilderExt,
 	};
 
-	#[test]
-	fn warp_sync_proof_generate_verify() {
+	/// A chain of 100 blocks, with an authority set change every 10 blocks.
+	struct TestChain {
+		client: Arc<TestClient>,
+		backend: Arc<substrate_test_runtime_client::Backend>,
+		authority_set_changes: AuthoritySetChanges<u64>,
+		authorities: Vec<Ed25519Keyring>,
+		set_id: SetId,
+	}
+
+	fn to_authority_list(keyrings: &[Ed25519Keyring]) -> AuthorityList {
+		keyrings.iter().map(|keyring| (keyring.public().into(), 1)).collect()
+	}
+
+	fn justification(
+		client: &Arc<TestClient>,
+		authorities: &[Ed25519Keyring],
+		set_id: SetId,
+		target_hash: Hash,
+		target_number: u64,
+	) -> GrandpaJustification<Block> {
+		let mut precommits = Vec::new();
+		for keyring in authorities {
+			let precommit = finality_grandpa::Precommit {
+				target_hash,
+				target_number,
+			};
+
+			let msg = finality_grandpa::Message::Precommit(precommit.clone());
+			let encoded = sp_finality_grandpa::localized_payload(42, set_id, &msg);
+			let signature = keyring.sign(&encoded[..]).into();
+
+			let precommit = finality_grandpa::SignedPrecommit {
+				precommit,
+				signature,
+				id: keyring.public().into(),
+			};
+
+			precommits.push(precommit);
+		}
+
+		let commit = finality_grandpa::Commit {
+			target_hash,
+			target_number,
+			precommits,
+		};
+
+		GrandpaJustification::from_commit(client, 42, commit).unwrap()
+	}
+
+	fn test_chain() -> TestChain {
 		let mut rng = rand::rngs::StdRng::from_seed([0; 32]);
 		let builder = TestClientBuilder::new();
 		let backend = builder.backend();
 		let mut client = Arc::new(builder.build());
 
 		let available_authorities = Ed25519Keyring::iter().collect::<Vec<_>>();
-		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
 
 		let mut current_authorities = vec![Ed25519Keyring::Alice];
 		let mut current_set_id = 0;
@@ -212,25 +296,19 @@ mod tests {
 					.cloned()
 					.collect::<Vec<_>>();
 
-				new_authorities = Some(next_authorities.clone());
-
-				let next_authorities = next_authorities
-					.iter()
-					.map(|keyring| (keyring.public().into(), 1))
-					.collect::<Vec<_>>();
-
 				let digest = sp_runtime::generic::DigestItem::Consensus(
 					sp_finality_grandpa::GRANDPA_ENGINE_ID,
 					sp_finality_grandpa::ConsensusLog::ScheduledChange(
 						sp_finality_grandpa::ScheduledChange {
 							delay: 0u64,
-							next_authorities,
+							next_authorities: to_authority_list(&next_authorities),
 						},
 					)
 					.encode(),
 				);
 
 				block.header.digest_mut().logs.push(digest);
+				new_authorities = Some(next_authorities);
 			}
 
 			client.import(BlockOrigin::Own, block).unwrap();
@@ -243,33 +321,13 @@ mod tests {
 					(info.best_hash, info.best_number)
 				};
 
-				let mut precommits = Vec::new();
-				for keyring in &current_authorities {
-					let precommit = finality_grandpa::Precommit {
-						target_hash,
-						target_number,
-					};
-
-					let msg = finality_grandpa::Message::Precommit(precommit.clone());
-					let encoded = sp_finality_grandpa::localized_payload(42, current_set_id, &msg);
-					let signature = keyring.sign(&encoded[..]).into();
-
-					let precommit = finality_grandpa::SignedPrecommit {
-						precommit,
-						signature,
-						id: keyring.public().into(),
-					};
-
-					precommits.push(precommit);
-				}
-
-				let commit = finality_grandpa::Commit {
+				let justification = justification(
+					&client,
+					&current_authorities,
+					current_set_id,
 					target_hash,
 					target_number,
-					precommits,
-				};
-
-				let justification = GrandpaJustification::from_commit(&client, 42, commit).unwrap();
+				);
 
 				client
 					.finalize_block(BlockId::Hash(target_hash), Some(justification.encode()))
@@ -282,24 +340,100 @@ mod tests {
 			}
 		}
 
-		let authority_set_changes = AuthoritySetChanges::from(authority_set_changes);
+		TestChain {
+			client,
+			backend,
+			authority_set_changes: AuthoritySetChanges::from(authority_set_changes),
+			authorities: current_authorities,
+			set_id: current_set_id,
+		}
+	}
+
+	#[test]
+	fn warp_sync_proof_generate_verify() {
+		let chain = test_chain();
+		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
 
 		// generate a warp sync proof
-		let genesis_hash = client.hash(0).unwrap().unwrap();
+		let genesis_hash = chain.client.hash(0).unwrap().unwrap();
 
 		let warp_sync_proof =
-			WarpSyncProof::generate(backend.blockchain(), genesis_hash, &authority_set_changes)
+			WarpSyncProof::generate(&*chain.backend, genesis_hash, &chain.authority_set_changes)
 				.unwrap();
+		assert!(warp_sync_proof.is_finished);
 
 		// verifying the proof should yield the last set id and authorities
 		let (new_set_id, new_authorities) = warp_sync_proof.verify(0, genesis_authorities).unwrap();
 
-		let expected_authorities = current_authorities
-			.iter()
-			.map(|keyring| (keyring.public().into(), 1))
-			.collect::<Vec<_>>();
+		assert_eq!(new_set_id, chain.set_id);
+		assert_eq!(new_authorities, to_authority_list(&chain.authorities));
+	}
+
+	#[test]
+	fn warp_sync_proof_ends_with_latest_justification() {
+		let mut chain = test_chain();
+		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
+
+		// blocks finalized by the current set, after its last change.
+		for _ in 0..5 {
+			let block = chain.client.new_block(Default::default()).unwrap().build().unwrap().block;
+			chain.client.import(BlockOrigin::Own, block).unwrap();
+		}
+		let info = chain.client.info();
+		let justification = justification(
+			&chain.client,
+			&chain.authorities,
+			chain.set_id,
+			info.best_hash,
+			info.best_number,
+		);
+		chain.client
+			.finalize_block(BlockId::Hash(info.best_hash), Some(justification.encode()))
+			.unwrap();
+		chain.backend
+			.insert_aux(&[(&b"grandpa_best_justification"[..], &justification.encode()[..])], &[])
+			.unwrap();
+
+		let genesis_hash = chain.client.hash(0).unwrap().unwrap();
+		let warp_sync_proof =
+			WarpSyncProof::generate(&*chain.backend, genesis_hash, &chain.authority_set_changes)
+				.unwrap();
+
+		assert!(warp_sync_proof.is_finished);
+		assert_eq!(warp_sync_proof.proofs.len(), 11);
+		assert_eq!(warp_sync_proof.proofs.last().unwrap().header.hash(), info.best_hash);
+
+		// the last fragment doesn't change the authority set.
+		let (new_set_id, new_authorities) =
+			warp_sync_proof.verify(0, genesis_authorities.clone()).unwrap();
+		assert_eq!(new_set_id, chain.set_id);
+		assert_eq!(new_authorities, to_authority_list(&chain.authorities));
+
+		// only the last fragment of a finished proof may not change the authority set.
+		let unfinished_proof = WarpSyncProof::<Block> {
+			proofs: warp_sync_proof.proofs,
+			is_finished: false,
+		};
+		assert!(unfinished_proof.verify(0, genesis_authorities).is_err());
+	}
+
+	#[test]
+	fn warp_sync_proof_with_mismatched_header_is_rejected() {
+		let chain = test_chain();
+		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
+
+		let genesis_hash = chain.client.hash(0).unwrap().unwrap();
+		let mut warp_sync_proof =
+			WarpSyncProof::generate(&*chain.backend, genesis_hash, &chain.authority_set_changes)
+				.unwrap();
+
+		// the justification of the first change, along with the header of its parent.
+		let parent = chain.client.header(&BlockId::Number(9)).unwrap().unwrap();
+		warp_sync_proof.proofs[0].header = parent;
 
-		assert_eq!(new_set_id, current_set_id);
-		assert_eq!(new_authorities, expected_authorities);
+		assert!(matches!(
+			warp_sync_proof.verify(0, genesis_authorities),
+			Err(Error::InvalidProof(e)) if e == "Mismatch between header and justification",
+		));
 	}
 }
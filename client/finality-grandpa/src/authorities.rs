@@ -110,6 +110,11 @@ where N: Add<Output=N> + Ord + Clone + Debug,
 		)
 	}
 
+	/// Get the current authorities and their weights as an `AuthorityList`.
+	pub fn current_authority_list(&self) -> AuthorityList {
+		self.inner.read().current_authorities.clone()
+	}
+
 	/// Clone the inner `AuthoritySet`.
 	pub fn clone_inner(&self) -> AuthoritySet<H, N> {
 		self.inner.read().clone()
@@ -1268,7 +1268,7 @@ where
 		let justification = match justification_or_commit {
 			JustificationOrCommit::Justification(justification) => {
 				notify_justification(justification_sender, || Ok(justification.clone()));
-				Some(justification.encode())
+				Some(justification)
 			},
 			JustificationOrCommit::Commit((round_number, commit)) => {
 				let mut justification_required =
@@ -1303,7 +1303,7 @@ where
 					let justification = justification()?;
 					notify_justification(justification_sender, || Ok(justification.clone()));
 
-					Some(justification.encode())
+					Some(justification)
 				} else {
 					notify_justification(justification_sender, justification);
 
@@ -1312,6 +1312,20 @@ where
 			},
 		};
 
+		let justification = match justification {
+			Some(justification) => {
+				// keep the latest justification around so that finality of the best
+				// finalized block can always be proven, e.g. to warp syncing nodes.
+				crate::aux_schema::update_best_justification(
+					&justification,
+					|insert| apply_aux(import_op, insert, &[]),
+				)?;
+
+				Some(justification.encode())
+			},
+			None => None,
+		};
+
 		debug!(target: "afg", "Finalizing blocks up to ({:?}, {})", number, hash);
 
 		// ideally some handle to a synchronization oracle would be used
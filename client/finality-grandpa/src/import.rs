@@ -19,11 +19,13 @@
 use std::{sync::Arc, collections::HashMap};
 
 use log::debug;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
 use parking_lot::RwLockWriteGuard;
 
 use sp_blockchain::{BlockStatus, well_known_cache_keys};
-use sc_client_api::{backend::Backend, utils::is_descendent_of};
+use sc_client_api::{
+	backend::Backend, utils::is_descendent_of, CallExecutor, ExecutionStrategy, ExecutorProvider,
+};
 use sp_utils::mpsc::TracingUnboundedSender;
 use sp_api::TransactionFor;
 
@@ -32,7 +34,7 @@ use sp_consensus::{
 	BlockCheckParams, BlockImportParams, BlockOrigin, ImportResult, JustificationImport,
 	SelectChain,
 };
-use sp_finality_grandpa::{ConsensusLog, ScheduledChange, SetId, GRANDPA_ENGINE_ID};
+use sp_finality_grandpa::{AuthorityList, ConsensusLog, ScheduledChange, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::Justification;
 use sp_runtime::generic::{BlockId, OpaqueDigestItemId};
 use sp_runtime::traits::{
@@ -40,7 +42,9 @@ use sp_runtime::traits::{
 };
 
 use crate::{Error, CommandOrError, NewAuthoritySet, VoterCommand};
-use crate::authorities::{AuthoritySet, SharedAuthoritySet, DelayKind, PendingChange};
+use crate::authorities::{
+	AuthoritySet, AuthoritySetChanges, SharedAuthoritySet, DelayKind, PendingChange,
+};
 use crate::environment::finalize_block;
 use crate::justification::GrandpaJustification;
 use crate::notification::GrandpaJustificationSender;
@@ -428,7 +432,17 @@ impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 		// early exit if block already in chain, otherwise the check for
 		// authority changes will error when trying to re-import a change block
 		match self.inner.status(BlockId::Hash(hash)) {
+			Ok(BlockStatus::InChain) if block.import_existing => {
+				// the authority set changes have been applied when the block was first
+				// imported, so it is passed on as is, e.g. to import its state.
+				block.justification = None;
+				return (&*self.inner).import_block(block, new_cache)
+					.map_err(|e| ConsensusError::ClientImport(e.to_string()));
+			},
 			Ok(BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
+			Ok(BlockStatus::Unknown) if block.imported_state.is_some() => {
+				return self.import_state(block, new_cache);
+			},
 			Ok(BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
 		}
@@ -549,6 +563,94 @@ impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 	}
 }
 
+impl<BE, Block: BlockT, Client, SC> GrandpaBlockImport<BE, Block, Client, SC> where
+	NumberFor<Block>: finality_grandpa::BlockNumberOps,
+	BE: Backend<Block>,
+	Client: crate::ClientForGrandpa<Block, BE>,
+	for<'a> &'a Client:
+		BlockImport<Block, Error = ConsensusError, Transaction = TransactionFor<Client, Block>>,
+{
+	/// Import a block along with its complete state, as downloaded by warp sync.
+	///
+	/// The block is imported as finalized and its ancestry is not required to be known. We
+	/// trust the sync module to have verified its finality and the correctness of the state,
+	/// so the authority set is reset to the one found in the imported state.
+	fn import_state(
+		&mut self,
+		mut block: BlockImportParams<Block, TransactionFor<Client, Block>>,
+		new_cache: HashMap<well_known_cache_keys::Id, Vec<u8>>,
+	) -> Result<ImportResult, ConsensusError> {
+		let hash = block.post_hash();
+		let number = *block.header.number();
+
+		block.finalized = true;
+		block.justification = None;
+
+		let imported_aux = match (&*self.inner).import_block(block, new_cache) {
+			Ok(ImportResult::Imported(aux)) => aux,
+			Ok(r) => return Ok(r),
+			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
+		};
+
+		let block_id = BlockId::Hash(hash);
+		let authorities: AuthorityList = self.call_api(&block_id, "GrandpaApi_grandpa_authorities")?;
+		let set_id: SetId = self.call_api(&block_id, "GrandpaApi_current_set_id")?;
+
+		let authority_set = AuthoritySet::new(
+			authorities.clone(),
+			set_id,
+			fork_tree::ForkTree::new(),
+			Vec::new(),
+			AuthoritySetChanges::empty(),
+		).ok_or(ConsensusError::InvalidAuthoritiesSet)?;
+
+		let new_set = NewAuthoritySet {
+			canon_number: number,
+			canon_hash: hash,
+			set_id,
+			authorities,
+		};
+
+		crate::aux_schema::update_authority_set::<Block, _, _>(
+			&authority_set,
+			Some(&new_set),
+			|insert| self.inner.insert_aux(insert, &[]),
+		).map_err(|e| ConsensusError::ClientImport(e.to_string()))?;
+
+		*self.authority_set.inner().write() = authority_set;
+		self.authority_set_hard_forks.clear();
+
+		debug!(
+			target: "afg",
+			"Imported state of #{} ({}), GRANDPA authority set reset to set id {}",
+			number,
+			hash,
+			set_id,
+		);
+
+		let _ = self.send_voter_commands.unbounded_send(VoterCommand::ChangeAuthorities(new_set));
+
+		Ok(ImportResult::Imported(imported_aux))
+	}
+
+	/// Call a GRANDPA runtime API method without arguments on top of the state at the given
+	/// block and decode the result.
+	fn call_api<T: Decode>(
+		&self,
+		id: &BlockId<Block>,
+		method: &str,
+	) -> Result<T, ConsensusError> {
+		self.inner.executor()
+			.call(id, method, &[], ExecutionStrategy::NativeElseWasm, None)
+			.and_then(|call_result| {
+				Decode::decode(&mut &call_result[..]).map_err(|err| {
+					sp_blockchain::Error::CallResultDecode("failed to decode GRANDPA runtime API result", err)
+				})
+			})
+			.map_err(|e| ConsensusError::ClientImport(e.to_string()))
+	}
+}
+
 impl<Backend, Block: BlockT, Client, SC> GrandpaBlockImport<Backend, Block, Client, SC> {
 	pub(crate) fn new(
 		inner: Arc<Client>,
@@ -112,6 +112,11 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 		}
 	}
 
+	/// The target block number and hash that this justifications proves finality for.
+	pub fn target(&self) -> (NumberFor<Block>, Block::Hash) {
+		(self.commit.target_number, self.commit.target_hash)
+	}
+
 	/// Validate the commit and the votes' ancestry proofs.
 	pub fn verify(&self, set_id: u64, authorities: &AuthorityList) -> Result<(), ClientError>
 	where
@@ -0,0 +1,287 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk format of the keys held by the local keystore.
+//!
+//! Keystores without a password store every key as a JSON string holding the secret URI.
+//! Keystores with a password store a versioned JSON object instead, holding the secret
+//! encrypted with ChaCha20-Poly1305 under a key derived from the password with scrypt.
+
+use std::{
+	fs::{self, File},
+	io::Write,
+	path::{Path, PathBuf},
+};
+use chacha20poly1305::{
+	ChaCha20Poly1305,
+	aead::{Aead, NewAead, generic_array::GenericArray},
+};
+use rand::{RngCore, rngs::OsRng};
+use serde::{Serialize, Deserialize};
+use sp_core::Bytes;
+
+use crate::{Result, Error};
+
+/// Version of the encrypted key file format.
+pub const KEY_FILE_VERSION: u32 = 1;
+
+/// Name of the only supported cipher.
+const CIPHER: &str = "chacha20-poly1305";
+
+/// Scrypt cost parameter (`log2(N)`) for newly encrypted keys.
+///
+/// Kept low in tests, where keys are encrypted and decrypted in unoptimized builds.
+const SCRYPT_LOG_N: u8 = if cfg!(test) { 4 } else { 15 };
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// The secret of a key.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeySecret {
+	/// The secret URI.
+	pub suri: String,
+	/// Password the key pair is derived with, overriding any password in `suri`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub password: Option<String>,
+}
+
+impl KeySecret {
+	/// Returns a secret URI that yields the key pair without any additional password.
+	pub fn to_suri(&self) -> String {
+		match &self.password {
+			Some(password) => {
+				let suri = self.suri.split("///").next().unwrap_or_default();
+				format!("{}///{}", suri, password)
+			},
+			None => self.suri.clone(),
+		}
+	}
+}
+
+/// Parameters of the key derivation function.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "function", rename_all = "camelCase")]
+pub enum Kdf {
+	/// Scrypt with the given parameters.
+	#[serde(rename_all = "camelCase")]
+	Scrypt {
+		/// Random salt.
+		salt: Bytes,
+		/// `log2(N)`.
+		log_n: u8,
+		/// Block size.
+		r: u32,
+		/// Parallelization.
+		p: u32,
+	},
+}
+
+/// A key secret encrypted with a password.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EncryptedKey {
+	/// Format version.
+	pub version: u32,
+	/// How the encryption key is derived from the password.
+	pub kdf: Kdf,
+	/// The cipher used.
+	pub cipher: String,
+	/// Cipher nonce.
+	pub nonce: Bytes,
+	/// The encrypted and authenticated JSON encoded [`KeySecret`].
+	pub ciphertext: Bytes,
+}
+
+impl EncryptedKey {
+	/// Encrypt `secret` with `password`.
+	pub fn encrypt(secret: &KeySecret, password: &str) -> Result<Self> {
+		let mut salt = vec![0u8; SALT_LEN];
+		let mut nonce = vec![0u8; NONCE_LEN];
+		OsRng.fill_bytes(&mut salt);
+		OsRng.fill_bytes(&mut nonce);
+
+		let kdf = Kdf::Scrypt { salt: salt.into(), log_n: SCRYPT_LOG_N, r: SCRYPT_R, p: SCRYPT_P };
+		let cipher = cipher(&kdf, password)?;
+		let plaintext = serde_json::to_vec(secret)?;
+		let ciphertext = cipher
+			.encrypt(GenericArray::from_slice(&nonce), plaintext.as_slice())
+			.map_err(|_| Error::Encryption)?;
+
+		Ok(Self {
+			version: KEY_FILE_VERSION,
+			kdf,
+			cipher: CIPHER.into(),
+			nonce: nonce.into(),
+			ciphertext: ciphertext.into(),
+		})
+	}
+
+	/// Decrypt the secret with `password`.
+	///
+	/// Fails with [`Error::InvalidPassword`] if the password is wrong.
+	pub fn decrypt(&self, password: &str) -> Result<KeySecret> {
+		if self.version != KEY_FILE_VERSION {
+			return Err(Error::UnsupportedKeyFileVersion(self.version));
+		}
+		if self.cipher != CIPHER || self.nonce.len() != NONCE_LEN {
+			return Err(Error::Encryption);
+		}
+		let cipher = cipher(&self.kdf, password)?;
+		let plaintext = cipher
+			.decrypt(GenericArray::from_slice(&self.nonce), &self.ciphertext[..])
+			.map_err(|_| Error::InvalidPassword)?;
+		Ok(serde_json::from_slice(&plaintext)?)
+	}
+}
+
+fn cipher(kdf: &Kdf, password: &str) -> Result<ChaCha20Poly1305> {
+	let mut key = [0u8; KEY_LEN];
+	match kdf {
+		Kdf::Scrypt { salt, log_n, r, p } => {
+			let params = scrypt::ScryptParams::new(*log_n, *r, *p).map_err(|_| Error::Encryption)?;
+			scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+				.map_err(|_| Error::Encryption)?;
+		},
+	}
+	let cipher = ChaCha20Poly1305::new(GenericArray::from_slice(&key));
+	key.iter_mut().for_each(|b| *b = 0);
+	Ok(cipher)
+}
+
+/// Contents of a key file.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyFile {
+	/// Secret URI stored in plain text.
+	Plain(String),
+	/// Encrypted secret.
+	Encrypted(EncryptedKey),
+}
+
+impl KeyFile {
+	/// Store `secret`, encrypted if a password is given.
+	pub fn new(secret: &KeySecret, password: Option<&str>) -> Result<Self> {
+		match password {
+			Some(password) => EncryptedKey::encrypt(secret, password).map(KeyFile::Encrypted),
+			None => Ok(KeyFile::Plain(secret.to_suri())),
+		}
+	}
+
+	/// Read a key file.
+	pub fn read(path: &Path) -> Result<Self> {
+		let file = File::open(path)?;
+		Ok(serde_json::from_reader(&file)?)
+	}
+
+	/// Write the key file, replacing any file at `path`.
+	///
+	/// The file is written next to its destination first, so an existing key is never left
+	/// half written.
+	pub fn write(&self, path: &Path) -> Result<()> {
+		let tmp_path = self.write_pending(path)?;
+		fs::rename(tmp_path, path)?;
+		Ok(())
+	}
+
+	/// Write the key file next to `path`, without replacing any file at `path`.
+	///
+	/// Returns the path of the written file, which replaces the key once renamed to `path`.
+	pub fn write_pending(&self, path: &Path) -> Result<PathBuf> {
+		let mut tmp_path = PathBuf::from(path);
+		tmp_path.set_extension("tmp");
+		let written = File::create(&tmp_path).map_err(Error::Io).and_then(|mut file| {
+			serde_json::to_writer(&file, self)?;
+			file.flush()?;
+			file.sync_all()?;
+			Ok(())
+		});
+		if let Err(e) = written {
+			let _ = fs::remove_file(&tmp_path);
+			return Err(e);
+		}
+		Ok(tmp_path)
+	}
+
+	/// Returns the secret of the key.
+	///
+	/// `password` is the keystore password. Plain keys are derived with it, encrypted keys
+	/// are decrypted with it.
+	pub fn secret(&self, password: Option<&str>) -> Result<KeySecret> {
+		match self {
+			KeyFile::Plain(suri) => Ok(KeySecret {
+				suri: suri.clone(),
+				password: password.map(Into::into),
+			}),
+			KeyFile::Encrypted(key) => key.decrypt(password.ok_or(Error::PasswordRequired)?),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::TempDir;
+
+	fn secret() -> KeySecret {
+		KeySecret { suri: "//Alice".into(), password: Some("derive".into()) }
+	}
+
+	#[test]
+	fn encrypted_key_roundtrip() {
+		let key = EncryptedKey::encrypt(&secret(), "password").unwrap();
+		assert!(secret() == key.decrypt("password").unwrap());
+		assert!(matches!(key.decrypt("wrong"), Err(Error::InvalidPassword)));
+	}
+
+	#[test]
+	fn unknown_version_is_rejected() {
+		let mut key = EncryptedKey::encrypt(&secret(), "password").unwrap();
+		key.version = KEY_FILE_VERSION + 1;
+		assert!(matches!(key.decrypt("password"), Err(Error::UnsupportedKeyFileVersion(_))));
+	}
+
+	#[test]
+	fn key_files_roundtrip() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().join("key");
+
+		KeyFile::new(&secret(), None).unwrap().write(&path).unwrap();
+		let file = KeyFile::read(&path).unwrap();
+		assert!(matches!(file, KeyFile::Plain(_)));
+		assert_eq!(file.secret(None).unwrap().to_suri(), "//Alice///derive");
+
+		KeyFile::new(&secret(), Some("password")).unwrap().write(&path).unwrap();
+		let file = KeyFile::read(&path).unwrap();
+		assert!(matches!(file, KeyFile::Encrypted(_)));
+		assert!(file.secret(Some("password")).unwrap() == secret());
+		assert!(matches!(file.secret(None), Err(Error::PasswordRequired)));
+		assert!(matches!(file.secret(Some("wrong")), Err(Error::InvalidPassword)));
+		assert!(!dir.path().join("key.tmp").exists());
+	}
+
+	#[test]
+	fn suri_password_is_replaced() {
+		let secret = KeySecret { suri: "//Alice///old".into(), password: Some("new".into()) };
+		assert_eq!(secret.to_suri(), "//Alice///new");
+		let secret = KeySecret { suri: "//Alice///old".into(), password: None };
+		assert_eq!(secret.to_suri(), "//Alice///old");
+	}
+}
@@ -25,7 +25,14 @@ use sp_keystore::Error as TraitError;
 
 /// Local keystore implementation
 mod local;
+/// Key file format of the local keystore
+mod key_file;
 pub use local::LocalKeystore;
+/// Remote keystore implementation
+mod remote;
+pub use remote::{
+	RemoteKeystore, RemoteSigner, redact_token, DEFAULT_REQUEST_TIMEOUT, DEFAULT_KEY_CACHE_TTL,
+};
 
 /// Keystore error.
 #[derive(Debug, derive_more::Display, derive_more::From)]
@@ -49,6 +56,30 @@ pub enum Error {
 	/// Keystore unavailable
 	#[display(fmt="Keystore unavailable")]
 	Unavailable,
+	/// Invalid remote keystore URI
+	#[display(fmt="Invalid keystore URI: {}", _0)]
+	#[from(ignore)]
+	InvalidUri(String),
+	/// The remote signer returned an error
+	#[display(fmt="Remote signer error: {}", _0)]
+	#[from(ignore)]
+	Remote(String),
+	/// The remote signer did not answer in time
+	#[display(fmt="Remote signer request timed out")]
+	Timeout,
+	/// Operation is not supported by the keystore
+	#[display(fmt="Operation not supported by this keystore")]
+	Unsupported,
+	/// Keys are encrypted, but no password was given
+	#[display(fmt="Keystore is encrypted, a password is required")]
+	PasswordRequired,
+	/// Key file version is not supported
+	#[display(fmt="Unsupported key file version: {}", _0)]
+	#[from(ignore)]
+	UnsupportedKeyFileVersion(u32),
+	/// Key encryption failed
+	#[display(fmt="Key encryption failed")]
+	Encryption,
 }
 
 /// Keystore Result
@@ -58,10 +89,15 @@ impl From<Error> for TraitError {
 	fn from(error: Error) -> Self {
 		match error {
 			Error::KeyNotSupported(id) => TraitError::KeyNotSupported(id),
-			Error::InvalidSeed | Error::InvalidPhrase | Error::InvalidPassword => {
+			Error::InvalidSeed | Error::InvalidPhrase | Error::InvalidPassword
+				| Error::PasswordRequired => {
 				TraitError::ValidationError(error.to_string())
 			},
 			Error::Unavailable => TraitError::Unavailable,
+			Error::InvalidUri(_) | Error::Remote(_) | Error::Timeout | Error::Unsupported
+				| Error::UnsupportedKeyFileVersion(_) | Error::Encryption => {
+				TraitError::Other(error.to_string())
+			},
 			Error::Io(e) => TraitError::Other(e.to_string()),
 			Error::Json(e) => TraitError::Other(e.to_string()),
 		}
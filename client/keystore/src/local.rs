@@ -19,16 +19,17 @@
 
 use std::{
 	collections::{HashMap, HashSet},
-	fs::{self, File},
-	io::Write,
+	fs,
 	path::PathBuf,
 	sync::Arc,
 };
 use async_trait::async_trait;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use serde::{Serialize, Deserialize};
 use sp_core::{
 	crypto::{CryptoTypePublicPair, KeyTypeId, Pair as PairT, ExposeSecret, SecretString, Public},
 	sr25519::{Public as Sr25519Public, Pair as Sr25519Pair},
+	Bytes,
 	Encode,
 };
 use sp_keystore::{
@@ -40,13 +41,18 @@ use sp_keystore::{
 };
 use sp_application_crypto::{ed25519, sr25519, ecdsa, AppPair, AppKey, IsWrappedBy};
 
-use crate::{Result, Error};
+use crate::{Result, Error, key_file::{EncryptedKey, KeyFile, KeySecret}};
 
 /// A local based keystore that is either memory-based or filesystem-based.
 pub struct LocalKeystore(RwLock<KeystoreInner>);
 
 impl LocalKeystore {
 	/// Create a local keystore from filesystem.
+	///
+	/// If a password is given, all keys are encrypted with it and keys still stored in plain
+	/// text are encrypted right away. Fails with [`Error::InvalidPassword`] if the password
+	/// does not match the stored keys and with [`Error::PasswordRequired`] if no password is
+	/// given for encrypted keys.
 	pub fn open<T: Into<PathBuf>>(path: T, password: Option<SecretString>) -> Result<Self> {
 		let inner = KeystoreInner::open(path, password)?;
 		Ok(Self(RwLock::new(inner)))
@@ -65,6 +71,80 @@ impl LocalKeystore {
 	pub fn key_pair<Pair: AppPair>(&self, public: &<Pair as AppKey>::Public) -> Result<Option<Pair>> {
 		self.0.read().key_pair::<Pair>(public)
 	}
+
+	/// Re-encrypt all keys stored on disk with a new password.
+	///
+	/// Keys derived with the old password keep their public key. No key is changed if any of
+	/// them can't be decrypted with the current password, or written with the new one.
+	pub fn change_password(&self, password: SecretString) -> Result<()> {
+		self.0.write().change_password(password)
+	}
+
+	/// Export the key with the given type and public key, encrypted with `password`.
+	///
+	/// Returns `Ok(None)` if the key doesn't exist. The exported key can be added to another
+	/// keystore with [`import_key`](Self::import_key).
+	pub fn export_key(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		password: &SecretString,
+	) -> Result<Option<String>> {
+		let secret = match self.0.read().key_secret_by_type(public, key_type)? {
+			Some(secret) => secret,
+			None => return Ok(None),
+		};
+		let exported = ExportedKey {
+			key_type: key_type.0.to_vec().into(),
+			public: public.to_vec().into(),
+			key: EncryptedKey::encrypt(&secret, password.expose_secret())?,
+		};
+		Ok(Some(serde_json::to_string(&exported)?))
+	}
+
+	/// Import a key exported with [`export_key`](Self::export_key), decrypting it with
+	/// `password`.
+	///
+	/// Returns the type and public key of the imported key.
+	pub fn import_key(&self, exported: &str, password: &SecretString) -> Result<(KeyTypeId, Vec<u8>)> {
+		let exported: ExportedKey = serde_json::from_str(exported)?;
+		let secret = exported.key.decrypt(password.expose_secret())?;
+		if exported.key_type.len() != 4 {
+			return Err(Error::InvalidPhrase);
+		}
+		let mut key_type = KeyTypeId::default();
+		key_type.0.copy_from_slice(&exported.key_type);
+		let public = exported.public.0;
+
+		if !derives_public(&secret, &public) {
+			return Err(Error::InvalidPhrase);
+		}
+
+		self.0.read().write_key(key_type, &public, &secret)?;
+		Ok((key_type, public))
+	}
+}
+
+/// Whether `secret` is the secret of `public` for any of the supported crypto types.
+fn derives_public(secret: &KeySecret, public: &[u8]) -> bool {
+	fn derives<Pair: PairT>(secret: &KeySecret, public: &[u8]) -> bool {
+		Pair::from_string(&secret.suri, secret.password.as_deref())
+			.map(|pair| pair.public().as_slice() == public)
+			.unwrap_or(false)
+	}
+
+	derives::<sr25519::Pair>(secret, public)
+		|| derives::<ed25519::Pair>(secret, public)
+		|| derives::<ecdsa::Pair>(secret, public)
+}
+
+/// A key exported from a keystore.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedKey {
+	key_type: Bytes,
+	public: Bytes,
+	key: EncryptedKey,
 }
 
 #[async_trait]
@@ -279,7 +359,7 @@ impl SyncCryptoStore for LocalKeystore {
 
 	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
 		public_keys.iter()
-			.all(|(p, t)| self.0.read().key_phrase_by_type(&p, *t).ok().flatten().is_some())
+			.all(|(p, t)| self.0.read().key_secret_by_type(&p, *t).ok().flatten().is_some())
 	}
 
 	fn sr25519_vrf_sign(
@@ -325,6 +405,8 @@ struct KeystoreInner {
 	/// Map over `(KeyTypeId, Raw public key)` -> `Key phrase/seed`
 	additional: HashMap<(KeyTypeId, Vec<u8>), String>,
 	password: Option<SecretString>,
+	/// Secrets read from the file system store, decrypting keys is slow by design.
+	secrets: Mutex<HashMap<(KeyTypeId, Vec<u8>), KeySecret>>,
 }
 
 impl KeystoreInner {
@@ -335,7 +417,22 @@ impl KeystoreInner {
 		let path = path.into();
 		fs::create_dir_all(&path)?;
 
-		let instance = Self { path: Some(path), additional: HashMap::new(), password };
+		let instance = Self {
+			path: Some(path),
+			additional: HashMap::new(),
+			password,
+			secrets: Mutex::new(HashMap::new()),
+		};
+
+		// Check the password and encrypt plain keys right away. Keys that can't be read for
+		// other reasons are reported when they are used.
+		for (key_type, public) in instance.stored_keys()? {
+			match instance.key_secret_by_type(&public, key_type) {
+				Err(e @ Error::InvalidPassword) | Err(e @ Error::PasswordRequired) => return Err(e),
+				_ => {},
+			}
+		}
+
 		Ok(instance)
 	}
 
@@ -351,7 +448,8 @@ impl KeystoreInner {
 		Self {
 			path: None,
 			additional: HashMap::new(),
-			password: None
+			password: None,
+			secrets: Mutex::new(HashMap::new()),
 		}
 	}
 
@@ -377,10 +475,17 @@ impl KeystoreInner {
 	///
 	/// Places it into the file system store, if a path is configured.
 	fn insert_unknown(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<()> {
+		let secret = KeySecret { suri: suri.into(), password: self.password().map(Into::into) };
+		self.write_key(key_type, public, &secret)
+	}
+
+	/// Write a key to the file system store, if a path is configured.
+	///
+	/// The key is encrypted if the store has a password.
+	fn write_key(&self, key_type: KeyTypeId, public: &[u8], secret: &KeySecret) -> Result<()> {
 		if let Some(path) = self.key_file_path(public, key_type) {
-			let mut file = File::create(path).map_err(Error::Io)?;
-			serde_json::to_writer(&file, &suri).map_err(Error::Json)?;
-			file.flush().map_err(Error::Io)?;
+			KeyFile::new(secret, self.password())?.write(&path)?;
+			self.secrets.lock().insert((key_type, public.to_vec()), secret.clone());
 		}
 		Ok(())
 	}
@@ -391,10 +496,9 @@ impl KeystoreInner {
 	/// it into the memory cache only.
 	fn generate_by_type<Pair: PairT>(&mut self, key_type: KeyTypeId) -> Result<Pair> {
 		let (pair, phrase, _) = Pair::generate_with_phrase(self.password());
-		if let Some(path) = self.key_file_path(pair.public().as_slice(), key_type) {
-			let mut file = File::create(path)?;
-			serde_json::to_writer(&file, &phrase)?;
-			file.flush()?;
+		if self.path.is_some() {
+			let secret = KeySecret { suri: phrase, password: self.password().map(Into::into) };
+			self.write_key(key_type, pair.public().as_slice(), &secret)?;
 		} else {
 			self.insert_ephemeral_pair(&pair, &phrase, key_type);
 		}
@@ -414,10 +518,15 @@ impl KeystoreInner {
 		Ok(pair)
 	}
 
-	/// Get the key phrase for a given public key and key type.
-	fn key_phrase_by_type(&self, public: &[u8], key_type: KeyTypeId) -> Result<Option<String>> {
+	/// Get the secret for a given public key and key type.
+	///
+	/// Keys found in plain text are encrypted if the store has a password.
+	fn key_secret_by_type(&self, public: &[u8], key_type: KeyTypeId) -> Result<Option<KeySecret>> {
 		if let Some(phrase) = self.get_additional_pair(public, key_type) {
-			return Ok(Some(phrase.clone()))
+			return Ok(Some(KeySecret {
+				suri: phrase.clone(),
+				password: self.password().map(Into::into),
+			}))
 		}
 
 		let path = if let Some(path) = self.key_file_path(public, key_type) {
@@ -426,13 +535,39 @@ impl KeystoreInner {
 			return Ok(None);
 		};
 
-		if path.exists() {
-			let file = File::open(path)?;
+		if !path.exists() {
+			return Ok(None);
+		}
 
-			serde_json::from_reader(&file).map_err(Into::into).map(Some)
-		} else {
-			Ok(None)
+		let key = (key_type, public.to_vec());
+		if let Some(secret) = self.secrets.lock().get(&key) {
+			return Ok(Some(secret.clone()));
 		}
+
+		let secret = match KeyFile::read(&path)? {
+			KeyFile::Plain(suri) if self.password().is_some() => {
+				// Plain keys were derived with the store password, unless they were stored
+				// before the store had one. The key is only encrypted once it is known to be
+				// right, a mistyped password must not end up in the file.
+				let with_password = KeySecret {
+					suri: suri.clone(),
+					password: self.password().map(Into::into),
+				};
+				let without_password = KeySecret { suri, password: None };
+				let secret = if derives_public(&with_password, public) {
+					with_password
+				} else if derives_public(&without_password, public) {
+					without_password
+				} else {
+					return Err(Error::InvalidPassword);
+				};
+				KeyFile::new(&secret, self.password())?.write(&path)?;
+				secret
+			},
+			file => file.secret(self.password())?,
+		};
+		self.secrets.lock().insert(key, secret.clone());
+		Ok(Some(secret))
 	}
 
 	/// Get a key pair for the given public key and key type.
@@ -441,15 +576,15 @@ impl KeystoreInner {
 		public: &Pair::Public,
 		key_type: KeyTypeId,
 	) -> Result<Option<Pair>> {
-		let phrase = if let Some(p) = self.key_phrase_by_type(public.as_slice(), key_type)? {
-			p
+		let secret = if let Some(s) = self.key_secret_by_type(public.as_slice(), key_type)? {
+			s
 		} else {
 			return Ok(None)
 		};
 
 		let pair = Pair::from_string(
-			&phrase,
-			self.password(),
+			&secret.suri,
+			secret.password.as_deref(),
 		).map_err(|_| Error::InvalidPhrase)?;
 
 		if &pair.public() == public {
@@ -477,6 +612,19 @@ impl KeystoreInner {
 			.filter_map(|k| if k.0 == id { Some(k.1.clone()) } else { None })
 			.collect();
 
+		public_keys.extend(
+			self.stored_keys()?
+				.into_iter()
+				.filter_map(|(key_type, public)| if key_type == id { Some(public) } else { None })
+		);
+
+		Ok(public_keys)
+	}
+
+	/// Returns the key type and raw public key of all keys in the file system store.
+	fn stored_keys(&self) -> Result<Vec<(KeyTypeId, Vec<u8>)>> {
+		let mut keys = Vec::new();
+
 		if let Some(path) = &self.path {
 			for entry in fs::read_dir(&path)? {
 				let entry = entry?;
@@ -486,11 +634,9 @@ impl KeystoreInner {
 				if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
 					match hex::decode(name) {
 						Ok(ref hex) if hex.len() > 4 => {
-							if &hex[0..4] != &id.0 {
-								continue;
-							}
-							let public = hex[4..].to_vec();
-							public_keys.push(public);
+							let mut key_type = [0u8; 4];
+							key_type.copy_from_slice(&hex[0..4]);
+							keys.push((KeyTypeId(key_type), hex[4..].to_vec()));
 						}
 						_ => continue,
 					}
@@ -498,7 +644,47 @@ impl KeystoreInner {
 			}
 		}
 
-		Ok(public_keys)
+		Ok(keys)
+	}
+
+	/// Re-encrypt all keys in the file system store with `password`.
+	fn change_password(&mut self, password: SecretString) -> Result<()> {
+		// Read all keys first, so nothing is changed if any of them can't be decrypted.
+		let keys = self.stored_keys()?
+			.into_iter()
+			.map(|(key_type, public)| {
+				let secret = self.key_secret_by_type(&public, key_type)?
+					.ok_or(Error::Unavailable)?;
+				Ok((key_type, public, secret))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		// Write all keys before replacing any of them, so a failure doesn't leave the keys
+		// encrypted with different passwords.
+		let mut pending = Vec::new();
+		for (key_type, public, secret) in &keys {
+			let path = match self.key_file_path(public, *key_type) {
+				Some(path) => path,
+				None => continue,
+			};
+			let written = KeyFile::new(secret, Some(password.expose_secret().as_str()))
+				.and_then(|file| file.write_pending(&path));
+			match written {
+				Ok(tmp_path) => pending.push((tmp_path, path)),
+				Err(e) => {
+					for (tmp_path, _) in pending {
+						let _ = fs::remove_file(tmp_path);
+					}
+					return Err(e);
+				},
+			}
+		}
+
+		for (tmp_path, path) in pending {
+			fs::rename(tmp_path, path)?;
+		}
+		self.password = Some(password);
+		Ok(())
 	}
 
 	/// Get a key pair for the given public key.
@@ -624,9 +810,12 @@ mod tests {
 			store.key_pair::<ed25519::AppPair>(&pair.public()).unwrap().unwrap().public(),
 		);
 
-		// Without the password the key should not be retrievable
-		let store = KeystoreInner::open(temp_dir.path(), None).unwrap();
-		assert!(store.key_pair::<ed25519::AppPair>(&pair.public()).is_err());
+		// Without the password the keystore can not be opened
+		assert!(matches!(KeystoreInner::open(temp_dir.path(), None), Err(Error::PasswordRequired)));
+		assert!(matches!(
+			KeystoreInner::open(temp_dir.path(), Some(FromStr::from_str("wrong").unwrap())),
+			Err(Error::InvalidPassword),
+		));
 
 		let store = KeystoreInner::open(
 			temp_dir.path(),
@@ -638,6 +827,115 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn keys_are_encrypted_with_password() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(
+			temp_dir.path(),
+			Some(FromStr::from_str("password").unwrap()),
+		).unwrap();
+		let public = SyncCryptoStore::sr25519_generate_new(&store, TEST_KEY_TYPE, None).unwrap();
+
+		let file = fs::read_to_string(store.0.read().key_file_path(public.as_ref(), TEST_KEY_TYPE).unwrap())
+			.unwrap();
+		let file: serde_json::Value = serde_json::from_str(&file).unwrap();
+		assert_eq!(file["version"], crate::key_file::KEY_FILE_VERSION);
+		assert!(file["ciphertext"].is_string());
+	}
+
+	#[test]
+	fn plain_keys_are_migrated() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		let public = SyncCryptoStore::sr25519_generate_new(&store, TEST_KEY_TYPE, None).unwrap();
+		let path = store.0.read().key_file_path(public.as_ref(), TEST_KEY_TYPE).unwrap();
+		drop(store);
+		assert!(matches!(KeyFile::read(&path).unwrap(), KeyFile::Plain(_)));
+
+		let password = || Some(FromStr::from_str("password").unwrap());
+		let store = LocalKeystore::open(temp_dir.path(), password()).unwrap();
+		assert!(matches!(KeyFile::read(&path).unwrap(), KeyFile::Encrypted(_)));
+		drop(store);
+
+		let store = LocalKeystore::open(temp_dir.path(), password()).unwrap();
+		let pair = store.0.read().key_pair_by_type::<sr25519::Pair>(&public, TEST_KEY_TYPE).unwrap();
+		assert_eq!(pair.unwrap().public(), public);
+	}
+
+	#[test]
+	fn plain_keys_are_not_migrated_with_wrong_password() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		// a plain key derived with a password, as stored before keys were encrypted.
+		let (pair, phrase, _) = sr25519::Pair::generate_with_phrase(Some("password"));
+		let path = store.0.read().key_file_path(pair.public().as_ref(), TEST_KEY_TYPE).unwrap();
+		KeyFile::Plain(phrase).write(&path).unwrap();
+		drop(store);
+
+		assert!(matches!(
+			LocalKeystore::open(temp_dir.path(), Some(FromStr::from_str("wrong").unwrap())),
+			Err(Error::InvalidPassword),
+		));
+		assert!(matches!(KeyFile::read(&path).unwrap(), KeyFile::Plain(_)));
+
+		let store = LocalKeystore::open(
+			temp_dir.path(),
+			Some(FromStr::from_str("password").unwrap()),
+		).unwrap();
+		assert!(matches!(KeyFile::read(&path).unwrap(), KeyFile::Encrypted(_)));
+		let pair = store.0.read()
+			.key_pair_by_type::<sr25519::Pair>(&pair.public(), TEST_KEY_TYPE)
+			.unwrap();
+		assert!(pair.is_some());
+	}
+
+	#[test]
+	fn change_password_keeps_keys() {
+		let temp_dir = TempDir::new().unwrap();
+		let old = || Some(FromStr::from_str("old").unwrap());
+		let store = LocalKeystore::open(temp_dir.path(), old()).unwrap();
+		let public = SyncCryptoStore::ed25519_generate_new(&store, TEST_KEY_TYPE, None).unwrap();
+
+		store.change_password(FromStr::from_str("new").unwrap()).unwrap();
+		drop(store);
+
+		assert!(matches!(LocalKeystore::open(temp_dir.path(), old()), Err(Error::InvalidPassword)));
+		let store = LocalKeystore::open(
+			temp_dir.path(),
+			Some(FromStr::from_str("new").unwrap()),
+		).unwrap();
+		let pair = store.0.read().key_pair_by_type::<ed25519::Pair>(&public, TEST_KEY_TYPE).unwrap();
+		assert_eq!(pair.unwrap().public(), public);
+	}
+
+	#[test]
+	fn export_and_import_key() {
+		let source_dir = TempDir::new().unwrap();
+		let source = LocalKeystore::open(
+			source_dir.path(),
+			Some(FromStr::from_str("source").unwrap()),
+		).unwrap();
+		let public = SyncCryptoStore::sr25519_generate_new(&source, TEST_KEY_TYPE, None).unwrap();
+		let export_password = FromStr::from_str("export").unwrap();
+
+		assert!(source.export_key(SR25519, public.as_ref(), &export_password).unwrap().is_none());
+		let exported = source.export_key(TEST_KEY_TYPE, public.as_ref(), &export_password).unwrap().unwrap();
+
+		let target_dir = TempDir::new().unwrap();
+		let target = LocalKeystore::open(target_dir.path(), None).unwrap();
+		assert!(matches!(
+			target.import_key(&exported, &FromStr::from_str("wrong").unwrap()),
+			Err(Error::InvalidPassword),
+		));
+		assert_eq!(
+			target.import_key(&exported, &export_password).unwrap(),
+			(TEST_KEY_TYPE, public.to_raw_vec()),
+		);
+
+		let pair = target.0.read().key_pair_by_type::<sr25519::Pair>(&public, TEST_KEY_TYPE).unwrap();
+		assert_eq!(pair.unwrap().public(), public);
+	}
+
 	#[test]
 	fn public_keys_are_returned() {
 		let temp_dir = TempDir::new().unwrap();
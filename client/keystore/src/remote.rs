@@ -0,0 +1,1256 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote keystore implementation.
+//!
+//! [`RemoteKeystore`] keeps no private keys. Public key queries and signing requests are
+//! forwarded to an external signer process over a TCP (`tcp://host:port`) or Unix
+//! (`unix:///path/to/socket`) socket. Messages are newline delimited JSON-RPC 2.0 objects
+//! and all binary values are `0x` prefixed hex strings. The signer has to implement:
+//!
+//! - `keystore_publicKeys({ keyType, cryptoType })` returning a list of public keys.
+//! - `keystore_sign({ keyType, cryptoType, public, message })` returning the SCALE encoded
+//!   signature or `null` if the key is unknown.
+//! - `keystore_sr25519VrfSign({ keyType, public, transcript })` returning
+//!   `{ output, proof }` or `null` if the key is unknown.
+//! - `keystore_authenticate({ token })` returning `true`. A signer configured with a token
+//!   rejects all other requests on a connection until it is authenticated.
+//!
+//! The token is given with [`RemoteKeystore::with_token`], or as the user of the URI, e.g.
+//! `tcp://token@host:port`. Use [`redact_token`] before showing such a URI anywhere.
+//!
+//! TCP connections are not encrypted. Anyone who can observe the traffic between the node and
+//! the signer learns the token and can then request signatures with all the keys of the signer.
+//! Only use TCP over the loopback interface or a link that is otherwise secured, e.g. a VPN or an
+//! SSH tunnel, and prefer Unix sockets when the signer runs on the same host.
+//!
+//! [`RemoteSigner`] implements the signer side on top of any [`SyncCryptoStore`].
+
+use std::{
+	collections::{HashMap, HashSet},
+	io::{self, BufRead, BufReader, Read, Write},
+	net::{TcpStream, ToSocketAddrs},
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
+	time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::{os::unix::net::UnixStream, path::PathBuf};
+use async_trait::async_trait;
+use parking_lot::{Mutex, RwLock};
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId, Public},
+	Bytes,
+};
+use sp_keystore::{
+	CryptoStore,
+	SyncCryptoStorePtr,
+	Error as TraitError,
+	SyncCryptoStore,
+	vrf::{VRFTranscriptData, VRFTranscriptValue, VRFSignature},
+};
+use sp_application_crypto::{ed25519, sr25519, ecdsa};
+use subtle::ConstantTimeEq;
+
+use crate::{Result, Error};
+
+/// Default time after which a request to the signer is abandoned.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+/// Default time public keys returned by the signer are cached for.
+pub const DEFAULT_KEY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Maximum number of distinct transcript labels the signer accepts.
+///
+/// Transcript labels have to be `'static`, so the signer leaks every label it has not seen
+/// before. The limit bounds the memory a misbehaving client can make it leak.
+const MAX_TRANSCRIPT_LABELS: usize = 256;
+
+const CRYPTO_TYPES: [CryptoTypeId; 3] = [sr25519::CRYPTO_ID, ed25519::CRYPTO_ID, ecdsa::CRYPTO_ID];
+
+/// Length of the public keys of the given crypto type.
+fn public_key_len(crypto_type: CryptoTypeId) -> Option<usize> {
+	match crypto_type {
+		sr25519::CRYPTO_ID | ed25519::CRYPTO_ID => Some(32),
+		ecdsa::CRYPTO_ID => Some(33),
+		_ => None,
+	}
+}
+
+/// Where the signer can be reached.
+#[derive(Debug, Clone)]
+enum Endpoint {
+	Tcp(String),
+	#[cfg(unix)]
+	Unix(PathBuf),
+}
+
+impl Endpoint {
+	/// Parse `uri` into the endpoint and the token to authenticate with, if any.
+	fn parse(uri: &str) -> Result<(Self, Option<String>)> {
+		if let Some(path) = uri.strip_prefix("unix://") {
+			let (token, path) = split_token(path);
+			#[cfg(unix)]
+			return Ok((Endpoint::Unix(path.into()), token));
+			#[cfg(not(unix))]
+			return Err(Error::InvalidUri(format!("Unix sockets are not supported: {}", path)));
+		}
+		let address = match uri.strip_prefix("tcp://") {
+			Some(address) => address,
+			None if uri.contains("://") => return Err(Error::InvalidUri(redact_token(uri))),
+			None => uri,
+		};
+		let (token, address) = split_token(address);
+		if address.is_empty() || address.contains("://") {
+			return Err(Error::InvalidUri(redact_token(uri)));
+		}
+		Ok((Endpoint::Tcp(address.into()), token))
+	}
+
+	fn connect(&self, timeout: Duration) -> io::Result<Connection> {
+		match self {
+			Endpoint::Tcp(address) => {
+				let mut last_error = None;
+				for address in address.to_socket_addrs()? {
+					match TcpStream::connect_timeout(&address, timeout) {
+						Ok(stream) => {
+							stream.set_nodelay(true)?;
+							return Ok(Connection::Tcp(stream));
+						},
+						Err(e) => last_error = Some(e),
+					}
+				}
+				Err(last_error.unwrap_or_else(|| io::Error::new(
+					io::ErrorKind::InvalidInput,
+					"Signer address does not resolve",
+				)))
+			},
+			#[cfg(unix)]
+			Endpoint::Unix(path) => UnixStream::connect(path).map(Connection::Unix),
+		}
+	}
+}
+
+fn split_token(address: &str) -> (Option<String>, &str) {
+	match address.find('@') {
+		Some(i) => (Some(address[..i].into()), &address[i + 1..]),
+		None => (None, address),
+	}
+}
+
+/// Remove the token from a signer `uri`, so it can be logged or shown in an error.
+pub fn redact_token(uri: &str) -> String {
+	let (scheme, rest) = match uri.find("://") {
+		Some(i) => uri.split_at(i + 3),
+		None => ("", uri),
+	};
+	format!("{}{}", scheme, split_token(rest).1)
+}
+
+/// An open connection to the signer.
+enum Connection {
+	Tcp(TcpStream),
+	#[cfg(unix)]
+	Unix(UnixStream),
+}
+
+impl Connection {
+	fn set_timeout(&self, timeout: Duration) -> io::Result<()> {
+		match self {
+			Connection::Tcp(stream) => {
+				stream.set_read_timeout(Some(timeout))?;
+				stream.set_write_timeout(Some(timeout))
+			},
+			#[cfg(unix)]
+			Connection::Unix(stream) => {
+				stream.set_read_timeout(Some(timeout))?;
+				stream.set_write_timeout(Some(timeout))
+			},
+		}
+	}
+}
+
+impl Read for Connection {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		match self {
+			Connection::Tcp(stream) => stream.read(buf),
+			#[cfg(unix)]
+			Connection::Unix(stream) => stream.read(buf),
+		}
+	}
+}
+
+impl Write for Connection {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		match self {
+			Connection::Tcp(stream) => stream.write(buf),
+			#[cfg(unix)]
+			Connection::Unix(stream) => stream.write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		match self {
+			Connection::Tcp(stream) => stream.flush(),
+			#[cfg(unix)]
+			Connection::Unix(stream) => stream.flush(),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request<T> {
+	jsonrpc: String,
+	id: u64,
+	method: String,
+	params: T,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+	jsonrpc: String,
+	id: Option<u64>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	result: Option<serde_json::Value>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	error: Option<RpcError>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RpcError {
+	code: i64,
+	message: String,
+}
+
+const METHOD_PUBLIC_KEYS: &str = "keystore_publicKeys";
+const METHOD_SIGN: &str = "keystore_sign";
+const METHOD_VRF_SIGN: &str = "keystore_sr25519VrfSign";
+const METHOD_AUTHENTICATE: &str = "keystore_authenticate";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SIGNER_ERROR: i64 = -32000;
+const UNAUTHORIZED: i64 = -32001;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeysParams {
+	key_type: Bytes,
+	crypto_type: Bytes,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AuthenticateParams {
+	token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignParams {
+	key_type: Bytes,
+	crypto_type: Bytes,
+	public: Bytes,
+	message: Bytes,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VrfSignParams {
+	key_type: Bytes,
+	public: Bytes,
+	transcript: Transcript,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Transcript {
+	label: Bytes,
+	items: Vec<(String, TranscriptValue)>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum TranscriptValue {
+	Bytes(Bytes),
+	U64(u64),
+}
+
+#[derive(Serialize, Deserialize)]
+struct VrfSignature {
+	output: Bytes,
+	proof: Bytes,
+}
+
+impl From<VRFTranscriptData> for Transcript {
+	fn from(data: VRFTranscriptData) -> Self {
+		Transcript {
+			label: data.label.to_vec().into(),
+			items: data.items.into_iter().map(|(label, value)| {
+				let value = match value {
+					VRFTranscriptValue::Bytes(bytes) => TranscriptValue::Bytes(bytes.into()),
+					VRFTranscriptValue::U64(val) => TranscriptValue::U64(val),
+				};
+				(label.into(), value)
+			}).collect(),
+		}
+	}
+}
+
+impl From<VRFSignature> for VrfSignature {
+	fn from(signature: VRFSignature) -> Self {
+		VrfSignature {
+			output: signature.output.to_bytes().to_vec().into(),
+			proof: signature.proof.to_bytes().to_vec().into(),
+		}
+	}
+}
+
+impl VrfSignature {
+	fn into_signature(self) -> Result<VRFSignature> {
+		let invalid = |_| Error::Remote("Invalid VRF signature".into());
+		Ok(VRFSignature {
+			output: schnorrkel::vrf::VRFOutput::from_bytes(&self.output).map_err(invalid)?,
+			proof: schnorrkel::vrf::VRFProof::from_bytes(&self.proof).map_err(invalid)?,
+		})
+	}
+}
+
+fn key_type_from_bytes(bytes: &[u8]) -> Option<KeyTypeId> {
+	let mut id = [0u8; 4];
+	if bytes.len() != id.len() {
+		return None;
+	}
+	id.copy_from_slice(bytes);
+	Some(KeyTypeId(id))
+}
+
+fn crypto_type_from_bytes(bytes: &[u8]) -> Option<CryptoTypeId> {
+	key_type_from_bytes(bytes).map(|KeyTypeId(id)| CryptoTypeId(id))
+}
+
+struct CachedKeys {
+	keys: Vec<Vec<u8>>,
+	fetched: Instant,
+}
+
+/// A keystore that forwards all requests to an external signer.
+///
+/// Public keys returned by the signer are cached for [`DEFAULT_KEY_CACHE_TTL`] unless
+/// configured otherwise. Key generation and insertion are not supported; keys have to be
+/// managed on the signer.
+///
+/// Requests made through [`CryptoStore`] are sent from a separate thread, so they don't block
+/// the executor polling them. Clones share the connection and the key cache.
+#[derive(Clone)]
+pub struct RemoteKeystore {
+	endpoint: Endpoint,
+	token: Option<String>,
+	request_timeout: Duration,
+	key_cache_ttl: Duration,
+	connection: Arc<Mutex<Option<BufReader<Connection>>>>,
+	next_id: Arc<AtomicU64>,
+	public_keys: Arc<RwLock<HashMap<(KeyTypeId, CryptoTypeId), CachedKeys>>>,
+}
+
+impl RemoteKeystore {
+	/// Create a keystore that talks to the signer at `uri`.
+	///
+	/// The connection is established on the first request and re-established after any
+	/// connection failure.
+	pub fn open(uri: &str) -> Result<Self> {
+		let (endpoint, token) = Endpoint::parse(uri)?;
+		Ok(Self {
+			endpoint,
+			token,
+			request_timeout: DEFAULT_REQUEST_TIMEOUT,
+			key_cache_ttl: DEFAULT_KEY_CACHE_TTL,
+			connection: Arc::new(Mutex::new(None)),
+			next_id: Arc::new(AtomicU64::new(0)),
+			public_keys: Arc::new(RwLock::new(HashMap::new())),
+		})
+	}
+
+	/// Authenticate with `token` instead of the token given in the URI, if any.
+	pub fn with_token(mut self, token: String) -> Self {
+		self.token = Some(token);
+		self
+	}
+
+	/// Set the time after which a request to the signer fails with [`Error::Timeout`].
+	pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+		self.request_timeout = timeout;
+		self
+	}
+
+	/// Set the time public keys are cached for.
+	pub fn with_key_cache_ttl(mut self, ttl: Duration) -> Self {
+		self.key_cache_ttl = ttl;
+		self
+	}
+
+	/// Drop all cached public keys, so they are fetched from the signer again.
+	pub fn clear_key_cache(&self) {
+		self.public_keys.write().clear();
+	}
+
+	fn request<P: Serialize, R: DeserializeOwned>(&self, method: &str, params: P) -> Result<R> {
+		let deadline = Instant::now() + self.request_timeout;
+		let mut connection = self.connection.lock();
+		if connection.is_none() {
+			let stream = self.endpoint.connect(self.request_timeout).map_err(|e| match e {
+				e if is_timeout(&e) => Error::Timeout,
+				e => Error::Io(e),
+			})?;
+			let mut stream = BufReader::new(stream);
+			if let Some(token) = &self.token {
+				let params = AuthenticateParams { token: token.clone() };
+				self.exchange(&mut stream, METHOD_AUTHENTICATE, params, deadline)?;
+			}
+			*connection = Some(stream);
+		}
+		let response = self.exchange(
+			connection.as_mut().expect("Connection is established above; qed"),
+			method,
+			params,
+			deadline,
+		);
+		if response.is_err() {
+			// The connection is in an unknown state, start over on the next request.
+			*connection = None;
+		}
+		drop(connection);
+
+		match response? {
+			Response { error: Some(error), .. } => Err(Error::Remote(error.message)),
+			Response { result, .. } => Ok(serde_json::from_value(
+				result.unwrap_or(serde_json::Value::Null)
+			)?),
+		}
+	}
+
+	/// Send a request over `connection` and wait for its response.
+	///
+	/// Fails if the response can't be read in time or is an error for the authentication
+	/// request.
+	fn exchange<P: Serialize>(
+		&self,
+		connection: &mut BufReader<Connection>,
+		method: &str,
+		params: P,
+		deadline: Instant,
+	) -> Result<Response> {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let mut request = serde_json::to_vec(&Request {
+			jsonrpc: "2.0".into(),
+			id,
+			method: method.into(),
+			params,
+		})?;
+		request.push(b'\n');
+
+		let response = Self::send(connection, id, &request, deadline).map_err(|e| match e {
+			Error::Io(e) if is_timeout(&e) => Error::Timeout,
+			e => e,
+		})?;
+		match response {
+			Response { error: Some(error), .. } if method == METHOD_AUTHENTICATE =>
+				Err(Error::Remote(error.message)),
+			response => Ok(response),
+		}
+	}
+
+	fn send(
+		connection: &mut BufReader<Connection>,
+		id: u64,
+		request: &[u8],
+		deadline: Instant,
+	) -> Result<Response> {
+		connection.get_ref().set_timeout(remaining(deadline)?)?;
+		connection.get_mut().write_all(request)?;
+		connection.get_mut().flush()?;
+
+		let mut line = Vec::new();
+		loop {
+			connection.get_ref().set_timeout(remaining(deadline)?)?;
+			let (done, used) = {
+				let available = connection.fill_buf()?;
+				if available.is_empty() {
+					return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+				}
+				match available.iter().position(|b| *b == b'\n') {
+					Some(i) => {
+						line.extend_from_slice(&available[..i]);
+						(true, i + 1)
+					},
+					None => {
+						line.extend_from_slice(available);
+						(false, available.len())
+					},
+				}
+			};
+			connection.consume(used);
+			if done {
+				break;
+			}
+		}
+
+		let response: Response = serde_json::from_slice(&line)?;
+		if response.id != Some(id) {
+			return Err(Error::Remote(format!(
+				"Response id mismatch: expected {}, got {:?}",
+				id,
+				response.id,
+			)));
+		}
+		Ok(response)
+	}
+
+	/// Returns public keys for the given key and crypto type.
+	///
+	/// Falls back to stale cached keys if the signer can't be reached.
+	fn raw_public_keys(&self, key_type: KeyTypeId, crypto_type: CryptoTypeId) -> Result<Vec<Vec<u8>>> {
+		if let Some(cached) = self.public_keys.read().get(&(key_type, crypto_type)) {
+			if cached.fetched.elapsed() < self.key_cache_ttl {
+				return Ok(cached.keys.clone());
+			}
+		}
+
+		let params = PublicKeysParams {
+			key_type: key_type.0.to_vec().into(),
+			crypto_type: crypto_type.0.to_vec().into(),
+		};
+		let keys = self.request::<_, Vec<Bytes>>(METHOD_PUBLIC_KEYS, params).and_then(|keys| {
+			let len = public_key_len(crypto_type);
+			keys.into_iter().map(|key| match len {
+				Some(len) if key.len() != len => Err(Error::Remote(format!(
+					"Invalid public key length {}, expected {}",
+					key.len(),
+					len,
+				))),
+				_ => Ok(key.0),
+			}).collect::<Result<Vec<_>>>()
+		});
+		match keys {
+			Ok(keys) => {
+				self.public_keys.write().insert(
+					(key_type, crypto_type),
+					CachedKeys { keys: keys.clone(), fetched: Instant::now() },
+				);
+				Ok(keys)
+			},
+			Err(e) => match self.public_keys.read().get(&(key_type, crypto_type)) {
+				Some(cached) => {
+					log::warn!(
+						target: "keystore",
+						"Failed to refresh public keys from remote signer, using cached keys: {}",
+						e,
+					);
+					Ok(cached.keys.clone())
+				},
+				None => Err(e),
+			},
+		}
+	}
+
+	fn public_keys<P: Public>(&self, key_type: KeyTypeId, crypto_type: CryptoTypeId) -> Vec<P> {
+		match self.raw_public_keys(key_type, crypto_type) {
+			// The length of the keys is checked by `raw_public_keys`.
+			Ok(keys) => keys.iter().map(|k| P::from_slice(k)).collect(),
+			Err(e) => {
+				log::warn!(target: "keystore", "Failed to fetch public keys from remote signer: {}", e);
+				Vec::new()
+			},
+		}
+	}
+}
+
+fn remaining(deadline: Instant) -> Result<Duration> {
+	let now = Instant::now();
+	if now >= deadline {
+		return Err(Error::Timeout);
+	}
+	Ok(deadline - now)
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+	matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+}
+
+/// Run `f` on a new thread, so blocking socket I/O doesn't block the executor.
+async fn spawn_blocking<R: Send + 'static>(f: impl FnOnce() -> R + Send + 'static) -> R {
+	let (sender, receiver) = futures::channel::oneshot::channel();
+	std::thread::spawn(move || {
+		let _ = sender.send(f());
+	});
+	receiver.await.expect("The result is sent unless the request panicked; qed")
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn keys(&self, id: KeyTypeId) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		let this = self.clone();
+		spawn_blocking(move || SyncCryptoStore::keys(&this, id)).await
+	}
+
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		let this = self.clone();
+		spawn_blocking(move || SyncCryptoStore::sr25519_public_keys(&this, id)).await
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		SyncCryptoStore::sr25519_generate_new(self, id, seed)
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		let this = self.clone();
+		spawn_blocking(move || SyncCryptoStore::ed25519_public_keys(&this, id)).await
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		SyncCryptoStore::ed25519_generate_new(self, id, seed)
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		let this = self.clone();
+		spawn_blocking(move || SyncCryptoStore::ecdsa_public_keys(&this, id)).await
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		SyncCryptoStore::ecdsa_generate_new(self, id, seed)
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> std::result::Result<(), ()> {
+		SyncCryptoStore::insert_unknown(self, id, suri, public)
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let this = self.clone();
+		let public_keys = public_keys.to_vec();
+		spawn_blocking(move || SyncCryptoStore::has_keys(&this, &public_keys)).await
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		let this = self.clone();
+		spawn_blocking(move || SyncCryptoStore::supported_keys(&this, id, keys)).await
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> std::result::Result<Option<Vec<u8>>, TraitError> {
+		let this = self.clone();
+		let (key, msg) = (key.clone(), msg.to_vec());
+		spawn_blocking(move || SyncCryptoStore::sign_with(&this, id, &key, &msg)).await
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> std::result::Result<Option<VRFSignature>, TraitError> {
+		let this = self.clone();
+		let public = *public;
+		spawn_blocking(move || {
+			SyncCryptoStore::sr25519_vrf_sign(&this, key_type, &public, transcript_data)
+		}).await
+	}
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn keys(
+		&self,
+		id: KeyTypeId
+	) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		let mut keys = Vec::new();
+		for crypto_type in CRYPTO_TYPES.iter() {
+			keys.extend(
+				self.raw_public_keys(id, *crypto_type)?
+					.into_iter()
+					.map(|k| CryptoTypePublicPair(*crypto_type, k))
+			);
+		}
+		Ok(keys)
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>
+	) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		let all_keys = SyncCryptoStore::keys(self, id)?
+			.into_iter()
+			.collect::<HashSet<_>>();
+		Ok(keys.into_iter().filter(|key| all_keys.contains(key)).collect::<Vec<_>>())
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> std::result::Result<Option<Vec<u8>>, TraitError> {
+		if !CRYPTO_TYPES.contains(&key.0) {
+			return Err(TraitError::KeyNotSupported(id));
+		}
+		let params = SignParams {
+			key_type: id.0.to_vec().into(),
+			crypto_type: key.0 .0.to_vec().into(),
+			public: key.1.clone().into(),
+			message: msg.to_vec().into(),
+		};
+		self.request::<_, Option<Bytes>>(METHOD_SIGN, params)
+			.map(|signature| signature.map(|s| s.0))
+			.map_err(Into::into)
+	}
+
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(key_type, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		_id: KeyTypeId,
+		_seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		Err(Error::Unsupported.into())
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(key_type, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		_id: KeyTypeId,
+		_seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		Err(Error::Unsupported.into())
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(key_type, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		_id: KeyTypeId,
+		_seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		Err(Error::Unsupported.into())
+	}
+
+	fn insert_unknown(&self, _key_type: KeyTypeId, _suri: &str, _public: &[u8])
+		-> std::result::Result<(), ()>
+	{
+		Err(())
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		public_keys.iter().all(|(public, key_type)| {
+			CRYPTO_TYPES.iter().any(|crypto_type| {
+				self.raw_public_keys(*key_type, *crypto_type)
+					.map(|keys| keys.contains(public))
+					.unwrap_or(false)
+			})
+		})
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> std::result::Result<Option<VRFSignature>, TraitError> {
+		let params = VrfSignParams {
+			key_type: key_type.0.to_vec().into(),
+			public: public.to_raw_vec().into(),
+			transcript: transcript_data.into(),
+		};
+		self.request::<_, Option<VrfSignature>>(METHOD_VRF_SIGN, params)?
+			.map(VrfSignature::into_signature)
+			.transpose()
+			.map_err(Into::into)
+	}
+}
+
+impl Into<SyncCryptoStorePtr> for RemoteKeystore {
+	fn into(self) -> SyncCryptoStorePtr {
+		Arc::new(self)
+	}
+}
+
+impl Into<Arc<dyn CryptoStore>> for RemoteKeystore {
+	fn into(self) -> Arc<dyn CryptoStore> {
+		Arc::new(self)
+	}
+}
+
+/// The signer side of the remote keystore protocol.
+///
+/// Answers requests of a [`RemoteKeystore`] using the wrapped keystore.
+pub struct RemoteSigner {
+	keystore: SyncCryptoStorePtr,
+	token: Option<String>,
+	labels: Mutex<HashSet<&'static str>>,
+}
+
+impl RemoteSigner {
+	/// Create a signer that serves the keys of `keystore`.
+	pub fn new(keystore: SyncCryptoStorePtr) -> Self {
+		Self { keystore, token: None, labels: Mutex::new(HashSet::new()) }
+	}
+
+	/// Only answer requests on connections which authenticated with `token`.
+	pub fn with_token(mut self, token: String) -> Self {
+		self.token = Some(token);
+		self
+	}
+
+	/// Answer requests read from `stream` until it is closed.
+	pub fn serve<S: Read + Write>(&self, stream: S) -> io::Result<()> {
+		let mut stream = BufReader::new(stream);
+		let mut line = String::new();
+		let mut authenticated = false;
+		loop {
+			line.clear();
+			if stream.read_line(&mut line)? == 0 {
+				return Ok(());
+			}
+			if line.trim().is_empty() {
+				continue;
+			}
+			let mut response = self.handle_request(&line, &mut authenticated);
+			response.push('\n');
+			stream.get_mut().write_all(response.as_bytes())?;
+			stream.get_mut().flush()?;
+		}
+	}
+
+	/// Answer a single JSON-RPC request.
+	///
+	/// `authenticated` is the authentication state of the connection the request was received
+	/// on, it is set by a successful authentication request.
+	pub fn handle_request(&self, request: &str, authenticated: &mut bool) -> String {
+		let response = match serde_json::from_str::<Request<serde_json::Value>>(request) {
+			Ok(Request { id, method, params, .. }) => {
+				let result = self.authenticate(&method, &params, authenticated)
+					.and_then(|()| self.dispatch(&method, params));
+				let (result, error) = match result {
+					Ok(result) => (Some(result), None),
+					Err(error) => (None, Some(error)),
+				};
+				Response { jsonrpc: "2.0".into(), id: Some(id), result, error }
+			},
+			Err(e) => Response {
+				jsonrpc: "2.0".into(),
+				id: None,
+				result: None,
+				error: Some(RpcError { code: PARSE_ERROR, message: e.to_string() }),
+			},
+		};
+		serde_json::to_string(&response).expect("Responses are always serializable; qed")
+	}
+
+	/// Check an authentication request or whether the connection is authenticated.
+	fn authenticate(
+		&self,
+		method: &str,
+		params: &serde_json::Value,
+		authenticated: &mut bool,
+	) -> std::result::Result<(), RpcError> {
+		if method != METHOD_AUTHENTICATE {
+			return match (&self.token, *authenticated) {
+				(Some(_), false) => Err(RpcError {
+					code: UNAUTHORIZED,
+					message: "Not authenticated".into(),
+				}),
+				_ => Ok(()),
+			}
+		}
+		let AuthenticateParams { token } = serde_json::from_value(params.clone())
+			.map_err(|e| RpcError { code: INVALID_PARAMS, message: e.to_string() })?;
+		match &self.token {
+			Some(expected) if !bool::from(expected.as_bytes().ct_eq(token.as_bytes())) => {
+				*authenticated = false;
+				Err(RpcError { code: UNAUTHORIZED, message: "Invalid token".into() })
+			},
+			_ => {
+				*authenticated = true;
+				Ok(())
+			},
+		}
+	}
+
+	fn dispatch(
+		&self,
+		method: &str,
+		params: serde_json::Value,
+	) -> std::result::Result<serde_json::Value, RpcError> {
+		fn decode<T: DeserializeOwned>(params: serde_json::Value) -> std::result::Result<T, RpcError> {
+			// Treat missing parameters the same as an empty parameter object.
+			let params = match params {
+				serde_json::Value::Null => serde_json::Value::Object(Default::default()),
+				params => params,
+			};
+			serde_json::from_value(params)
+				.map_err(|e| RpcError { code: INVALID_PARAMS, message: e.to_string() })
+		}
+		fn invalid(what: &str) -> RpcError {
+			RpcError { code: INVALID_PARAMS, message: format!("Invalid {}", what) }
+		}
+		fn signer_error(e: TraitError) -> RpcError {
+			RpcError { code: SIGNER_ERROR, message: e.to_string() }
+		}
+
+		let result = match method {
+			METHOD_AUTHENTICATE => Ok(serde_json::Value::Bool(true)),
+			METHOD_PUBLIC_KEYS => {
+				let PublicKeysParams { key_type, crypto_type } = decode(params)?;
+				let key_type = key_type_from_bytes(&key_type).ok_or_else(|| invalid("key type"))?;
+				let crypto_type = crypto_type_from_bytes(&crypto_type)
+					.ok_or_else(|| invalid("crypto type"))?;
+				let keys: Vec<Bytes> = SyncCryptoStore::keys(&*self.keystore, key_type)
+					.map_err(signer_error)?
+					.into_iter()
+					.filter(|k| k.0 == crypto_type)
+					.map(|k| k.1.into())
+					.collect();
+				serde_json::to_value(keys)
+			},
+			METHOD_SIGN => {
+				let SignParams { key_type, crypto_type, public, message } = decode(params)?;
+				let key_type = key_type_from_bytes(&key_type).ok_or_else(|| invalid("key type"))?;
+				let crypto_type = crypto_type_from_bytes(&crypto_type)
+					.ok_or_else(|| invalid("crypto type"))?;
+				let key = CryptoTypePublicPair(crypto_type, public.0);
+				let signature = SyncCryptoStore::sign_with(&*self.keystore, key_type, &key, &message)
+					.map_err(signer_error)?;
+				serde_json::to_value(signature.map(Bytes))
+			},
+			METHOD_VRF_SIGN => {
+				let VrfSignParams { key_type, public, transcript } = decode(params)?;
+				let key_type = key_type_from_bytes(&key_type).ok_or_else(|| invalid("key type"))?;
+				if public.len() != 32 {
+					return Err(invalid("public key"));
+				}
+				let public = sr25519::Public::from_slice(&public);
+				let transcript = self.transcript_data(transcript)?;
+				let signature = SyncCryptoStore::sr25519_vrf_sign(
+					&*self.keystore,
+					key_type,
+					&public,
+					transcript,
+				).map_err(signer_error)?;
+				serde_json::to_value(signature.map(VrfSignature::from))
+			},
+			_ => return Err(RpcError {
+				code: METHOD_NOT_FOUND,
+				message: format!("Method not found: {}", method),
+			}),
+		};
+		result.map_err(|e| RpcError { code: SIGNER_ERROR, message: e.to_string() })
+	}
+
+	fn transcript_data(&self, transcript: Transcript) -> std::result::Result<VRFTranscriptData, RpcError> {
+		let label = String::from_utf8(transcript.label.0)
+			.map_err(|_| RpcError { code: INVALID_PARAMS, message: "Invalid transcript label".into() })?;
+		let label = self.intern(label)?.as_bytes();
+		let items = transcript.items.into_iter().map(|(label, value)| {
+			let value = match value {
+				TranscriptValue::Bytes(bytes) => VRFTranscriptValue::Bytes(bytes.0),
+				TranscriptValue::U64(val) => VRFTranscriptValue::U64(val),
+			};
+			Ok((self.intern(label)?, value))
+		}).collect::<std::result::Result<_, _>>()?;
+		Ok(VRFTranscriptData { label, items })
+	}
+
+	fn intern(&self, label: String) -> std::result::Result<&'static str, RpcError> {
+		let mut labels = self.labels.lock();
+		if let Some(label) = labels.get(label.as_str()) {
+			return Ok(label);
+		}
+		if labels.len() >= MAX_TRANSCRIPT_LABELS {
+			return Err(RpcError {
+				code: INVALID_PARAMS,
+				message: "Too many distinct transcript labels".into(),
+			});
+		}
+		let label: &'static str = Box::leak(label.into_boxed_str());
+		labels.insert(label);
+		Ok(label)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{net::TcpListener, thread};
+	use sp_core::{Pair, testing::{ED25519, SR25519}};
+	use sp_keystore::vrf::make_transcript;
+	use crate::LocalKeystore;
+
+	/// Start a signer backed by an in-memory keystore, returning its address.
+	fn start_signer(keystore: Arc<LocalKeystore>) -> String {
+		serve(RemoteSigner::new(keystore))
+	}
+
+	fn serve(signer: RemoteSigner) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+		let signer = Arc::new(signer);
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let signer = signer.clone();
+				let stream = stream.unwrap();
+				thread::spawn(move || signer.serve(stream));
+			}
+		});
+		address
+	}
+
+	/// Start a signer answering public key requests with a key of the wrong length.
+	fn start_broken_signer() -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let mut stream = BufReader::new(stream.unwrap());
+				let mut line = String::new();
+				while stream.read_line(&mut line).unwrap() > 0 {
+					let request: Request<serde_json::Value> = serde_json::from_str(&line).unwrap();
+					let response = format!(r#"{{"jsonrpc":"2.0","id":{},"result":["0x01"]}}"#, request.id);
+					stream.get_mut().write_all(format!("{}\n", response).as_bytes()).unwrap();
+					line.clear();
+				}
+			}
+		});
+		address
+	}
+
+	#[test]
+	fn parses_uris() {
+		assert!(matches!(
+			Endpoint::parse("127.0.0.1:1234"),
+			Ok((Endpoint::Tcp(a), None)) if a == "127.0.0.1:1234"
+		));
+		assert!(matches!(
+			Endpoint::parse("tcp://localhost:1234"),
+			Ok((Endpoint::Tcp(a), None)) if a == "localhost:1234"
+		));
+		assert!(matches!(
+			Endpoint::parse("tcp://secret@localhost:1234"),
+			Ok((Endpoint::Tcp(a), Some(t))) if a == "localhost:1234" && t == "secret"
+		));
+		assert!(matches!(Endpoint::parse("http://localhost:1234"), Err(Error::InvalidUri(_))));
+		assert!(matches!(Endpoint::parse(""), Err(Error::InvalidUri(_))));
+		assert!(matches!(
+			Endpoint::parse("http://secret@localhost:1234"),
+			Err(Error::InvalidUri(u)) if u == "http://localhost:1234"
+		));
+		assert_eq!(redact_token("tcp://secret@localhost:1234"), "tcp://localhost:1234");
+		assert_eq!(redact_token("secret@localhost:1234"), "localhost:1234");
+		assert_eq!(redact_token("unix:///tmp/signer"), "unix:///tmp/signer");
+		#[cfg(unix)]
+		assert!(matches!(
+			Endpoint::parse("unix:///tmp/signer"),
+			Ok((Endpoint::Unix(p), None)) if p == PathBuf::from("/tmp/signer")
+		));
+	}
+
+	#[test]
+	fn public_keys_and_signatures_are_forwarded() {
+		let local = Arc::new(LocalKeystore::in_memory());
+		let sr_public = SyncCryptoStore::sr25519_generate_new(&*local, SR25519, None).unwrap();
+		let ed_public = SyncCryptoStore::ed25519_generate_new(&*local, ED25519, None).unwrap();
+		let remote = RemoteKeystore::open(&start_signer(local)).unwrap();
+
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519), vec![sr_public]);
+		assert_eq!(SyncCryptoStore::ed25519_public_keys(&remote, ED25519), vec![ed_public]);
+		assert!(SyncCryptoStore::has_keys(&remote, &[(sr_public.to_raw_vec(), SR25519)]));
+		assert!(!SyncCryptoStore::has_keys(&remote, &[(ed_public.to_raw_vec(), SR25519)]));
+
+		let msg = b"remote signing";
+		let key = CryptoTypePublicPair(ed25519::CRYPTO_ID, ed_public.to_raw_vec());
+		let signature = SyncCryptoStore::sign_with(&remote, ED25519, &key, msg).unwrap().unwrap();
+		let signature = ed25519::Signature::from_slice(&signature);
+		assert!(ed25519::Pair::verify(&signature, msg, &ed_public));
+
+		let unknown = CryptoTypePublicPair(ed25519::CRYPTO_ID, vec![0u8; 32]);
+		assert_eq!(SyncCryptoStore::sign_with(&remote, ED25519, &unknown, msg).unwrap(), None);
+	}
+
+	#[test]
+	fn vrf_signatures_are_forwarded() {
+		let local = Arc::new(LocalKeystore::in_memory());
+		let public = SyncCryptoStore::sr25519_generate_new(&*local, SR25519, None).unwrap();
+		let remote = RemoteKeystore::open(&start_signer(local.clone())).unwrap();
+
+		let transcript_data = || VRFTranscriptData {
+			label: b"Test",
+			items: vec![
+				("one", VRFTranscriptValue::U64(1)),
+				("two", VRFTranscriptValue::Bytes(b"test".to_vec())),
+			],
+		};
+		let signature = SyncCryptoStore::sr25519_vrf_sign(&remote, SR25519, &public, transcript_data())
+			.unwrap()
+			.unwrap();
+
+		let expected = SyncCryptoStore::sr25519_vrf_sign(&*local, SR25519, &public, transcript_data())
+			.unwrap()
+			.unwrap();
+		assert_eq!(signature.output, expected.output);
+		let schnorrkel_public = schnorrkel::PublicKey::from_bytes(public.as_ref()).unwrap();
+		assert!(
+			schnorrkel_public
+				.vrf_verify(make_transcript(transcript_data()), &signature.output, &signature.proof)
+				.is_ok()
+		);
+
+		let unknown = sr25519::Public::from_raw([0u8; 32]);
+		assert!(
+			SyncCryptoStore::sr25519_vrf_sign(&remote, SR25519, &unknown, transcript_data())
+				.unwrap()
+				.is_none()
+		);
+	}
+
+	#[test]
+	fn async_requests_are_forwarded() {
+		let local = Arc::new(LocalKeystore::in_memory());
+		let public = SyncCryptoStore::sr25519_generate_new(&*local, SR25519, None).unwrap();
+		let remote = RemoteKeystore::open(&start_signer(local)).unwrap();
+
+		let msg = b"remote signing";
+		let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, public.to_raw_vec());
+		futures::executor::block_on(async {
+			assert_eq!(CryptoStore::sr25519_public_keys(&remote, SR25519).await, vec![public]);
+			let signature = CryptoStore::sign_with(&remote, SR25519, &key, msg).await.unwrap().unwrap();
+			let signature = sr25519::Signature::from_slice(&signature);
+			assert!(sr25519::Pair::verify(&signature, msg, &public));
+		});
+	}
+
+	#[test]
+	fn signer_requires_token() {
+		let local = Arc::new(LocalKeystore::in_memory());
+		let public = SyncCryptoStore::sr25519_generate_new(&*local, SR25519, None).unwrap();
+		let address = serve(RemoteSigner::new(local).with_token("secret".into()));
+
+		let remote = RemoteKeystore::open(&address).unwrap();
+		assert!(SyncCryptoStore::keys(&remote, SR25519).is_err());
+		let remote = RemoteKeystore::open(&format!("wrong@{}", address)).unwrap();
+		assert!(SyncCryptoStore::keys(&remote, SR25519).is_err());
+		let remote = RemoteKeystore::open(&format!("tcp://secret@{}", address)).unwrap();
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519), vec![public]);
+		let remote = RemoteKeystore::open(&format!("wrong@{}", address))
+			.unwrap()
+			.with_token("secret".into());
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519), vec![public]);
+	}
+
+	#[test]
+	fn invalid_public_keys_are_rejected() {
+		let remote = RemoteKeystore::open(&start_broken_signer()).unwrap();
+		assert!(matches!(
+			SyncCryptoStore::keys(&remote, SR25519),
+			Err(TraitError::Other(e)) if e.contains("Invalid public key length")
+		));
+		assert!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519).is_empty());
+	}
+
+	#[test]
+	fn public_keys_are_cached() {
+		let local = Arc::new(LocalKeystore::in_memory());
+		let first = SyncCryptoStore::sr25519_generate_new(&*local, SR25519, None).unwrap();
+		let remote = RemoteKeystore::open(&start_signer(local.clone())).unwrap();
+
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519), vec![first]);
+		let second = SyncCryptoStore::sr25519_generate_new(&*local, SR25519, None).unwrap();
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519), vec![first]);
+
+		remote.clear_key_cache();
+		let mut keys = SyncCryptoStore::sr25519_public_keys(&remote, SR25519);
+		keys.sort();
+		let mut expected = vec![first, second];
+		expected.sort();
+		assert_eq!(keys, expected);
+	}
+
+	#[test]
+	fn unresponsive_signer_times_out() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap().to_string();
+		// Accept connections, but never answer.
+		thread::spawn(move || {
+			let _streams = listener.incoming().collect::<Vec<_>>();
+		});
+
+		let remote = RemoteKeystore::open(&address)
+			.unwrap()
+			.with_request_timeout(Duration::from_millis(100));
+		let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, vec![0u8; 32]);
+		let started = Instant::now();
+		let result = SyncCryptoStore::sign_with(&remote, SR25519, &key, b"msg");
+		assert!(matches!(result, Err(TraitError::Other(e)) if e == Error::Timeout.to_string()));
+		assert!(started.elapsed() < Duration::from_secs(5));
+		assert!(SyncCryptoStore::sr25519_public_keys(&remote, SR25519).is_empty());
+	}
+
+	#[test]
+	fn key_generation_is_not_supported() {
+		let remote = RemoteKeystore::open("127.0.0.1:1").unwrap();
+		assert!(SyncCryptoStore::sr25519_generate_new(&remote, SR25519, None).is_err());
+		assert!(SyncCryptoStore::insert_unknown(&remote, SR25519, "//Alice", &[0u8; 32]).is_err());
+	}
+
+	#[test]
+	fn signer_rejects_malformed_requests() {
+		let signer = RemoteSigner::new(Arc::new(LocalKeystore::in_memory()));
+		let response: Response = serde_json::from_str(&signer.handle_request("not json", &mut false)).unwrap();
+		assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+
+		let request = r#"{"jsonrpc":"2.0","id":7,"method":"keystore_unknown","params":{}}"#;
+		let response: Response = serde_json::from_str(&signer.handle_request(request, &mut false)).unwrap();
+		assert_eq!(response.id, Some(7));
+		assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+
+		let request = r#"{"jsonrpc":"2.0","id":8,"method":"keystore_publicKeys","params":{"keyType":"0x01","cryptoType":"0x73723235"}}"#;
+		let response: Response = serde_json::from_str(&signer.handle_request(request, &mut false)).unwrap();
+		assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+	}
+}
@@ -79,6 +79,16 @@ pub struct Behaviour<B: BlockT> {
 	/// [`request_responses::RequestResponsesBehaviour`].
 	#[behaviour(ignore)]
 	block_request_protocol_name: String,
+
+	/// Protocol name used to send out state requests via
+	/// [`request_responses::RequestResponsesBehaviour`].
+	#[behaviour(ignore)]
+	state_request_protocol_name: String,
+
+	/// Protocol name used to send out warp sync requests via
+	/// [`request_responses::RequestResponsesBehaviour`].
+	#[behaviour(ignore)]
+	warp_sync_protocol_name: Option<String>,
 }
 
 /// Event generated by `Behaviour`.
@@ -183,6 +193,8 @@ impl<B: BlockT> Behaviour<B> {
 		block_request_protocol_config: request_responses::ProtocolConfig,
 		bitswap: Option<Bitswap<B>>,
 		light_client_request_protocol_config: request_responses::ProtocolConfig,
+		state_request_protocol_config: request_responses::ProtocolConfig,
+		warp_sync_protocol_config: Option<request_responses::ProtocolConfig>,
 		// All remaining request protocol configs.
 		mut request_response_protocols: Vec<request_responses::ProtocolConfig>,
 	) -> Result<Self, request_responses::RegisterError> {
@@ -190,6 +202,18 @@ impl<B: BlockT> Behaviour<B> {
 		let block_request_protocol_name = block_request_protocol_config.name.to_string();
 		request_response_protocols.push(block_request_protocol_config);
 
+		let state_request_protocol_name = state_request_protocol_config.name.to_string();
+		request_response_protocols.push(state_request_protocol_config);
+
+		let warp_sync_protocol_name = match warp_sync_protocol_config {
+			Some(config) => {
+				let name = config.name.to_string();
+				request_response_protocols.push(config);
+				Some(name)
+			},
+			None => None,
+		};
+
 		request_response_protocols.push(light_client_request_protocol_config);
 
 		Ok(Behaviour {
@@ -203,6 +227,8 @@ impl<B: BlockT> Behaviour<B> {
 			events: VecDeque::new(),
 
 			block_request_protocol_name,
+			state_request_protocol_name,
+			warp_sync_protocol_name,
 		})
 	}
 
@@ -324,6 +350,39 @@ Behaviour<B> {
 					&target, &self.block_request_protocol_name, buf, pending_response, IfDisconnected::ImmediateError,
 				);
 			},
+			CustomMessageOutcome::StateRequest { target, request, pending_response } => {
+				let mut buf = Vec::with_capacity(request.encoded_len());
+				if let Err(err) = request.encode(&mut buf) {
+					log::warn!(
+						target: "sync",
+						"Failed to encode state request {:?}: {:?}",
+						request, err
+					);
+					return
+				}
+
+				self.request_responses.send_request(
+					&target, &self.state_request_protocol_name, buf, pending_response, IfDisconnected::ImmediateError,
+				);
+			},
+			CustomMessageOutcome::WarpSyncRequest { target, request, pending_response } => {
+				match &self.warp_sync_protocol_name {
+					Some(name) => self.request_responses.send_request(
+						&target,
+						name,
+						codec::Encode::encode(&request),
+						pending_response,
+						IfDisconnected::ImmediateError,
+					),
+					None => {
+						log::warn!(
+							target: "sync",
+							"Trying to send warp sync request when no protocol is configured {:?}",
+							request,
+						);
+					}
+				}
+			},
 			CustomMessageOutcome::NotificationStreamOpened { remote, protocol, roles, notifications_sink } => {
 				self.events.push_back(BehaviourOut::NotificationStreamOpened {
 					remote,
@@ -36,6 +36,7 @@ pub use libp2p::{identity, core::PublicKey, wasm_ext::ExtTransport, build_multia
 pub use crate::protocol::ProtocolConfig;
 
 use crate::ExHashT;
+use crate::warp_request_handler::WarpSyncProvider;
 
 use core::{fmt, iter};
 use futures::future;
@@ -123,6 +124,24 @@ pub struct Params<B: BlockT, H: ExHashT> {
 	/// [`crate::light_client_requests::handler::LightClientRequestHandler::new`] allowing
 	/// both outgoing and incoming requests.
 	pub light_client_request_protocol_config: RequestResponseConfig,
+
+	/// Request response configuration for the state request protocol.
+	///
+	/// Can be constructed either via
+	/// [`crate::state_request_handler::generate_protocol_config`] allowing outgoing but not
+	/// incoming requests, or constructed via
+	/// [`crate::state_request_handler::StateRequestHandler::new`] allowing
+	/// both outgoing and incoming requests.
+	pub state_request_protocol_config: RequestResponseConfig,
+
+	/// Optional warp sync protocol support. Include protocol config and sync provider.
+	///
+	/// The protocol config can be constructed either via
+	/// [`crate::warp_request_handler::generate_request_response_config`] allowing outgoing but
+	/// not incoming requests, or constructed via
+	/// [`crate::warp_request_handler::RequestHandler::new`] allowing both outgoing and incoming
+	/// requests.
+	pub warp_sync: Option<(Arc<dyn WarpSyncProvider<B>>, RequestResponseConfig)>,
 }
 
 /// Role of the local node.
@@ -153,6 +172,24 @@ impl fmt::Display for Role {
 	}
 }
 
+/// Sync operation mode.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SyncMode {
+	/// Full block download and verification.
+	Full,
+	/// Download blocks without executing them and download the latest finalized state.
+	Fast,
+	/// Prove finality of the latest finalized block with GRANDPA warp proofs and download its
+	/// state. Blocks below it are not downloaded.
+	Warp,
+}
+
+impl Default for SyncMode {
+	fn default() -> Self {
+		SyncMode::Full
+	}
+}
+
 /// Result of the transaction import.
 #[derive(Clone, Copy, Debug)]
 pub enum TransactionImport {
@@ -395,6 +432,8 @@ pub struct NetworkConfiguration {
 	pub transport: TransportConfig,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// Initial syncing mode.
+	pub sync_mode: SyncMode,
 
 	/// True if Kademlia random discovery should be enabled.
 	///
@@ -457,6 +496,7 @@ impl NetworkConfiguration {
 				wasm_external_transport: None,
 			},
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
 			enable_dht_random_walk: true,
 			allow_non_globals_in_dht: false,
 			kademlia_disjoint_query_paths: false,
@@ -129,6 +129,9 @@
 //! requests for information about blocks. Each request is the encoding of a `BlockRequest` and
 //! each response is the encoding of a `BlockResponse`, as defined in the `api.v1.proto` file in
 //! this source tree.
+//! - **`/<protocol-id>/state/1`** is a request-response protocol that lets one download a range
+//! of the state of a block, along with a proof. Each request is the encoding of a `StateRequest`
+//! and each response is the encoding of a `StateResponse`, as defined in the `api.v1.proto` file.
 //! - **`/<protocol-id>/light/2`** is a request-response protocol (see below) that lets one perform
 //! light-client-related requests for information about the state. Each request is the encoding of
 //! a `light::Request` and each response is the encoding of a `light::Response`, as defined in the
@@ -258,6 +261,8 @@ mod transport;
 mod utils;
 
 pub mod block_request_handler;
+pub mod state_request_handler;
+pub mod warp_request_handler;
 pub mod bitswap;
 pub mod light_client_requests;
 pub mod config;
@@ -268,7 +273,11 @@ pub mod transactions;
 
 #[doc(inline)]
 pub use libp2p::{multiaddr, Multiaddr, PeerId};
-pub use protocol::{event::{DhtEvent, Event, ObservedRole}, sync::SyncState, PeerInfo};
+pub use protocol::{
+	event::{DhtEvent, Event, ObservedRole},
+	sync::{SyncState, StateSyncProgress, WarpSyncProgress, WarpSyncPhase},
+	PeerInfo,
+};
 pub use service::{
 	NetworkService, NetworkWorker, RequestFailure, OutboundFailure, NotificationSender,
 	NotificationSenderReady, IfDisconnected,
@@ -321,4 +330,8 @@ pub struct NetworkStatus<B: BlockT> {
 	pub total_bytes_inbound: u64,
 	/// The total number of bytes sent.
 	pub total_bytes_outbound: u64,
+	/// State sync in progress.
+	pub state_sync: Option<StateSyncProgress>,
+	/// Warp sync in progress.
+	pub warp_sync: Option<WarpSyncProgress>,
 }
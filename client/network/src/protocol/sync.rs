@@ -39,8 +39,15 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 use crate::protocol::message::{
 	self, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse, Roles,
 };
+use crate::config::SyncMode;
+use crate::schema::v1::{StateRequest, StateResponse};
+use crate::warp_request_handler::{
+	EncodedProof, Request as WarpProofRequest, WarpSyncProvider,
+};
 use either::Either;
 use extra_requests::ExtraRequests;
+use state::{StateSync, ImportResult as StateImportResult};
+use warp::{WarpSync, WarpProofImportResult};
 use libp2p::PeerId;
 use log::{debug, trace, warn, info, error};
 use sp_runtime::{
@@ -59,6 +66,11 @@ use futures::{task::Poll, Future, stream::FuturesUnordered, FutureExt, StreamExt
 
 mod blocks;
 mod extra_requests;
+mod state;
+mod warp;
+
+pub use state::StateSyncProgress;
+pub use warp::{WarpSyncPhase, WarpSyncProgress};
 
 /// Maximum blocks to request in a single packet.
 const MAX_BLOCKS_TO_REQUEST: usize = 128;
@@ -91,6 +103,9 @@ const MAX_CONCURRENT_BLOCK_ANNOUNCE_VALIDATIONS_PER_PEER: usize = 4;
 /// so far behind.
 const MAJOR_SYNC_BLOCKS: u8 = 5;
 
+/// Number of peers that need to be connected before warp sync is started.
+const MIN_PEERS_TO_START_WARP_SYNC: usize = 3;
+
 mod rep {
 	use sc_peerset::ReputationChange as Rep;
 	/// Reputation change when a peer sent us a message that led to a
@@ -209,6 +224,18 @@ pub struct ChainSync<B: BlockT> {
 	>,
 	/// Stats per peer about the number of concurrent block announce validations.
 	block_announce_validation_per_peer_stats: HashMap<PeerId, usize>,
+	/// Sync mode. Fast sync starts as header-only and switches to full once the state of a
+	/// recent finalized block has been downloaded.
+	mode: SyncMode,
+	/// State sync in progress, if any.
+	state_sync: Option<StateSync<B>>,
+	/// The block the state was downloaded for. Blocks above it were imported without state
+	/// and are downloaded and imported once again, along with their bodies.
+	state_sync_base: Option<(B::Hash, NumberFor<B>)>,
+	/// Warp sync in progress, if any.
+	warp_sync: Option<WarpSync<B>>,
+	/// Warp sync provider.
+	warp_sync_provider: Option<Arc<dyn WarpSyncProvider<B>>>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -281,6 +308,10 @@ pub enum PeerSyncState<B: BlockT> {
 	DownloadingStale(B::Hash),
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
+	/// Downloading state.
+	DownloadingState,
+	/// Downloading warp proof.
+	DownloadingWarpProof,
 }
 
 impl<B: BlockT> PeerSyncState<B> {
@@ -309,6 +340,10 @@ pub struct Status<B: BlockT> {
 	pub num_peers: u32,
 	/// Number of blocks queued for import
 	pub queued_blocks: u32,
+	/// State sync status in progress, if any.
+	pub state_sync: Option<StateSyncProgress>,
+	/// Warp sync in progress, if any.
+	pub warp_sync: Option<WarpSyncProgress>,
 }
 
 /// A peer did not behave as expected and should be reported.
@@ -344,6 +379,16 @@ impl<B: BlockT> OnBlockData<B> {
 	}
 }
 
+/// Result of [`ChainSync::on_state_data`].
+#[derive(Debug)]
+pub enum OnStateData<B: BlockT> {
+	/// The block and state that should be imported.
+	Import(BlockOrigin, IncomingBlock<B>),
+	/// More state data is needed. The next request is returned by
+	/// [`ChainSync::state_request`].
+	Continue,
+}
+
 /// Result of [`ChainSync::poll_block_announce_validation`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PollBlockAnnounceValidation<H> {
@@ -442,15 +487,30 @@ enum HasSlotForBlockAnnounceValidation {
 impl<B: BlockT> ChainSync<B> {
 	/// Create a new instance.
 	pub fn new(
+		mode: SyncMode,
 		role: Roles,
 		client: Arc<dyn crate::chain::Client<B>>,
 		info: &BlockchainInfo<B>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		warp_sync_provider: Option<Arc<dyn WarpSyncProvider<B>>>,
 	) -> Self {
+		// Fast sync is only useful on a node that does not have the state of a recent block yet.
+		// Warp sync starts at genesis, so it is only used on a node that has not imported any
+		// blocks yet.
+		let mode = match mode {
+			SyncMode::Fast if !role.is_full() => SyncMode::Full,
+			SyncMode::Warp if !role.is_full() || warp_sync_provider.is_none() => SyncMode::Full,
+			SyncMode::Warp if !info.best_number.is_zero() => SyncMode::Full,
+			SyncMode::Fast if !info.finalized_number.is_zero() && client
+				.block_status(&BlockId::Hash(info.finalized_hash))
+				.map_or(false, |s| s == BlockStatus::InChainWithState) => SyncMode::Full,
+			mode => mode,
+		};
+
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
-		if role.is_full() {
+		if role.is_full() && mode == SyncMode::Full {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
@@ -471,6 +531,11 @@ impl<B: BlockT> ChainSync<B> {
 			downloaded_blocks: 0,
 			block_announce_validation: Default::default(),
 			block_announce_validation_per_peer_stats: Default::default(),
+			mode,
+			state_sync: None,
+			state_sync_base: None,
+			warp_sync: None,
+			warp_sync_provider,
 		}
 	}
 
@@ -497,11 +562,22 @@ impl<B: BlockT> ChainSync<B> {
 				SyncState::Idle
 			};
 
+		let warp_sync_progress = match (&self.warp_sync, &self.mode) {
+			(None, SyncMode::Warp) => Some(WarpSyncProgress {
+				phase: WarpSyncPhase::AwaitingPeers,
+				total_bytes: 0,
+			}),
+			(Some(sync), _) => Some(sync.progress()),
+			_ => None,
+		};
+
 		Status {
 			state: sync_state,
 			best_seen_block: best_seen,
 			num_peers: self.peers.len() as u32,
 			queued_blocks: self.queue_blocks.len() as u32,
+			state_sync: self.state_sync.as_ref().map(|s| s.progress()),
+			warp_sync: warp_sync_progress,
 		}
 	}
 
@@ -604,9 +680,16 @@ impl<B: BlockT> ChainSync<B> {
 					best_hash,
 					best_number,
 				);
+				// Blocks above the downloaded state are known, but still have to be imported
+				// again with their bodies.
+				let common_number = if self.state_sync_base.is_some() {
+					std::cmp::min(best_number, self.best_queued_number)
+				} else {
+					best_number
+				};
 				self.peers.insert(who.clone(), PeerSync {
 					peer_id: who.clone(),
-					common_number: best_number,
+					common_number,
 					best_hash,
 					best_number,
 					state: PeerSyncState::Available,
@@ -712,7 +795,7 @@ impl<B: BlockT> ChainSync<B> {
 
 	/// Get an iterator over all block requests of all peers.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (&PeerId, BlockRequest<B>)> + '_ {
-		if self.pending_requests.is_empty() {
+		if self.pending_requests.is_empty() || self.state_sync.is_some() || self.mode == SyncMode::Warp {
 			return Either::Left(std::iter::empty())
 		}
 		if self.queue_blocks.len() > MAX_IMPORTING_BLOCKS {
@@ -789,6 +872,77 @@ impl<B: BlockT> ChainSync<B> {
 		Either::Right(iter)
 	}
 
+	/// Get a state request, if any.
+	pub fn state_request(&mut self) -> Option<(PeerId, StateRequest)> {
+		if self.peers.iter().any(|(_, peer)| peer.state == PeerSyncState::DownloadingState) {
+			// Only one pending state request is allowed.
+			return None
+		}
+		if let Some(sync) = &self.state_sync {
+			if sync.is_complete() {
+				return None
+			}
+			for (id, peer) in self.peers.iter_mut() {
+				if peer.state.is_available() && peer.common_number >= sync.target_block_num() {
+					trace!(target: "sync", "New state request to {}", id);
+					peer.state = PeerSyncState::DownloadingState;
+					return Some((id.clone(), sync.next_request()))
+				}
+			}
+		}
+		if let Some(sync) = &self.warp_sync {
+			if sync.is_complete() {
+				return None
+			}
+			if let (Some(request), Some(target)) =
+				(sync.next_state_request(), sync.target_block_number())
+			{
+				// The target block is not in our chain yet, so there is no common block to
+				// compare against.
+				for (id, peer) in self.peers.iter_mut() {
+					if peer.state.is_available() && peer.best_number >= target {
+						trace!(target: "sync", "New StateRequest for warp sync to {}", id);
+						peer.state = PeerSyncState::DownloadingState;
+						return Some((id.clone(), request))
+					}
+				}
+			}
+		}
+		None
+	}
+
+	/// Get a warp sync request, if any.
+	pub fn warp_sync_request(&mut self) -> Option<(PeerId, WarpProofRequest<B>)> {
+		if self.mode == SyncMode::Warp
+			&& self.warp_sync.is_none()
+			&& self.peers.len() >= MIN_PEERS_TO_START_WARP_SYNC
+		{
+			if let Some(provider) = &self.warp_sync_provider {
+				info!(target: "sync", "Starting warp sync with {} peers", self.peers.len());
+				self.warp_sync = Some(WarpSync::new(self.client.clone(), provider.clone()));
+			}
+		}
+		if let Some(sync) = &self.warp_sync {
+			if self.peers.iter().any(|(_, peer)| peer.state == PeerSyncState::DownloadingWarpProof) {
+				// Only one pending warp proof request is allowed.
+				return None
+			}
+			if let Some(request) = sync.next_warp_proof_request() {
+				// The peer with the highest best block is the most likely to have finalized the
+				// most recent authority set changes.
+				let peer = self.peers.iter_mut()
+					.filter(|(_, peer)| peer.state.is_available())
+					.max_by_key(|(_, peer)| peer.best_number);
+				if let Some((id, peer)) = peer {
+					trace!(target: "sync", "New WarpProofRequest to {}", id);
+					peer.state = PeerSyncState::DownloadingWarpProof;
+					return Some((id.clone(), request))
+				}
+			}
+		}
+		None
+	}
+
 	/// Handle a response from the remote to a block request that we made.
 	///
 	/// `request` must be the original request that triggered `response`.
@@ -803,6 +957,7 @@ impl<B: BlockT> ChainSync<B> {
 		response: BlockResponse<B>
 	) -> Result<OnBlockData<B>, BadPeer> {
 		self.downloaded_blocks += response.blocks.len();
+		let import_existing = self.state_sync_base.is_some();
 		let mut new_blocks: Vec<IncomingBlock<B>> =
 			if let Some(peer) = self.peers.get_mut(who) {
 				let mut blocks = response.blocks;
@@ -830,7 +985,8 @@ impl<B: BlockT> ChainSync<B> {
 										justification: block_data.block.justification,
 										origin: block_data.origin,
 										allow_missing_state: true,
-										import_existing: false,
+										import_existing,
+										state: None,
 									}
 								}).collect()
 						}
@@ -850,6 +1006,7 @@ impl<B: BlockT> ChainSync<B> {
 									origin: Some(who.clone()),
 									allow_missing_state: true,
 									import_existing: false,
+									state: None,
 								}
 							}).collect()
 						}
@@ -945,7 +1102,9 @@ impl<B: BlockT> ChainSync<B> {
 						}
 
 						| PeerSyncState::Available
-						| PeerSyncState::DownloadingJustification(..) => Vec::new()
+						| PeerSyncState::DownloadingJustification(..)
+						| PeerSyncState::DownloadingState
+						| PeerSyncState::DownloadingWarpProof => Vec::new()
 					}
 				} else {
 					// When request.is_none() this is a block announcement. Just accept blocks.
@@ -959,6 +1118,7 @@ impl<B: BlockT> ChainSync<B> {
 							origin: Some(who.clone()),
 							allow_missing_state: true,
 							import_existing: false,
+							state: None,
 						}
 					}).collect()
 				}
@@ -995,6 +1155,112 @@ impl<B: BlockT> ChainSync<B> {
 		Ok(OnBlockData::Import(origin, new_blocks))
 	}
 
+	/// Handle a state request that we made and which failed without a usable response.
+	///
+	/// The peer is available again, so that the state can be requested once more.
+	pub fn on_state_request_failed(&mut self, who: &PeerId) {
+		if let Some(peer) = self.peers.get_mut(who) {
+			if let PeerSyncState::DownloadingState = peer.state {
+				peer.state = PeerSyncState::Available;
+			}
+		}
+	}
+
+	/// Handle a response from the remote to a state request that we made.
+	///
+	/// Returns the block that must be imported, along with its state, once the state
+	/// download is complete.
+	pub fn on_state_data(
+		&mut self,
+		who: &PeerId,
+		response: StateResponse,
+	) -> Result<OnStateData<B>, BadPeer> {
+		if let Some(peer) = self.peers.get_mut(who) {
+			if let PeerSyncState::DownloadingState = peer.state {
+				peer.state = PeerSyncState::Available;
+			}
+		}
+
+		let import_result = if let Some(sync) = &mut self.state_sync {
+			debug!(
+				target: "sync",
+				"Importing state data from {} with {} bytes of proof",
+				who,
+				response.proof.len(),
+			);
+			sync.import(response)
+		} else if let Some(sync) = &mut self.warp_sync {
+			debug!(
+				target: "sync",
+				"Importing warp sync state data from {} with {} bytes of proof",
+				who,
+				response.proof.len(),
+			);
+			sync.import_state(response)
+		} else {
+			debug!(target: "sync", "Ignored obsolete state response from {}", who);
+			return Err(BadPeer(who.clone(), rep::NOT_REQUESTED));
+		};
+
+		match import_result {
+			StateImportResult::Import(hash, header, state) => {
+				let origin = BlockOrigin::NetworkInitialSync;
+				let block = IncomingBlock {
+					hash,
+					header: Some(header),
+					body: None,
+					justification: None,
+					origin: None,
+					allow_missing_state: true,
+					import_existing: true,
+					state: Some(state),
+				};
+				debug!(target: "sync", "State sync is complete. Import is queued");
+				self.queue_blocks.insert(hash);
+				Ok(OnStateData::Import(origin, block))
+			}
+			StateImportResult::Continue => Ok(OnStateData::Continue),
+			StateImportResult::BadResponse => {
+				debug!(target: "sync", "Bad state data received from {}", who);
+				Err(BadPeer(who.clone(), rep::BAD_RESPONSE))
+			}
+		}
+	}
+
+	/// Handle a response from the remote to a warp proof request that we made.
+	pub fn on_warp_sync_data(
+		&mut self,
+		who: &PeerId,
+		response: EncodedProof,
+	) -> Result<(), BadPeer> {
+		if let Some(peer) = self.peers.get_mut(who) {
+			if let PeerSyncState::DownloadingWarpProof = peer.state {
+				peer.state = PeerSyncState::Available;
+			}
+		}
+
+		let import_result = if let Some(sync) = &mut self.warp_sync {
+			debug!(
+				target: "sync",
+				"Importing warp proof data from {}, {} bytes.",
+				who,
+				response.0.len(),
+			);
+			sync.import_warp_proof(response)
+		} else {
+			debug!(target: "sync", "Ignored obsolete warp sync response from {}", who);
+			return Err(BadPeer(who.clone(), rep::NOT_REQUESTED));
+		};
+
+		match import_result {
+			WarpProofImportResult::Success => Ok(()),
+			WarpProofImportResult::BadResponse => {
+				debug!(target: "sync", "Bad warp proof response from {}", who);
+				Err(BadPeer(who.clone(), rep::BAD_RESPONSE))
+			}
+		}
+	}
+
 	/// Handle a response from the remote to a justification request that we made.
 	///
 	/// `request` must be the original request that triggered `response`.
@@ -1074,6 +1340,14 @@ impl<B: BlockT> ChainSync<B> {
 
 			if result.is_err() {
 				has_error = true;
+				if self.state_sync.as_ref().map_or(false, |s| s.target() == hash) {
+					debug!(target: "sync", "Failed to import the downloaded state of {:?}", hash);
+					self.state_sync = None;
+				}
+				if self.warp_sync.as_ref().map_or(false, |s| s.target_block_hash() == Some(hash)) {
+					debug!(target: "sync", "Failed to import the warp sync target {:?}", hash);
+					self.warp_sync = None;
+				}
 			}
 
 			match result {
@@ -1113,6 +1387,32 @@ impl<B: BlockT> ChainSync<B> {
 					if let Some(peer) = who.and_then(|p| self.peers.get_mut(&p)) {
 						peer.update_common_number(number);
 					}
+
+					if self.state_sync.as_ref().map_or(false, |s| s.target() == hash) {
+						info!(
+							target: "sync",
+							"State sync is complete ({}), restarting block sync.",
+							number,
+						);
+						self.state_sync = None;
+						self.state_sync_base = Some((hash, number));
+						self.mode = SyncMode::Full;
+						self.required_block_attributes |= BlockAttributes::BODY;
+						output.extend(self.restart());
+					} else if self.warp_sync.as_ref().map_or(false, |s| s.target_block_hash() == Some(hash)) {
+						info!(
+							target: "sync",
+							"Warp sync is complete ({}), restarting block sync.",
+							number,
+						);
+						self.warp_sync = None;
+						self.mode = SyncMode::Full;
+						self.required_block_attributes |= BlockAttributes::BODY;
+						output.extend(self.restart());
+					} else if self.state_sync_base.is_some() && number >= self.client.info().best_number {
+						trace!(target: "sync", "Blocks imported before the state are complete");
+						self.state_sync_base = None;
+					}
 				},
 				Err(BlockImportError::IncompleteHeader(who)) => {
 					if let Some(peer) = who {
@@ -1182,6 +1482,24 @@ impl<B: BlockT> ChainSync<B> {
 			is_descendent_of(&**client, base, block)
 		});
 
+		if self.mode == SyncMode::Fast && self.state_sync.is_none() && !self.peers.is_empty() {
+			// Only download the state of a block that is close to the head of the chain.
+			let mut heads: Vec<_> = self.peers.iter().map(|(_, peer)| peer.best_number).collect();
+			heads.sort();
+			let median = heads[heads.len() / 2];
+			if number + MAJOR_SYNC_BLOCKS.into() >= median {
+				if let Ok(Some(header)) = self.client.header(BlockId::hash(hash.clone())) {
+					info!(
+						target: "sync",
+						"Starting state sync for #{} ({})",
+						number,
+						hash,
+					);
+					self.state_sync = Some(StateSync::new(self.client.clone(), header));
+				}
+			}
+		}
+
 		if let Err(err) = r {
 			warn!(
 				target: "sync",
@@ -1540,9 +1858,15 @@ impl<B: BlockT> ChainSync<B> {
 		&'a mut self,
 	) -> impl Iterator<Item = Result<(PeerId, BlockRequest<B>), BadPeer>> + 'a {
 		self.blocks.clear();
-		let info = self.client.info();
-		self.best_queued_hash = info.best_hash;
-		self.best_queued_number = info.best_number;
+		if let Some((hash, number)) = self.state_sync_base {
+			// Start from the block with the downloaded state.
+			self.best_queued_hash = hash;
+			self.best_queued_number = number;
+		} else {
+			let info = self.client.info();
+			self.best_queued_hash = info.best_hash;
+			self.best_queued_number = info.best_number;
+		}
 		self.pending_requests.set_all();
 		debug!(target:"sync", "Restarted with {} ({})", self.best_queued_number, self.best_queued_hash);
 		let old_peers = std::mem::take(&mut self.peers);
@@ -1918,11 +2242,13 @@ mod test {
 		let peer_id = PeerId::random();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
 			block_announce_validator,
 			1,
+			None,
 		);
 
 		let (a1_hash, a1_number) = {
@@ -1989,11 +2315,13 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			1,
+			None,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -2155,11 +2483,13 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			None,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -2269,11 +2599,13 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			None,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -2388,11 +2720,13 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			None,
 		);
 
 		let finalized_block = blocks[MAX_BLOCKS_TO_LOOK_BACKWARDS as usize * 2 - 1].clone();
@@ -0,0 +1,241 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! State sync support.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use codec::{Encode, Decode};
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
+use sp_core::storage::{well_known_keys, ChildInfo, ChildType, PrefixedStorageKey};
+use sc_client_api::StorageProof;
+use sp_consensus::ImportedState;
+use crate::chain::Client;
+use crate::schema::v1::{StateRequest, StateResponse};
+
+/// State sync state machine. Accumulates partial state data until it
+/// is ready to be imported.
+///
+/// The main trie is downloaded first. The child tries whose roots it contains are downloaded
+/// next, one after the other.
+pub struct StateSync<B: BlockT> {
+	target_block: B::Hash,
+	target_header: B::Header,
+	target_root: B::Hash,
+	/// Last key received for the trie being downloaded.
+	last_key: Vec<u8>,
+	state: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Child trie being downloaded, along with the entries received so far.
+	child: Option<(ChildInfo, Vec<(Vec<u8>, Vec<u8>)>)>,
+	/// Child tries found in the main trie which are still to be downloaded.
+	pending_children: VecDeque<ChildInfo>,
+	children: Vec<(ChildInfo, Vec<(Vec<u8>, Vec<u8>)>)>,
+	complete: bool,
+	client: Arc<dyn Client<B>>,
+	imported_bytes: u64,
+}
+
+/// Reported state download progress.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct StateSyncProgress {
+	/// Estimated download percentage.
+	pub percentage: u32,
+	/// Total state size in bytes downloaded so far.
+	pub size: u64,
+}
+
+/// Import state chunk result.
+pub enum ImportResult<B: BlockT> {
+	/// State is complete and ready for import.
+	Import(B::Hash, B::Header, ImportedState<B>),
+	/// Continue downloading.
+	Continue,
+	/// Bad state chunk.
+	BadResponse,
+}
+
+impl<B: BlockT> StateSync<B> {
+	/// Create a new instance.
+	pub fn new(client: Arc<dyn Client<B>>, target: B::Header) -> Self {
+		StateSync {
+			client,
+			target_block: target.hash(),
+			target_root: target.state_root().clone(),
+			target_header: target,
+			last_key: Vec::default(),
+			state: Vec::default(),
+			child: None,
+			pending_children: VecDeque::new(),
+			children: Vec::new(),
+			complete: false,
+			imported_bytes: 0,
+		}
+	}
+
+	/// Validate and import a state response.
+	pub fn import(&mut self, response: StateResponse) -> ImportResult<B> {
+		if response.proof.is_empty() {
+			log::debug!(
+				target: "sync",
+				"Bad state response",
+			);
+			return ImportResult::BadResponse;
+		}
+		if self.complete {
+			log::debug!(
+				target: "sync",
+				"Unexpected state response: sync is complete",
+			);
+			return ImportResult::BadResponse;
+		}
+		let proof = match StorageProof::decode(&mut response.proof.as_ref()) {
+			Ok(proof) => proof,
+			Err(e) => {
+				log::debug!(target: "sync", "Error decoding proof: {:?}", e);
+				return ImportResult::BadResponse;
+			}
+		};
+		let start = self.next_start_key();
+		let (values, complete) = match self.client.verify_range_proof(
+			self.target_root,
+			proof,
+			self.child.as_ref().map(|(child_info, _)| child_info),
+			&start,
+		) {
+			Err(e) => {
+				log::debug!(
+					target: "sync",
+					"StateResponse failed proof verification: {:?}",
+					e,
+				);
+				return ImportResult::BadResponse;
+			},
+			Ok(values) => values,
+		};
+		if values.is_empty() && !complete {
+			log::debug!(
+				target: "sync",
+				"Empty state response that does not complete the state",
+			);
+			return ImportResult::BadResponse;
+		}
+		log::debug!(target: "sync", "Imported with {} keys", values.len());
+
+		if let Some(last) = values.last().map(|(k, _)| k) {
+			self.last_key = last.clone();
+		}
+
+		self.imported_bytes += values.iter()
+			.map(|(k, v)| (k.len() + v.len()) as u64)
+			.sum::<u64>();
+
+		match &mut self.child {
+			Some((_, entries)) => entries.extend(values),
+			None => {
+				for (key, _) in &values {
+					if !well_known_keys::is_child_storage_key(key) {
+						continue
+					}
+					let prefixed_key = PrefixedStorageKey::new_ref(key);
+					match ChildType::from_prefixed_key(prefixed_key) {
+						Some((ChildType::ParentKeyId, storage_key)) => {
+							self.pending_children.push_back(ChildInfo::new_default(storage_key));
+						},
+						None => {
+							log::debug!(target: "sync", "Unknown child trie type in the state");
+							return ImportResult::BadResponse;
+						},
+					}
+				}
+				self.state.extend(values);
+			},
+		}
+
+		if complete {
+			self.children.extend(self.child.take());
+			self.last_key.clear();
+			self.child = self.pending_children.pop_front()
+				.map(|child_info| (child_info, Vec::new()));
+			self.complete = self.child.is_none();
+		}
+
+		if self.complete {
+			ImportResult::Import(self.target_block.clone(), self.target_header.clone(), ImportedState {
+				block: self.target_block.clone(),
+				state: std::mem::take(&mut self.state),
+				children: std::mem::take(&mut self.children),
+			})
+		} else {
+			ImportResult::Continue
+		}
+	}
+
+	/// Produce next state request.
+	pub fn next_request(&self) -> StateRequest {
+		StateRequest {
+			block: self.target_block.encode(),
+			start: self.next_start_key(),
+			child_storage_key: self.child.as_ref()
+				.map(|(child_info, _)| child_info.prefixed_storage_key().into_inner())
+				.unwrap_or_default(),
+		}
+	}
+
+	/// Check if the state is complete.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// Returns target block number.
+	pub fn target_block_num(&self) -> NumberFor<B> {
+		self.target_header.number().clone()
+	}
+
+	/// Returns target block hash.
+	pub fn target(&self) -> B::Hash {
+		self.target_block.clone()
+	}
+
+	/// Returns state sync estimated progress.
+	pub fn progress(&self) -> StateSyncProgress {
+		// Keys are downloaded in order, so the first byte of the last key is a rough
+		// estimate of how much of the key space has been covered. The child tries are
+		// downloaded last and only make up the last percent.
+		let percent_done = match self.last_key.first() {
+			_ if self.complete => 100,
+			_ if self.child.is_some() => 99,
+			Some(first) => (*first as u32) * 99 / 256,
+			None => 0,
+		};
+		StateSyncProgress {
+			percentage: percent_done,
+			size: self.imported_bytes,
+		}
+	}
+
+	/// The first key of the next range: the successor of the last key received.
+	fn next_start_key(&self) -> Vec<u8> {
+		if self.last_key.is_empty() {
+			Vec::new()
+		} else {
+			let mut start = self.last_key.clone();
+			start.push(0);
+			start
+		}
+	}
+}
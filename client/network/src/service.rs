@@ -48,7 +48,7 @@ use crate::{
 		Protocol,
 		Ready,
 		event::Event,
-		sync::SyncState,
+		sync::{SyncState, WarpSyncProgress},
 	},
 	transactions,
 	transport, ReputationChange,
@@ -196,6 +196,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			protocol::ProtocolConfig {
 				roles: From::from(&params.role),
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
+				sync_mode: params.network_config.sync_mode,
 			},
 			params.chain.clone(),
 			params.protocol_id.clone(),
@@ -204,6 +205,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 				.map(|_| default_notif_handshake_message.clone())).collect(),
 			params.block_announce_validator,
 			params.metrics_registry.as_ref(),
+			params.warp_sync.as_ref().map(|(p, _)| p.clone()),
 		)?;
 
 		// List of multiaddresses that we know in the network.
@@ -341,6 +343,8 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 					params.block_request_protocol_config,
 					bitswap,
 					params.light_client_request_protocol_config,
+					params.state_request_protocol_config,
+					params.warp_sync.map(|(_, config)| config),
 					params.network_config.request_response_protocols,
 				);
 
@@ -450,6 +454,8 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			num_active_peers: self.num_active_peers(),
 			total_bytes_inbound: self.total_bytes_inbound(),
 			total_bytes_outbound: self.total_bytes_outbound(),
+			state_sync: self.network_service.user_protocol().state_sync_progress(),
+			warp_sync: self.warp_sync_progress(),
 		}
 	}
 
@@ -483,6 +489,11 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 		self.network_service.user_protocol().best_seen_block()
 	}
 
+	/// Warp sync progress, if any.
+	pub fn warp_sync_progress(&self) -> Option<WarpSyncProgress> {
+		self.network_service.user_protocol().warp_sync_progress()
+	}
+
 	/// Number of peers participating in syncing.
 	pub fn num_sync_peers(&self) -> u32 {
 		self.network_service.user_protocol().num_sync_peers()
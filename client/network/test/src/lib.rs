@@ -30,6 +30,7 @@ use std::{
 use libp2p::build_multiaddr;
 use log::trace;
 use sc_network::block_request_handler::{self, BlockRequestHandler};
+use sc_network::state_request_handler::{self, StateRequestHandler};
 use sc_network::light_client_requests::{self, handler::LightClientRequestHandler};
 use sp_blockchain::{
 	HeaderBackend, Result as ClientResult,
@@ -56,7 +57,7 @@ use sc_network::{
 	NetworkWorker, NetworkService, config::{ProtocolId, MultiaddrWithPeerId, NonReservedPeerMode},
 	Multiaddr,
 };
-use sc_network::config::{NetworkConfiguration, NonDefaultSetConfig, TransportConfig};
+use sc_network::config::{NetworkConfiguration, NonDefaultSetConfig, SyncMode, TransportConfig};
 use libp2p::PeerId;
 use parking_lot::Mutex;
 use sp_core::H256;
@@ -610,6 +611,10 @@ pub struct FullPeerConfig {
 	///
 	/// If `None`, it will be connected to all other peers.
 	pub connect_to_peers: Option<Vec<usize>>,
+	/// Sync mode of the peer.
+	pub sync_mode: SyncMode,
+	/// Storage added to the genesis storage of the peer.
+	pub extra_storage: Option<sp_core::storage::Storage>,
 }
 
 pub trait TestNetFactory: Sized {
@@ -667,10 +672,21 @@ pub trait TestNetFactory: Sized {
 
 	/// Add a full peer.
 	fn add_full_peer_with_config(&mut self, config: FullPeerConfig) {
-		let test_client_builder = match config.keep_blocks {
+		let mut test_client_builder = match config.keep_blocks {
 			Some(keep_blocks) => TestClientBuilder::with_pruning_window(keep_blocks),
 			None => TestClientBuilder::with_default_backend(),
 		};
+		if let Some(storage) = config.extra_storage {
+			for (key, value) in storage.top {
+				test_client_builder = test_client_builder.add_extra_storage(key, value);
+			}
+			for child in storage.children_default.values() {
+				for (key, value) in &child.data {
+					test_client_builder = test_client_builder
+						.add_extra_child_storage(&child.child_info, key.clone(), value.clone());
+				}
+			}
+		}
 		let backend = test_client_builder.backend();
 		let (c, longest_chain) = test_client_builder.build_with_longest_chain();
 		let client = Arc::new(c);
@@ -707,6 +723,7 @@ pub trait TestNetFactory: Sized {
 		network_config.transport = TransportConfig::MemoryOnly;
 		network_config.listen_addresses = vec![listen_addr.clone()];
 		network_config.allow_non_globals_in_dht = true;
+		network_config.sync_mode = config.sync_mode;
 		network_config.extra_sets = config.notifications_protocols.into_iter().map(|p| {
 			NonDefaultSetConfig {
 				notifications_protocol: p,
@@ -742,6 +759,16 @@ pub trait TestNetFactory: Sized {
 			protocol_config
 		};
 
+		let state_request_protocol_config = {
+			let (handler, protocol_config) = StateRequestHandler::new(
+				&protocol_id,
+				client.clone(),
+				50,
+			);
+			self.spawn_task(handler.run().boxed());
+			protocol_config
+		};
+
 		let network = NetworkWorker::new(sc_network::config::Params {
 			role: Role::Full,
 			executor: None,
@@ -757,6 +784,8 @@ pub trait TestNetFactory: Sized {
 			metrics_registry: None,
 			block_request_protocol_config,
 			light_client_request_protocol_config,
+			state_request_protocol_config,
+			warp_sync: None,
 		}).unwrap();
 
 		trace!(target: "test_network", "Peer identifier: {}", network.service().local_peer_id());
@@ -833,6 +862,10 @@ pub trait TestNetFactory: Sized {
 		let light_client_request_protocol_config =
 			light_client_requests::generate_protocol_config(&protocol_id);
 
+		let state_request_protocol_config = state_request_handler::generate_protocol_config(
+			&protocol_id,
+		);
+
 		let network = NetworkWorker::new(sc_network::config::Params {
 			role: Role::Light,
 			executor: None,
@@ -847,6 +880,8 @@ pub trait TestNetFactory: Sized {
 			metrics_registry: None,
 			block_request_protocol_config,
 			light_client_request_protocol_config,
+			state_request_protocol_config,
+			warp_sync: None,
 		}).unwrap();
 
 		self.mut_peers(|peers| {
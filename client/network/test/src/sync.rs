@@ -22,6 +22,9 @@ use futures::{Future, executor::block_on};
 use super::*;
 use sp_consensus::block_validation::Validation;
 use substrate_test_runtime::Header;
+use sc_client_api::StorageProvider;
+use sp_consensus::BlockStatus;
+use sp_core::storage::{ChildInfo, StorageChild, StorageData, StorageKey};
 
 fn test_ancestor_search_when_common_is(n: usize) {
 	sp_tracing::try_init_simple();
@@ -935,3 +938,76 @@ fn continue_to_sync_after_some_block_announcement_verifications_failed() {
 	net.block_until_sync();
 	assert!(net.peer(1).has_block(&block_hash));
 }
+
+#[test]
+fn syncs_state_with_child_tries() {
+	sp_tracing::try_init_simple();
+	let mut genesis_storage = sp_core::storage::Storage::default();
+	genesis_storage.top.insert(b"additional_key".to_vec(), vec![1]);
+	// Enough data for several state responses per child trie.
+	let data: std::collections::BTreeMap<_, _> = (0..64u32)
+		.map(|i| (i.to_le_bytes().to_vec(), vec![i as u8; 64 * 1024]))
+		.collect();
+	for name in &[&b"first"[..], &b"second"[..]] {
+		let child_info = ChildInfo::new_default(name);
+		genesis_storage.children_default.insert(
+			child_info.storage_key().to_vec(),
+			StorageChild { data: data.clone(), child_info },
+		);
+	}
+
+	let mut net = TestNet::new(0);
+	for _ in 0..2 {
+		net.add_full_peer_with_config(FullPeerConfig {
+			extra_storage: Some(genesis_storage.clone()),
+			..Default::default()
+		});
+	}
+	net.add_full_peer_with_config(FullPeerConfig {
+		extra_storage: Some(genesis_storage),
+		sync_mode: SyncMode::Fast,
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+
+	// Wait for peer 2 to sync the header chain.
+	block_on(futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+		if net.peer(1).client().info().best_number < 64
+			|| net.peer(2).client().info().best_number < 64
+		{
+			return Poll::Pending
+		}
+		Poll::Ready(())
+	}));
+
+	// The state of a finalized block is what gets downloaded.
+	for peer in 1..3 {
+		net.peer(peer).client().finalize_block(BlockId::Number(60), None, true).unwrap();
+	}
+	let client = net.peer(2).client().as_full().unwrap();
+	block_on(futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+		match client.block_status(&BlockId::Number(64)).unwrap() {
+			BlockStatus::InChainWithState => Poll::Ready(()),
+			_ => Poll::Pending,
+		}
+	}));
+
+	// The blocks below the downloaded state were imported without their state.
+	assert_eq!(client.block_status(&BlockId::Number(1)).unwrap(), BlockStatus::InChainPruned);
+	let at = BlockId::Number(60);
+	assert_eq!(
+		client.storage(&at, &StorageKey(b"additional_key".to_vec())).unwrap(),
+		Some(StorageData(vec![1])),
+	);
+	for name in &[&b"first"[..], &b"second"[..]] {
+		let child_info = ChildInfo::new_default(name);
+		for (key, value) in &data {
+			assert_eq!(
+				client.child_storage(&at, &child_info, &StorageKey(key.clone())).unwrap(),
+				Some(StorageData(value.clone())),
+			);
+		}
+	}
+}
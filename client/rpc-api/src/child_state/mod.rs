@@ -18,9 +18,11 @@
 
 //! Substrate state API.
 
+use jsonrpc_core::Result as RpcResult;
 use jsonrpc_derive::rpc;
-use sp_core::storage::{StorageKey, PrefixedStorageKey, StorageData};
-use crate::state::error::FutureResult;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use sp_core::storage::{StorageKey, PrefixedStorageKey, StorageData, StorageChangeSet};
+use crate::state::{ReadProof, error::FutureResult};
 
 pub use self::gen_client::Client as ChildStateClient;
 
@@ -68,4 +70,55 @@ pub trait ChildStateApi<Hash> {
 		key: StorageKey,
 		hash: Option<Hash>
 	) -> FutureResult<Option<u64>>;
+
+	/// Query historical child storage entries (by key) starting from a block given as the
+	/// third parameter.
+	///
+	/// NOTE This first returned result contains the initial state of storage for all keys.
+	/// Subsequent values in the vector represent changes to the previous state (diffs).
+	#[rpc(name = "childstate_queryStorage")]
+	fn query_storage(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+		block: Hash,
+		hash: Option<Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Hash>>>;
+
+	/// Query child storage entries (by key) starting at block hash given as the third parameter.
+	#[rpc(name = "childstate_queryStorageAt")]
+	fn query_storage_at(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+		at: Option<Hash>,
+	) -> FutureResult<Vec<StorageChangeSet<Hash>>>;
+
+	/// Returns proof of child storage entries at a specific block's state.
+	#[rpc(name = "childstate_getReadProof")]
+	fn read_proof(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+		hash: Option<Hash>
+	) -> FutureResult<ReadProof<Hash>>;
+
+	/// New child storage subscription
+	///
+	/// Notifies about changes of the given keys of the child storage, or of all its keys if
+	/// none are given.
+	#[pubsub(subscription = "childstate_storage", subscribe, name = "childstate_subscribeStorage")]
+	fn subscribe_storage(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Hash>>,
+		child_storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	);
+
+	/// Unsubscribe from child storage subscription
+	#[pubsub(subscription = "childstate_storage", unsubscribe, name = "childstate_unsubscribeStorage")]
+	fn unsubscribe_storage(
+		&self, metadata: Option<Self::Metadata>, id: SubscriptionId
+	) -> RpcResult<bool>;
 }
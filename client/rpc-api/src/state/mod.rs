@@ -107,6 +107,39 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getReadProof")]
 	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> FutureResult<ReadProof<Hash>>;
 
+	/// The `traceBlock` RPC provides a way to trace the re-execution of a single
+	/// block, collecting Spans and Events from both the client and the relevant WASM runtime.
+	/// The Spans and Events are conceptually equivalent to those from the [Tracing][1] crate.
+	///
+	/// The traced block is executed on top of the state of its parent, with a dedicated
+	/// tracing subscriber that only records the given `targets`.
+	///
+	/// ## Parameters
+	///
+	/// - `block` (param index 0): Hash of the block to trace.
+	/// - `targets` (param index 1): String of comma separated (no spaces) targets. Specified
+	///   targets match with trace targets by prefix (i.e if a target is in the beginning
+	///   of a trace target it is considered a match). If an empty string is specified no
+	///   targets will be filtered out. Defaults to `pallet,frame,state`.
+	/// - `storage_keys` (param index 2): String of comma separated (no spaces) hex encoded
+	///   (no `0x` prefix) storage key prefixes. Storage events (target `state`) are only
+	///   returned if their key starts with one of the given prefixes. If not given, all
+	///   storage events are returned.
+	///
+	/// ## Response
+	///
+	/// Either a `blockTrace` with the recorded spans and events, or a `traceError`
+	/// describing why the block could not be executed.
+	///
+	/// [1]: https://crates.io/crates/tracing
+	#[rpc(name = "state_traceBlock")]
+	fn trace_block(
+		&self,
+		block: Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::TraceBlockResponse>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
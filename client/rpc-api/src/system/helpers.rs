@@ -95,7 +95,21 @@ pub struct SyncState<Number> {
 	/// Height of the highest block learned from the network. Missing if no block is known yet.
 	#[serde(default = "Default::default", skip_serializing_if = "Option::is_none")]
 	pub highest_block: Option<Number>,
+	/// Progress of the warp sync. Missing if warp sync is not active.
+	#[serde(default = "Default::default", skip_serializing_if = "Option::is_none")]
+	pub warp_sync: Option<WarpSyncState>,
+}
+
+/// The state of a warp sync in progress.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarpSyncState {
+	/// Current warp sync phase.
+	pub phase: String,
+	/// Total bytes downloaded so far.
+	pub total_bytes: u64,
 }
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -132,6 +146,7 @@ mod tests {
 				starting_block: 12u32,
 				current_block: 50u32,
 				highest_block: Some(128u32),
+				warp_sync: None,
 			}).unwrap(),
 			r#"{"startingBlock":12,"currentBlock":50,"highestBlock":128}"#,
 		);
@@ -141,8 +156,22 @@ mod tests {
 				starting_block: 12u32,
 				current_block: 50u32,
 				highest_block: None,
+				warp_sync: None,
 			}).unwrap(),
 			r#"{"startingBlock":12,"currentBlock":50}"#,
 		);
+
+		assert_eq!(
+			::serde_json::to_string(&SyncState {
+				starting_block: 0u32,
+				current_block: 0u32,
+				highest_block: Some(128u32),
+				warp_sync: Some(WarpSyncState {
+					phase: "Downloading state".into(),
+					total_bytes: 1024,
+				}),
+			}).unwrap(),
+			r#"{"startingBlock":0,"currentBlock":0,"highestBlock":128,"warpSync":{"phase":"Downloading state","totalBytes":1024}}"#,
+		);
 	}
 }
@@ -27,7 +27,7 @@ use futures::{future::BoxFuture, compat::Compat};
 
 use self::error::Result as SystemResult;
 
-pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState};
+pub use self::helpers::{SystemInfo, Health, PeerInfo, NodeRole, SyncState, WarpSyncState};
 pub use self::gen_client::Client as SystemClient;
 
 /// Substrate system RPC API
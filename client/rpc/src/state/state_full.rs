@@ -43,7 +43,7 @@ use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 
 use super::{StateBackend, ChildStateBackend, error::{FutureResult, Error, Result}, client_err};
 use std::marker::PhantomData;
-use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider};
+use sc_client_api::{BlockBackend, CallExecutor, StorageProvider, ExecutorProvider, ProofProvider};
 
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
@@ -139,10 +139,41 @@ impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
 		})
 	}
 
+	/// Returns the value of `key` in the child storage described by `child_info`, or in the top
+	/// storage if it is `None`.
+	fn storage_at(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		key: &StorageKey,
+	) -> Result<Option<StorageData>> {
+		match child_info {
+			Some(child_info) => self.client.child_storage(id, child_info, key),
+			None => self.client.storage(id, key),
+		}.map_err(client_err)
+	}
+
+	/// Collects the changes of `keys` in the range `from..=to`.
+	fn query_storage_range(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		child_info: Option<&ChildInfo>,
+		keys: &[StorageKey],
+	) -> Result<Vec<StorageChangeSet<Block::Hash>>> {
+		let range = self.split_query_storage_range(from, to)?;
+		let mut changes = Vec::new();
+		let mut last_values = HashMap::new();
+		self.query_storage_unfiltered(&range, child_info, keys, &mut last_values, &mut changes)?;
+		self.query_storage_filtered(&range, child_info, keys, &last_values, &mut changes)?;
+		Ok(changes)
+	}
+
 	/// Iterates through range.unfiltered_range and check each block for changes of keys' values.
 	fn query_storage_unfiltered(
 		&self,
 		range: &QueryStorageRange<Block>,
+		child_info: Option<&ChildInfo>,
 		keys: &[StorageKey],
 		last_values: &mut HashMap<StorageKey, Option<StorageData>>,
 		changes: &mut Vec<StorageChangeSet<Block::Hash>>,
@@ -153,7 +184,7 @@ impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
 			let id = BlockId::hash(block_hash);
 			for key in keys {
 				let (has_changed, data) = {
-					let curr_data = self.client.storage(&id, key).map_err(client_err)?;
+					let curr_data = self.storage_at(&id, child_info, key)?;
 					match last_values.get(key) {
 						Some(prev_data) => (curr_data != *prev_data, curr_data),
 						None => (true, curr_data),
@@ -175,6 +206,7 @@ impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
 	fn query_storage_filtered(
 		&self,
 		range: &QueryStorageRange<Block>,
+		child_info: Option<&ChildInfo>,
 		keys: &[StorageKey],
 		last_values: &HashMap<StorageKey, Option<StorageData>>,
 		changes: &mut Vec<StorageChangeSet<Block::Hash>>,
@@ -186,11 +218,13 @@ impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
 			),
 			None => return Ok(()),
 		};
+		let storage_key = child_info.map(ChildInfo::prefixed_storage_key);
 		let mut changes_map: BTreeMap<NumberFor<Block>, StorageChangeSet<Block::Hash>> = BTreeMap::new();
 		for key in keys {
 			let mut last_block = None;
 			let mut last_value = last_values.get(key).cloned().unwrap_or_default();
-			let key_changes = self.client.key_changes(begin, end, None, key).map_err(client_err)?;
+			let key_changes = self.client.key_changes(begin, end, storage_key.as_ref(), key)
+				.map_err(client_err)?;
 			for (block, _) in key_changes.into_iter().rev() {
 				if last_block == Some(block) {
 					continue;
@@ -198,7 +232,7 @@ impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
 
 				let block_hash = range.hashes[(block - range.first_number).saturated_into::<usize>()].clone();
 				let id = BlockId::Hash(block_hash);
-				let value_at_block = self.client.storage(&id, key).map_err(client_err)?;
+				let value_at_block = self.storage_at(&id, child_info, key)?;
 				if last_value == value_at_block {
 					continue;
 				}
@@ -223,7 +257,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	BE: Backend<Block> + 'static,
 	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
-		+ CallApiAt<Block> + ProvideRuntimeApi<Block>
+		+ CallApiAt<Block> + ProvideRuntimeApi<Block> + BlockBackend<Block>
 		+ Send + Sync + 'static,
 	Client::Api: Metadata<Block>,
 {
@@ -369,15 +403,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		to: Option<Block::Hash>,
 		keys: Vec<StorageKey>,
 	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
-		let call_fn = move || {
-			let range = self.split_query_storage_range(from, to)?;
-			let mut changes = Vec::new();
-			let mut last_values = HashMap::new();
-			self.query_storage_unfiltered(&range, &keys, &mut last_values, &mut changes)?;
-			self.query_storage_filtered(&range, &keys, &last_values, &mut changes)?;
-			Ok(changes)
-		};
-		Box::new(result(call_fn()))
+		Box::new(result(self.query_storage_range(from, to, None, &keys)))
 	}
 
 	fn query_storage_at(
@@ -386,7 +412,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		at: Option<Block::Hash>
 	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
 		let at = at.unwrap_or_else(|| self.client.info().best_hash);
-		self.query_storage(at, Some(at), keys)
+		StateBackend::query_storage(self, at, Some(at), keys)
 	}
 
 	fn read_proof(
@@ -409,6 +435,24 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		))
 	}
 
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::TraceBlockResponse> {
+		let block_executor = sc_tracing::block::BlockExecutor::new(
+			self.client.clone(),
+			block,
+			targets,
+			storage_keys,
+		);
+		Box::new(result(
+			block_executor.trace_block()
+				.map_err(|e| invalid_block::<Block>(block, None, e.to_string()))
+		))
+	}
+
 	fn subscribe_runtime_version(
 		&self,
 		_meta: crate::Metadata,
@@ -532,7 +576,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block, Client> where
 	Block: BlockT + 'static,
 	BE: Backend<Block> + 'static,
-	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + HeaderBackend<Block>
+	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
 		+ CallApiAt<Block> + ProvideRuntimeApi<Block>
 		+ Send + Sync + 'static,
@@ -547,10 +591,7 @@ impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block
 		Box::new(result(
 			self.block_or_best(block)
 				.and_then(|block| {
-					let child_info = match ChildType::from_prefixed_key(&storage_key) {
-						Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
-						None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-					};
+					let child_info = child_info(&storage_key)?;
 					self.client.child_storage_keys(
 						&BlockId::Hash(block),
 						&child_info,
@@ -569,10 +610,7 @@ impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block
 		Box::new(result(
 			self.block_or_best(block)
 				.and_then(|block| {
-					let child_info = match ChildType::from_prefixed_key(&storage_key) {
-						Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
-						None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-					};
+					let child_info = child_info(&storage_key)?;
 					self.client.child_storage(
 						&BlockId::Hash(block),
 						&child_info,
@@ -591,10 +629,7 @@ impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block
 		Box::new(result(
 			self.block_or_best(block)
 				.and_then(|block| {
-					let child_info = match ChildType::from_prefixed_key(&storage_key) {
-						Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
-						None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
-					};
+					let child_info = child_info(&storage_key)?;
 					self.client.child_storage_hash(
 						&BlockId::Hash(block),
 						&child_info,
@@ -603,6 +638,131 @@ impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block
 				})
 				.map_err(client_err)))
 	}
+
+	fn query_storage(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		Box::new(result(
+			child_info(&storage_key)
+				.map_err(client_err)
+				.and_then(|child_info| self.query_storage_range(from, to, Some(&child_info), &keys))
+		))
+	}
+
+	fn query_storage_at(
+		&self,
+		storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+		at: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		ChildStateBackend::query_storage(self, at, Some(at), storage_key, keys)
+	}
+
+	fn read_proof(
+		&self,
+		block: Option<Block::Hash>,
+		storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.and_then(|block| {
+					let child_info = child_info(&storage_key)?;
+					self.client
+						.read_child_proof(
+							&BlockId::Hash(block),
+							&child_info,
+							&mut keys.iter().map(|key| key.0.as_ref()),
+						)
+						.map(|proof| proof.iter_nodes().map(|node| node.into()).collect())
+						.map(|proof| ReadProof { at: block, proof })
+				})
+				.map_err(client_err),
+		))
+	}
+
+	fn subscribe_storage(
+		&self,
+		_meta: crate::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	) {
+		let child_info = match child_info(&storage_key) {
+			Ok(child_info) => child_info,
+			Err(err) => {
+				let _ = subscriber.reject(client_err(err).into());
+				return;
+			},
+		};
+
+		// storage notifications identify child tries by their unprefixed storage key
+		let child_storage_key = StorageKey(child_info.storage_key().to_vec());
+		let stream = match self.client.storage_changes_notification_stream(
+			Some(&[]),
+			Some(&[(child_storage_key.clone(), keys.clone())]),
+		) {
+			Ok(stream) => stream,
+			Err(err) => {
+				let _ = subscriber.reject(client_err(err).into());
+				return;
+			},
+		};
+
+		// initial values
+		let initial = stream::iter_result(keys
+			.map(|keys| {
+				let block = self.client.info().best_hash;
+				let id = BlockId::Hash(block);
+				let changes = keys
+					.into_iter()
+					.map(|key| {
+						let value = self.storage_at(&id, Some(&child_info), &key).unwrap_or(None);
+						(key, value)
+					})
+					.collect();
+				vec![Ok(Ok(StorageChangeSet { block, changes }))]
+			}).unwrap_or_default());
+
+		self.subscriptions.add(subscriber, |sink| {
+			let stream = stream
+				.map(move |(block, changes)| Ok::<_, ()>(Ok(StorageChangeSet {
+					block,
+					changes: changes.iter()
+						.filter_map(|(o_sk, k, v)| if o_sk == Some(&child_storage_key) {
+							Some((k.clone(), v.cloned()))
+						} else { None }).collect(),
+				})))
+				.compat();
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(initial.chain(stream))
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_storage(
+		&self,
+		_meta: Option<crate::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
+
+/// Returns the child info of the default child trie at the prefixed `storage_key`.
+fn child_info(storage_key: &PrefixedStorageKey) -> ClientResult<ChildInfo> {
+	match ChildType::from_prefixed_key(storage_key) {
+		Some((ChildType::ParentKeyId, storage_key)) => Ok(ChildInfo::new_default(storage_key)),
+		None => Err(sp_blockchain::Error::InvalidChildStorageKey),
+	}
 }
 
 /// Splits passed range into two subranges where:
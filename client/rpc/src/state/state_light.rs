@@ -297,6 +297,15 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
 	}
 
+	fn trace_block(
+		&self,
+		_block: Block::Hash,
+		_targets: Option<String>,
+		_storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::TraceBlockResponse> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
 	fn subscribe_storage(
 		&self,
 		_meta: crate::Metadata,
@@ -533,6 +542,111 @@ impl<Block, F, Client> ChildStateBackend<Block, Client> for LightState<Block, F,
 			)
 		)
 	}
+
+	fn query_storage(
+		&self,
+		_from: Block::Hash,
+		_to: Option<Block::Hash>,
+		_storage_key: PrefixedStorageKey,
+		_keys: Vec<StorageKey>,
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
+	fn query_storage_at(
+		&self,
+		_storage_key: PrefixedStorageKey,
+		_keys: Vec<StorageKey>,
+		_at: Option<Block::Hash>
+	) -> FutureResult<Vec<StorageChangeSet<Block::Hash>>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
+	fn read_proof(
+		&self,
+		_block: Option<Block::Hash>,
+		_storage_key: PrefixedStorageKey,
+		_keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
+	fn subscribe_storage(
+		&self,
+		_meta: crate::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	) {
+		let keys = match keys {
+			Some(keys) if !keys.is_empty() => keys,
+			_ => {
+				warn!("Cannot subscribe to all child storage keys on light client. Subscription rejected.");
+				return;
+			}
+		};
+
+		self.subscriptions.add(subscriber, move |sink| {
+			let fetcher = self.fetcher.clone();
+			let remote_blockchain = self.remote_blockchain.clone();
+			let initial_block = self.block_or_best(None);
+			let keys = keys.into_iter().map(|k| k.0).collect::<Vec<_>>();
+			// child storage subscriptions are rare, so requests are only shared within the subscription
+			let child_storage_requests: SimpleSubscriptions<Block::Hash, StorageMap> =
+				Arc::new(Mutex::new(HashMap::new()));
+
+			let changes_stream = subscription_stream::<Block, _, _, _, _, _, _, _, _>(
+				child_storage_requests,
+				self.client
+					.import_notification_stream()
+					.map(|notification| Ok::<_, ()>(notification.hash))
+					.compat(),
+				display_error(child_storage(
+					&*remote_blockchain,
+					fetcher.clone(),
+					initial_block,
+					storage_key.clone(),
+					keys.clone(),
+				).map(move |r| r.map(|r| (initial_block, r)))),
+				move |block| child_storage(
+					&*remote_blockchain,
+					fetcher.clone(),
+					block,
+					storage_key.clone(),
+					keys.clone(),
+				),
+				|block, old_value, new_value| {
+					let value_differs = old_value
+						.map(|old_value| old_value != new_value)
+						.unwrap_or(true);
+					match value_differs {
+						true => Some(StorageChangeSet {
+							block,
+							changes: new_value
+								.iter()
+								.map(|(k, v)| (k.clone(), v.clone()))
+								.collect(),
+						}),
+						false => None,
+					}
+				}
+			);
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(changes_stream.map(|changes| Ok(changes)))
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_storage(
+		&self,
+		_meta: Option<crate::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
 }
 
 /// Resolve header by hash.
@@ -619,6 +733,33 @@ fn storage<Block: BlockT, F: Fetcher<Block>>(
 		})
 }
 
+/// Get child storage values at given keys at given block.
+fn child_storage<Block: BlockT, F: Fetcher<Block>>(
+	remote_blockchain: &dyn RemoteBlockchain<Block>,
+	fetcher: Arc<F>,
+	block: Block::Hash,
+	storage_key: PrefixedStorageKey,
+	keys: Vec<Vec<u8>>,
+) -> impl std::future::Future<Output = Result<StorageMap, Error>> {
+	resolve_header(remote_blockchain, &*fetcher, block)
+		.then(move |result| match result {
+			Ok(header) => Either::Left(fetcher.remote_read_child(RemoteReadChildRequest {
+				block,
+				header,
+				storage_key,
+				keys,
+				retry_count: Default::default(),
+			}).then(|result| ready(result
+				.map(|result| result
+					.into_iter()
+					.map(|(key, value)| (StorageKey(key), value.map(StorageData)))
+					.collect()
+				).map_err(client_err)
+			))),
+			Err(error) => Either::Right(ready(Err(error))),
+		})
+}
+
 /// Returns subscription stream that issues request on every imported block and
 /// if value has changed from previous block, emits (stream) item.
 fn subscription_stream<
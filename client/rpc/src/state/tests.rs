@@ -34,6 +34,7 @@ use substrate_test_runtime_client::{
 };
 use sc_rpc_api::DenyUnsafe;
 use sp_runtime::generic::BlockId;
+use sp_rpc::tracing::TraceBlockResponse;
 use crate::testing::TaskExecutor;
 use futures::{executor, compat::Future01CompatExt};
 
@@ -136,6 +137,57 @@ fn should_return_child_storage() {
 	);
 }
 
+#[test]
+fn should_query_and_prove_child_storage() {
+	let child_info = ChildInfo::new_default(STORAGE_KEY);
+	let client = Arc::new(substrate_test_runtime_client::TestClientBuilder::new()
+		.add_child_storage(&child_info, "key", vec![42_u8])
+		.build());
+	let genesis_hash = client.genesis_hash();
+	let (_client, child) = new_full(
+		client,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::No,
+	);
+	let child_key = prefixed_storage_key();
+	let keys = vec![StorageKey(b"key".to_vec()), StorageKey(b"missing".to_vec())];
+
+	assert_eq!(
+		child.query_storage_at(child_key.clone(), keys.clone(), Some(genesis_hash)).wait().unwrap(),
+		vec![StorageChangeSet {
+			block: genesis_hash,
+			changes: vec![
+				(keys[0].clone(), Some(StorageData(vec![42]))),
+				(keys[1].clone(), None),
+			],
+		}],
+	);
+	assert_matches!(
+		child.read_proof(child_key.clone(), keys.clone(), None).wait(),
+		Ok(ReadProof { at, ref proof }) if at == genesis_hash && !proof.is_empty()
+	);
+	assert_matches!(
+		child.query_storage_at(
+			PrefixedStorageKey::new(b"not a child key".to_vec()),
+			keys.clone(),
+			None,
+		).wait(),
+		Err(Error::Client(_))
+	);
+
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+	child.subscribe_storage(Default::default(), subscriber, child_key, Some(vec![keys[0].clone()]));
+
+	// assert id assigned
+	assert!(matches!(
+		executor::block_on(id.compat()),
+		Ok(Ok(SubscriptionId::String(_)))
+	));
+	// assert initial values sent to transport
+	let (notification, _next) = executor::block_on(transport.into_future().compat()).unwrap();
+	assert!(notification.is_some());
+}
+
 #[test]
 fn should_call_contract() {
 	let client = Arc::new(substrate_test_runtime_client::new());
@@ -446,6 +498,49 @@ fn should_query_storage() {
 	);
 }
 
+#[test]
+fn should_trace_block() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(vec![1], Some(vec![1])).unwrap();
+	builder.push_storage_change(vec![2], Some(vec![2])).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	let (api, _child) = new_full(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::Yes,
+	);
+	assert_matches!(
+		api.trace_block(block_hash, None, None).wait(),
+		Err(Error::UnsafeRpcCalled(_))
+	);
+
+	let (api, _child) = new_full(
+		client,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::No,
+	);
+	assert_matches!(
+		api.trace_block(H256::repeat_byte(42), None, None).wait(),
+		Err(Error::InvalidBlockRange { .. })
+	);
+
+	let trace = match api.trace_block(block_hash, Some("state".into()), Some("02".into())).wait() {
+		Ok(TraceBlockResponse::BlockTrace(trace)) => trace,
+		other => panic!("Unexpected trace response: {:?}", other),
+	};
+	assert_eq!(trace.block_hash, format!("{:?}", block_hash));
+	assert_eq!(trace.tracing_targets, "state");
+	let puts: Vec<_> = trace.events.iter()
+		.filter(|e| e.data.string_values.get("method").map(String::as_str) == Some("Put"))
+		.collect();
+	assert!(!puts.is_empty());
+	assert!(trace.events.iter().all(|e| e.target == "state" && e.data.string_values["key"].starts_with("02")));
+}
+
 #[test]
 fn should_split_ranges() {
 	assert_eq!(split_range(1, None), (0..1, None));
@@ -112,6 +112,7 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						starting_block: 1,
 						current_block: 2,
 						highest_block: Some(3),
+						warp_sync: None,
 					});
 				}
 			};
@@ -309,6 +310,7 @@ fn system_sync_state() {
 			starting_block: 1,
 			current_block: 2,
 			highest_block: Some(3),
+			warp_sync: None,
 		}
 	);
 }
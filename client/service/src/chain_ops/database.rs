@@ -0,0 +1,56 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::config::{Configuration, DatabaseConfig};
+use crate::error::Error;
+use sp_runtime::traits::Block as BlockT;
+
+pub use sc_client_db::maintenance::{AuxKeys, ColumnStats, DatabaseStats, PruneSummary};
+
+/// The database settings of the node described by `config`.
+fn database_settings(config: &Configuration) -> sc_client_db::DatabaseSettings {
+	sc_client_db::DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		state_pruning: config.state_pruning.clone(),
+		source: config.database.clone(),
+		keep_blocks: config.keep_blocks.clone(),
+		transaction_storage: config.transaction_storage.clone(),
+	}
+}
+
+/// Collect the statistics of the database of the node.
+pub fn database_stats<B: BlockT>(config: &Configuration) -> Result<DatabaseStats, Error> {
+	Ok(sc_client_db::maintenance::stats::<B>(&database_settings(config))?)
+}
+
+/// Apply the configured block and state pruning to the database of the node.
+pub fn prune_database<B: BlockT>(config: &Configuration) -> Result<PruneSummary, Error> {
+	Ok(sc_client_db::maintenance::prune::<B>(&database_settings(config))?)
+}
+
+/// Copy the database of the node to the empty database `target`.
+///
+/// `aux_keys` names the auxiliary data to copy out of a database that can not enumerate its keys.
+pub fn migrate_database<B: BlockT>(
+	config: &Configuration,
+	target: DatabaseConfig,
+	aux_keys: &AuxKeys<B::Hash>,
+) -> Result<(), Error> {
+	Ok(sc_client_db::maintenance::migrate::<B>(&database_settings(config), target, aux_keys)?)
+}
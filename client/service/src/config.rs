@@ -55,6 +55,8 @@ pub struct Configuration {
 	pub keystore: KeystoreConfig,
 	/// Remote URI to connect to for async keystore support
 	pub keystore_remote: Option<String>,
+	/// Token to authenticate with at the remote keystore, instead of the token in the URI.
+	pub keystore_remote_token: Option<SecretString>,
 	/// Configuration for the database.
 	pub database: DatabaseConfig,
 	/// Size of internal state cache in Bytes
@@ -220,6 +222,13 @@ impl Configuration {
 		};
 		sc_network::config::ProtocolId::from(protocol_id_full)
 	}
+
+	/// Returns the directory the executor stores its files in, e.g. precompiled runtimes.
+	///
+	/// The path looks like `$base_path/cache`.
+	pub fn executor_cache_path(&self) -> Option<PathBuf> {
+		self.base_path.as_ref().map(|base_path| base_path.path().join("cache"))
+	}
 }
 
 /// Available RPC methods.
@@ -314,12 +314,16 @@ async fn build_network_future<
 						let _ = sender.send(vec![node_role]);
 					}
 					sc_rpc::system::Request::SyncState(sender) => {
-						use sc_rpc::system::SyncState;
+						use sc_rpc::system::{SyncState, WarpSyncState};
 
 						let _ = sender.send(SyncState {
 							starting_block: starting_block,
 							current_block: client.info().best_number,
 							highest_block: network.best_seen_block(),
+							warp_sync: network.warp_sync_progress().map(|progress| WarpSyncState {
+								phase: progress.phase.to_string(),
+								total_bytes: progress.total_bytes,
+							}),
 						});
 					}
 				}
@@ -241,6 +241,7 @@ fn node_config<G: RuntimeGenesis + 'static, E: ChainSpecExtension + Clone + 'sta
 		transaction_pool: Default::default(),
 		network: network_config,
 		keystore_remote: Default::default(),
+		keystore_remote_token: Default::default(),
 		keystore: KeystoreConfig::Path {
 			path: root.join("key"),
 			password: None
@@ -89,6 +89,8 @@ pub enum Error<E: fmt::Debug> {
 	InvalidParent,
 	/// Invalid pruning mode specified. Contains expected mode.
 	InvalidPruningMode(String),
+	/// Trying to import state while there are non-canonical blocks.
+	InvalidStateImport,
 }
 
 /// Pinning error type.
@@ -112,6 +114,7 @@ impl<E: fmt::Debug> fmt::Debug for Error<E> {
 			Error::InvalidBlockNumber => write!(f, "Trying to insert block with invalid number"),
 			Error::InvalidParent => write!(f, "Trying to insert block with unknown parent"),
 			Error::InvalidPruningMode(e) => write!(f, "Expected pruning mode: {}", e),
+			Error::InvalidStateImport => write!(f, "Trying to import state with non-canonical blocks pending"),
 		}
 	}
 }
@@ -274,6 +277,32 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		}
 	}
 
+	fn import_state<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		mut changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		changeset.deleted.clear();
+		let mut commit = CommitSet {
+			data: changeset,
+			meta: Default::default(),
+		};
+		// The state may be imported into a database without any block.
+		commit.meta.inserted.push((to_meta_key(PRUNING_MODE, &()), self.mode.id().into()));
+		match self.mode {
+			PruningMode::ArchiveAll => {},
+			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical => {
+				self.non_canonical.set_last_canonicalized(hash, number, &mut commit)?;
+				if let Some(ref mut pruning) = self.pruning {
+					pruning.note_canonical(&hash, &mut commit);
+				}
+				self.prune(&mut commit);
+			}
+		}
+		Ok(commit)
+	}
+
 	fn canonicalize_block<E: fmt::Debug>(
 		&mut self,
 		hash: &BlockHash,
@@ -319,6 +348,10 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		}
 	}
 
+	fn first_available(&self) -> u64 {
+		self.pruning.as_ref().map_or(0, |pruning| pruning.pending())
+	}
+
 	fn prune(&mut self, commit: &mut CommitSet<Key>) {
 		if let (&mut Some(ref mut pruning), &PruningMode::Constrained(ref constraints)) = (&mut self.pruning, &self.mode) {
 			loop {
@@ -434,6 +467,22 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 	}
 }
 
+/// Returns the journal changes that replace every node key referenced by the journals in `db`
+/// with `f(key)`.
+///
+/// The journals of the non-canonical and the not yet pruned blocks reference the nodes these
+/// blocks inserted and deleted. They must be rewritten when the nodes are moved to a database
+/// that stores them under different keys.
+pub fn rewrite_journal_keys<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	f: impl Fn(&Key) -> Key,
+) -> Result<ChangeSet<Vec<u8>>, Error<D::Error>> {
+	let mut changes = ChangeSet::default();
+	noncanonical::rewrite_journal_keys::<BlockHash, Key, D>(db, &f, &mut changes)?;
+	pruning::rewrite_journal_keys::<BlockHash, Key, D>(db, &f, &mut changes)?;
+	Ok(changes)
+}
+
 /// State DB maintenance. See module description.
 /// Can be shared across threads.
 pub struct StateDb<BlockHash: Hash, Key: Hash> {
@@ -463,6 +512,17 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		self.db.write().insert_block(hash, number, parent_hash, changeset)
 	}
 
+	/// Import the complete state of an already finalized block, which becomes the last
+	/// canonical block. There must be no non-canonical blocks.
+	pub fn import_state<E: fmt::Debug>(
+		&self,
+		hash: &BlockHash,
+		number: u64,
+		changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		self.db.write().import_state(hash, number, changeset)
+	}
+
 	/// Finalize a previously inserted block.
 	pub fn canonicalize_block<E: fmt::Debug>(
 		&self,
@@ -503,6 +563,21 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		return self.db.read().best_canonical()
 	}
 
+	/// Returns the number of the first canonical block with a state that is not pruned.
+	pub fn first_available(&self) -> u64 {
+		self.db.read().first_available()
+	}
+
+	/// Prune the canonical states that are outside of the pruning window.
+	///
+	/// Pruning normally happens when a block is canonicalized. This applies a pruning window
+	/// that was reduced since the last canonicalization to the database right away.
+	pub fn prune(&self) -> CommitSet<Key> {
+		let mut commit = CommitSet::default();
+		self.db.write().prune(&mut commit);
+		commit
+	}
+
 	/// Check if block is pruned away.
 	pub fn is_pruned(&self, hash: &BlockHash, number: u64) -> bool {
 		return self.db.read().is_pruned(hash, number)
@@ -528,7 +603,7 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 mod tests {
 	use std::io;
 	use sp_core::H256;
-	use crate::{StateDb, PruningMode, Constraints};
+	use crate::{StateDb, PruningMode, Constraints, CommitSet, rewrite_journal_keys};
 	use crate::test::{make_db, make_changeset, TestDb};
 
 	fn make_test_db(settings: PruningMode) -> (TestDb, StateDb<H256, H256>) {
@@ -645,6 +720,106 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn prune_applies_reduced_window() {
+		let (mut db, _) = make_test_db(PruningMode::keep_blocks(2));
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(0), false, &db).unwrap();
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(21), 2));
+
+		db.commit(&state_db.prune());
+		state_db.apply_pending();
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(21), 2));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(3), 3));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(4), 4));
+		assert!(db.data_eq(&make_db(&[21, 3, 922, 94])));
+
+		// The pruned window is persisted.
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(0), false, &db).unwrap();
+		assert!(state_db.prune().data.deleted.is_empty());
+	}
+
+	#[test]
+	fn rewritten_journal_keys_are_pruned() {
+		let shift = |k: &H256| H256::from_low_u64_be(k.to_low_u64_be() + 1000);
+		let (mut db, _) = make_test_db(PruningMode::keep_blocks(2));
+
+		// Move all nodes to new keys.
+		let meta = rewrite_journal_keys::<H256, H256, _>(&db, shift).unwrap();
+		db.commit(&CommitSet { data: Default::default(), meta });
+		db.data = db.data.into_iter().map(|(k, v)| (shift(&k), v)).collect();
+
+		let state_db: StateDb<H256, H256> = StateDb::new(PruningMode::keep_blocks(0), false, &db).unwrap();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(4)).unwrap());
+		state_db.apply_pending();
+
+		let mut keys: Vec<_> = db.data.keys().map(|k| k.to_low_u64_be()).collect();
+		keys.sort();
+		assert_eq!(keys, vec![1003, 1004, 1021, 1922]);
+	}
+
+	#[test]
+	fn import_state_sets_canonical_base() {
+		let mut db = make_db(&[]);
+		let state_db = StateDb::new(PruningMode::keep_blocks(1), false, &db).unwrap();
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(0),
+					0,
+					&H256::from_low_u64_be(0),
+					make_changeset(&[90], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(0)).unwrap());
+		state_db.apply_pending();
+
+		// Blocks 1 to 9 are imported without state, the state of block 10 is imported directly.
+		db.commit(
+			&state_db
+				.import_state::<io::Error>(&H256::from_low_u64_be(10), 10, make_changeset(&[10], &[90]))
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(10));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(10), 10));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(9), 9));
+		assert!(db.data_eq(&make_db(&[10, 90])));
+
+		// Execution continues on top of the imported state.
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(11),
+					11,
+					&H256::from_low_u64_be(10),
+					make_changeset(&[11], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(11)).unwrap());
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(11));
+
+		// The state can't be imported while there are non-canonical blocks.
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(12),
+					12,
+					&H256::from_low_u64_be(11),
+					make_changeset(&[12], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert!(state_db
+			.import_state::<io::Error>(&H256::from_low_u64_be(20), 20, make_changeset(&[20], &[]))
+			.is_err());
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
@@ -144,6 +144,41 @@ fn discard_descendants<BlockHash: Hash, Key: Hash>(
 	pinned_children
 }
 
+/// Rewrite the node keys of all journal records in `db` with `f`.
+pub fn rewrite_journal_keys<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	f: &dyn Fn(&Key) -> Key,
+	changes: &mut ChangeSet<Vec<u8>>,
+) -> Result<(), Error<D::Error>> {
+	let last_canonicalized = db.get_meta(&to_meta_key(LAST_CANONICAL, &()))
+		.map_err(|e| Error::Db(e))?;
+	let mut block = match last_canonicalized {
+		Some(buffer) => <(BlockHash, u64)>::decode(&mut buffer.as_slice())?.1 + 1,
+		None => return Ok(()),
+	};
+	loop {
+		let mut index: u64 = 0;
+		loop {
+			let journal_key = to_journal_key(block, index);
+			match db.get_meta(&journal_key).map_err(|e| Error::Db(e))? {
+				Some(record) => {
+					let mut record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice())?;
+					record.inserted.iter_mut().for_each(|(k, _)| *k = f(k));
+					record.deleted.iter_mut().for_each(|k| *k = f(k));
+					changes.inserted.push((journal_key, record.encode()));
+					index += 1;
+				},
+				None => break,
+			}
+		}
+		if index == 0 {
+			break;
+		}
+		block += 1;
+	}
+	Ok(())
+}
+
 impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	/// Creates a new instance. Does not expect any metadata to be present in the DB.
 	pub fn new<D: MetaDb>(db: &D) -> Result<NonCanonicalOverlay<BlockHash, Key>, Error<D::Error>> {
@@ -266,6 +301,25 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 		Ok(commit)
 	}
 
+	/// Use the given block as the last canonicalized one, without any overlay. This is used
+	/// when the complete state of a finalized block is imported directly instead of being
+	/// built by executing all of its ancestors. The overlay must be empty.
+	pub fn set_last_canonicalized<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		commit: &mut CommitSet<Key>,
+	) -> Result<(), Error<E>> {
+		if !self.levels.is_empty() || !self.pending_canonicalizations.is_empty() {
+			return Err(Error::InvalidStateImport);
+		}
+		let last_canonicalized = (hash.clone(), number);
+		commit.meta.inserted.push((to_meta_key(LAST_CANONICAL, &()), last_canonicalized.encode()));
+		trace!(target: "state-db", "Imported canonical state #{} ({:?})", number, hash);
+		self.last_canonicalized = Some(last_canonicalized);
+		Ok(())
+	}
+
 	fn discard_journals(
 		&self,
 		level_index: usize,
@@ -26,7 +26,7 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use codec::{Encode, Decode};
-use crate::{CommitSet, Error, MetaDb, to_meta_key, Hash};
+use crate::{ChangeSet, CommitSet, Error, MetaDb, to_meta_key, Hash};
 use log::{trace, warn};
 
 const LAST_PRUNED: &[u8] = b"last_pruned";
@@ -71,6 +71,34 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+/// Rewrite the node keys of all journal records in `db` with `f`.
+pub fn rewrite_journal_keys<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	f: &dyn Fn(&Key) -> Key,
+	changes: &mut ChangeSet<Vec<u8>>,
+) -> Result<(), Error<D::Error>> {
+	let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
+		.map_err(|e| Error::Db(e))?;
+	let mut block: u64 = match last_pruned {
+		Some(buffer) => u64::decode(&mut buffer.as_slice())? + 1,
+		None => 0,
+	};
+	loop {
+		let journal_key = to_journal_key(block);
+		match db.get_meta(&journal_key).map_err(|e| Error::Db(e))? {
+			Some(record) => {
+				let mut record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice())?;
+				record.inserted.iter_mut().for_each(|k| *k = f(k));
+				record.deleted.iter_mut().for_each(|k| *k = f(k));
+				changes.inserted.push((journal_key, record.encode()));
+			},
+			None => break,
+		}
+		block += 1;
+	}
+	Ok(())
+}
+
 impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	pub fn new<D: MetaDb>(db: &D, count_insertions: bool) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
 		let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
@@ -0,0 +1,418 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Utilities for tracing block execution.
+
+use std::{
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
+	time::Instant,
+};
+
+use codec::Encode;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use tracing::{
+	dispatcher,
+	event::Event,
+	Dispatch,
+	Level,
+	Metadata,
+	span::{Attributes, Id, Record},
+	subscriber::{Interest, Subscriber},
+};
+use tracing_subscriber::CurrentSpan;
+
+use sc_client_api::{BlockBackend, CallExecutor, ExecutionStrategy, ExecutorProvider};
+use sp_blockchain::HeaderBackend;
+use sp_rpc::tracing::{BlockTrace, Data, Span, TraceBlockResponse, TraceError};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header},
+};
+use sp_tracing::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+
+use crate::{parse_target, SpanDatum, TraceEvent, Values, ZERO_DURATION};
+
+/// Targets traced when the caller does not provide any.
+pub const DEFAULT_TARGETS: &str = "pallet,frame,state";
+/// Target of the storage access events emitted by `sp-state-machine`.
+const STORAGE_TARGET: &str = "state";
+/// Field of the storage access events holding the accessed key.
+const STORAGE_KEY_FIELD: &str = "key";
+/// Field of storage events holding the cleared prefix of `ClearPrefix` and `ChildClearPrefix`.
+const STORAGE_PREFIX_FIELD: &str = "prefix";
+/// Runtime function used to re-execute the block.
+const EXECUTE_BLOCK_METHOD: &str = "Core_execute_block";
+
+/// Tracing block error.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+	#[error("Invalid block Id: {0}")]
+	InvalidBlockId(#[from] sp_blockchain::Error),
+	#[error("Missing block component: {0}")]
+	MissingBlockComponent(String),
+	#[error("Dispatch error: {0}")]
+	Dispatch(String),
+}
+
+/// Tracing block result.
+pub type TraceBlockResult<T> = Result<T, Error>;
+
+/// Collects all spans and events emitted while it is the default dispatcher.
+struct BlockSubscriber {
+	targets: Vec<(String, Level)>,
+	next_id: AtomicU64,
+	current_span: CurrentSpan,
+	spans: Mutex<FxHashMap<Id, SpanDatum>>,
+	events: Mutex<Vec<TraceEvent>>,
+}
+
+impl BlockSubscriber {
+	fn new(targets: &str) -> Self {
+		BlockSubscriber {
+			targets: targets.split(',').map(parse_target).collect(),
+			next_id: AtomicU64::new(1),
+			current_span: CurrentSpan::default(),
+			spans: Mutex::new(FxHashMap::default()),
+			events: Mutex::new(Vec::new()),
+		}
+	}
+
+	fn check_target(&self, target: &str, level: &Level) -> bool {
+		self.targets.iter().any(|(t, l)| target.starts_with(t.as_str()) && level <= l)
+	}
+}
+
+impl Subscriber for BlockSubscriber {
+	fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+		// Interest is cached per callsite across all dispatchers, so it must not depend on
+		// the targets of this (short lived) subscriber.
+		Interest::sometimes()
+	}
+
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		// Wasm spans and events only carry their real target in the recorded values,
+		// so they are filtered once the block has been executed.
+		metadata.target() == WASM_TRACE_IDENTIFIER ||
+			self.check_target(metadata.target(), metadata.level())
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let id = Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed));
+		let mut values = Values::default();
+		attrs.record(&mut values);
+		let span_datum = SpanDatum {
+			id: id.clone(),
+			parent_id: attrs.parent().cloned().or_else(|| self.current_span.id()),
+			name: attrs.metadata().name().to_owned(),
+			target: attrs.metadata().target().to_owned(),
+			level: attrs.metadata().level().clone(),
+			line: attrs.metadata().line().unwrap_or(0),
+			start_time: Instant::now(),
+			overall_time: ZERO_DURATION,
+			values,
+		};
+		self.spans.lock().insert(id.clone(), span_datum);
+		id
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			values.record(&mut s.values);
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+	fn event(&self, event: &Event<'_>) {
+		let mut values = Values::default();
+		event.record(&mut values);
+		let trace_event = TraceEvent {
+			name: event.metadata().name(),
+			target: event.metadata().target().to_owned(),
+			level: event.metadata().level().clone(),
+			values,
+			parent_id: event.parent().cloned().or_else(|| self.current_span.id()),
+		};
+		self.events.lock().push(trace_event);
+	}
+
+	fn enter(&self, span: &Id) {
+		self.current_span.enter(span.clone());
+	}
+
+	fn exit(&self, _span: &Id) {
+		self.current_span.exit();
+	}
+}
+
+/// Holds a reference to the client in order to execute the given block and record the
+/// spans and events emitted by the runtime while doing so.
+pub struct BlockExecutor<Block: BlockT, Client> {
+	client: Arc<Client>,
+	block: Block::Hash,
+	targets: Option<String>,
+	storage_keys: Option<String>,
+}
+
+impl<Block, Client> BlockExecutor<Block, Client>
+	where
+		Block: BlockT + 'static,
+		Client: HeaderBackend<Block> + BlockBackend<Block> + ExecutorProvider<Block>
+			+ Send + Sync + 'static,
+{
+	/// Create a new `BlockExecutor`.
+	///
+	/// `targets` is a comma separated list of tracing targets, defaulting to
+	/// [`DEFAULT_TARGETS`]. `storage_keys` is a comma separated list of hex encoded key
+	/// prefixes the storage events are filtered by; all storage events are kept if it
+	/// is not given.
+	pub fn new(
+		client: Arc<Client>,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> Self {
+		Self { client, block, targets, storage_keys }
+	}
+
+	/// Execute the block on top of its parent state and collect the spans and events
+	/// emitted by the runtime.
+	///
+	/// A failure to execute the block is reported as a [`TraceError`] response.
+	pub fn trace_block(&self) -> TraceBlockResult<TraceBlockResponse> {
+		tracing::debug!(target: "state_tracing", "Tracing block: {:?}", self.block);
+		let id = BlockId::Hash(self.block);
+		let header = self.client.header(id)?
+			.ok_or_else(|| Error::MissingBlockComponent("Header not found".to_string()))?;
+		let extrinsics = self.client.block_body(&id)?
+			.ok_or_else(|| Error::MissingBlockComponent("Extrinsics not found".to_string()))?;
+		let parent_hash = *header.parent_hash();
+		let parent_id = BlockId::Hash(parent_hash);
+
+		// The seal is removed from the header before the block is imported, so it has to be
+		// removed here as well for the runtime to accept the block.
+		let mut header = header;
+		header.digest_mut().logs.retain(|d| d.as_seal().is_none());
+		let block = Block::new(header, extrinsics);
+
+		let targets = self.targets.as_ref().map(String::as_str).unwrap_or(DEFAULT_TARGETS);
+		let dispatch = Dispatch::new(BlockSubscriber::new(targets));
+		let result = dispatcher::with_default(&dispatch, || {
+			self.client.executor().call(
+				&parent_id,
+				EXECUTE_BLOCK_METHOD,
+				&block.encode(),
+				ExecutionStrategy::AlwaysWasm,
+				None,
+			)
+		});
+		if let Err(e) = result {
+			return Ok(TraceBlockResponse::TraceError(TraceError {
+				error: Error::Dispatch(
+					format!("Failed to collect traces and execute block: {}", e)
+				).to_string(),
+			}));
+		}
+
+		let subscriber = dispatch.downcast_ref::<BlockSubscriber>()
+			.ok_or_else(|| Error::Dispatch(
+				"Cannot downcast Dispatch to BlockSubscriber after tracing block".to_string()
+			))?;
+
+		let storage_keys = self.storage_keys.as_ref().map(|keys| keys
+			.split(',')
+			.map(|key| key.trim().trim_start_matches("0x").to_lowercase())
+			.filter(|key| !key.is_empty())
+			.collect::<Vec<_>>()
+		).unwrap_or_default();
+
+		let mut spans: Vec<_> = std::mem::take(&mut *subscriber.spans.lock())
+			.into_iter()
+			.map(|(_, span)| patch_wasm_span(span))
+			.filter(|(span, _)| subscriber.check_target(&span.target, &span.level))
+			.map(|(span, wasm)| Span {
+				id: span.id.into_u64(),
+				parent_id: span.parent_id.map(|id| id.into_u64()),
+				name: span.name,
+				target: span.target,
+				wasm,
+			})
+			.collect();
+		spans.sort_by_key(|span| span.id);
+
+		let events = std::mem::take(&mut *subscriber.events.lock())
+			.into_iter()
+			.map(patch_wasm_event)
+			.filter(|event| subscriber.check_target(&event.target, &event.level))
+			.filter(|event| event_matches_storage_keys(event, &storage_keys))
+			.map(|event| sp_rpc::tracing::Event {
+				target: event.target,
+				data: event_data(event.values),
+				parent_id: event.parent_id.map(|id| id.into_u64()),
+			})
+			.collect();
+
+		Ok(TraceBlockResponse::BlockTrace(BlockTrace {
+			block_hash: format!("{:?}", self.block),
+			parent_hash: format!("{:?}", parent_hash),
+			tracing_targets: targets.to_string(),
+			storage_keys: self.storage_keys.clone().unwrap_or_default(),
+			spans,
+			events,
+		}))
+	}
+}
+
+/// Replace the name and target of a wasm span with the ones recorded by the runtime.
+///
+/// Returns the span together with a flag telling whether it originates from wasm.
+fn patch_wasm_span(mut span: SpanDatum) -> (SpanDatum, bool) {
+	if span.name != WASM_TRACE_IDENTIFIER {
+		return (span, false)
+	}
+	if let Some(name) = span.values.string_values.remove(WASM_NAME_KEY) {
+		span.name = name;
+	}
+	if let Some(target) = span.values.string_values.remove(WASM_TARGET_KEY) {
+		span.target = target;
+	}
+	(span, true)
+}
+
+/// Replace the target of a wasm event with the one recorded by the runtime.
+fn patch_wasm_event(mut event: TraceEvent) -> TraceEvent {
+	if event.target == WASM_TRACE_IDENTIFIER {
+		if let Some(target) = event.values.string_values.remove(WASM_TARGET_KEY) {
+			event.target = target;
+		}
+	}
+	event
+}
+
+/// Storage events are only kept if their key starts with one of the given (hex) prefixes,
+/// or if no prefixes are given. Other events are always kept.
+///
+/// Events clearing a prefix are kept if the cleared prefix and one of the given prefixes overlap,
+/// i.e. either one starts with the other.
+fn event_matches_storage_keys(event: &TraceEvent, storage_keys: &[String]) -> bool {
+	if event.target != STORAGE_TARGET || storage_keys.is_empty() {
+		return true
+	}
+	let values = &event.values.string_values;
+	if let Some(key) = values.get(STORAGE_KEY_FIELD) {
+		return storage_keys.iter().any(|prefix| key.starts_with(prefix.as_str()))
+	}
+	values.get(STORAGE_PREFIX_FIELD)
+		.map(|cleared| storage_keys.iter().any(|prefix| {
+			cleared.starts_with(prefix.as_str()) || prefix.starts_with(cleared.as_str())
+		}))
+		.unwrap_or(false)
+}
+
+/// Flatten all recorded values into strings.
+fn event_data(values: Values) -> Data {
+	let Values { bool_values, i64_values, u64_values, mut string_values } = values;
+	string_values.extend(bool_values.into_iter().map(|(k, v)| (k, v.to_string())));
+	string_values.extend(i64_values.into_iter().map(|(k, v)| (k, v.to_string())));
+	string_values.extend(u64_values.into_iter().map(|(k, v)| (k, v.to_string())));
+	Data { string_values }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn storage_event(key: &str) -> TraceEvent {
+		let mut values = Values::default();
+		values.string_values.insert(STORAGE_KEY_FIELD.to_owned(), key.to_owned());
+		TraceEvent {
+			name: "event",
+			target: STORAGE_TARGET.to_owned(),
+			level: Level::TRACE,
+			values,
+			parent_id: None,
+		}
+	}
+
+	#[test]
+	fn storage_events_are_filtered_by_key_prefix() {
+		let prefixes = vec!["26aa".to_owned(), "c2261276".to_owned()];
+		assert!(event_matches_storage_keys(&storage_event("26aa394eea5630e0"), &prefixes));
+		assert!(event_matches_storage_keys(&storage_event("c2261276cc9d1f85"), &prefixes));
+		assert!(!event_matches_storage_keys(&storage_event("3a636f6465"), &prefixes));
+		assert!(event_matches_storage_keys(&storage_event("3a636f6465"), &[]));
+	}
+
+	#[test]
+	fn clear_prefix_events_are_filtered_by_overlapping_prefix() {
+		let clear_prefix = |prefix: &str| {
+			let mut event = storage_event("");
+			event.values.string_values.clear();
+			event.values.string_values.insert(STORAGE_PREFIX_FIELD.to_owned(), prefix.to_owned());
+			event
+		};
+		let prefixes = vec!["26aa394e".to_owned()];
+		assert!(event_matches_storage_keys(&clear_prefix("26aa394eea5630e0"), &prefixes));
+		assert!(event_matches_storage_keys(&clear_prefix("26aa"), &prefixes));
+		assert!(!event_matches_storage_keys(&clear_prefix("26ab"), &prefixes));
+		assert!(!event_matches_storage_keys(&clear_prefix("c2261276"), &prefixes));
+	}
+
+	#[test]
+	fn wasm_span_takes_recorded_name_and_target() {
+		let mut values = Values::default();
+		values.string_values.insert(WASM_NAME_KEY.to_owned(), "on_initialize".to_owned());
+		values.string_values.insert(WASM_TARGET_KEY.to_owned(), "pallet_balances".to_owned());
+		let span = SpanDatum {
+			id: Id::from_u64(1),
+			parent_id: None,
+			name: WASM_TRACE_IDENTIFIER.to_owned(),
+			target: WASM_TRACE_IDENTIFIER.to_owned(),
+			level: Level::TRACE,
+			line: 0,
+			start_time: Instant::now(),
+			overall_time: ZERO_DURATION,
+			values,
+		};
+		let (span, wasm) = patch_wasm_span(span);
+		assert!(wasm);
+		assert_eq!(span.name, "on_initialize");
+		assert_eq!(span.target, "pallet_balances");
+		assert!(span.values.string_values.is_empty());
+	}
+
+	#[test]
+	fn subscriber_collects_spans_and_events() {
+		let dispatch = Dispatch::new(BlockSubscriber::new("state,pallet"));
+		dispatcher::with_default(&dispatch, || {
+			let span = tracing::trace_span!(target: "pallet", "outer");
+			let _enter = span.enter();
+			tracing::trace!(target: "state", key = "00");
+			tracing::trace!(target: "other", key = "01");
+		});
+		let subscriber = dispatch.downcast_ref::<BlockSubscriber>().unwrap();
+		let spans = subscriber.spans.lock();
+		let events = subscriber.events.lock();
+		assert_eq!(spans.len(), 1);
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0].target, "state");
+		assert_eq!(events[0].parent_id, spans.keys().next().cloned());
+	}
+}
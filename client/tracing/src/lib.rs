@@ -29,6 +29,7 @@
 #![warn(missing_docs)]
 
 pub mod logging;
+pub mod block;
 
 use rustc_hash::FxHashMap;
 use std::fmt;
@@ -114,6 +114,10 @@
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
+//! The pallet also implements the `fungibles` traits of `frame_support::traits::tokens`, so that
+//! other pallets can deal with any of its assets generically. [`BalanceToAssetBalance`] converts
+//! a native balance into the balance of a sufficient asset, e.g. to pay transaction fees in it.
+//!
 //! ## Related Modules
 //!
 //! * [`System`](../frame_system/index.html)
@@ -130,17 +134,22 @@ pub mod mock;
 #[cfg(test)]
 mod tests;
 
+mod impl_fungibles;
+
 use sp_std::prelude::*;
 use sp_runtime::{
-	RuntimeDebug,
+	RuntimeDebug, FixedPointNumber, FixedPointOperand, FixedU128,
 	traits::{
-		AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedSub, CheckedAdd,
+		AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedSub, CheckedAdd, Convert,
 	}
 };
 use codec::{Encode, Decode, HasCompact};
 use frame_support::{
 	ensure,
-	traits::{Currency, ReservableCurrency, BalanceStatus::Reserved},
+	traits::{
+		Currency, ReservableCurrency, BalanceStatus::Reserved,
+		tokens::{fungible, BalanceConversion, DepositConsequence, WithdrawConsequence},
+	},
 	dispatch::{DispatchError, DispatchResult},
 };
 pub use weights::WeightInfo;
@@ -251,6 +260,67 @@ pub struct DestroyWitness {
 	approvals: u32,
 }
 
+/// How an account may be debited.
+#[derive(Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+struct DebitFlags {
+	/// The debit may not take the balance of the account below the minimum balance.
+	keep_alive: bool,
+	/// Less than the requested amount may be debited if the account does not have enough.
+	best_effort: bool,
+}
+
+/// Possible errors when converting between external and asset balances.
+#[derive(Eq, PartialEq, Copy, Clone, RuntimeDebug, Encode, Decode)]
+pub enum ConversionError {
+	/// The external minimum balance must not be zero.
+	MinBalanceZero,
+	/// The asset is not present in storage.
+	AssetMissing,
+	/// The asset is not sufficient and thus does not have a reliable `min_balance` so it cannot be
+	/// converted.
+	AssetNotSufficient,
+}
+
+type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+type BalanceOf<F, T> = <F as fungible::Inspect<AccountIdOf<T>>>::Balance;
+
+/// Converts a balance value into an asset balance based on the ratio between the fungible's
+/// minimum balance and the minimum asset balance.
+pub struct BalanceToAssetBalance<F, T, CON>(sp_std::marker::PhantomData<(F, T, CON)>);
+
+impl<F, T, CON> BalanceConversion<BalanceOf<F, T>, T::AssetId, T::Balance>
+	for BalanceToAssetBalance<F, T, CON>
+where
+	F: fungible::Inspect<AccountIdOf<T>>,
+	T: Config,
+	CON: Convert<BalanceOf<F, T>, T::Balance>,
+	BalanceOf<F, T>: FixedPointOperand + Zero,
+	T::Balance: FixedPointOperand + Zero,
+{
+	type Error = ConversionError;
+
+	/// Convert the given balance value into an asset balance based on the ratio between the
+	/// fungible's minimum balance and the minimum asset balance.
+	///
+	/// Will return `Err` if the asset is not found, not sufficient or the fungible's minimum
+	/// balance is zero.
+	fn to_asset_balance(
+		balance: BalanceOf<F, T>,
+		asset_id: T::AssetId,
+	) -> Result<T::Balance, ConversionError> {
+		let asset = Asset::<T>::get(asset_id).ok_or(ConversionError::AssetMissing)?;
+		// only sufficient assets have a min balance with reliable value
+		ensure!(asset.is_sufficient, ConversionError::AssetNotSufficient);
+		let min_balance = CON::convert(F::minimum_balance());
+		// make sure we don't divide by zero
+		ensure!(!min_balance.is_zero(), ConversionError::MinBalanceZero);
+		let balance = CON::convert(balance);
+		// balance * asset.min_balance / min_balance
+		Ok(FixedU128::saturating_from_rational(asset.min_balance, min_balance)
+			.saturating_mul_int(balance))
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{
@@ -611,25 +681,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let beneficiary = T::Lookup::lookup(beneficiary)?;
-
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-
-				ensure!(&origin == &details.issuer, Error::<T>::NoPermission);
-				details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-
-				Account::<T>::try_mutate(id, &beneficiary, |t| -> DispatchResult {
-					let new_balance = t.balance.saturating_add(amount);
-					ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
-					if t.balance.is_zero() {
-						t.sufficient = Self::new_account(&beneficiary, details)?;
-					}
-					t.balance = new_balance;
-					Ok(())
-				})?;
-				Self::deposit_event(Event::Issued(id, beneficiary, amount));
-				Ok(())
-			})
+			Self::do_mint(id, &beneficiary, amount, Some(origin))
 		}
 
 		/// Reduce the balance of `who` by as much as possible up to `amount` assets of `id`.
@@ -657,33 +709,8 @@ pub mod pallet {
 			let origin = ensure_signed(origin)?;
 			let who = T::Lookup::lookup(who)?;
 
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let d = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-				ensure!(&origin == &d.admin, Error::<T>::NoPermission);
-
-				let burned = Account::<T>::try_mutate_exists(
-					id,
-					&who,
-					|maybe_account| -> Result<T::Balance, DispatchError> {
-						let mut account = maybe_account.take().ok_or(Error::<T>::BalanceZero)?;
-						let mut burned = amount.min(account.balance);
-						account.balance -= burned;
-						*maybe_account = if account.balance < d.min_balance {
-							burned += account.balance;
-							Self::dead_account(&who, d, account.sufficient);
-							None
-						} else {
-							Some(account)
-						};
-						Ok(burned)
-					}
-				)?;
-
-				d.supply = d.supply.saturating_sub(burned);
-
-				Self::deposit_event(Event::Burned(id, who, burned));
-				Ok(())
-			})
+			let f = DebitFlags { keep_alive: false, best_effort: true };
+			Self::do_burn(id, &who, amount, Some(origin), f).map(|_| ())
 		}
 
 		/// Move some assets from the sender account to another.
@@ -1400,6 +1427,218 @@ impl<T: Config> Pallet<T> {
 		d.accounts = d.accounts.saturating_sub(1);
 	}
 
+	/// The consequence of increasing the `id` balance of `who` by `amount`.
+	fn can_increase(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> DepositConsequence {
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return DepositConsequence::UnknownAsset,
+		};
+		if details.supply.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		let account = Account::<T>::get(id, who);
+		if account.balance.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		if account.balance.is_zero() {
+			if amount < details.min_balance {
+				return DepositConsequence::BelowMinimum
+			}
+			if !details.is_sufficient && frame_system::Module::<T>::providers(who) == 0 {
+				return DepositConsequence::CannotCreate
+			}
+			if details.is_sufficient && details.sufficients.checked_add(1).is_none() {
+				return DepositConsequence::Overflow
+			}
+		}
+
+		DepositConsequence::Success
+	}
+
+	/// The consequence of decreasing the `id` balance of `who` by `amount`.
+	fn can_decrease(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> WithdrawConsequence<T::Balance> {
+		use WithdrawConsequence::*;
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return UnknownAsset,
+		};
+		if details.supply.checked_sub(&amount).is_none() {
+			return Underflow
+		}
+		if details.is_frozen {
+			return Frozen
+		}
+		let account = Account::<T>::get(id, who);
+		if account.is_frozen {
+			return Frozen
+		}
+		match account.balance.checked_sub(&amount) {
+			Some(rest) if rest < details.min_balance => {
+				if keep_alive { WouldDie } else { ReducedToZero(rest) }
+			},
+			Some(_) => Success,
+			None => NoFunds,
+		}
+	}
+
+	/// The amount of `id` that `who` can debit, optionally keeping the account alive.
+	fn reducible_balance(
+		id: T::AssetId,
+		who: &T::AccountId,
+		keep_alive: bool,
+	) -> Result<T::Balance, Error<T>> {
+		let details = Asset::<T>::get(id).ok_or(Error::<T>::Unknown)?;
+		ensure!(!details.is_frozen, Error::<T>::Frozen);
+
+		let account = Account::<T>::get(id, who);
+		ensure!(!account.is_frozen, Error::<T>::Frozen);
+
+		if keep_alive {
+			Ok(account.balance.saturating_sub(details.min_balance))
+		} else {
+			Ok(account.balance)
+		}
+	}
+
+	/// Works out the amount which would be debited from `target` when trying to debit `amount`
+	/// with the flags `f`, including any dust that would be left behind.
+	fn prep_debit(
+		id: T::AssetId,
+		target: &T::AccountId,
+		amount: T::Balance,
+		f: DebitFlags,
+	) -> Result<T::Balance, DispatchError> {
+		let actual = Self::reducible_balance(id, target, f.keep_alive)?.min(amount);
+		ensure!(f.best_effort || actual >= amount, Error::<T>::BalanceLow);
+
+		match Self::can_decrease(id, target, actual, f.keep_alive).into_result() {
+			Ok(dust) => Ok(actual.saturating_add(dust)),
+			Err(e) => {
+				debug_assert!(false, "passed from reducible_balance; qed");
+				Err(e)
+			},
+		}
+	}
+
+	/// Increases the `id` balance of `beneficiary` by `amount`, creating the account if needed.
+	///
+	/// `check` is called with the asset details before the balance is changed, which lets callers
+	/// verify permissions and alter the details, e.g. the supply, in the same storage mutation.
+	///
+	/// NOTE: LOW-LEVEL: This does not alter the supply of the asset by itself.
+	fn increase_balance(
+		id: T::AssetId,
+		beneficiary: &T::AccountId,
+		amount: T::Balance,
+		check: impl FnOnce(
+			&mut AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T>>,
+		) -> DispatchResult,
+	) -> DispatchResult {
+		Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			check(details)?;
+
+			Account::<T>::try_mutate(id, beneficiary, |t| -> DispatchResult {
+				let new_balance = t.balance.saturating_add(amount);
+				ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
+				if t.balance.is_zero() {
+					t.sufficient = Self::new_account(beneficiary, details)?;
+				}
+				t.balance = new_balance;
+				Ok(())
+			})
+		})
+	}
+
+	/// Decreases the `id` balance of `target` by `amount` according to the flags `f`, removing
+	/// the account if it drops below the minimum balance. Returns the amount actually debited.
+	///
+	/// `check` is called with the amount to be debited and the asset details before the balance
+	/// is changed.
+	///
+	/// NOTE: LOW-LEVEL: This does not alter the supply of the asset by itself.
+	fn decrease_balance(
+		id: T::AssetId,
+		target: &T::AccountId,
+		amount: T::Balance,
+		f: DebitFlags,
+		check: impl FnOnce(
+			T::Balance,
+			&mut AssetDetails<T::Balance, T::AccountId, DepositBalanceOf<T>>,
+		) -> DispatchResult,
+	) -> Result<T::Balance, DispatchError> {
+		let actual = Self::prep_debit(id, target, amount, f)?;
+
+		Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			check(actual, details)?;
+
+			Account::<T>::try_mutate_exists(id, target, |maybe_account| -> DispatchResult {
+				let mut account = maybe_account.take().ok_or(Error::<T>::BalanceZero)?;
+				debug_assert!(account.balance >= actual, "checked in prep; qed");
+				account.balance = account.balance.saturating_sub(actual);
+				*maybe_account = if account.balance < details.min_balance {
+					debug_assert!(account.balance.is_zero(), "checked in prep; qed");
+					Self::dead_account(target, details, account.sufficient);
+					None
+				} else {
+					Some(account)
+				};
+				Ok(())
+			})
+		})?;
+
+		Ok(actual)
+	}
+
+	/// Mints `amount` of `id` into the account of `beneficiary`, increasing the supply.
+	///
+	/// If `maybe_check_issuer` is `Some`, then it must be the issuer of the asset.
+	fn do_mint(
+		id: T::AssetId,
+		beneficiary: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_issuer: Option<T::AccountId>,
+	) -> DispatchResult {
+		Self::increase_balance(id, beneficiary, amount, |details| -> DispatchResult {
+			if let Some(check_issuer) = maybe_check_issuer {
+				ensure!(&check_issuer == &details.issuer, Error::<T>::NoPermission);
+			}
+			details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			Ok(())
+		})?;
+		Self::deposit_event(Event::Issued(id, beneficiary.clone(), amount));
+		Ok(())
+	}
+
+	/// Burns `amount` of `id` from the account of `target` according to the flags `f`, reducing
+	/// the supply. Returns the amount actually burned.
+	///
+	/// If `maybe_check_admin` is `Some`, then it must be the admin of the asset.
+	fn do_burn(
+		id: T::AssetId,
+		target: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_admin: Option<T::AccountId>,
+		f: DebitFlags,
+	) -> Result<T::Balance, DispatchError> {
+		let actual = Self::decrease_balance(id, target, amount, f, |actual, details| {
+			if let Some(check_admin) = maybe_check_admin {
+				ensure!(&check_admin == &details.admin, Error::<T>::NoPermission);
+			}
+			debug_assert!(details.supply >= actual, "checked in prep; qed");
+			details.supply = details.supply.saturating_sub(actual);
+			Ok(())
+		})?;
+		Self::deposit_event(Event::Burned(id, target.clone(), actual));
+		Ok(actual)
+	}
+
 	fn do_transfer(
 		id: T::AssetId,
 		source: &T::AccountId,
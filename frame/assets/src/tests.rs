@@ -490,4 +490,95 @@ fn set_metadata_should_work() {
 	});
 }
 
+#[test]
+fn fungibles_inspect_and_mutate_work() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::tokens::fungibles::{Inspect, Mutate};
+
+		assert_eq!(<Assets as Inspect<_>>::can_deposit(0, &1, 100), DepositConsequence::UnknownAsset);
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 10));
+		assert_eq!(<Assets as Inspect<_>>::minimum_balance(0), 10);
+		assert_eq!(<Assets as Inspect<_>>::can_deposit(0, &1, 5), DepositConsequence::BelowMinimum);
+
+		assert_ok!(<Assets as Mutate<_>>::mint_into(0, &1, 100));
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 100);
+		assert_eq!(<Assets as Inspect<_>>::balance(0, &1), 100);
+		assert_eq!(<Assets as Inspect<_>>::reducible_balance(0, &1, true), 90);
+		assert_eq!(<Assets as Inspect<_>>::can_withdraw(0, &1, 95), WithdrawConsequence::ReducedToZero(5));
+		assert_noop!(<Assets as Mutate<_>>::burn_from(0, &1, 101), Error::<Test>::BalanceLow);
+
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+		assert_eq!(<Assets as Inspect<_>>::can_withdraw(0, &1, 10), WithdrawConsequence::Frozen);
+		assert_eq!(<Assets as Inspect<_>>::reducible_balance(0, &1, false), 0);
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+
+		assert_eq!(<Assets as Mutate<_>>::burn_from(0, &1, 95), Ok(100));
+		assert_eq!(<Assets as Inspect<_>>::balance(0, &1), 0);
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 0);
+	});
+}
+
+frame_support::parameter_types! {
+	pub const AssetZero: u32 = 0;
+}
+
+#[test]
+fn fungibles_balanced_and_currency_adapter_work() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::{
+			ExistenceRequirement, Imbalance, WithdrawReasons,
+			tokens::{fungible, fungibles::{Balanced, Inspect}},
+		};
+		type AssetZeroCurrency = fungible::AsCurrency<fungible::ItemOf<Assets, AssetZero, u64>>;
+
+		assert_ok!(Assets::force_create(Origin::root(), 0, 1, true, 1));
+		let credit = <Assets as Balanced<_>>::issue(0, 50);
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 50);
+		assert!(<Assets as Balanced<_>>::resolve(&1, credit).is_ok());
+		assert_eq!(Assets::balance(0, 1), 50);
+
+		assert_eq!(<AssetZeroCurrency as Currency<u64>>::free_balance(&1), 50);
+		assert_ok!(<AssetZeroCurrency as Currency<u64>>::transfer(
+			&1,
+			&2,
+			20,
+			ExistenceRequirement::KeepAlive,
+		));
+		assert_eq!(Assets::balance(0, 2), 20);
+
+		let credit = <AssetZeroCurrency as Currency<u64>>::withdraw(
+			&1,
+			30,
+			WithdrawReasons::all(),
+			ExistenceRequirement::AllowDeath,
+		).unwrap();
+		assert_eq!(credit.peek(), 30);
+		assert_eq!(Assets::balance(0, 1), 0);
+		// Dropping the credit burns the withdrawn assets.
+		drop(credit);
+		assert_eq!(<Assets as Inspect<_>>::total_issuance(0), 20);
+	});
+}
+
+#[test]
+fn balance_conversion_should_work() {
+	new_test_ext().execute_with(|| {
+		use sp_runtime::traits::Identity;
+		type Converter = BalanceToAssetBalance<Balances, Test, Identity>;
+
+		let id = 42;
+		assert_ok!(Assets::force_create(Origin::root(), id, 1, true, 10));
+		let not_sufficient = 23;
+		assert_ok!(Assets::force_create(Origin::root(), not_sufficient, 1, false, 10));
+
+		assert_eq!(Converter::to_asset_balance(100, 1234), Err(ConversionError::AssetMissing));
+		assert_eq!(
+			Converter::to_asset_balance(100, not_sufficient),
+			Err(ConversionError::AssetNotSufficient),
+		);
+		// 10 / 1 == 10 -> the conversion should 10x the value
+		assert_eq!(Converter::to_asset_balance(100, id), Ok(100 * 10));
+	});
+}
+
 // TODO: tests for force_set_metadata, force_clear_metadata, force_asset_status
@@ -42,7 +42,8 @@ use sp_timestamp::OnTimestampSet;
 
 use sp_consensus_babe::{
 	digests::{NextConfigDescriptor, NextEpochDescriptor, PreDigest},
-	BabeAuthorityWeight, ConsensusLog, Epoch, EquivocationProof, Slot, BABE_ENGINE_ID,
+	BabeAuthorityWeight, BabeEpochConfiguration, ConsensusLog, Epoch, EquivocationProof, Slot,
+	BABE_ENGINE_ID,
 };
 use sp_consensus_vrf::schnorrkel;
 
@@ -190,6 +191,13 @@ decl_storage! {
 		/// Next epoch configuration, if changed.
 		NextEpochConfig: Option<NextConfigDescriptor>;
 
+		/// Configuration of the current epoch, `None` if the genesis configuration was never
+		/// changed.
+		EpochConfig: Option<BabeEpochConfiguration>;
+
+		/// Configuration of the next epoch, `None` if it is the same as the current one.
+		UpcomingEpochConfig: Option<BabeEpochConfiguration>;
+
 		/// Next epoch randomness.
 		NextRandomness: schnorrkel::Randomness;
 
@@ -490,7 +498,13 @@ impl<T: Config> Module<T> {
 		};
 		Self::deposit_consensus(ConsensusLog::NextEpochData(next_epoch));
 
+		// The configuration announced at the last epoch change applies from now on.
+		if let Some(config) = UpcomingEpochConfig::take() {
+			EpochConfig::put(config);
+		}
+
 		if let Some(next_config) = NextEpochConfig::take() {
+			UpcomingEpochConfig::put(BabeEpochConfiguration::from(next_config.clone()));
 			Self::deposit_consensus(ConsensusLog::NextConfigData(next_config));
 		}
 	}
@@ -510,6 +524,7 @@ impl<T: Config> Module<T> {
 			duration: T::EpochDuration::get(),
 			authorities: Self::authorities(),
 			randomness: Self::randomness(),
+			config: EpochConfig::get(),
 		}
 	}
 
@@ -527,6 +542,7 @@ impl<T: Config> Module<T> {
 			duration: T::EpochDuration::get(),
 			authorities: NextAuthorities::get(),
 			randomness: NextRandomness::get(),
+			config: UpcomingEpochConfig::get().or_else(EpochConfig::get),
 		}
 	}
 
@@ -154,6 +154,7 @@ parameter_types! {
 
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
 	type Balance = u128;
 	type DustRemoval = ();
 	type Event = Event;
@@ -255,6 +255,34 @@ fn can_enact_next_config() {
 	});
 }
 
+#[test]
+fn can_fetch_current_and_next_epoch_config() {
+	new_test_ext(1).execute_with(|| {
+		go_to_block(1, 6);
+
+		// the genesis configuration is used until it is changed.
+		assert_eq!(Babe::current_epoch().config, None);
+		assert_eq!(Babe::next_epoch().config, None);
+
+		let next_config = NextConfigDescriptor::V1 {
+			c: (1, 4),
+			allowed_slots: AllowedSlots::PrimarySlots,
+		};
+		Babe::plan_config_change(Origin::root(), next_config.clone()).unwrap();
+
+		// the change is announced at the next epoch change and applies to the epoch after it.
+		progress_to_block(4);
+		assert_eq!(Babe::epoch_index(), 1);
+		assert_eq!(Babe::current_epoch().config, None);
+		assert_eq!(Babe::next_epoch().config, Some(next_config.clone().into()));
+
+		progress_to_block(7);
+		assert_eq!(Babe::epoch_index(), 2);
+		assert_eq!(Babe::current_epoch().config, Some(next_config.clone().into()));
+		assert_eq!(Babe::next_epoch().config, Some(next_config.into()));
+	});
+}
+
 #[test]
 fn only_root_can_enact_config_change() {
 	use sp_runtime::DispatchError;
@@ -74,6 +74,8 @@
 //! fungible assets system.
 //! - [`ReservableCurrency`](frame_support::traits::ReservableCurrency):
 //! Functions for dealing with assets that can be reserved from an account.
+//! - [`NamedReservableCurrency`](frame_support::traits::NamedReservableCurrency):
+//! Functions for dealing with assets that can be reserved from an account under a name.
 //! - [`LockableCurrency`](frame_support::traits::LockableCurrency): Functions for
 //! dealing with accounts that allow liquidity restrictions.
 //! - [`Imbalance`](frame_support::traits::Imbalance): Functions for handling
@@ -163,14 +165,16 @@ use frame_support::{
 	traits::{
 		Currency, OnUnbalanced, TryDrop, StoredMap,
 		WithdrawReasons, LockIdentifier, LockableCurrency, ExistenceRequirement,
-		Imbalance, SignedImbalance, ReservableCurrency, Get, ExistenceRequirement::KeepAlive,
+		Imbalance, SignedImbalance, ReservableCurrency, NamedReservableCurrency, ReserveIdentifier,
+		Get, ExistenceRequirement::KeepAlive,
 		ExistenceRequirement::AllowDeath, BalanceStatus as Status,
+		tokens::{fungible, DepositConsequence, WithdrawConsequence},
 	}
 };
 #[cfg(feature = "std")]
 use frame_support::traits::GenesisBuild;
 use sp_runtime::{
-	RuntimeDebug, DispatchResult, DispatchError,
+	RuntimeDebug, DispatchResult, DispatchError, TokenError,
 	traits::{
 		Zero, AtLeast32BitUnsigned, StaticLookup, CheckedAdd, CheckedSub,
 		MaybeSerializeDeserialize, Saturating, Bounded, StoredMapError,
@@ -213,6 +217,9 @@ pub mod pallet {
 		/// The maximum number of locks that should exist on an account.
 		/// Not strictly enforced, but used for weight estimation.
 		type MaxLocks: Get<u32>;
+
+		/// The maximum number of named reserves that can exist on an account.
+		type MaxReserves: Get<u32>;
 	}
 
 	#[pallet::pallet]
@@ -413,6 +420,8 @@ pub mod pallet {
 		ExistingVestingSchedule,
 		/// Beneficiary account must pre-exist
 		DeadAccount,
+		/// Number of named reserves exceed MaxReserves
+		TooManyReserves,
 	}
 
 	/// The total units issued in the system.
@@ -444,9 +453,36 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+	/// Named reserves on some account balances.
+	#[pallet::storage]
+	#[pallet::getter(fn reserves)]
+	pub type Reserves<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<ReserveData<T::Balance>, T::MaxReserves>,
+		ValueQuery
+	>;
+
+	/// Deposits which are reserved without identifier because `name_reserved` couldn't name them,
+	/// the account having too many named reserves already.
+	///
+	/// `unreserve_named_or_unnamed` releases at most this much from the unnamed reserve.
+	#[pallet::storage]
+	#[pallet::getter(fn unnamed_deposits)]
+	pub type UnnamedDeposits<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		ReserveIdentifier,
+		T::Balance,
+		ValueQuery
+	>;
+
 	/// Storage version of the pallet.
 	///
-	/// This is set to v2.0.0 for new networks.
+	/// This is set to v3.0.0 for new networks.
 	#[pallet::storage]
 	pub(super) type StorageVersion<T: Config<I>, I: 'static = ()> = StorageValue<
 		_,
@@ -476,7 +512,7 @@ pub mod pallet {
 				.fold(Zero::zero(), |acc: T::Balance, &(_, n)| acc + n);
 			<TotalIssuance<T, I>>::put(total);
 
-			<StorageVersion<T, I>>::put(Releases::V2_0_0);
+			<StorageVersion<T, I>>::put(Releases::V3_0_0);
 
 			for (_, balance) in &self.balances {
 				assert!(
@@ -560,6 +596,15 @@ pub struct BalanceLock<Balance> {
 	pub reasons: Reasons,
 }
 
+/// Store named reserved balance.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ReserveData<Balance> {
+	/// The identifier for the named reserve.
+	pub id: ReserveIdentifier,
+	/// The amount of the named reserve.
+	pub amount: Balance,
+}
+
 /// All balance information for an account.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
 pub struct AccountData<Balance> {
@@ -611,6 +656,8 @@ impl<Balance: Saturating + Copy + Ord> AccountData<Balance> {
 enum Releases {
 	V1_0_0,
 	V2_0_0,
+	/// The deposits reserved before named reserves existed have been named.
+	V3_0_0,
 }
 
 impl Default for Releases {
@@ -653,11 +700,94 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Self::account(who.borrow()).reserved
 	}
 
+	/// Get the part of the reserved balance of an account which isn't reserved under any
+	/// identifier.
+	fn unnamed_reserved_balance(who: &T::AccountId) -> T::Balance {
+		let named = Self::reserves(who)
+			.iter()
+			.fold(Zero::zero(), |acc: T::Balance, data| acc.saturating_add(data.amount));
+		Self::reserved_balance(who).saturating_sub(named)
+	}
+
 	/// Get both the free and reserved balances of an account.
 	fn account(who: &T::AccountId) -> AccountData<T::Balance> {
 		T::AccountStore::get(&who)
 	}
 
+	/// The consequence of depositing `amount` into the account of `who`, with the current state
+	/// of the account being `account`.
+	fn deposit_consequence(
+		_who: &T::AccountId,
+		amount: T::Balance,
+		account: &AccountData<T::Balance>,
+	) -> DepositConsequence {
+		if amount.is_zero() { return DepositConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+
+		let new_total_balance = match account.total().checked_add(&amount) {
+			Some(x) => x,
+			None => return DepositConsequence::Overflow,
+		};
+
+		if new_total_balance < T::ExistentialDeposit::get() {
+			return DepositConsequence::BelowMinimum
+		}
+
+		// NOTE: We assume that we are a provider, so don't need to do any checks in the
+		// case of account creation.
+
+		DepositConsequence::Success
+	}
+
+	/// The consequence of withdrawing `amount` from the account of `who`, with the current state
+	/// of the account being `account`.
+	fn withdraw_consequence(
+		who: &T::AccountId,
+		amount: T::Balance,
+		account: &AccountData<T::Balance>,
+	) -> WithdrawConsequence<T::Balance> {
+		if amount.is_zero() { return WithdrawConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+
+		let new_total_balance = match account.total().checked_sub(&amount) {
+			Some(x) => x,
+			None => return WithdrawConsequence::NoFunds,
+		};
+
+		// Provider restriction - total account balance cannot be reduced to zero if it cannot
+		// sustain the loss of a provider reference.
+		// NOTE: This assumes that the pallet is a provider (which is true). Is this ever changes,
+		// then this will need to adapt accordingly.
+		let ed = T::ExistentialDeposit::get();
+		let success = if new_total_balance < ed {
+			if system::Pallet::<T>::is_provider_required(who) {
+				return WithdrawConsequence::WouldDie
+			}
+			WithdrawConsequence::ReducedToZero(new_total_balance)
+		} else {
+			WithdrawConsequence::Success
+		};
+
+		// Enough free funds to have them be reduced.
+		let new_free_balance = match account.free.checked_sub(&amount) {
+			Some(b) => b,
+			None => return WithdrawConsequence::NoFunds,
+		};
+
+		// Eventual free funds must be no less than the frozen balance.
+		if new_free_balance < account.frozen(Reasons::All) {
+			return WithdrawConsequence::Frozen
+		}
+
+		success
+	}
+
 	/// Handles any steps needed after mutating an account.
 	///
 	/// This includes DustRemoval unbalancing, in the case than the `new` account's total balance
@@ -802,6 +932,145 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 }
 
+impl<T: Config<I>, I: 'static> fungible::Inspect<T::AccountId> for Pallet<T, I> {
+	type Balance = T::Balance;
+
+	fn total_issuance() -> Self::Balance {
+		TotalIssuance::<T, I>::get()
+	}
+	fn minimum_balance() -> Self::Balance {
+		T::ExistentialDeposit::get()
+	}
+	fn balance(who: &T::AccountId) -> Self::Balance {
+		Self::account(who).total()
+	}
+	fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Self::Balance {
+		let a = Self::account(who);
+		// Liquid balance is what is neither reserved nor locked/frozen.
+		let liquid = a.free.saturating_sub(a.frozen(Reasons::All));
+		if !keep_alive && !system::Pallet::<T>::is_provider_required(who) {
+			liquid
+		} else {
+			// `must_remain_to_exist` is the part of liquid balance which must remain to keep total
+			// over ED.
+			let must_remain_to_exist = T::ExistentialDeposit::get().saturating_sub(a.total() - liquid);
+			liquid.saturating_sub(must_remain_to_exist)
+		}
+	}
+	fn can_deposit(who: &T::AccountId, amount: Self::Balance) -> DepositConsequence {
+		Self::deposit_consequence(who, amount, &Self::account(who))
+	}
+	fn can_withdraw(who: &T::AccountId, amount: Self::Balance) -> WithdrawConsequence<Self::Balance> {
+		Self::withdraw_consequence(who, amount, &Self::account(who))
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Mutate<T::AccountId> for Pallet<T, I> {
+	fn mint_into(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() { return Ok(()) }
+		Self::try_mutate_account(who, |account, _is_new| -> DispatchResult {
+			Self::deposit_consequence(who, amount, &account).into_result()?;
+			account.free += amount;
+			Ok(())
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t += amount);
+		Ok(())
+	}
+
+	fn burn_from(who: &T::AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+		if amount.is_zero() { return Ok(Zero::zero()) }
+		let actual = Self::try_mutate_account(who, |account, _is_new| -> Result<T::Balance, DispatchError> {
+			let extra = Self::withdraw_consequence(who, amount, &account).into_result()?;
+			// Any dust left in the reserved balance is removed by `post_mutation`.
+			let actual = (amount + extra).min(account.free);
+			account.free -= actual;
+			Ok(actual)
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t -= actual);
+		Ok(actual)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Transfer<T::AccountId> for Pallet<T, I> {
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		let er = if keep_alive { KeepAlive } else { AllowDeath };
+		<Self as Currency<T::AccountId>>::transfer(source, dest, amount, er)
+			.map(|_| amount)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Unbalanced<T::AccountId> for Pallet<T, I> {
+	fn set_balance(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		Self::try_mutate_account(who, |account, _is_new| -> DispatchResult {
+			// Make sure the reserved balance is not affected.
+			account.free = amount.checked_sub(&account.reserved).ok_or(TokenError::NoFunds)?;
+			Ok(())
+		})
+	}
+
+	fn set_total_issuance(amount: Self::Balance) {
+		TotalIssuance::<T, I>::put(amount)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T, I> {
+	fn balance_on_hold(who: &T::AccountId) -> T::Balance {
+		Self::account(who).reserved
+	}
+	fn can_hold(who: &T::AccountId, amount: T::Balance) -> bool {
+		<Self as ReservableCurrency<T::AccountId>>::can_reserve(who, amount)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> {
+	fn hold(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		<Self as ReservableCurrency<T::AccountId>>::reserve(who, amount)
+	}
+
+	fn release(who: &T::AccountId, amount: Self::Balance, best_effort: bool)
+		-> Result<T::Balance, DispatchError>
+	{
+		if amount.is_zero() { return Ok(amount) }
+		let actual = Self::try_mutate_account(who, |account, _is_new| -> Result<T::Balance, DispatchError> {
+			let actual = account.reserved.min(amount);
+			ensure!(best_effort || actual == amount, Error::<T, I>::InsufficientBalance);
+			account.reserved -= actual;
+			// This can never overflow since total issuance, which is at least free + reserved,
+			// fits into the same data type.
+			account.free = account.free.saturating_add(actual);
+			Ok(actual)
+		})?;
+		Self::deposit_event(Event::Unreserved(who.clone(), actual));
+		Ok(actual)
+	}
+
+	fn transfer_held(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+		on_hold: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		ensure!(
+			best_effort || Self::account(source).reserved >= amount,
+			Error::<T, I>::InsufficientBalance
+		);
+		let status = if on_hold { Status::Reserved } else { Status::Free };
+		let remaining = <Self as ReservableCurrency<T::AccountId>>::repatriate_reserved(
+			source,
+			dest,
+			amount,
+			status,
+		)?;
+		Ok(amount - remaining)
+	}
+}
+
 // wrapping these imbalances in a private module is necessary to ensure absolute privacy
 // of the inner member.
 mod imbalances {
@@ -1399,6 +1668,283 @@ impl<T: Config<I>, I: 'static> ReservableCurrency<T::AccountId> for Pallet<T, I>
 	}
 }
 
+impl<T: Config<I>, I: 'static> NamedReservableCurrency<T::AccountId> for Pallet<T, I>  where
+	T::Balance: MaybeSerializeDeserialize + Debug
+{
+	fn reserved_balance_named(id: &ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+		let reserves = Self::reserves(who);
+		reserves
+			.binary_search_by_key(id, |data| data.id)
+			.map(|index| reserves[index].amount)
+			.unwrap_or_default()
+	}
+
+	/// Move `value` from the free balance from `who` to a named reserve balance.
+	///
+	/// Is a no-op if value to be reserved is zero.
+	fn reserve_named(id: &ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		if value.is_zero() { return Ok(()) }
+
+		Reserves::<T, I>::try_mutate(who, |reserves| -> DispatchResult {
+			match reserves.binary_search_by_key(id, |data| data.id) {
+				Ok(index) => {
+					// this add can't overflow but just to be defensive.
+					reserves[index].amount = reserves[index].amount.saturating_add(value);
+				},
+				Err(index) => {
+					reserves.try_insert(index, ReserveData { id: *id, amount: value })
+						.map_err(|_| Error::<T, I>::TooManyReserves)?;
+				},
+			};
+			<Self as ReservableCurrency<_>>::reserve(who, value)
+		})
+	}
+
+	/// Unreserve some funds, returning any amount that was unable to be unreserved.
+	///
+	/// Is a no-op if the value to be unreserved is zero or the named reserve does not exist.
+	fn unreserve_named(id: &ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		if value.is_zero() { return Zero::zero() }
+
+		Reserves::<T, I>::mutate_exists(who, |maybe_reserves| -> Self::Balance {
+			if let Some(reserves) = maybe_reserves.as_mut() {
+				match reserves.binary_search_by_key(id, |data| data.id) {
+					Ok(index) => {
+						let to_change = cmp::min(reserves[index].amount, value);
+						let remain = <Self as ReservableCurrency<_>>::unreserve(who, to_change);
+						// remain should always be zero but just to be defensive here
+						let actual = to_change.saturating_sub(remain);
+						// `actual <= to_change` and `to_change <= amount`; qed;
+						reserves[index].amount -= actual;
+						if reserves[index].amount.is_zero() {
+							reserves.remove(index);
+						}
+						if reserves.is_empty() {
+							// no more named reserves
+							*maybe_reserves = None;
+						}
+						value - actual
+					},
+					Err(_) => value,
+				}
+			} else {
+				value
+			}
+		})
+	}
+
+	/// Slash from the named reserve, returning the negative imbalance created,
+	/// and any amount that was unable to be slashed.
+	///
+	/// Is a no-op if the value to be slashed is zero or the named reserve does not exist.
+	fn slash_reserved_named(
+		id: &ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance
+	) -> (Self::NegativeImbalance, Self::Balance) {
+		if value.is_zero() { return (NegativeImbalance::zero(), Zero::zero()) }
+
+		Reserves::<T, I>::mutate_exists(who, |maybe_reserves| -> (Self::NegativeImbalance, Self::Balance) {
+			if let Some(reserves) = maybe_reserves.as_mut() {
+				match reserves.binary_search_by_key(id, |data| data.id) {
+					Ok(index) => {
+						let to_change = cmp::min(reserves[index].amount, value);
+						let (imb, remain) = <Self as ReservableCurrency<_>>::slash_reserved(who, to_change);
+						// remain should always be zero but just to be defensive here
+						let actual = to_change.saturating_sub(remain);
+						// `actual <= to_change` and `to_change <= amount`; qed;
+						reserves[index].amount -= actual;
+						if reserves[index].amount.is_zero() {
+							reserves.remove(index);
+						}
+						if reserves.is_empty() {
+							*maybe_reserves = None;
+						}
+						(imb, value - actual)
+					},
+					Err(_) => (NegativeImbalance::zero(), value),
+				}
+			} else {
+				(NegativeImbalance::zero(), value)
+			}
+		})
+	}
+
+	/// Move the reserved balance of one account into the balance of another, according to `status`.
+	/// If `status` is `Reserved`, the balance will be reserved under the same name on `beneficiary`.
+	///
+	/// Is a no-op if:
+	/// - the value to be moved is zero; or
+	/// - the `slashed` id equal to `beneficiary` and the `status` is `Reserved`.
+	fn repatriate_reserved_named(
+		id: &ReserveIdentifier,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: Status,
+	) -> Result<Self::Balance, DispatchError> {
+		if value.is_zero() { return Ok(Zero::zero()) }
+
+		if slashed == beneficiary {
+			return match status {
+				Status::Free => Ok(Self::unreserve_named(id, slashed, value)),
+				Status::Reserved => Ok(value.saturating_sub(Self::reserved_balance_named(id, slashed))),
+			};
+		}
+
+		Reserves::<T, I>::try_mutate_exists(slashed, |maybe_reserves| -> Result<Self::Balance, DispatchError> {
+			let reserves = match maybe_reserves.as_mut() {
+				Some(reserves) => reserves,
+				None => return Ok(value),
+			};
+			let index = match reserves.binary_search_by_key(id, |data| data.id) {
+				Ok(index) => index,
+				Err(_) => return Ok(value),
+			};
+			let to_change = cmp::min(reserves[index].amount, value);
+
+			let actual = if status == Status::Reserved {
+				// make it the reserved under same identifier
+				Reserves::<T, I>::try_mutate(beneficiary, |to_reserves| -> Result<T::Balance, DispatchError> {
+					let to_index = to_reserves.binary_search_by_key(id, |data| data.id);
+					// check the bound before anything is moved
+					if to_index.is_err() {
+						ensure!(
+							(to_reserves.len() as u32) < T::MaxReserves::get(),
+							Error::<T, I>::TooManyReserves,
+						);
+					}
+					let remain = <Self as ReservableCurrency<_>>::repatriate_reserved(
+						slashed,
+						beneficiary,
+						to_change,
+						status,
+					)?;
+					// remain should always be zero but just to be defensive here
+					let actual = to_change.saturating_sub(remain);
+					match to_index {
+						Ok(to_index) => {
+							// this add can't overflow but just to be defensive.
+							to_reserves[to_index].amount = to_reserves[to_index].amount.saturating_add(actual);
+						},
+						Err(to_index) => {
+							to_reserves.try_insert(to_index, ReserveData { id: *id, amount: actual })
+								.map_err(|_| Error::<T, I>::TooManyReserves)?;
+						},
+					}
+					Ok(actual)
+				})?
+			} else {
+				let remain = <Self as ReservableCurrency<_>>::repatriate_reserved(
+					slashed,
+					beneficiary,
+					to_change,
+					status,
+				)?;
+				// remain should always be zero but just to be defensive here
+				to_change.saturating_sub(remain)
+			};
+
+			// `actual <= to_change` and `to_change <= amount`; qed;
+			reserves[index].amount -= actual;
+			if reserves[index].amount.is_zero() {
+				reserves.remove(index);
+			}
+			if reserves.is_empty() {
+				*maybe_reserves = None;
+			}
+			Ok(value - actual)
+		})
+	}
+
+	/// Name some of the reserved balance which isn't reserved under any identifier, returning
+	/// any amount that was unable to be named.
+	///
+	/// If the account has too many named reserves, the amount is recorded in `UnnamedDeposits`
+	/// instead.
+	///
+	/// Is a no-op if the value to be named is zero.
+	fn name_reserved(
+		id: &ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		if value.is_zero() { return Zero::zero() }
+
+		let to_change = cmp::min(Self::unnamed_reserved_balance(who), value);
+		if to_change.is_zero() { return value }
+
+		let named = Reserves::<T, I>::try_mutate(who, |reserves| -> Result<(), ()> {
+			match reserves.binary_search_by_key(id, |data| data.id) {
+				Ok(index) => {
+					// this add can't overflow but just to be defensive.
+					reserves[index].amount = reserves[index].amount.saturating_add(to_change);
+					Ok(())
+				},
+				Err(index) => {
+					reserves.try_insert(index, ReserveData { id: *id, amount: to_change })
+				},
+			}
+		});
+		match named {
+			Ok(()) => value - to_change,
+			Err(()) => {
+				log::warn!(
+					target: "runtime::balances",
+					"Warning: A deposit couldn't be named, the user has too many named reserves. \
+					It is recorded as an unnamed deposit instead."
+				);
+				UnnamedDeposits::<T, I>::mutate(who, id, |deposit| {
+					*deposit = deposit.saturating_add(to_change)
+				});
+				value
+			},
+		}
+	}
+
+	fn unnamed_deposit(id: &ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+		Self::unnamed_deposits(who, id)
+	}
+
+	/// Unreserve some of the reserved balance which isn't reserved under any identifier,
+	/// returning any amount that was unable to be unreserved.
+	///
+	/// Is a no-op if the value to be unreserved is zero.
+	fn unreserve_unnamed(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		if value.is_zero() { return Zero::zero() }
+
+		let to_change = cmp::min(Self::unnamed_reserved_balance(who), value);
+		let remain = <Self as ReservableCurrency<_>>::unreserve(who, to_change);
+		// `to_change <= value`; qed;
+		value - to_change + remain
+	}
+
+	/// Unreserve from the reserved balance named `id`, then from the deposits recorded for `id`
+	/// in `UnnamedDeposits`, returning any amount that was unable to be unreserved.
+	///
+	/// Is a no-op if the value to be unreserved is zero.
+	fn unreserve_named_or_unnamed(
+		id: &ReserveIdentifier,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		let remaining = Self::unreserve_named(id, who, value);
+		if remaining.is_zero() { return Zero::zero() }
+
+		let recorded = UnnamedDeposits::<T, I>::get(who, id);
+		let to_change = cmp::min(recorded, remaining);
+		if to_change.is_zero() { return remaining }
+
+		if recorded == to_change {
+			UnnamedDeposits::<T, I>::remove(who, id);
+		} else {
+			UnnamedDeposits::<T, I>::insert(who, id, recorded - to_change);
+		}
+		// `to_change <= remaining`; qed;
+		remaining - to_change + Self::unreserve_unnamed(who, to_change)
+	}
+}
+
 impl<T: Config<I>, I: 'static> LockableCurrency<T::AccountId> for Pallet<T, I>
 where
 	T::Balance: MaybeSerializeDeserialize + Debug
@@ -1463,3 +2009,27 @@ where
 		Self::update_locks(who, &locks[..]);
 	}
 }
+
+pub mod migration {
+	use super::*;
+	use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+
+	/// Runs `M` once, on the first runtime upgrade which finds this pallet storage older than
+	/// named reserves.
+	///
+	/// `M` should name the deposits which other pallets reserved before, e.g. with
+	/// [`NamedReservableCurrency::name_deposits`].
+	pub struct MigrateToNamedReserves<T, I, M>(sp_std::marker::PhantomData<(T, I, M)>);
+
+	impl<T: Config<I>, I: 'static, M: OnRuntimeUpgrade> OnRuntimeUpgrade
+		for MigrateToNamedReserves<T, I, M>
+	{
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::<T, I>::get() == Releases::V3_0_0 {
+				return T::DbWeight::get().reads(1)
+			}
+			StorageVersion::<T, I>::put(Releases::V3_0_0);
+			M::on_runtime_upgrade().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		}
+	}
+}
@@ -974,5 +974,355 @@ macro_rules! decl_tests {
 					assert_eq!(Balances::total_balance(&2), 100);
 				});
 		}
+
+		#[test]
+		fn fungible_inspect_and_mutate_work() {
+			<$ext_builder>::default()
+				.existential_deposit(10)
+				.build()
+				.execute_with(|| {
+					use fungible::{Inspect, Mutate};
+					let issuance = <Balances as Inspect<_>>::total_issuance();
+
+					assert_eq!(<Balances as Inspect<_>>::can_deposit(&1, 5), DepositConsequence::BelowMinimum);
+					assert_noop!(<Balances as Mutate<_>>::mint_into(&1, 5), TokenError::BelowMinimum);
+					assert_ok!(<Balances as Mutate<_>>::mint_into(&1, 100));
+					assert_eq!(<Balances as Inspect<_>>::balance(&1), 100);
+					assert_eq!(<Balances as Inspect<_>>::total_issuance(), issuance + 100);
+
+					assert_ok!(Balances::reserve(&1, 50));
+					assert_eq!(<Balances as Inspect<_>>::balance(&1), 100);
+					assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, false), 50);
+					assert_eq!(<Balances as Inspect<_>>::can_withdraw(&1, 60), WithdrawConsequence::NoFunds);
+					Balances::unreserve(&1, 50);
+
+					Balances::set_lock(ID_1, &1, 60, WithdrawReasons::all());
+					assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, false), 40);
+					assert_eq!(<Balances as Inspect<_>>::can_withdraw(&1, 50), WithdrawConsequence::Frozen);
+					Balances::remove_lock(ID_1, &1);
+
+					assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, true), 90);
+					assert_eq!(<Balances as Inspect<_>>::can_withdraw(&1, 95), WithdrawConsequence::ReducedToZero(5));
+					assert_eq!(<Balances as Mutate<_>>::burn_from(&1, 95), Ok(100));
+					assert_eq!(<Balances as Inspect<_>>::balance(&1), 0);
+					assert_eq!(<Balances as Inspect<_>>::total_issuance(), issuance);
+				});
+		}
+
+		#[test]
+		fn fungible_balanced_works() {
+			<$ext_builder>::default()
+				.existential_deposit(1)
+				.monied(true)
+				.build()
+				.execute_with(|| {
+					use fungible::{Balanced, Inspect};
+					let issuance = <Balances as Inspect<_>>::total_issuance();
+
+					let credit = <Balances as Balanced<_>>::issue(10);
+					assert_eq!(<Balances as Inspect<_>>::total_issuance(), issuance + 10);
+					assert!(<Balances as Balanced<_>>::resolve(&1, credit).is_ok());
+					assert_eq!(Balances::free_balance(1), 20);
+
+					let (credit, left) = <Balances as Balanced<_>>::slash(&1, 25);
+					assert_eq!(credit.peek(), 20);
+					assert_eq!(left, 5);
+					assert_eq!(Balances::free_balance(1), 0);
+
+					// Dropping the credit burns the slashed funds.
+					drop(credit);
+					assert_eq!(<Balances as Inspect<_>>::total_issuance(), issuance - 10);
+				});
+		}
+
+		#[test]
+		fn fungible_hold_works() {
+			<$ext_builder>::default()
+				.existential_deposit(1)
+				.monied(true)
+				.build()
+				.execute_with(|| {
+					use fungible::{InspectHold, MutateHold};
+
+					assert!(<Balances as InspectHold<_>>::can_hold(&1, 10));
+					assert!(!<Balances as InspectHold<_>>::can_hold(&1, 11));
+					assert_ok!(<Balances as MutateHold<_>>::hold(&1, 8));
+					assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&1), 8);
+
+					assert_noop!(
+						<Balances as MutateHold<_>>::release(&1, 9, false),
+						Error::<$test, _>::InsufficientBalance
+					);
+					assert_eq!(<Balances as MutateHold<_>>::release(&1, 3, false), Ok(3));
+					assert_eq!(Balances::free_balance(1), 5);
+
+					assert_noop!(
+						<Balances as MutateHold<_>>::transfer_held(&1, &2, 6, false, false),
+						Error::<$test, _>::InsufficientBalance
+					);
+					assert_eq!(<Balances as MutateHold<_>>::transfer_held(&1, &2, 6, true, true), Ok(5));
+					assert_eq!(Balances::reserved_balance(1), 0);
+					assert_eq!(Balances::reserved_balance(2), 5);
+					assert_eq!(Balances::free_balance(2), 20);
+				});
+		}
+
+		#[test]
+		fn named_reserve_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 111);
+
+				let id_1: ReserveIdentifier = *b"id_1    ";
+				let id_2: ReserveIdentifier = *b"id_2    ";
+				let id_3: ReserveIdentifier = *b"id_3    ";
+
+				// reserve
+				assert_noop!(Balances::reserve_named(&id_1, &1, 112), Error::<$test, _>::InsufficientBalance);
+				assert_ok!(Balances::reserve_named(&id_1, &1, 12));
+				assert_eq!(Balances::reserved_balance(1), 12);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 12);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 0);
+
+				assert_ok!(Balances::reserve_named(&id_1, &1, 2));
+				assert_eq!(Balances::reserved_balance(1), 14);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 14);
+
+				assert_ok!(Balances::reserve_named(&id_2, &1, 23));
+				assert_eq!(Balances::reserved_balance(1), 37);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 14);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 23);
+
+				// an anonymous reserve does not belong to any named reserve
+				assert_ok!(Balances::reserve(&1, 34));
+				assert_eq!(Balances::reserved_balance(1), 71);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 14);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 23);
+				assert_eq!(Balances::free_balance(1), 40);
+
+				// unreserve
+				assert_eq!(Balances::unreserve_named(&id_1, &1, 10), 0);
+				assert_eq!(Balances::reserved_balance(1), 61);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 4);
+
+				// only the named reserve is unreserved
+				assert_eq!(Balances::unreserve_named(&id_1, &1, 5), 1);
+				assert_eq!(Balances::reserved_balance(1), 57);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 23);
+
+				// unknown names are not unreserved
+				assert_eq!(Balances::unreserve_named(&id_3, &1, 5), 5);
+				assert_eq!(Balances::reserved_balance(1), 57);
+				assert_eq!(Balances::free_balance(1), 54);
+
+				// emptied named reserves are removed
+				assert_eq!(Balances::reserves(&1).len(), 1);
+				assert_eq!(Balances::unreserve_all_named(&id_2, &1), 23);
+				assert!(!Reserves::<$test>::contains_key(&1));
+				assert_eq!(Balances::reserved_balance(1), 34);
+			});
+		}
+
+		#[test]
+		fn reserve_named_fails_with_too_many_reserves() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::NamedReservableCurrency;
+
+				let _ = Balances::deposit_creating(&1, 1000);
+				let max_reserves = <$test as Config>::MaxReserves::get() as u8;
+				for i in 0..max_reserves {
+					assert_ok!(Balances::reserve_named(&[i; 8], &1, 1));
+				}
+				// existing names can still be increased
+				assert_ok!(Balances::reserve_named(&[0; 8], &1, 1));
+				assert_noop!(
+					Balances::reserve_named(&[max_reserves; 8], &1, 1),
+					Error::<$test, _>::TooManyReserves,
+				);
+				assert_eq!(Balances::reserved_balance(1), max_reserves as u64 + 1);
+			});
+		}
+
+		#[test]
+		fn slash_reserved_named_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 111);
+
+				let id_1: ReserveIdentifier = *b"id_1    ";
+				let id_2: ReserveIdentifier = *b"id_2    ";
+
+				assert_ok!(Balances::reserve_named(&id_1, &1, 10));
+				assert_ok!(Balances::reserve_named(&id_2, &1, 10));
+				assert_ok!(Balances::reserve(&1, 10));
+
+				assert_eq!(Balances::slash_reserved_named(&id_1, &1, 4).1, 0);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 6);
+				assert_eq!(Balances::reserved_balance(1), 26);
+
+				// only the named reserve is slashed
+				assert_eq!(Balances::slash_reserved_named(&id_1, &1, 10).1, 4);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 10);
+				assert_eq!(Balances::reserved_balance(1), 20);
+				assert_eq!(<TotalIssuance<$test>>::get(), 101);
+			});
+		}
+
+		#[test]
+		fn repatriate_reserved_named_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 110);
+				let _ = Balances::deposit_creating(&2, 10);
+
+				let id_1: ReserveIdentifier = *b"id_1    ";
+				let id_2: ReserveIdentifier = *b"id_2    ";
+
+				assert_ok!(Balances::reserve_named(&id_1, &1, 50));
+				assert_ok!(Balances::reserve(&1, 50));
+
+				assert_ok!(Balances::repatriate_reserved_named(&id_1, &1, &2, 10, Status::Free), 0);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 40);
+				assert_eq!(Balances::reserved_balance(1), 90);
+				assert_eq!(Balances::free_balance(2), 20);
+
+				// reserved funds keep their name on the beneficiary
+				assert_ok!(Balances::repatriate_reserved_named(&id_1, &1, &2, 30, Status::Reserved), 0);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 10);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &2), 30);
+				assert_eq!(Balances::reserved_balance(2), 30);
+
+				// only the named reserve is repatriated
+				assert_ok!(Balances::repatriate_reserved_named(&id_1, &1, &2, 20, Status::Free), 10);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::reserved_balance(1), 50);
+				assert_eq!(Balances::free_balance(2), 30);
+
+				// unknown names are not repatriated
+				assert_ok!(Balances::repatriate_reserved_named(&id_2, &1, &2, 20, Status::Free), 20);
+				assert_eq!(Balances::reserved_balance(1), 50);
+
+				// the beneficiary must exist
+				assert_ok!(Balances::reserve_named(&id_1, &1, 10));
+				assert_noop!(
+					Balances::repatriate_reserved_named(&id_1, &1, &3, 10, Status::Free),
+					Error::<$test, _>::DeadAccount,
+				);
+			});
+		}
+
+		#[test]
+		fn ensure_reserved_named_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 111);
+
+				let id: ReserveIdentifier = *b"id_1    ";
+
+				assert_ok!(Balances::ensure_reserved_named(&id, &1, 15));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 15);
+
+				assert_ok!(Balances::ensure_reserved_named(&id, &1, 10));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 10);
+
+				assert_ok!(Balances::ensure_reserved_named(&id, &1, 20));
+				assert_eq!(Balances::reserved_balance_named(&id, &1), 20);
+				assert_eq!(Balances::reserved_balance(1), 20);
+			});
+		}
+
+		#[test]
+		fn name_deposits_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 111);
+				let _ = Balances::deposit_creating(&2, 111);
+
+				let id_1: ReserveIdentifier = *b"id_1    ";
+				let id_2: ReserveIdentifier = *b"id_2    ";
+
+				assert_ok!(Balances::reserve(&1, 30));
+				assert_ok!(Balances::reserve_named(&id_2, &1, 5));
+				assert_ok!(Balances::reserve(&2, 5));
+
+				// deposits are summed per account and never taken from other names
+				let deposits = vec![(1, 10), (2, 10), (1, 15)];
+				assert_eq!(Balances::name_deposits(&id_1, deposits.clone()), 2);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 25);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 5);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &2), 5);
+				assert_eq!(Balances::reserved_balance(1), 35);
+				assert_eq!(Balances::free_balance(1), 76);
+
+				// naming the same deposits again changes nothing
+				assert_eq!(Balances::name_deposits(&id_1, deposits), 2);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 25);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &2), 5);
+				assert_eq!(Balances::reserved_balance(1), 35);
+			});
+		}
+
+		#[test]
+		fn unreserve_named_or_unnamed_should_work() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 111);
+
+				let id_1: ReserveIdentifier = *b"id_1    ";
+				let id_2: ReserveIdentifier = *b"id_2    ";
+
+				assert_ok!(Balances::reserve_named(&id_1, &1, 10));
+				assert_ok!(Balances::reserve_named(&id_2, &1, 20));
+				assert_ok!(Balances::reserve(&1, 5));
+
+				// nothing is recorded, so the unnamed reserve and other names are never touched
+				assert_eq!(Balances::unreserve_named_or_unnamed(&id_1, &1, 20), 10);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::reserved_balance_named(&id_2, &1), 20);
+				assert_eq!(Balances::reserved_balance(1), 25);
+			});
+		}
+
+		#[test]
+		fn deposits_which_cant_be_named_are_recorded() {
+			<$ext_builder>::default().build().execute_with(|| {
+				use frame_support::traits::{Get, NamedReservableCurrency, ReserveIdentifier};
+
+				let _ = Balances::deposit_creating(&1, 111);
+
+				let id_1: ReserveIdentifier = *b"id_1    ";
+
+				assert_ok!(Balances::reserve(&1, 10));
+				for i in 0..<$test as Config>::MaxReserves::get() {
+					assert_ok!(Balances::reserve_named(&[i as u8; 8], &1, 1));
+				}
+				let named = <$test as Config>::MaxReserves::get() as u64;
+
+				// the account has too many named reserves, so the deposit is recorded instead
+				assert_eq!(Balances::name_deposits(&id_1, vec![(1, 10)]), 1);
+				assert_eq!(Balances::reserved_balance_named(&id_1, &1), 0);
+				assert_eq!(Balances::unnamed_deposits(1, id_1), 10);
+
+				// naming the same deposits again changes nothing
+				assert_eq!(Balances::name_deposits(&id_1, vec![(1, 10)]), 1);
+				assert_eq!(Balances::unnamed_deposits(1, id_1), 10);
+
+				// only the recorded deposit is released, not what another module reserved since
+				assert_ok!(Balances::reserve(&1, 5));
+				assert_eq!(Balances::unreserve_named_or_unnamed(&id_1, &1, 20), 10);
+				assert_eq!(Balances::unnamed_deposits(1, id_1), 0);
+				assert_eq!(Balances::reserved_balance(1), named + 5);
+				assert_eq!(Balances::unreserve_named_or_unnamed(&id_1, &1, 5), 5);
+				assert_eq!(Balances::reserved_balance(1), named + 5);
+			});
+		}
 	}
 }
@@ -109,6 +109,7 @@ impl OnUnbalanced<NegativeImbalance<Test>> for OnDustRemoval {
 }
 parameter_types! {
 	pub const MaxLocks: u32 = 50;
+	pub const MaxReserves: u32 = 50;
 }
 impl Config for Test {
 	type Balance = u64;
@@ -122,6 +123,7 @@ impl Config for Test {
 		super::AccountData<u64>,
 	>;
 	type MaxLocks = MaxLocks;
+	type MaxReserves = MaxReserves;
 	type WeightInfo = ();
 }
 
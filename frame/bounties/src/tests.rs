@@ -86,6 +86,7 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -26,11 +26,75 @@ use sp_std::prelude::*;
 
 /// Result type of a `bare_call` call.
 ///
-/// The result of a contract execution along with a gas consumed.
+/// The result of a contract execution along with a gas consumed. The remaining fields are
+/// only populated when debugging was requested for the call and are empty otherwise.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
-pub struct ContractExecResult {
+pub struct ContractExecResult<AccountId, Balance> {
 	pub exec_result: ExecResult,
 	pub gas_consumed: u64,
+	/// The messages written by contracts using `seal_debug_message`.
+	///
+	/// Contracts are expected to write UTF-8 but this is not enforced across messages.
+	pub debug_message: Vec<u8>,
+	/// Every call frame that was entered during the execution in the order of entering.
+	pub frames: Vec<CallFrameTrace<AccountId>>,
+	/// The events emitted by contracts whose emitting call frame was not rolled back.
+	pub events: Vec<EmittedEvent<AccountId>>,
+	/// The rent that was charged from contracts whose charging was not rolled back.
+	pub rent: Vec<RentCharge<AccountId, Balance>>,
+}
+
+/// The gas consumption and outcome of a single call frame.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct CallFrameTrace<AccountId> {
+	/// The contract in whose context the frame executed.
+	pub contract: AccountId,
+	/// The call depth of the frame. The outermost contract is at depth `1`.
+	pub depth: u32,
+	/// The gas consumed by this frame including all frames nested into it.
+	pub gas_consumed: u64,
+	/// Whether the frame completed without trapping or reverting.
+	pub success: bool,
+}
+
+/// An event that was deposited by a contract through `seal_deposit_event`.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct EmittedEvent<AccountId> {
+	/// The contract that emitted the event.
+	pub contract: AccountId,
+	/// The data of the event as supplied by the contract.
+	pub data: Vec<u8>,
+}
+
+/// Rent that was charged from a contract when it was called or instantiated.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct RentCharge<AccountId, Balance> {
+	/// The contract that payed the rent.
+	pub contract: AccountId,
+	/// The amount of rent that was payed.
+	pub amount: Balance,
+}
+
+/// Result type of a `bare_call` call as returned by version 1 of the `ContractsApi`.
+///
+/// Runtimes implementing that version return no debugging information.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractExecResultV1 {
+	pub exec_result: ExecResult,
+	pub gas_consumed: u64,
+}
+
+impl<AccountId, Balance> From<ContractExecResultV1> for ContractExecResult<AccountId, Balance> {
+	fn from(result: ContractExecResultV1) -> Self {
+		Self {
+			exec_result: result.exec_result,
+			gas_consumed: result.gas_consumed,
+			debug_message: Vec::new(),
+			frames: Vec::new(),
+			events: Vec::new(),
+			rent: Vec::new(),
+		}
+	}
 }
 
 /// Result type of a `get_storage` call.
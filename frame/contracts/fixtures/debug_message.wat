@@ -0,0 +1,42 @@
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "seal0" "seal_deposit_event" (func $seal_deposit_event (param i32 i32 i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 12) the debug message
+	(data (i32.const 0) "Hello World!")
+
+	;; [12, 16) the event data
+	(data (i32.const 12) "\01\02\03\04")
+
+	;; [16, 20) buffer where input is copied
+
+	;; [20, 24) length of the input buffer
+	(data (i32.const 20) "\04")
+
+	(func (export "deploy"))
+
+	;; Writes a debug message and deposits an event. Reverts if the first input byte is non zero.
+	(func (export "call")
+		(call $seal_input (i32.const 16) (i32.const 20))
+		(drop
+			(call $seal_debug_message
+				(i32.const 0)	;; Pointer to the text buffer
+				(i32.const 12)	;; The size of the buffer
+			)
+		)
+		(call $seal_deposit_event
+			(i32.const 0)	;; Pointer to the topics buffer
+			(i32.const 0)	;; The size of the topics buffer
+			(i32.const 12)	;; Pointer to the event data
+			(i32.const 4)	;; The size of the event data
+		)
+		(call $seal_return
+			(i32.load8_u (i32.const 16))	;; Exit status
+			(i32.const 0)	;; Pointer to the data to return
+			(i32.const 0)	;; Length of the data to return
+		)
+	)
+)
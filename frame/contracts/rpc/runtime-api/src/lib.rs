@@ -25,10 +25,13 @@
 
 use codec::Codec;
 use sp_std::vec::Vec;
-use pallet_contracts_primitives::{ContractExecResult, GetStorageResult, RentProjectionResult};
+use pallet_contracts_primitives::{
+	ContractExecResult, ContractExecResultV1, GetStorageResult, RentProjectionResult,
+};
 
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
+	#[api_version(2)]
 	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
 		AccountId: Codec,
 		Balance: Codec,
@@ -37,13 +40,27 @@ sp_api::decl_runtime_apis! {
 		/// Perform a call from a specified account to a given contract.
 		///
 		/// See the contracts' `call` dispatchable function for more details.
+		///
+		/// As this is never executed on-chain the returned result also contains the
+		/// information collected for debugging purposes.
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractExecResult<AccountId, Balance>;
+
+		/// Perform a call from a specified account to a given contract, without collecting any
+		/// debugging information.
+		#[changed_in(2)]
 		fn call(
 			origin: AccountId,
 			dest: AccountId,
 			value: Balance,
 			gas_limit: u64,
 			input_data: Vec<u8>,
-		) -> ContractExecResult;
+		) -> ContractExecResultV1;
 
 		/// Query a given storage key in a given contract.
 		///
@@ -24,7 +24,7 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use pallet_contracts_primitives::RentProjection;
 use serde::{Deserialize, Serialize};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, H256};
 use sp_rpc::number;
@@ -94,37 +94,93 @@ struct RpcContractExecSuccess {
 	data: Bytes,
 }
 
+/// An RPC serializable call frame. See `pallet_contracts_primitives::CallFrameTrace`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCallFrame<AccountId> {
+	/// The contract in whose context the frame executed.
+	contract: AccountId,
+	/// The call depth of the frame. The outermost contract is at depth `1`.
+	depth: u32,
+	/// The gas consumed by this frame including all frames nested into it.
+	gas_consumed: u64,
+	/// Whether the frame completed without trapping or reverting.
+	success: bool,
+}
+
+/// An RPC serializable contract event. See `pallet_contracts_primitives::EmittedEvent`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEmittedEvent<AccountId> {
+	/// The contract that emitted the event.
+	contract: AccountId,
+	/// The data of the event as supplied by the contract.
+	data: Bytes,
+}
+
+/// An RPC serializable rent charge. See `pallet_contracts_primitives::RentCharge`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRentCharge<AccountId, Balance> {
+	/// The contract that payed the rent.
+	contract: AccountId,
+	/// The amount of rent that was payed.
+	amount: Balance,
+}
+
 /// An RPC serializable result of contract execution
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
-pub struct RpcContractExecResult {
+pub struct RpcContractExecResult<AccountId, Balance> {
 	/// How much gas was consumed by the call. In case of an error this is the amount
 	/// that was used up until the error occurred.
 	gas_consumed: u64,
 	/// Additional dynamic human readable error information for debugging. An empty string
 	/// indicates that no additional information is available.
 	debug_message: String,
+	/// Every call frame that was entered during the execution in the order of entering.
+	frames: Vec<RpcCallFrame<AccountId>>,
+	/// The events emitted by contracts whose emitting call frame was not rolled back.
+	events: Vec<RpcEmittedEvent<AccountId>>,
+	/// The rent that was charged from contracts whose charging was not rolled back.
+	rent: Vec<RpcRentCharge<AccountId, Balance>>,
 	/// Indicates whether the contract execution was successful or not.
 	result: std::result::Result<RpcContractExecSuccess, DispatchError>,
 }
 
-impl From<ContractExecResult> for RpcContractExecResult {
-	fn from(r: ContractExecResult) -> Self {
-		match r.exec_result {
-			Ok(val) => RpcContractExecResult {
-				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
-				result: Ok(RpcContractExecSuccess {
-					flags: val.flags.bits(),
-					data: val.data.into(),
-				}),
-			},
-			Err(err) => RpcContractExecResult {
-				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
-				result: Err(err.error),
-			},
+impl<AccountId, Balance> From<ContractExecResult<AccountId, Balance>>
+	for RpcContractExecResult<AccountId, Balance>
+{
+	fn from(r: ContractExecResult<AccountId, Balance>) -> Self {
+		let result = match r.exec_result {
+			Ok(val) => Ok(RpcContractExecSuccess {
+				flags: val.flags.bits(),
+				data: val.data.into(),
+			}),
+			Err(err) => Err(err.error),
+		};
+		RpcContractExecResult {
+			gas_consumed: r.gas_consumed,
+			debug_message: String::from_utf8_lossy(&r.debug_message).into_owned(),
+			frames: r.frames.into_iter().map(|f| RpcCallFrame {
+				contract: f.contract,
+				depth: f.depth,
+				gas_consumed: f.gas_consumed,
+				success: f.success,
+			}).collect(),
+			events: r.events.into_iter().map(|e| RpcEmittedEvent {
+				contract: e.contract,
+				data: e.data.into(),
+			}).collect(),
+			rent: r.rent.into_iter().map(|c| RpcRentCharge {
+				contract: c.contract,
+				amount: c.amount,
+			}).collect(),
+			result,
 		}
 	}
 }
@@ -137,13 +193,15 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 	/// This call is performed locally without submitting any transactions. Thus executing this
 	/// won't change any state. Nonetheless, the calling state-changing contracts is still possible.
 	///
-	/// This method is useful for calling getter-like methods on contracts.
+	/// This method is useful for calling getter-like methods on contracts. The result contains
+	/// the messages written through `seal_debug_message`, the gas consumed by each call frame,
+	/// the emitted contract events and the charged rent which helps to diagnose failing calls.
 	#[rpc(name = "contracts_call")]
 	fn call(
 		&self,
 		call_request: CallRequest<AccountId>,
 		at: Option<BlockHash>,
-	) -> Result<RpcContractExecResult>;
+	) -> Result<RpcContractExecResult<AccountId, Balance>>;
 
 	/// Returns the value under a specified storage `key` in a contract given by `address` param,
 	/// or `None` if it is not set.
@@ -207,7 +265,7 @@ where
 		&self,
 		call_request: CallRequest<AccountId>,
 		at: Option<<Block as BlockT>::Hash>,
-	) -> Result<RpcContractExecResult> {
+	) -> Result<RpcContractExecResult<AccountId, Balance>> {
 		let api = self.client.runtime_api();
 		let at = BlockId::hash(at.unwrap_or_else(||
 			// If the block hash is not supplied assume the best block.
@@ -247,10 +305,23 @@ where
 			});
 		}
 
-		let exec_result = api
-			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		let has_debug_info = api
+			.has_api_with::<dyn ContractsRuntimeApi<
+				Block,
+				AccountId,
+				Balance,
+				<<Block as BlockT>::Header as HeaderT>::Number,
+			>, _>(&at, |version| version >= 2)
 			.map_err(runtime_error_into_rpc_err)?;
 
+		let exec_result = if has_debug_info {
+			api.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		} else {
+			#[allow(deprecated)] // old call without debugging information
+			api.call_before_version_2(&at, origin, dest, value, gas_limit, input_data.to_vec())
+				.map(Into::into)
+		}.map_err(runtime_error_into_rpc_err)?;
+
 		Ok(exec_result.into())
 	}
 
@@ -329,11 +400,11 @@ mod tests {
 	#[test]
 	fn result_should_serialize_deserialize_properly() {
 		fn test(expected: &str) {
-			let res: RpcContractExecResult = serde_json::from_str(expected).unwrap();
+			let res: RpcContractExecResult<String, u64> = serde_json::from_str(expected).unwrap();
 			let actual = serde_json::to_string(&res).unwrap();
 			assert_eq!(actual, expected);
 		}
-		test(r#"{"gasConsumed":5000,"debugMessage":"helpOk","result":{"Ok":{"flags":5,"data":"0x1234"}}}"#);
-		test(r#"{"gasConsumed":3400,"debugMessage":"helpErr","result":{"Err":"BadOrigin"}}"#);
+		test(r#"{"gasConsumed":5000,"debugMessage":"helpOk","frames":[],"events":[],"rent":[],"result":{"Ok":{"flags":5,"data":"0x1234"}}}"#);
+		test(r#"{"gasConsumed":3400,"debugMessage":"helpErr","frames":[{"contract":"5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL","depth":1,"gasConsumed":3000,"success":false}],"events":[{"contract":"5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL","data":"0x01"}],"rent":[{"contract":"5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL","amount":12}],"result":{"Err":"BadOrigin"}}"#);
 	}
 }
@@ -24,6 +24,7 @@ use sp_core::crypto::UncheckedFrom;
 use sp_std::{
 	prelude::*,
 	marker::PhantomData,
+	cell::RefCell,
 };
 use sp_runtime::traits::{Bounded, Zero, Convert, Saturating};
 use frame_support::{
@@ -32,7 +33,10 @@ use frame_support::{
 	weights::Weight,
 	ensure,
 };
-use pallet_contracts_primitives::{ErrorOrigin, ExecError, ExecReturnValue, ExecResult, ReturnFlags};
+use pallet_contracts_primitives::{
+	ErrorOrigin, ExecError, ExecReturnValue, ExecResult, ReturnFlags, CallFrameTrace, EmittedEvent,
+	RentCharge,
+};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
@@ -131,6 +135,24 @@ pub trait Ext: sealing::Sealed {
 		input_data: Vec<u8>,
 	) -> Result<(ExecReturnValue, u32), (ExecError, u32)>;
 
+	/// Execute the code identified by `code_hash` in the context of the current contract.
+	///
+	/// In contrast to [`Self::call`] no other contract is entered: The executed code operates on
+	/// the storage and balance of the current contract and observes the same caller and
+	/// transferred value. This allows a contract to use uploaded code as a library.
+	///
+	/// Returns the original code size of the executed code.
+	///
+	/// # Return Value
+	///
+	/// Result<(ExecReturnValue, CodeSize), (ExecError, CodeSize)>
+	fn delegate_call(
+		&mut self,
+		code_hash: CodeHash<Self::T>,
+		gas_meter: &mut GasMeter<Self::T>,
+		input_data: Vec<u8>,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)>;
+
 	/// Restores the given destination contract sacrificing the current one.
 	///
 	/// Since this function removes the self contract eagerly, if succeeded, no further actions should
@@ -184,6 +206,15 @@ pub trait Ext: sealing::Sealed {
 	/// Set rent allowance of the contract
 	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<Self::T>);
 
+	/// Append a string to the debug buffer.
+	///
+	/// It is added as-is without any additional new line.
+	///
+	/// This is a no-op if debugging was not requested for the current execution.
+	///
+	/// Returns `true` if debugging is enabled and the message was appended.
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
+
 	/// Rent allowance of the contract
 	fn rent_allowance(&self) -> BalanceOf<Self::T>;
 
@@ -275,6 +306,27 @@ pub trait Executable<T: Config>: Sized {
 	fn code_len(&self) -> u32;
 }
 
+/// Information that is collected during an execution when debugging was requested.
+///
+/// See [`pallet_contracts_primitives::ContractExecResult`] for a description of the fields.
+pub struct ExecTrace<T: Config> {
+	pub debug_message: Vec<u8>,
+	pub frames: Vec<CallFrameTrace<T::AccountId>>,
+	pub events: Vec<EmittedEvent<T::AccountId>>,
+	pub rent: Vec<RentCharge<T::AccountId, BalanceOf<T>>>,
+}
+
+impl<T: Config> Default for ExecTrace<T> {
+	fn default() -> Self {
+		Self {
+			debug_message: Vec::new(),
+			frames: Vec::new(),
+			events: Vec::new(),
+			rent: Vec::new(),
+		}
+	}
+}
+
 pub struct ExecutionContext<'a, T: Config + 'a, E> {
 	pub caller: Option<&'a ExecutionContext<'a, T, E>>,
 	pub self_account: T::AccountId,
@@ -283,6 +335,8 @@ pub struct ExecutionContext<'a, T: Config + 'a, E> {
 	pub schedule: &'a Schedule<T>,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	/// Collects debugging information if it was requested for this execution.
+	pub trace: Option<&'a RefCell<ExecTrace<T>>>,
 	_phantom: PhantomData<E>,
 }
 
@@ -305,10 +359,17 @@ where
 			schedule,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			trace: None,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Collect debugging information into `trace` for this execution and all nested ones.
+	pub fn with_trace(mut self, trace: &'a RefCell<ExecTrace<T>>) -> Self {
+		self.trace = Some(trace);
+		self
+	}
+
 	fn nested<'b, 'c: 'b>(&'c self, dest: T::AccountId, trie_id: TrieId)
 		-> ExecutionContext<'b, T, E>
 	{
@@ -320,6 +381,7 @@ where
 			schedule: self.schedule,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			trace: self.trace,
 			_phantom: Default::default(),
 		}
 	}
@@ -353,12 +415,15 @@ where
 		// changes would be rolled back in case this contract is called by another
 		// contract.
 		// See: https://github.com/paritytech/substrate/issues/6439#issuecomment-648754324
+		let rent_payed = contract.rent_payed;
 		let contract = Rent::<T, E>::charge(&dest, contract, executable.occupied_storage())
 			.map_err(|e| (e.into(), code_len))?
 			.ok_or((Error::<T>::NotCallable.into(), code_len))?;
+		self.trace_rent(&dest, contract.rent_payed.saturating_sub(rent_payed));
 
 		let transactor_kind = self.transactor_kind();
 		let caller = self.self_account.clone();
+		let frame = self.trace_enter(&dest, gas_meter);
 
 		let result = self.with_nested_context(dest.clone(), contract.trie_id.clone(), |nested| {
 			if value > BalanceOf::<T>::zero() {
@@ -378,7 +443,53 @@ where
 				gas_meter,
 			).map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })?;
 			Ok(output)
-		}).map_err(|e| (e, code_len))?;
+		});
+		self.trace_exit(frame, gas_meter, &result);
+		let result = result.map_err(|e| (e, code_len))?;
+		Ok((result, code_len))
+	}
+
+	/// Execute the code identified by `code_hash` in the context of the currently executing
+	/// contract.
+	///
+	/// The executed code sees `caller` as its caller and `value` as the transferred value and
+	/// operates on the storage of the current contract. No funds are transferred and no rent is
+	/// charged as the current contract is already alive.
+	///
+	/// # Return Value
+	///
+	/// Result<(ExecReturnValue, CodeSize), (ExecError, CodeSize)>
+	pub fn delegate_call(
+		&mut self,
+		caller: T::AccountId,
+		value: BalanceOf<T>,
+		code_hash: CodeHash<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+		if self.depth == T::MaxDepth::get() as usize {
+			return Err((Error::<T>::MaxCallDepthReached.into(), 0));
+		}
+
+		let executable = E::from_storage(code_hash, &self.schedule, gas_meter)
+			.map_err(|e| (e.into(), 0))?;
+		let code_len = executable.code_len();
+
+		let dest = self.self_account.clone();
+		let trie_id = self.self_trie_id.clone().ok_or((Error::<T>::NotCallable.into(), code_len))?;
+		let frame = self.trace_enter(&dest, gas_meter);
+
+		let result = self.with_nested_context(dest, trie_id, |nested| {
+			let output = executable.execute(
+				nested.new_call_context(caller, value),
+				&ExportedFunction::Call,
+				input_data,
+				gas_meter,
+			).map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })?;
+			Ok(output)
+		});
+		self.trace_exit(frame, gas_meter, &result);
+		let result = result.map_err(|e| (e, code_len))?;
 		Ok((result, code_len))
 	}
 
@@ -401,6 +512,7 @@ where
 		let output = frame_support::storage::with_transaction(|| {
 			// Generate the trie id in a new transaction to only increment the counter on success.
 			let dest_trie_id = Storage::<T>::generate_trie_id(&dest);
+			let frame = self.trace_enter(&dest, gas_meter);
 
 			let output = self.with_nested_context(dest.clone(), dest_trie_id, |nested| {
 				Storage::<T>::place_contract(
@@ -440,20 +552,23 @@ where
 				let contract = <ContractInfoOf<T>>::get(&dest)
 					.and_then(|contract| contract.get_alive())
 					.ok_or(Error::<T>::NotCallable)?;
+				let rent_payed = contract.rent_payed;
 
 				// Collect the rent for the first block to prevent the creation of very large
 				// contracts that never intended to pay for even one block.
 				// This also makes sure that it is above the subsistence threshold
 				// in order to keep up the guarantuee that we always leave a tombstone behind
 				// with the exception of a contract that called `seal_terminate`.
-				Rent::<T, E>::charge(&dest, contract, occupied_storage)?
+				let contract = Rent::<T, E>::charge(&dest, contract, occupied_storage)?
 					.ok_or(Error::<T>::NewContractNotFunded)?;
+				nested.trace_rent(&dest, contract.rent_payed.saturating_sub(rent_payed));
 
 				// Deposit an instantiation event.
 				deposit_event::<T>(vec![], Event::Instantiated(caller.clone(), dest.clone()));
 
 				Ok(output)
 			});
+			self.trace_exit(frame, gas_meter, &output);
 
 			use frame_support::storage::TransactionOutcome::*;
 			match output {
@@ -488,16 +603,72 @@ where
 		where F: FnOnce(&mut ExecutionContext<T, E>) -> ExecResult
 	{
 		use frame_support::storage::TransactionOutcome::*;
+		let trace = self.trace;
+		let checkpoint = trace.map(|t| {
+			let t = t.borrow();
+			(t.events.len(), t.rent.len())
+		});
 		let mut nested = self.nested(dest, trie_id);
 		frame_support::storage::with_transaction(|| {
 			let output = func(&mut nested);
 			match output {
 				Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT) => Commit(output),
-				_ => Rollback(output),
+				_ => {
+					// Events and rent charges are rolled back together with the storage.
+					if let (Some(trace), Some((events, rent))) = (trace, checkpoint) {
+						let mut trace = trace.borrow_mut();
+						trace.events.truncate(events);
+						trace.rent.truncate(rent);
+					}
+					Rollback(output)
+				},
 			}
 		})
 	}
 
+	/// Record that a new call frame for `contract` is entered.
+	///
+	/// Returns the index of the frame and the gas spent so far if tracing is enabled.
+	fn trace_enter(&self, contract: &T::AccountId, gas_meter: &GasMeter<T>)
+		-> Option<(usize, Weight)>
+	{
+		self.trace.map(|trace| {
+			let mut trace = trace.borrow_mut();
+			trace.frames.push(CallFrameTrace {
+				contract: contract.clone(),
+				depth: (self.depth + 1) as u32,
+				gas_consumed: 0,
+				success: false,
+			});
+			(trace.frames.len() - 1, gas_meter.gas_spent())
+		})
+	}
+
+	/// Record the gas consumption and outcome of a frame previously entered by `trace_enter`.
+	fn trace_exit<R>(
+		&self,
+		frame: Option<(usize, Weight)>,
+		gas_meter: &GasMeter<T>,
+		result: &Result<ExecReturnValue, R>,
+	) {
+		if let (Some(trace), Some((index, gas_before))) = (self.trace, frame) {
+			let mut trace = trace.borrow_mut();
+			if let Some(frame) = trace.frames.get_mut(index) {
+				frame.gas_consumed = gas_meter.gas_spent().saturating_sub(gas_before);
+				frame.success = result.as_ref().map_or(false, |rv| rv.is_success());
+			}
+		}
+	}
+
+	/// Record that `amount` of rent was charged from `contract`.
+	fn trace_rent(&self, contract: &T::AccountId, amount: BalanceOf<T>) {
+		if let Some(trace) = self.trace {
+			if !amount.is_zero() {
+				trace.borrow_mut().rent.push(RentCharge { contract: contract.clone(), amount });
+			}
+		}
+	}
+
 	/// Returns whether a contract, identified by address, is currently live in the execution
 	/// stack, meaning it is in the middle of an execution.
 	fn is_live(&self, account: &T::AccountId) -> bool {
@@ -689,6 +860,21 @@ where
 		self.ctx.call(to.clone(), value, gas_meter, input_data)
 	}
 
+	fn delegate_call(
+		&mut self,
+		code_hash: CodeHash<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+		self.ctx.delegate_call(
+			self.caller.clone(),
+			self.value_transferred,
+			code_hash,
+			gas_meter,
+			input_data,
+		)
+	}
+
 	fn restore_to(
 		&mut self,
 		dest: AccountIdOf<Self::T>,
@@ -756,6 +942,12 @@ where
 	}
 
 	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) {
+		if let Some(trace) = self.ctx.trace {
+			trace.borrow_mut().events.push(EmittedEvent {
+				contract: self.ctx.self_account.clone(),
+				data: data.clone(),
+			});
+		}
 		deposit_event::<Self::T>(
 			topics,
 			Event::ContractEmitted(self.ctx.self_account.clone(), data)
@@ -773,6 +965,15 @@ where
 		}
 	}
 
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		if let Some(trace) = self.ctx.trace {
+			trace.borrow_mut().debug_message.extend(msg.as_bytes());
+			true
+		} else {
+			false
+		}
+	}
+
 	fn rent_allowance(&self) -> BalanceOf<T> {
 		Storage::<T>::rent_allowance(&self.ctx.self_account)
 			.unwrap_or_else(|_| <BalanceOf<T>>::max_value()) // Must never be triggered actually
@@ -836,6 +1037,7 @@ mod tests {
 	};
 	use sp_runtime::DispatchError;
 	use assert_matches::assert_matches;
+	use frame_support::assert_ok;
 	use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 	type MockContext<'a> = ExecutionContext<'a, Test, MockExecutable>;
@@ -1296,6 +1498,73 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn delegate_call_preserves_context() {
+		let library_ch = MockLoader::insert(|ctx| {
+			// The library observes the context of the delegating contract.
+			assert_eq!(*ctx.ext.address(), BOB);
+			assert_eq!(*ctx.ext.caller(), ALICE);
+			assert_eq!(ctx.ext.value_transferred(), 7);
+
+			let mut data = ctx.ext.get_storage(&[1; 32]).unwrap();
+			assert_ok!(ctx.ext.set_storage([2; 32], Some(ctx.input_data.clone())));
+			data.extend(ctx.input_data);
+			Ok(ExecReturnValue { flags: ReturnFlags::empty(), data })
+		});
+		let bob_ch = MockLoader::insert(move |ctx| {
+			assert_ok!(ctx.ext.set_storage([1; 32], Some(vec![1, 2, 3])));
+
+			// Delegate into the library code.
+			let (output, _) = ctx.ext
+				.delegate_call(library_ch, ctx.gas_meter, vec![42])
+				.map_err(|(e, _)| e)?;
+			assert_eq!(output.data, vec![1, 2, 3, 42]);
+
+			// Changes to the storage made by the library are visible.
+			assert_eq!(ctx.ext.get_storage(&[2; 32]), Some(vec![42]));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = Contracts::current_schedule();
+			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			set_balance(&ALICE, 1_000_000);
+			place_contract(&BOB, bob_ch);
+			let bob_balance = get_balance(&BOB);
+
+			let result = ctx.call(
+				BOB,
+				7,
+				&mut GasMeter::<Test>::new(GAS_LIMIT),
+				vec![],
+			);
+			assert_matches!(result, Ok(_));
+
+			// Only the value of the call itself was transferred.
+			assert_eq!(get_balance(&BOB), bob_balance + 7);
+		});
+	}
+
+	#[test]
+	fn delegate_call_to_missing_code_fails() {
+		let bob_ch = MockLoader::insert(|ctx| {
+			let missing = <Test as frame_system::Config>::Hash::from_low_u64_be(u64::max_value() - 1);
+			assert_eq!(
+				ctx.ext.delegate_call(missing, ctx.gas_meter, vec![]).map_err(|(e, _)| e.error),
+				Err(Error::<Test>::CodeNotFound.into()),
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = Contracts::current_schedule();
+			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			place_contract(&BOB, bob_ch);
+
+			assert_matches!(ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]), Ok(_));
+		});
+	}
+
 	#[test]
 	fn refuse_instantiate_with_value_below_existential_deposit() {
 		let dummy_ch = MockLoader::insert(|_| exec_success());
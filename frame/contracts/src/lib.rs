@@ -104,13 +104,13 @@ pub use crate::{
 };
 use crate::{
 	gas::GasMeter,
-	exec::{ExecutionContext, Executable},
+	exec::{ExecutionContext, Executable, ExecTrace},
 	rent::Rent,
 	storage::{Storage, DeletedContract},
 	weights::WeightInfo,
 };
 use sp_core::crypto::UncheckedFrom;
-use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
+use sp_std::{prelude::*, marker::PhantomData, fmt::Debug, cell::RefCell};
 use codec::{Codec, Encode, Decode};
 use sp_runtime::{
 	traits::{
@@ -602,6 +602,8 @@ pub mod pallet {
 		StorageExhausted,
 		/// A contract with the same AccountId already exists.
 		DuplicateContract,
+		/// The debug message specified to `seal_debug_message` does contain invalid UTF-8.
+		DebugMessageInvalidUTF8,
 	}
 
 	/// Current cost schedule for contracts.
@@ -667,21 +669,36 @@ where
 	/// suitable for calling directly from Rust.
 	///
 	/// It returns the execution result and the amount of used weight.
+	///
+	/// If `debug` is set to `true` the messages written by `seal_debug_message`, the gas
+	/// consumed by each call frame, the emitted events and the charged rent are collected
+	/// and returned as part of the result. This must never be enabled for on-chain execution
+	/// as the collected data is not accounted for in the weight.
 	pub fn bare_call(
 		origin: T::AccountId,
 		dest: T::AccountId,
 		value: BalanceOf<T>,
 		gas_limit: Weight,
 		input_data: Vec<u8>,
-	) -> ContractExecResult {
+		debug: bool,
+	) -> ContractExecResult<T::AccountId, BalanceOf<T>> {
 		let mut gas_meter = GasMeter::new(gas_limit);
 		let schedule = <Module<T>>::current_schedule();
+		let trace = RefCell::new(ExecTrace::<T>::default());
 		let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
+		if debug {
+			ctx = ctx.with_trace(&trace);
+		}
 		let result = ctx.call(dest, value, &mut gas_meter, input_data);
 		let gas_consumed = gas_meter.gas_spent();
+		let trace = trace.into_inner();
 		ContractExecResult {
 			exec_result: result.map(|r| r.0).map_err(|r| r.0),
 			gas_consumed,
+			debug_message: trace.debug_message,
+			frames: trace.frames,
+			events: trace.events,
+			rent: trace.rent,
 		}
 	}
 
@@ -264,6 +264,9 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight of calling `seal_random`.
 	pub random: Weight,
 
+	/// Weight of calling `seal_debug_message`.
+	pub debug_message: Weight,
+
 	/// Weight of calling `seal_reposit_event`.
 	pub deposit_event: Weight,
 
@@ -309,6 +312,9 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight per output byte received through `seal_call`.
 	pub call_per_output_byte: Weight,
 
+	/// Weight of calling `seal_delegate_call`.
+	pub delegate_call: Weight,
+
 	/// Weight of calling `seal_instantiate`.
 	pub instantiate: Weight,
 
@@ -544,6 +550,7 @@ impl<T: Config> Default for HostFnWeights<T> {
 			restore_to_per_tombstone_code_byte: cost_byte_args!(seal_restore_to_per_code_kb_delta, 0, 1, 0),
 			restore_to_per_delta: cost_batched_args!(seal_restore_to_per_code_kb_delta, 0, 0, 1),
 			random: cost_batched!(seal_random),
+			debug_message: cost_batched!(seal_debug_message),
 			deposit_event: cost_batched!(seal_deposit_event),
 			deposit_event_per_topic: cost_batched_args!(seal_deposit_event_per_topic_and_kb, 1, 0),
 			deposit_event_per_byte: cost_byte_batched_args!(seal_deposit_event_per_topic_and_kb, 0, 1),
@@ -559,6 +566,7 @@ impl<T: Config> Default for HostFnWeights<T> {
 			call_transfer_surcharge: cost_batched_args!(seal_call_per_code_transfer_input_output_kb, 0, 1, 0, 0),
 			call_per_input_byte: cost_byte_batched_args!(seal_call_per_code_transfer_input_output_kb, 0, 0, 1, 0),
 			call_per_output_byte: cost_byte_batched_args!(seal_call_per_code_transfer_input_output_kb, 0, 0, 0, 1),
+			delegate_call: cost_batched!(seal_delegate_call),
 			instantiate: cost_batched!(seal_instantiate),
 			instantiate_per_code_byte: cost_byte_batched_args!(seal_instantiate_per_code_input_output_salt_kb, 1, 0, 0, 0),
 			instantiate_per_input_byte: cost_byte_batched_args!(seal_instantiate_per_code_input_output_salt_kb, 0, 1, 0, 0),
@@ -29,6 +29,7 @@ use crate::{
 };
 use assert_matches::assert_matches;
 use codec::Encode;
+use pallet_contracts_primitives::EmittedEvent;
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
 	testing::{Header, H256},
@@ -224,6 +225,7 @@ impl frame_system::Config for Test {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -1884,6 +1886,7 @@ fn crypto_hashes() {
 					0,
 					GAS_LIMIT,
 					params,
+					false,
 				).exec_result.unwrap();
 				assert!(result.is_success());
 				let expected = hash_fn(input.as_ref());
@@ -1919,6 +1922,7 @@ fn transfer_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
@@ -1933,6 +1937,7 @@ fn transfer_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 	});
@@ -1967,6 +1972,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&DJANGO).to_vec(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::NotCallable);
 
@@ -1990,6 +1996,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
@@ -2004,6 +2011,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
@@ -2015,6 +2023,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&1u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
@@ -2025,6 +2034,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&2u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
@@ -2072,6 +2082,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.clone(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
@@ -2086,6 +2097,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.clone(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
@@ -2097,6 +2109,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CodeNotFound);
 
@@ -2107,6 +2120,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.iter().chain(&1u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
@@ -2117,6 +2131,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.iter().chain(&2u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
@@ -2204,6 +2219,7 @@ fn chain_extension_works() {
 			0,
 			GAS_LIMIT,
 			vec![0, 99],
+			false,
 		);
 		let gas_consumed = result.gas_consumed;
 		assert_eq!(TestExtension::last_seen_buffer(), vec![0, 99]);
@@ -2216,6 +2232,7 @@ fn chain_extension_works() {
 			0,
 			GAS_LIMIT,
 			vec![1],
+			false,
 		).exec_result.unwrap();
 		// those values passed in the fixture
 		assert_eq!(TestExtension::last_seen_inputs(), (4, 1, 16, 12));
@@ -2227,6 +2244,7 @@ fn chain_extension_works() {
 			0,
 			GAS_LIMIT,
 			vec![2, 42],
+			false,
 		);
 		assert_ok!(result.exec_result);
 		assert_eq!(result.gas_consumed, gas_consumed + 42);
@@ -2238,6 +2256,7 @@ fn chain_extension_works() {
 			0,
 			GAS_LIMIT,
 			vec![3],
+			false,
 		).exec_result.unwrap();
 		assert_eq!(result.flags, ReturnFlags::REVERT);
 		assert_eq!(result.data, vec![42, 99]);
@@ -2765,6 +2784,7 @@ fn reinstrument_does_charge() {
 			0,
 			GAS_LIMIT,
 			zero.clone(),
+			false,
 		);
 		assert!(result0.exec_result.unwrap().is_success());
 
@@ -2774,6 +2794,7 @@ fn reinstrument_does_charge() {
 			0,
 			GAS_LIMIT,
 			zero.clone(),
+			false,
 		);
 		assert!(result1.exec_result.unwrap().is_success());
 
@@ -2792,6 +2813,7 @@ fn reinstrument_does_charge() {
 			0,
 			GAS_LIMIT,
 			zero.clone(),
+			false,
 		);
 		assert!(result2.exec_result.unwrap().is_success());
 		assert!(result2.gas_consumed > result1.gas_consumed);
@@ -2801,3 +2823,53 @@ fn reinstrument_does_charge() {
 		);
 	});
 }
+
+#[test]
+fn debug_trace_is_only_collected_when_requested() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = Module::<Test>::subsistence_threshold();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		assert_ok!(
+			Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				subsistence * 100,
+				GAS_LIMIT,
+				wasm,
+				vec![],
+				vec![],
+			),
+		);
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		// Nothing is collected without debugging.
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![0], false);
+		assert!(result.exec_result.unwrap().is_success());
+		assert!(result.debug_message.is_empty());
+		assert!(result.frames.is_empty());
+		assert!(result.events.is_empty());
+
+		// With debugging the message, the frame and the event are returned.
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![0], true);
+		assert!(result.exec_result.unwrap().is_success());
+		assert_eq!(std::str::from_utf8(&result.debug_message).unwrap(), "Hello World!");
+		assert_eq!(result.frames.len(), 1);
+		assert_eq!(result.frames[0].contract, addr);
+		assert_eq!(result.frames[0].depth, 1);
+		assert!(result.frames[0].gas_consumed > 0);
+		assert!(result.frames[0].gas_consumed <= result.gas_consumed);
+		assert!(result.frames[0].success);
+		assert_eq!(
+			result.events,
+			vec![EmittedEvent { contract: addr.clone(), data: vec![1, 2, 3, 4] }],
+		);
+
+		// The event of a reverted frame is dropped but the debug message is kept.
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![1], true);
+		assert!(!result.exec_result.unwrap().is_success());
+		assert_eq!(std::str::from_utf8(&result.debug_message).unwrap(), "Hello World!");
+		assert_eq!(result.frames.len(), 1);
+		assert!(!result.frames[0].success);
+		assert!(result.events.is_empty());
+	});
+}
@@ -284,6 +284,12 @@ mod tests {
 		data: Vec<u8>,
 	}
 
+	#[derive(Debug, PartialEq, Eq)]
+	struct DelegateCallEntry {
+		code_hash: H256,
+		data: Vec<u8>,
+	}
+
 	#[derive(Default)]
 	pub struct MockExt {
 		storage: HashMap<StorageKey, Vec<u8>>,
@@ -291,10 +297,12 @@ mod tests {
 		instantiates: Vec<InstantiateEntry>,
 		terminations: Vec<TerminationEntry>,
 		transfers: Vec<TransferEntry>,
+		delegate_calls: Vec<DelegateCallEntry>,
 		restores: Vec<RestoreEntry>,
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
 		schedule: Schedule<Test>,
+		debug_buffer: Vec<u8>,
 	}
 
 	impl Ext for MockExt {
@@ -359,6 +367,19 @@ mod tests {
 			// TODO: Add tests for different call outcomes.
 			Ok((ExecReturnValue { flags: ReturnFlags::empty(), data: Vec::new() }, 0))
 		}
+		fn delegate_call(
+			&mut self,
+			code_hash: H256,
+			_gas_meter: &mut GasMeter<Test>,
+			data: Vec<u8>,
+		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+			self.delegate_calls.push(DelegateCallEntry {
+				code_hash,
+				data: data.clone(),
+			});
+			// Echo the input back so that the test can verify the output is written.
+			Ok((ExecReturnValue { flags: ReturnFlags::empty(), data }, 0))
+		}
 		fn terminate(
 			&mut self,
 			beneficiary: &AccountIdOf<Self::T>,
@@ -420,6 +441,11 @@ mod tests {
 			self.rent_allowance = rent_allowance;
 		}
 
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			self.debug_buffer.extend(msg.as_bytes());
+			true
+		}
+
 		fn rent_allowance(&self) -> u64 {
 			self.rent_allowance
 		}
@@ -478,6 +504,14 @@ mod tests {
 		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
 			(**self).call(to, value, gas_meter, input_data)
 		}
+		fn delegate_call(
+			&mut self,
+			code_hash: H256,
+			gas_meter: &mut GasMeter<Test>,
+			input_data: Vec<u8>,
+		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+			(**self).delegate_call(code_hash, gas_meter, input_data)
+		}
 		fn restore_to(
 			&mut self,
 			dest: AccountIdOf<Self::T>,
@@ -522,6 +556,9 @@ mod tests {
 		fn set_rent_allowance(&mut self, rent_allowance: u64) {
 			(**self).set_rent_allowance(rent_allowance)
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			(**self).append_debug_buffer(msg)
+		}
 		fn rent_allowance(&self) -> u64 {
 			(**self).rent_allowance()
 		}
@@ -675,6 +712,82 @@ mod tests {
 		);
 	}
 
+	const CODE_DELEGATE_CALL: &str = r#"
+(module
+	;; seal_delegate_call(
+	;;    code_hash_ptr: u32,
+	;;    code_hash_len: u32,
+	;;    gas: u64,
+	;;    input_data_ptr: u32,
+	;;    input_data_len: u32,
+	;;    output_ptr: u32,
+	;;    output_len_ptr: u32
+	;;) -> u32
+	(import "seal0" "seal_delegate_call" (func $seal_delegate_call
+		(param i32 i32 i64 i32 i32 i32 i32) (result i32)
+	))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+	(func (export "call")
+		(call $assert
+			(i32.eqz
+				(call $seal_delegate_call
+					(i32.const 4)  ;; Pointer to the code hash.
+					(i32.const 32) ;; Length of the code hash.
+					(i64.const 0)  ;; How much gas to devote for the execution. 0 = all.
+					(i32.const 36) ;; Pointer to input data buffer address
+					(i32.const 4)  ;; Length of input data buffer
+					(i32.const 40) ;; Pointer to the output buffer
+					(i32.const 44) ;; Pointer to the length of the output buffer
+				)
+			)
+		)
+		;; return the output of the delegated code
+		(call $seal_return (i32.const 0) (i32.const 40) (i32.load (i32.const 44)))
+	)
+	(func (export "deploy"))
+
+	;; Code hash of the library
+	(data (i32.const 4)
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+	)
+
+	(data (i32.const 36) "\01\02\03\04")
+
+	;; Length of the output buffer
+	(data (i32.const 44) "\04")
+)
+"#;
+
+	#[test]
+	fn contract_delegate_call() {
+		let mut mock_ext = MockExt::default();
+		let output = execute(
+			CODE_DELEGATE_CALL,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(
+			&mock_ext.delegate_calls,
+			&[DelegateCallEntry {
+				code_hash: [0x11; 32].into(),
+				data: vec![1, 2, 3, 4],
+			}]
+		);
+		assert_eq!(output.data, vec![1, 2, 3, 4]);
+	}
+
 	const CODE_INSTANTIATE: &str = r#"
 (module
 	;; seal_instantiate(
@@ -1840,4 +1953,74 @@ mod tests {
 		);
 	}
 
+	const CODE_DEBUG_MESSAGE: &str = r#"
+(module
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(data (i32.const 0) "Hello World!")
+
+	(func (export "call")
+		(call $seal_debug_message
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 12)	;; The size of the buffer
+		)
+		drop
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn debug_message_works() {
+		let mut mock_ext = MockExt::default();
+		execute(
+			CODE_DEBUG_MESSAGE,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(std::str::from_utf8(&mock_ext.debug_buffer).unwrap(), "Hello World!");
+	}
+
+	const CODE_DEBUG_MESSAGE_INVALID_UTF8: &str = r#"
+(module
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(data (i32.const 0) "\fc")
+
+	(func (export "call")
+		(call $seal_debug_message
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 1)	;; The size of the buffer
+		)
+		drop
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn debug_message_invalid_utf8_fails() {
+		let mut mock_ext = MockExt::default();
+		let result = execute(
+			CODE_DEBUG_MESSAGE_INVALID_UTF8,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		);
+
+		assert_eq!(
+			result,
+			Err(ExecError {
+				error: Error::<Test>::DebugMessageInvalidUTF8.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+		assert!(mock_ext.debug_buffer.is_empty());
+	}
 }
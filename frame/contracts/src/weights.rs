@@ -74,6 +74,7 @@ pub trait WeightInfo {
 	fn seal_restore_to(r: u32, ) -> Weight;
 	fn seal_restore_to_per_code_kb_delta(c: u32, t: u32, d: u32, ) -> Weight;
 	fn seal_random(r: u32, ) -> Weight;
+	fn seal_debug_message(r: u32, ) -> Weight;
 	fn seal_deposit_event(r: u32, ) -> Weight;
 	fn seal_deposit_event_per_topic_and_kb(t: u32, n: u32, ) -> Weight;
 	fn seal_set_rent_allowance(r: u32, ) -> Weight;
@@ -84,6 +85,7 @@ pub trait WeightInfo {
 	fn seal_get_storage_per_kb(n: u32, ) -> Weight;
 	fn seal_transfer(r: u32, ) -> Weight;
 	fn seal_call(r: u32, ) -> Weight;
+	fn seal_delegate_call(r: u32, ) -> Weight;
 	fn seal_call_per_code_transfer_input_output_kb(c: u32, t: u32, i: u32, o: u32, ) -> Weight;
 	fn seal_instantiate(r: u32, ) -> Weight;
 	fn seal_instantiate_per_code_input_output_salt_kb(c: u32, i: u32, o: u32, s: u32, ) -> Weight;
@@ -352,6 +354,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((643_555_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(T::DbWeight::get().reads(6 as Weight))
 	}
+	// Not benchmarked yet. Copying the message out of the sandbox is bounded by the work of
+	// `seal_deposit_event`, whose weight is charged until this is regenerated from the
+	// `seal_debug_message` benchmark.
+	fn seal_debug_message(r: u32, ) -> Weight {
+		Self::seal_deposit_event(r)
+	}
 	fn seal_deposit_event(r: u32, ) -> Weight {
 		(142_838_000 as Weight)
 			// Standard Error: 367_000
@@ -429,6 +437,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(6 as Weight))
 			.saturating_add(T::DbWeight::get().reads((200 as Weight).saturating_mul(r as Weight)))
 	}
+	// Not benchmarked yet. A delegate call does a subset of the work of `seal_call`, so the weight
+	// of the latter is charged until this is regenerated from the `seal_delegate_call` benchmark.
+	fn seal_delegate_call(r: u32, ) -> Weight {
+		Self::seal_call(r)
+	}
 	fn seal_call_per_code_transfer_input_output_kb(c: u32, t: u32, i: u32, o: u32, ) -> Weight {
 		(9_686_594_000 as Weight)
 			// Standard Error: 473_000
@@ -972,6 +985,12 @@ impl WeightInfo for () {
 			.saturating_add((643_555_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
 	}
+	// Not benchmarked yet. Copying the message out of the sandbox is bounded by the work of
+	// `seal_deposit_event`, whose weight is charged until this is regenerated from the
+	// `seal_debug_message` benchmark.
+	fn seal_debug_message(r: u32, ) -> Weight {
+		Self::seal_deposit_event(r)
+	}
 	fn seal_deposit_event(r: u32, ) -> Weight {
 		(142_838_000 as Weight)
 			// Standard Error: 367_000
@@ -1049,6 +1068,11 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
 			.saturating_add(RocksDbWeight::get().reads((200 as Weight).saturating_mul(r as Weight)))
 	}
+	// Not benchmarked yet. A delegate call does a subset of the work of `seal_call`, so the weight
+	// of the latter is charged until this is regenerated from the `seal_delegate_call` benchmark.
+	fn seal_delegate_call(r: u32, ) -> Weight {
+		Self::seal_call(r)
+	}
 	fn seal_call_per_code_transfer_input_output_kb(c: u32, t: u32, i: u32, o: u32, ) -> Weight {
 		(9_686_594_000 as Weight)
 			// Standard Error: 473_000
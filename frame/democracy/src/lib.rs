@@ -162,7 +162,7 @@ use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
 	weights::{Weight, DispatchClass, Pays},
 	traits::{
-		Currency, ReservableCurrency, LockableCurrency, WithdrawReasons, LockIdentifier, Get,
+		Currency, NamedReservableCurrency, LockableCurrency, WithdrawReasons, LockIdentifier, Get,
 		OnUnbalanced, BalanceStatus, schedule::{Named as ScheduleNamed, DispatchTime}, EnsureOrigin
 	},
 	dispatch::DispatchResultWithPostInfo,
@@ -186,6 +186,7 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
+/// The identifier under which the locks, reserves and scheduled calls of this module are kept.
 const DEMOCRACY_ID: LockIdentifier = *b"democrac";
 
 /// The maximum number of vetoers on a single proposal used to compute Weight.
@@ -208,7 +209,7 @@ pub trait Config: frame_system::Config + Sized {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
 
 	/// Currency type for this module.
-	type Currency: ReservableCurrency<Self::AccountId>
+	type Currency: NamedReservableCurrency<Self::AccountId>
 		+ LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
 
 	/// The minimum period of locking and the period between a proposal being approved and enacted.
@@ -601,7 +602,7 @@ decl_module! {
 				);
 			}
 
-			T::Currency::reserve(&who, value)?;
+			T::Currency::reserve_named(&DEMOCRACY_ID, &who, value)?;
 			PublicPropCount::put(index + 1);
 			<DepositOf<T>>::insert(index, (&[&who][..], value));
 
@@ -629,7 +630,7 @@ decl_module! {
 			ensure!(seconds <= seconds_upper_bound, Error::<T>::WrongUpperBound);
 			let mut deposit = Self::deposit_of(proposal)
 				.ok_or(Error::<T>::ProposalMissing)?;
-			T::Currency::reserve(&who, deposit.1)?;
+			T::Currency::reserve_named(&DEMOCRACY_ID, &who, deposit.1)?;
 			deposit.0.push(who);
 			<DepositOf<T>>::insert(proposal, deposit);
 		}
@@ -1010,7 +1011,13 @@ decl_module! {
 			ensure!(now >= since + voting + additional, Error::<T>::TooEarly);
 			ensure!(expiry.map_or(true, |e| now > e), Error::<T>::Imminent);
 
-			let _ = T::Currency::repatriate_reserved(&provider, &who, deposit, BalanceStatus::Free);
+			let _ = T::Currency::repatriate_reserved_named(
+				&DEMOCRACY_ID,
+				&provider,
+				&who,
+				deposit,
+				BalanceStatus::Free,
+			);
 			<Preimages<T>>::remove(&proposal_hash);
 			Self::deposit_event(RawEvent::PreimageReaped(proposal_hash, provider, deposit, who));
 		}
@@ -1124,7 +1131,9 @@ decl_module! {
 					let (prop_index, ..) = props.remove(index);
 					if let Some((whos, amount)) = DepositOf::<T>::take(prop_index) {
 						for who in whos.into_iter() {
-							T::Slash::on_unbalanced(T::Currency::slash_reserved(&who, amount).0);
+							let (imbalance, _) =
+								T::Currency::slash_reserved_named(&DEMOCRACY_ID, &who, amount);
+							T::Slash::on_unbalanced(imbalance);
 						}
 					}
 				}
@@ -1161,7 +1170,9 @@ decl_module! {
 			PublicProps::<T>::mutate(|props| props.retain(|p| p.0 != prop_index));
 			if let Some((whos, amount)) = DepositOf::<T>::take(prop_index) {
 				for who in whos.into_iter() {
-					T::Slash::on_unbalanced(T::Currency::slash_reserved(&who, amount).0);
+					let (imbalance, _) =
+						T::Currency::slash_reserved_named(&DEMOCRACY_ID, &who, amount);
+					T::Slash::on_unbalanced(imbalance);
 				}
 			}
 		}
@@ -1521,7 +1532,7 @@ impl<T: Config> Module<T> {
 			if let Some((depositors, deposit)) = <DepositOf<T>>::take(prop_index) {
 				// refund depositors
 				for d in &depositors {
-					T::Currency::unreserve(d, deposit);
+					T::Currency::unreserve_named_or_unnamed(&DEMOCRACY_ID, d, deposit);
 				}
 				Self::deposit_event(RawEvent::Tabled(prop_index, deposit, depositors));
 				Self::inject_referendum(
@@ -1541,7 +1552,7 @@ impl<T: Config> Module<T> {
 		let preimage = <Preimages<T>>::take(&proposal_hash);
 		if let Some(PreimageStatus::Available { data, provider, deposit, .. }) = preimage {
 			if let Ok(proposal) = T::Proposal::decode(&mut &data[..]) {
-				let _ = T::Currency::unreserve(&provider, deposit);
+				let _ = T::Currency::unreserve_named_or_unnamed(&DEMOCRACY_ID, &provider, deposit);
 				Self::deposit_event(RawEvent::PreimageUsed(proposal_hash, provider, deposit));
 
 				let ok = proposal.dispatch(frame_system::RawOrigin::Root.into()).is_ok();
@@ -1549,7 +1560,9 @@ impl<T: Config> Module<T> {
 
 				Ok(())
 			} else {
-				T::Slash::on_unbalanced(T::Currency::slash_reserved(&provider, deposit).0);
+				let (imbalance, _) =
+					T::Currency::slash_reserved_named(&DEMOCRACY_ID, &provider, deposit);
+				T::Slash::on_unbalanced(imbalance);
 				Self::deposit_event(RawEvent::PreimageInvalid(proposal_hash, index));
 				Err(Error::<T>::PreimageInvalid.into())
 			}
@@ -1714,7 +1727,7 @@ impl<T: Config> Module<T> {
 
 		let deposit = <BalanceOf<T>>::from(encoded_proposal.len() as u32)
 			.saturating_mul(T::PreimageByteDeposit::get());
-		T::Currency::reserve(&who, deposit)?;
+		T::Currency::reserve_named(&DEMOCRACY_ID, &who, deposit)?;
 
 		let now = <frame_system::Module<T>>::block_number();
 		let a = PreimageStatus::Available {
@@ -1774,3 +1787,26 @@ fn decode_compact_u32_at(key: &[u8]) -> Option<u32> {
 		}
 	}
 }
+
+pub mod migration {
+	use super::*;
+	use frame_support::storage::IterableStorageMap;
+
+	/// Names the deposits held by this module after `DEMOCRACY_ID`, see
+	/// [`NamedReservableCurrency::name_deposits`].
+	///
+	/// A seconder is counted once per second, and the provider of an available preimage once per
+	/// preimage.
+	pub fn migrate_to_named_reserves<T: Config>() -> Weight {
+		let mut reads: Weight = 0;
+		let seconds = DepositOf::<T>::iter()
+			.flat_map(|(_, (whos, deposit))| whos.into_iter().map(move |who| (who, deposit)));
+		let preimages = Preimages::<T>::iter().filter_map(|(_, status)| match status {
+			PreimageStatus::Available { provider, deposit, .. } => Some((provider, deposit)),
+			PreimageStatus::Missing(_) => None,
+		});
+		let deposits = seconds.chain(preimages).inspect(|_| reads += 1);
+		let accounts = T::Currency::name_deposits(&DEMOCRACY_ID, deposits) as Weight;
+		T::DbWeight::get().reads_writes(reads + accounts * 3, accounts)
+	}
+}
@@ -106,6 +106,7 @@ impl frame_system::Config for Test {
 }
 parameter_types! {
 	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+	pub const MaxSchedulerPreimageSize: u32 = 1024;
 }
 impl pallet_scheduler::Config for Test {
 	type Event = Event;
@@ -115,13 +116,20 @@ impl pallet_scheduler::Config for Test {
 	type MaximumWeight = MaximumSchedulerWeight;
 	type ScheduleOrigin = EnsureRoot<u64>;
 	type MaxScheduledPerBlock = ();
+	type Currency = Balances;
+	type PreimageBaseDeposit = ();
+	type PreimageByteDeposit = ();
+	type MaxPreimageSize = MaxSchedulerPreimageSize;
+	type NoPreimagePostponement = ();
 	type WeightInfo = ();
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
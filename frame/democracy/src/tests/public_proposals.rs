@@ -18,6 +18,7 @@
 //! The tests for the public proposal queue.
 
 use super::*;
+use frame_support::traits::ReservableCurrency;
 
 #[test]
 fn backing_for_should_work() {
@@ -150,3 +151,30 @@ fn runners_up_should_come_after() {
 		assert_ok!(Democracy::vote(Origin::signed(1), 2, aye(1)));
 	});
 }
+
+#[test]
+fn migrate_to_named_reserves_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(propose_set_balance_and_note(1, 2, 5));
+		assert_ok!(Democracy::second(Origin::signed(5), 0, u32::max_value()));
+		assert_ok!(Democracy::second(Origin::signed(5), 0, u32::max_value()));
+		assert_eq!(Balances::reserved_balance_named(&DEMOCRACY_ID, &1), 5);
+		assert_eq!(Balances::reserved_balance_named(&DEMOCRACY_ID, &5), 10);
+
+		// Hold the deposits in the anonymous reserve, as they were before named reserves existed.
+		for who in &[1, 5] {
+			let deposit = Balances::reserved_balance_named(&DEMOCRACY_ID, who);
+			Balances::unreserve_named(&DEMOCRACY_ID, who, deposit);
+			assert_ok!(Balances::reserve(who, deposit));
+		}
+
+		// Every second of a proposal is counted as a deposit of the seconder.
+		migration::migrate_to_named_reserves::<Test>();
+		assert_eq!(Balances::reserved_balance_named(&DEMOCRACY_ID, &1), 5);
+		assert_eq!(Balances::reserved_balance_named(&DEMOCRACY_ID, &5), 10);
+
+		fast_forward_to(3);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(5), 0);
+	});
+}
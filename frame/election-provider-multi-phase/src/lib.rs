@@ -49,7 +49,7 @@
 //!	In the signed phase, solutions (of type [`RawSolution`]) are submitted and queued on chain. A
 //! deposit is reserved, based on the size of the solution, for the cost of keeping this solution
 //! on-chain for a number of blocks, and the potential weight of the solution upon being checked. A
-//! maximum of `pallet::Config::MaxSignedSubmissions` solutions are stored. The queue is always
+//! maximum of [`pallet::Config::SignedMaxSubmissions`] solutions are stored. The queue is always
 //! sorted based on score (worse to best).
 //!
 //! Upon arrival of a new solution:
@@ -71,6 +71,10 @@
 //! check, it is considered to be the best one. The sender of the origin is rewarded, and the rest
 //! of the queued solutions get their deposit back and are discarded, without being checked.
 //!
+//! The feasibility checks all happen in a single block, so the runtime must configure
+//! [`pallet::Config::SignedMaxWeight`] such that [`pallet::Config::SignedMaxSubmissions`]
+//! solutions of that weight can all be checked within the maximum block weight.
+//!
 //! The following example covers all of the cases at the end of the signed phase:
 //!
 //! ```ignore
@@ -207,10 +211,10 @@ use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::DispatchResultWithPostInfo,
 	ensure,
-	traits::{Currency, Get, ReservableCurrency},
-	weights::Weight,
+	traits::{Currency, Get, OnUnbalanced, ReservableCurrency},
+	weights::{DispatchClass, Weight},
 };
-use frame_system::{ensure_none, offchain::SendTransactionTypes};
+use frame_system::{ensure_none, ensure_signed, offchain::SendTransactionTypes};
 use sp_election_providers::{ElectionDataProvider, ElectionProvider, onchain};
 use sp_npos_elections::{
 	assignment_ratio_to_staked_normalized, is_score_better, CompactSolution, ElectionScore,
@@ -238,9 +242,13 @@ pub mod helpers;
 
 const LOG_TARGET: &'static str = "runtime::election-provider";
 
+pub mod signed;
 pub mod unsigned;
 pub mod weights;
 
+pub use signed::{
+	BalanceOf, NegativeImbalanceOf, PositiveImbalanceOf, SignedSubmission, SignedSubmissionOf,
+};
 /// The weight declaration of the pallet.
 pub use weights::WeightInfo;
 
@@ -509,6 +517,34 @@ pub mod pallet {
 		#[pallet::constant]
 		type SignedPhase: Get<Self::BlockNumber>;
 
+		/// Maximum number of signed submissions that can be queued.
+		#[pallet::constant]
+		type SignedMaxSubmissions: Get<u32>;
+		/// Maximum weight of a signed solution.
+		///
+		/// All the queued solutions might be checked in the same block at the end of the signed
+		/// phase, so [`Config::SignedMaxSubmissions`] solutions of this weight must fit into a
+		/// block. This is checked in `integrity_test`.
+		#[pallet::constant]
+		type SignedMaxWeight: Get<Weight>;
+		/// Base reward for a signed solution
+		#[pallet::constant]
+		type SignedRewardBase: Get<BalanceOf<Self>>;
+		/// Base deposit for a signed solution.
+		#[pallet::constant]
+		type SignedDepositBase: Get<BalanceOf<Self>>;
+		/// Per-byte deposit for a signed solution.
+		#[pallet::constant]
+		type SignedDepositByte: Get<BalanceOf<Self>>;
+		/// Per-weight deposit for a signed solution.
+		#[pallet::constant]
+		type SignedDepositWeight: Get<BalanceOf<Self>>;
+
+		/// Handler for the slashed deposits.
+		type SlashHandler: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Handler for the rewards.
+		type RewardHandler: OnUnbalanced<PositiveImbalanceOf<Self>>;
+
 		/// The minimum amount of improvement to the solution score that defines a solution as
 		/// "better" (in any phase).
 		#[pallet::constant]
@@ -572,9 +608,10 @@ pub mod pallet {
 				{
 					let (need_snapshot, enabled, additional) = if current_phase == Phase::Signed {
 						// followed by a signed phase: close the signed phase, no need for snapshot.
-						// TWO_PHASE_NOTE: later on once we have signed phase, this should return
-						// something else.
-						(false, true, Weight::zero())
+						// The unsigned phase is still opened unconditionally as a defensive measure,
+						// even if a good signed solution was found.
+						let (_success, weight) = Self::finalize_signed_phase();
+						(false, true, weight)
 					} else {
 						// no signed phase: create a new snapshot, definitely `enable` the unsigned
 						// phase.
@@ -642,6 +679,18 @@ pub mod pallet {
 			let _: UpperOf<CompactAccuracyOf<T>> = maximum_chain_accuracy
 				.iter()
 				.fold(Zero::zero(), |acc, x| acc.checked_add(x).unwrap());
+
+			// 3. A full queue of the heaviest signed solutions must be checked within one block at
+			// the end of the signed phase.
+			let finalize_weight = T::WeightInfo::finalize_signed_phase_accept_solution()
+				.max(T::WeightInfo::finalize_signed_phase_reject_solution());
+			let max_signed_weight = T::SignedMaxWeight::get()
+				.saturating_add(finalize_weight)
+				.saturating_mul(T::SignedMaxSubmissions::get().into());
+			assert!(
+				max_signed_weight <= T::BlockWeights::get().max_block,
+				"signed submissions can't all be checked in a block, lower `SignedMaxWeight`",
+			);
 		}
 	}
 
@@ -698,6 +747,71 @@ pub mod pallet {
 
 			Ok(None.into())
 		}
+
+		/// Submit a solution for the signed phase.
+		///
+		/// The dispatch origin fo this call must be __signed__.
+		///
+		/// The solution is potentially queued, based on the claimed score and processed at the end
+		/// of the signed phase.
+		///
+		/// A deposit is reserved and recorded for the solution. Based on the outcome, the solution
+		/// might be rewarded, slashed, or get all or a part of the deposit back.
+		///
+		/// # <weight>
+		/// Queue size must be provided as witness data.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::submit(*num_signed_submissions))]
+		pub fn submit(
+			origin: OriginFor<T>,
+			solution: RawSolution<CompactOf<T>>,
+			num_signed_submissions: u32,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			// ensure witness data is correct.
+			let mut signed_submissions = Self::signed_submissions();
+			ensure!(
+				num_signed_submissions >= signed_submissions.len() as u32,
+				Error::<T>::SignedInvalidWitness,
+			);
+
+			// ensure solution is timely.
+			ensure!(Self::current_phase().is_signed(), Error::<T>::PreDispatchEarlySubmission);
+
+			// defensive-only: if phase is signed, snapshot will exist.
+			let size = Self::snapshot_metadata().unwrap_or_default();
+
+			ensure!(
+				Self::feasibility_weight_of(&solution, size) <= T::SignedMaxWeight::get(),
+				Error::<T>::SignedTooMuchWeight,
+			);
+
+			// ensure the claimed score makes it into the queue.
+			let (index, ejected) =
+				Self::insert_submission(&who, &mut signed_submissions, solution, size)
+					.ok_or(Error::<T>::SignedQueueFull)?;
+
+			// collect deposit. Thereafter, the function cannot fail.
+			let deposit = signed_submissions[index].deposit;
+			T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::SignedCannotPayDeposit)?;
+
+			// the weakest submission was ejected to make room for this one, return its deposit.
+			if let Some(SignedSubmission { who, deposit, .. }) = ejected {
+				let _remaining = T::Currency::unreserve(&who, deposit);
+				debug_assert!(_remaining.is_zero());
+			}
+
+			// store the new signed submission.
+			log!(
+				debug,
+				"queued signed solution with (claimed) score {:?}",
+				signed_submissions[index].solution.score,
+			);
+			<SignedSubmissions<T>>::put(signed_submissions);
+			Self::deposit_event(Event::SolutionStored(ElectionCompute::Signed));
+			Ok(None.into())
+		}
 	}
 
 	#[pallet::event]
@@ -731,6 +845,14 @@ pub mod pallet {
 		PreDispatchWrongWinnerCount,
 		/// Submission was too weak, score-wise.
 		PreDispatchWeakSubmission,
+		/// The queue was full, and the solution was not better than any of the existing ones.
+		SignedQueueFull,
+		/// The origin failed to pay the deposit.
+		SignedCannotPayDeposit,
+		/// Witness data to dispatchable is invalid.
+		SignedInvalidWitness,
+		/// The signed submission consumes too much weight
+		SignedTooMuchWeight,
 	}
 
 	#[pallet::origin]
@@ -832,6 +954,12 @@ pub mod pallet {
 	#[pallet::getter(fn snapshot_metadata)]
 	pub type SnapshotMetadata<T: Config> = StorageValue<_, SolutionOrSnapshotSize>;
 
+	/// Sorted (worse -> best) list of unchecked, signed solutions.
+	#[pallet::storage]
+	#[pallet::getter(fn signed_submissions)]
+	pub type SignedSubmissions<T: Config> =
+		StorageValue<_, Vec<SignedSubmissionOf<T>>, ValueQuery>;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T>(PhantomData<T>);
@@ -1007,6 +1135,20 @@ impl<T: Config> Pallet<T> {
 	}
 
 	fn do_elect() -> Result<Supports<T::AccountId>, ElectionError> {
+		// We have to unconditionally try finalizing the signed phase here. There are only two
+		// possibilities:
+		//
+		// - signed phase was open, in which case this is essential for correct functioning of the
+		//   system
+		// - signed phase was complete or not started, in which case finalization is idempotent and
+		//   inexpensive (1 read of an empty vector).
+		//
+		// This is not called from a hook, so the weight is registered with the system directly.
+		let (_, weight) = Self::finalize_signed_phase();
+		<frame_system::Pallet<T>>::register_extra_weight_unchecked(
+			weight,
+			DispatchClass::Mandatory,
+		);
 		<QueuedSolution<T>>::take()
 			.map_or_else(
 				|| match T::Fallback::get() {
@@ -0,0 +1,733 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The signed phase implementation.
+
+use crate::*;
+use codec::{Encode, HasCompact};
+use frame_support::traits::{Currency, Get, OnUnbalanced, ReservableCurrency};
+use sp_npos_elections::{is_score_better, CompactSolution};
+use sp_runtime::{Perbill, RuntimeDebug, traits::Zero};
+
+/// A raw, unchecked signed submission.
+///
+/// This is just a wrapper around [`RawSolution`] and some additional info.
+#[derive(PartialEq, Eq, Encode, Decode, Clone, RuntimeDebug, Default)]
+pub struct SignedSubmission<A, B: HasCompact, C> {
+	/// Who submitted this solution.
+	pub who: A,
+	/// The deposit reserved for storing this solution.
+	pub deposit: B,
+	/// The reward that should be paid for this solution, if accepted.
+	pub reward: B,
+	/// The raw solution itself.
+	pub solution: RawSolution<C>,
+}
+
+/// The balance type of the currency used by the pallet.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+/// The positive imbalance of the currency used by the pallet.
+pub type PositiveImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::PositiveImbalance;
+/// The negative imbalance of the currency used by the pallet.
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// A signed submission of the pallet.
+pub type SignedSubmissionOf<T> =
+	SignedSubmission<<T as frame_system::Config>::AccountId, BalanceOf<T>, CompactOf<T>>;
+
+impl<T: Config> Pallet<T> {
+	/// Finish the signed phase. Process the signed submissions from best to worse until a valid one
+	/// is found, rewarding the best one and slashing the invalid ones along the way.
+	///
+	/// Returns true if we have a good solution in the signed phase.
+	///
+	/// The feasibility checks are bounded by the maximum block weight. `integrity_test` ensures
+	/// that all the submissions fit, but defensively, once the next check would exceed the bound,
+	/// the remaining submissions are discarded as if a valid solution had been found. The best
+	/// submission is always checked.
+	///
+	/// This drains the [`SignedSubmissions`], potentially storing the best valid one in
+	/// [`QueuedSolution`].
+	pub fn finalize_signed_phase() -> (bool, Weight) {
+		let mut all_submissions = <SignedSubmissions<T>>::take();
+		let mut found_solution = false;
+		let mut weight = T::DbWeight::get().reads(1);
+		let max_weight = T::BlockWeights::get().max_block;
+		let mut checked_any = false;
+
+		// defensive-only: at the end of signed phase, snapshot will exist.
+		let size = Self::snapshot_metadata().unwrap_or_default();
+
+		while let Some(best) = all_submissions.pop() {
+			let feasibility_weight = Self::feasibility_weight_of(&best.solution, size);
+			let checked_weight = weight
+				.saturating_add(feasibility_weight)
+				.saturating_add(T::WeightInfo::finalize_signed_phase_accept_solution());
+			if checked_any && checked_weight > max_weight {
+				log!(
+					error,
+					"defensive: feasibility checks exhausted the block, discarding {} submissions.",
+					all_submissions.len() + 1,
+				);
+				all_submissions.push(best);
+				break;
+			}
+
+			let SignedSubmission { solution, who, deposit, reward } = best;
+			checked_any = true;
+			// the feasibility check itself has some weight, even if it fails early.
+			weight = weight.saturating_add(feasibility_weight);
+			match Self::feasibility_check(solution, ElectionCompute::Signed) {
+				Ok(ready_solution) => {
+					Self::finalize_signed_phase_accept_solution(ready_solution, &who, deposit, reward);
+					found_solution = true;
+
+					weight = weight
+						.saturating_add(T::WeightInfo::finalize_signed_phase_accept_solution());
+					break;
+				}
+				Err(err) => {
+					log!(warn, "signed submission of {:?} is invalid: {:?}", who, err);
+					Self::finalize_signed_phase_reject_solution(&who, deposit);
+					weight = weight
+						.saturating_add(T::WeightInfo::finalize_signed_phase_reject_solution());
+				}
+			}
+		}
+
+		// Any unprocessed solution is pointless to even consider. Feasible or malicious, they
+		// didn't end up being used. Unreserve the bonds.
+		for SignedSubmission { who, deposit, .. } in all_submissions {
+			let _remaining = T::Currency::unreserve(&who, deposit);
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			debug_assert!(_remaining.is_zero());
+		}
+
+		(found_solution, weight)
+	}
+
+	/// Helper function for the case where a solution is accepted in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible
+	pub fn finalize_signed_phase_accept_solution(
+		ready_solution: ReadySolution<T::AccountId>,
+		who: &T::AccountId,
+		deposit: BalanceOf<T>,
+		reward: BalanceOf<T>,
+	) {
+		// write this ready solution.
+		<QueuedSolution<T>>::put(ready_solution);
+
+		// emit reward event
+		Self::deposit_event(Event::Rewarded(who.clone()));
+
+		// unreserve deposit.
+		let _remaining = T::Currency::unreserve(who, deposit);
+		debug_assert!(_remaining.is_zero());
+
+		// Reward.
+		let positive_imbalance = T::Currency::deposit_creating(who, reward);
+		T::RewardHandler::on_unbalanced(positive_imbalance);
+	}
+
+	/// Helper function for the case where a solution is rejected in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible
+	pub fn finalize_signed_phase_reject_solution(who: &T::AccountId, deposit: BalanceOf<T>) {
+		Self::deposit_event(Event::Slashed(who.clone()));
+		let (negative_imbalance, _remaining) = T::Currency::slash_reserved(who, deposit);
+		debug_assert!(_remaining.is_zero());
+		T::SlashHandler::on_unbalanced(negative_imbalance);
+	}
+
+	/// Insert a solution into the queue while maintaining an ordering by solution quality.
+	///
+	/// Solutions are ordered from worse to best, i.e. the weakest solution has index 0.
+	///
+	/// If the solution is queued, its index is returned, along with the weakest submission that
+	/// was ejected to make room for it, if any. The deposit of the ejected submission is **not**
+	/// returned here. If the queue is full and the solution is not an improvement over any of the
+	/// queued ones, `None` is returned and the queue is left untouched.
+	pub fn insert_submission(
+		who: &T::AccountId,
+		queue: &mut Vec<SignedSubmissionOf<T>>,
+		solution: RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> Option<(usize, Option<SignedSubmissionOf<T>>)> {
+		// from the last score, compare and see if the current one is better. If none, then the
+		// awarded index is 0.
+		let at = queue
+			.iter()
+			.enumerate()
+			.rev()
+			.find_map(|(i, s)| {
+				if is_score_better::<Perbill>(
+					solution.score,
+					s.solution.score,
+					T::SolutionImprovementThreshold::get(),
+				) {
+					Some(i + 1)
+				} else {
+					None
+				}
+			})
+			.unwrap_or(0);
+
+		let max_submissions = T::SignedMaxSubmissions::get().saturated_into::<usize>();
+		if at == 0 && queue.len() >= max_submissions {
+			// if this is worse than all, and the queue is full, don't bother.
+			return None;
+		}
+
+		let reward = Self::reward_for(&solution);
+		let deposit = Self::deposit_for(&solution, size);
+		// Proof: `at` is either 0, or one of the queue indices + 1, thus `at <= queue.len()`.
+		queue.insert(at, SignedSubmission { who: who.clone(), deposit, reward, solution });
+
+		let outcome = if queue.len() > max_submissions {
+			// Proof: if `at` is 0, then the queue was not full before the insertion.
+			debug_assert!(at > 0);
+			Some((at - 1, Some(queue.remove(0))))
+		} else {
+			Some((at, None))
+		};
+
+		debug_assert!(queue.len() <= max_submissions);
+		outcome
+	}
+
+	/// The feasibility weight of the given raw solution.
+	pub fn feasibility_weight_of(
+		solution: &RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> Weight {
+		T::WeightInfo::feasibility_check(
+			size.voters,
+			size.targets,
+			solution.compact.voter_count() as u32,
+			solution.compact.unique_targets().len() as u32,
+		)
+	}
+
+	/// Collect sufficient deposit to store this solution on chain.
+	///
+	/// The deposit is composed of 3 main elements:
+	///
+	/// 1. base deposit, fixed for all submissions.
+	/// 2. a per-byte deposit, for renting the state usage.
+	/// 3. a per-weight deposit, for the potential weight usage in an upcoming on_initialize
+	pub fn deposit_for(
+		solution: &RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> BalanceOf<T> {
+		let encoded_len: BalanceOf<T> = solution.using_encoded(|e| e.len() as u32).into();
+		let feasibility_weight = Self::feasibility_weight_of(solution, size);
+
+		let len_deposit = T::SignedDepositByte::get().saturating_mul(encoded_len);
+		let weight_deposit = T::SignedDepositWeight::get()
+			.saturating_mul(feasibility_weight.saturated_into());
+
+		T::SignedDepositBase::get().saturating_add(len_deposit).saturating_add(weight_deposit)
+	}
+
+	/// The reward for this solution, if successfully chosen as the best one at the end of the
+	/// signed phase.
+	pub fn reward_for(_solution: &RawSolution<CompactOf<T>>) -> BalanceOf<T> {
+		T::SignedRewardBase::get()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{mock::*, Event, *};
+
+	fn submit_with_witness(
+		origin: Origin,
+		solution: RawSolution<CompactOf<Runtime>>,
+	) -> DispatchResultWithPostInfo {
+		MultiPhase::submit(origin, solution, MultiPhase::signed_submissions().len() as u32)
+	}
+
+	#[test]
+	fn cannot_submit_too_early() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(2);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+
+			// create a temp snapshot only for this test.
+			MultiPhase::create_snapshot();
+			let solution = raw_solution();
+
+			assert_noop!(
+				submit_with_witness(Origin::signed(10), solution),
+				Error::<Runtime>::PreDispatchEarlySubmission,
+			);
+		})
+	}
+
+	#[test]
+	fn wrong_witness_fails() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			// submit this once correctly
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+			assert_eq!(MultiPhase::signed_submissions().len(), 1);
+
+			// now try and cheat by passing a lower queue length
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), solution, 0),
+				Error::<Runtime>::SignedInvalidWitness,
+			);
+		})
+	}
+
+	#[test]
+	fn should_pay_deposit() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			assert_eq!(balances(&99), (95, 5));
+			assert_eq!(MultiPhase::signed_submissions()[0].deposit, 5);
+			assert_eq!(
+				multi_phase_events(),
+				vec![Event::SignedPhaseStarted(1), Event::SolutionStored(ElectionCompute::Signed)],
+			);
+		})
+	}
+
+	#[test]
+	fn cannot_submit_without_deposit() {
+		ExtBuilder::default().signed_deposit(200, 0, 0).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			assert_noop!(
+				submit_with_witness(Origin::signed(99), raw_solution()),
+				Error::<Runtime>::SignedCannotPayDeposit,
+			);
+		})
+	}
+
+	#[test]
+	fn deposit_depends_on_size_and_weight() {
+		ExtBuilder::default()
+			.signed_deposit(5, 1, 2)
+			.mock_weight_info(true)
+			.build_and_execute(|| {
+				roll_to(15);
+				assert!(MultiPhase::current_phase().is_signed());
+
+				let solution = raw_solution();
+				let weight = MultiPhase::feasibility_weight_of(&solution, witness());
+				assert!(weight > 0);
+
+				assert_eq!(
+					MultiPhase::deposit_for(&solution, witness()),
+					5 + solution.encoded_size() as u64 + 2 * weight,
+				);
+			})
+	}
+
+	#[test]
+	fn good_solution_is_rewarded() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			assert_eq!(balances(&99), (95, 5));
+
+			assert!(MultiPhase::finalize_signed_phase().0);
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert_eq!(MultiPhase::queued_solution().unwrap().compute, ElectionCompute::Signed);
+		})
+	}
+
+	#[test]
+	fn bad_solution_is_slashed() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			// make the solution invalid.
+			solution.score[0] += 1;
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			assert_eq!(balances(&99), (95, 5));
+
+			// no good solution was stored.
+			assert!(!MultiPhase::finalize_signed_phase().0);
+			// and the bond is gone.
+			assert_eq!(balances(&99), (95, 0));
+			assert!(MultiPhase::queued_solution().is_none());
+			assert!(multi_phase_events().contains(&Event::Slashed(99)));
+		})
+	}
+
+	#[test]
+	fn suppressed_solution_gets_bond_back() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (100, 0));
+
+			// submit as correct.
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// make the solution invalid and weaker.
+			solution.score[0] -= 1;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+			assert_eq!(balances(&99), (95, 5));
+			assert_eq!(balances(&999), (95, 5));
+
+			// _some_ good solution was stored.
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			// 999 gets everything back.
+			assert_eq!(balances(&999), (100, 0));
+		})
+	}
+
+	#[test]
+	fn feasibility_checks_are_bounded_by_block_weight() {
+		ExtBuilder::default().mock_weight_info(true).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			let solution_weight = MultiPhase::feasibility_weight_of(&solution, witness());
+
+			// make the solution invalid.
+			solution.score[0] += 1;
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// make the solution invalid and weaker.
+			solution.score[0] -= 2;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+
+			// only one feasibility check fits in a block.
+			let mut block_weights = BlockWeights::get();
+			block_weights.max_block = 2 * solution_weight - 1;
+			<BlockWeights>::set(block_weights);
+
+			let (found_solution, weight) = MultiPhase::finalize_signed_phase();
+			assert!(!found_solution);
+			assert_eq!(weight, solution_weight);
+
+			// 99 is checked and slashed.
+			assert_eq!(balances(&99), (95, 0));
+			// 999 is never checked and gets everything back.
+			assert_eq!(balances(&999), (100, 0));
+			assert!(!multi_phase_events().contains(&Event::Slashed(999)));
+		})
+	}
+
+	#[test]
+	fn invalid_best_solution_is_slashed_before_next_is_accepted() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// make the solution invalid and stronger.
+			solution.score[0] += 1;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 999 is slashed.
+			assert_eq!(balances(&999), (95, 0));
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert!(MultiPhase::signed_submissions().is_empty());
+		})
+	}
+
+	#[test]
+	fn cannot_submit_worse_with_full_queue() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			// weaker.
+			let solution = RawSolution { score: [4, 0, 0], ..Default::default() };
+
+			assert_noop!(
+				submit_with_witness(Origin::signed(99), solution),
+				Error::<Runtime>::SignedQueueFull,
+			);
+		})
+	}
+
+	#[test]
+	fn weakest_is_removed_if_better_provided() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7, 8, 9]
+			);
+			assert_eq!(balances(&99), (75, 25));
+
+			// better.
+			let solution = RawSolution { score: [20, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+
+			// the one with score 5 was rejected, the new one inserted.
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![6, 7, 8, 9, 20]
+			);
+			// and the deposit of the ejected one is returned.
+			assert_eq!(balances(&99), (80, 20));
+			assert_eq!(balances(&999), (95, 5));
+		})
+	}
+
+	#[test]
+	fn replace_weakest_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 1..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			let solution = RawSolution { score: [4, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![4, 6, 7, 8, 9],
+			);
+
+			// better.
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			// the one with score 4 was rejected, the new one inserted.
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7, 8, 9],
+			);
+		})
+	}
+
+	#[test]
+	fn equally_good_solution_is_not_accepted() {
+		ExtBuilder::default().signed_max_submission(3).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for i in 0..SignedMaxSubmissions::get() {
+				let solution = RawSolution { score: [(5 + i).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7]
+			);
+
+			// 5 is not accepted. This will only cause processing with no benefit.
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_noop!(
+				submit_with_witness(Origin::signed(99), solution),
+				Error::<Runtime>::SignedQueueFull,
+			);
+		})
+	}
+
+	#[test]
+	fn all_in_one_signed_submission_scenario() {
+		// a combination of:
+		// - good_solution_is_rewarded
+		// - bad_solution_is_slashed
+		// - suppressed_solution_gets_bond_back
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (100, 0));
+			assert_eq!(balances(&9999), (100, 0));
+			let mut solution = raw_solution();
+
+			// submit a correct one.
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// make the solution invalidly better and submit. This ought to be slashed.
+			solution.score[0] += 1;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution.clone()));
+
+			// make the solution invalidly worse and submit. This ought to be suppressed and
+			// returned.
+			solution.score[0] -= 2;
+			assert_ok!(submit_with_witness(Origin::signed(9999), solution));
+
+			assert_eq!(
+				MultiPhase::signed_submissions().iter().map(|x| x.who).collect::<Vec<_>>(),
+				vec![9999, 99, 999]
+			);
+
+			// _some_ good solution was stored.
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			// 999 is slashed.
+			assert_eq!(balances(&999), (95, 0));
+			// 9999 gets everything back.
+			assert_eq!(balances(&9999), (100, 0));
+		})
+	}
+
+	#[test]
+	fn cannot_consume_too_much_future_weight() {
+		ExtBuilder::default().mock_weight_info(true).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			let solution_weight = MultiPhase::feasibility_weight_of(&solution, witness());
+
+			// allowed max weight is exactly the weight of the solution.
+			<SignedMaxWeight>::set(solution_weight);
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// now the weight of the solution exceeds the allowed max weight.
+			<SignedMaxWeight>::set(solution_weight - 1);
+			assert_noop!(
+				submit_with_witness(Origin::signed(999), solution),
+				Error::<Runtime>::SignedTooMuchWeight,
+			);
+		})
+	}
+
+	#[test]
+	fn signed_phase_is_finalized_when_unsigned_phase_opens() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			assert_ok!(submit_with_witness(Origin::signed(99), raw_solution()));
+
+			roll_to(25);
+			assert!(MultiPhase::current_phase().is_unsigned());
+
+			assert!(MultiPhase::signed_submissions().is_empty());
+			assert_eq!(MultiPhase::queued_solution().unwrap().compute, ElectionCompute::Signed);
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert_eq!(
+				multi_phase_events(),
+				vec![
+					Event::SignedPhaseStarted(1),
+					Event::SolutionStored(ElectionCompute::Signed),
+					Event::Rewarded(99),
+					Event::UnsignedPhaseStarted(1),
+				],
+			);
+		})
+	}
+
+	#[test]
+	fn signed_phase_is_finalized_on_early_elect() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+			solution.score[0] -= 1;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+
+			// an unexpected call to elect, the signed submissions are processed right away.
+			roll_to(20);
+			MultiPhase::elect().unwrap();
+
+			assert!(MultiPhase::signed_submissions().is_empty());
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert_eq!(balances(&999), (100, 0));
+			assert_eq!(
+				multi_phase_events().last(),
+				Some(&Event::ElectionFinalized(Some(ElectionCompute::Signed))),
+			);
+		})
+	}
+}
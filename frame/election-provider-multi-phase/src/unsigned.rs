@@ -416,6 +416,15 @@ mod max_weight {
 		fn on_initialize_open_unsigned_without_snapshot() -> Weight {
 			unreachable!()
 		}
+		fn finalize_signed_phase_accept_solution() -> Weight {
+			unreachable!()
+		}
+		fn finalize_signed_phase_reject_solution() -> Weight {
+			unreachable!()
+		}
+		fn submit(_c: u32) -> Weight {
+			unreachable!()
+		}
 		fn submit_unsigned(v: u32, t: u32, a: u32, d: u32) -> Weight {
 			(0 * v + 0 * t + 1000 * a + 0 * d) as Weight
 		}
@@ -1098,6 +1098,7 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = frame_system::Module<Test>;
 		type MaxLocks = ();
+		type MaxReserves = ();
 		type WeightInfo = ();
 	}
 
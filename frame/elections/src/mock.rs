@@ -65,6 +65,7 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = ();
 	type Balance = u64;
 	type DustRemoval = ();
 	type Event = Event;
@@ -798,6 +798,7 @@ mod tests {
 	}
 	impl pallet_balances::Config for Test {
 		type MaxLocks = ();
+		type MaxReserves = ();
 		type Balance = u64;
 		type DustRemoval = ();
 		type Event = Event;
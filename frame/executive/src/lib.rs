@@ -122,6 +122,8 @@ use frame_support::{
 	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, OffchainWorker, ExecuteBlock},
 	dispatch::PostDispatchInfo,
 };
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::{PalletCheckOutcome, TryRuntimeChecks};
 use sp_runtime::{
 	generic::Digest, ApplyExtrinsicResult,
 	traits::{
@@ -212,24 +214,79 @@ where
 
 	/// Execute all `OnRuntimeUpgrade` of this runtime, including the pre and post migration checks.
 	///
+	/// The upgrades are executed in the same order as in [`Self::execute_on_runtime_upgrade`], but
+	/// the checks are run around each pallet's upgrade individually, and the outcome of each of
+	/// them is reported separately.
+	///
 	/// This should only be used for testing.
 	#[cfg(feature = "try-runtime")]
-	pub fn try_runtime_upgrade() -> Result<frame_support::weights::Weight, &'static str> {
-		<
-			(frame_system::Module::<System>, COnRuntimeUpgrade, AllModules)
+	pub fn try_runtime_upgrade() -> Vec<PalletCheckOutcome>
+	where
+		AllModules: TryRuntimeChecks<System::BlockNumber>,
+	{
+		let mut outcomes = <
+			frame_system::Module::<System>
 			as
-			OnRuntimeUpgrade
-		>::pre_upgrade()?;
+			TryRuntimeChecks<System::BlockNumber>
+		>::check_on_runtime_upgrade();
+		outcomes.push(
+			PalletCheckOutcome::on_runtime_upgrade::<COnRuntimeUpgrade>("COnRuntimeUpgrade"),
+		);
+		outcomes.extend(
+			<AllModules as TryRuntimeChecks<System::BlockNumber>>::check_on_runtime_upgrade(),
+		);
 
-		let weight = Self::execute_on_runtime_upgrade();
+		outcomes
+	}
 
-		<
-			(frame_system::Module::<System>, COnRuntimeUpgrade, AllModules)
+	/// Execute the `try_state` hook of all pallets of this runtime, on top of the current state.
+	///
+	/// This should only be used for testing.
+	#[cfg(feature = "try-runtime")]
+	pub fn try_state() -> Vec<PalletCheckOutcome>
+	where
+		AllModules: TryRuntimeChecks<System::BlockNumber>,
+	{
+		let n = <frame_system::Module<System>>::block_number();
+		let mut outcomes = <
+			frame_system::Module::<System>
 			as
-			OnRuntimeUpgrade
-		>::post_upgrade()?;
+			TryRuntimeChecks<System::BlockNumber>
+		>::check_try_state(n);
+		outcomes.extend(<AllModules as TryRuntimeChecks<System::BlockNumber>>::check_try_state(n));
+
+		outcomes
+	}
+
+	/// Execute given block, but don't do any of the `final_checks`.
+	///
+	/// Should only be used for testing.
+	#[cfg(feature = "try-runtime")]
+	pub fn execute_block_no_check(block: Block) -> frame_support::weights::Weight {
+		Self::initialize_block(block.header());
+		Self::initial_checks(&block);
+
+		let (header, extrinsics) = block.deconstruct();
+
+		Self::execute_extrinsics_with_book_keeping(extrinsics, *header.number());
+
+		// do some of the checks that would normally happen in `final_checks`, but definitely skip
+		// the state root check.
+		{
+			let new_header = <frame_system::Module<System>>::finalize();
+			let items_zip = header.digest().logs().iter().zip(new_header.digest().logs().iter());
+			for (header_item, computed_item) in items_zip {
+				header_item.check_equal(&computed_item);
+				assert!(header_item == computed_item, "Digest item must match that calculated.");
+			}
+
+			assert!(
+				header.extrinsics_root() == new_header.extrinsics_root(),
+				"Transaction trie root must be valid.",
+			);
+		}
 
-		Ok(weight)
+		frame_system::Module::<System>::block_weight().total()
 	}
 
 	/// Start the execution of a particular block.
@@ -652,6 +709,7 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = ();
+		type MaxReserves = ();
 		type WeightInfo = ();
 	}
 
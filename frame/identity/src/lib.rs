@@ -86,7 +86,10 @@ use sp_runtime::traits::{StaticLookup, Zero, AppendZerosInput, Saturating};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
 	dispatch::DispatchResultWithPostInfo,
-	traits::{Currency, ReservableCurrency, OnUnbalanced, Get, BalanceStatus, EnsureOrigin},
+	traits::{
+		Currency, NamedReservableCurrency, ReserveIdentifier, OnUnbalanced, Get, BalanceStatus,
+		EnsureOrigin,
+	},
 };
 use frame_system::ensure_signed;
 pub use weights::WeightInfo;
@@ -94,12 +97,15 @@ pub use weights::WeightInfo;
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
+/// The identifier under which the deposits of this module are reserved.
+const IDENTITY_ID: ReserveIdentifier = *b"identity";
+
 pub trait Config: frame_system::Config {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
 
 	/// The currency trait.
-	type Currency: ReservableCurrency<Self::AccountId>;
+	type Currency: NamedReservableCurrency<Self::AccountId>;
 
 	/// The amount held on deposit for a registered identity.
 	type BasicDeposit: Get<BalanceOf<Self>>;
@@ -595,10 +601,14 @@ decl_module! {
 			let old_deposit = id.deposit;
 			id.deposit = T::BasicDeposit::get() + fd;
 			if id.deposit > old_deposit {
-				T::Currency::reserve(&sender, id.deposit - old_deposit)?;
+				T::Currency::reserve_named(&IDENTITY_ID, &sender, id.deposit - old_deposit)?;
 			}
 			if old_deposit > id.deposit {
-				let _ = T::Currency::unreserve(&sender, old_deposit - id.deposit);
+				let _ = T::Currency::unreserve_named_or_unnamed(
+					&IDENTITY_ID,
+					&sender,
+					old_deposit - id.deposit,
+				);
 			}
 
 			let judgements = id.judgements.len();
@@ -653,9 +663,13 @@ decl_module! {
 			ensure!(not_other_sub, Error::<T>::AlreadyClaimed);
 
 			if old_deposit < new_deposit {
-				T::Currency::reserve(&sender, new_deposit - old_deposit)?;
+				T::Currency::reserve_named(&IDENTITY_ID, &sender, new_deposit - old_deposit)?;
 			} else if old_deposit > new_deposit {
-				let _ = T::Currency::unreserve(&sender, old_deposit - new_deposit);
+				let _ = T::Currency::unreserve_named_or_unnamed(
+					&IDENTITY_ID,
+					&sender,
+					old_deposit - new_deposit,
+				);
 			}
 			// do nothing if they're equal.
 
@@ -713,7 +727,7 @@ decl_module! {
 				<SuperOf<T>>::remove(sub);
 			}
 
-			let _ = T::Currency::unreserve(&sender, deposit.clone());
+			let _ = T::Currency::unreserve_named_or_unnamed(&IDENTITY_ID, &sender, deposit.clone());
 
 			Self::deposit_event(RawEvent::IdentityCleared(sender, deposit));
 
@@ -772,7 +786,7 @@ decl_module! {
 				Err(i) => id.judgements.insert(i, item),
 			}
 
-			T::Currency::reserve(&sender, registrar.fee)?;
+			T::Currency::reserve_named(&IDENTITY_ID, &sender, registrar.fee)?;
 
 			let judgements = id.judgements.len();
 			let extra_fields = id.info.additional.len();
@@ -819,7 +833,7 @@ decl_module! {
 				Err(Error::<T>::JudgementGiven)?
 			};
 
-			let _ = T::Currency::unreserve(&sender, fee);
+			let _ = T::Currency::unreserve_named_or_unnamed(&IDENTITY_ID, &sender, fee);
 			let judgements = id.judgements.len();
 			let extra_fields = id.info.additional.len();
 			<IdentityOf<T>>::insert(&sender, id);
@@ -966,7 +980,13 @@ decl_module! {
 			match id.judgements.binary_search_by_key(&reg_index, |x| x.0) {
 				Ok(position) => {
 					if let Judgement::FeePaid(fee) = id.judgements[position].1 {
-						let _ = T::Currency::repatriate_reserved(&target, &sender, fee, BalanceStatus::Free);
+						let _ = T::Currency::repatriate_reserved_named(
+							&IDENTITY_ID,
+							&target,
+							&sender,
+							fee,
+							BalanceStatus::Free,
+						);
 					}
 					id.judgements[position] = item
 				}
@@ -1021,7 +1041,8 @@ decl_module! {
 				<SuperOf<T>>::remove(sub);
 			}
 			// Slash their deposit from them.
-			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
+			let (imbalance, _) = T::Currency::slash_reserved_named(&IDENTITY_ID, &target, deposit);
+			T::Slashed::on_unbalanced(imbalance);
 
 			Self::deposit_event(RawEvent::IdentityKilled(target, deposit));
 
@@ -1052,7 +1073,7 @@ decl_module! {
 				// Ensure there is space and that the deposit is paid.
 				ensure!(sub_ids.len() < T::MaxSubAccounts::get() as usize, Error::<T>::TooManySubAccounts);
 				let deposit = T::SubAccountDeposit::get();
-				T::Currency::reserve(&sender, deposit)?;
+				T::Currency::reserve_named(&IDENTITY_ID, &sender, deposit)?;
 
 				SuperOf::<T>::insert(&sub, (sender.clone(), data));
 				sub_ids.push(sub.clone());
@@ -1095,7 +1116,7 @@ decl_module! {
 				sub_ids.retain(|x| x != &sub);
 				let deposit = T::SubAccountDeposit::get().min(*subs_deposit);
 				*subs_deposit -= deposit;
-				let _ = T::Currency::unreserve(&sender, deposit);
+				let _ = T::Currency::unreserve_named_or_unnamed(&IDENTITY_ID, &sender, deposit);
 				Self::deposit_event(RawEvent::SubIdentityRemoved(sub, sender, deposit));
 			});
 		}
@@ -1118,7 +1139,13 @@ decl_module! {
 				sub_ids.retain(|x| x != &sender);
 				let deposit = T::SubAccountDeposit::get().min(*subs_deposit);
 				*subs_deposit -= deposit;
-				let _ = T::Currency::repatriate_reserved(&sup, &sender, deposit, BalanceStatus::Free);
+				let _ = T::Currency::repatriate_reserved_named(
+					&IDENTITY_ID,
+					&sup,
+					&sender,
+					deposit,
+					BalanceStatus::Free,
+				);
 				Self::deposit_event(RawEvent::SubIdentityRevoked(sender, sup.clone(), deposit));
 			});
 		}
@@ -1135,3 +1162,22 @@ impl<T: Config> Module<T> {
 	}
 }
 
+
+pub mod migration {
+	use super::*;
+	use frame_support::{storage::IterableStorageMap, weights::Weight};
+
+	/// Names the deposits held by this module after `IDENTITY_ID`, see
+	/// [`NamedReservableCurrency::name_deposits`].
+	///
+	/// The deposit of an identity includes the fees paid for its pending judgements and the
+	/// deposit for its sub-accounts.
+	pub fn migrate_to_named_reserves<T: Config>() -> Weight {
+		let deposits = IdentityOf::<T>::iter().map(|(who, registration)| {
+			let (subs_deposit, _) = SubsOf::<T>::get(&who);
+			(who, registration.total_deposit().saturating_add(subs_deposit))
+		});
+		let accounts = T::Currency::name_deposits(&IDENTITY_ID, deposits) as Weight;
+		T::DbWeight::get().reads_writes(accounts * 5, accounts)
+	}
+}
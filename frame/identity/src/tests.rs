@@ -21,7 +21,9 @@ use super::*;
 use crate as pallet_identity;
 
 use sp_runtime::traits::BadOrigin;
-use frame_support::{assert_ok, assert_noop, parameter_types, ord_parameter_types};
+use frame_support::{
+	assert_ok, assert_noop, parameter_types, ord_parameter_types, traits::ReservableCurrency,
+};
 use sp_core::H256;
 use frame_system::{EnsureSignedBy, EnsureOneOf, EnsureRoot};
 use sp_runtime::{
@@ -74,6 +76,7 @@ impl frame_system::Config for Test {
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
 }
 impl pallet_balances::Config for Test {
 	type Balance = u64;
@@ -82,6 +85,7 @@ impl pallet_balances::Config for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
 	type WeightInfo = ();
 }
 parameter_types! {
@@ -470,3 +474,23 @@ fn setting_account_id_should_work() {
 		assert_ok!(Identity::set_account_id(Origin::signed(4), 0, 3));
 	});
 }
+
+#[test]
+fn migrate_to_named_reserves_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+		assert_ok!(Identity::add_sub(Origin::signed(10), 1, Data::Raw(vec![1])));
+		// Hold the deposit in the anonymous reserve, as it was before named reserves existed.
+		let deposit = Balances::reserved_balance_named(&IDENTITY_ID, &10);
+		assert_eq!(deposit, 20);
+		Balances::unreserve_named(&IDENTITY_ID, &10, deposit);
+		assert_ok!(Balances::reserve(&10, deposit));
+
+		// The identity and sub-account deposits are named together.
+		migration::migrate_to_named_reserves::<Test>();
+		assert_eq!(Balances::reserved_balance_named(&IDENTITY_ID, &10), 20);
+
+		assert_ok!(Identity::clear_identity(Origin::signed(10)));
+		assert_eq!(Balances::reserved_balance(10), 0);
+	});
+}
@@ -54,7 +54,7 @@ use sp_std::prelude::*;
 use codec::{Encode, Decode};
 use sp_io::hashing::blake2_256;
 use frame_support::{decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, RuntimeDebug};
-use frame_support::{traits::{Get, ReservableCurrency, Currency},
+use frame_support::{traits::{Get, NamedReservableCurrency, ReserveIdentifier, Currency},
 	weights::{Weight, GetDispatchInfo},
 	dispatch::{DispatchResultWithPostInfo, DispatchErrorWithPostInfo, PostDispatchInfo},
 };
@@ -63,6 +63,9 @@ use sp_runtime::{DispatchError, DispatchResult, traits::{Dispatchable, Zero}};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The identifier under which the deposits of this module are reserved.
+const MULTISIG_ID: ReserveIdentifier = *b"multisig";
 /// Just a bunch of bytes, but they should decode to a valid `Call`.
 pub type OpaqueCall = Vec<u8>;
 
@@ -76,7 +79,7 @@ pub trait Config: frame_system::Config {
 		+ GetDispatchInfo + From<frame_system::Call<Self>>;
 
 	/// The currency mechanism.
-	type Currency: ReservableCurrency<Self::AccountId>;
+	type Currency: NamedReservableCurrency<Self::AccountId>;
 
 	/// The base amount of currency needed to reserve for creating a multisig execution or to store
 	/// a dispatch call for later.
@@ -436,7 +439,7 @@ decl_module! {
 			ensure!(m.when == timepoint, Error::<T>::WrongTimepoint);
 			ensure!(m.depositor == who, Error::<T>::NotOwner);
 
-			let _ = T::Currency::unreserve(&m.depositor, m.deposit);
+			let _ = T::Currency::unreserve_named_or_unnamed(&MULTISIG_ID, &m.depositor, m.deposit);
 			<Multisigs<T>>::remove(&id, &call_hash);
 			Self::clear_call(&call_hash);
 
@@ -509,7 +512,7 @@ impl<T: Config> Module<T> {
 				// attack.
 				<Multisigs<T>>::remove(&id, call_hash);
 				Self::clear_call(&call_hash);
-				T::Currency::unreserve(&m.depositor, m.deposit);
+				T::Currency::unreserve_named_or_unnamed(&MULTISIG_ID, &m.depositor, m.deposit);
 
 				let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
 				Self::deposit_event(RawEvent::MultisigExecuted(
@@ -570,7 +573,7 @@ impl<T: Config> Module<T> {
 				Self::store_call_and_reserve(who.clone(), &call_hash, data, deposit)?;
 				true
 			} else {
-				T::Currency::reserve(&who, deposit)?;
+				T::Currency::reserve_named(&MULTISIG_ID, &who, deposit)?;
 				false
 			};
 
@@ -612,7 +615,7 @@ impl<T: Config> Module<T> {
 		ensure!(!Calls::<T>::contains_key(hash), Error::<T>::AlreadyStored);
 		let deposit = other_deposit + T::DepositBase::get()
 			+ T::DepositFactor::get() * BalanceOf::<T>::from(((data.len() + 31) / 32) as u32);
-		T::Currency::reserve(&who, deposit)?;
+		T::Currency::reserve_named(&MULTISIG_ID, &who, deposit)?;
 		Calls::<T>::insert(&hash, (data, who, deposit));
 		Ok(())
 	}
@@ -631,7 +634,7 @@ impl<T: Config> Module<T> {
 	/// Attempt to remove a call from storage, returning any deposit on it to the owner.
 	fn clear_call(hash: &[u8; 32]) {
 		if let Some((_, who, deposit)) = Calls::<T>::take(hash) {
-			T::Currency::unreserve(&who, deposit);
+			T::Currency::unreserve_named_or_unnamed(&MULTISIG_ID, &who, deposit);
 		}
 	}
 
@@ -674,3 +677,22 @@ fn get_result_weight(result: DispatchResultWithPostInfo) -> Option<Weight> {
 		Err(err) => err.post_info.actual_weight,
 	}
 }
+
+pub mod migration {
+	use super::*;
+	use frame_support::storage::{IterableStorageMap, IterableStorageDoubleMap};
+
+	/// Names the deposits held by this module after `MULTISIG_ID`, see
+	/// [`NamedReservableCurrency::name_deposits`].
+	///
+	/// A call stored along with the creation of its operation already accounts for the
+	/// operation's deposit; no more than the account has reserved is named in that case.
+	pub fn migrate_to_named_reserves<T: Config>() -> Weight {
+		let mut reads: Weight = 0;
+		let multisigs = Multisigs::<T>::iter().map(|(_, _, m)| (m.depositor, m.deposit));
+		let calls = Calls::<T>::iter().map(|(_, (_, who, deposit))| (who, deposit));
+		let deposits = multisigs.chain(calls).inspect(|_| reads += 1);
+		let accounts = T::Currency::name_deposits(&MULTISIG_ID, deposits) as Weight;
+		T::DbWeight::get().reads_writes(reads + accounts * 3, accounts)
+	}
+}
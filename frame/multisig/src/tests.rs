@@ -22,7 +22,7 @@
 use super::*;
 
 use frame_support::{
-	assert_ok, assert_noop, parameter_types, traits::Filter,
+	assert_ok, assert_noop, parameter_types, traits::{Filter, ReservableCurrency},
 };
 use sp_core::H256;
 use sp_runtime::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
@@ -74,9 +74,11 @@ impl frame_system::Config for Test {
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -559,3 +561,31 @@ fn multisig_handles_no_preimage_after_all_approve() {
 		assert_eq!(Balances::free_balance(6), 15);
 	});
 }
+
+#[test]
+fn migrate_to_named_reserves_works() {
+	new_test_ext().execute_with(|| {
+		let data = Call::Balances(BalancesCall::transfer(6, 15)).encode();
+		let hash = blake2_256(&data);
+		assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 3, vec![2, 3], None, hash.clone(), 0));
+		assert_ok!(Multisig::as_multi(Origin::signed(2), 3, vec![1, 3], Some(now()), data, true, 0));
+		assert_eq!(Balances::reserved_balance_named(&MULTISIG_ID, &1), 4);
+		assert_eq!(Balances::reserved_balance_named(&MULTISIG_ID, &2), 2);
+
+		// Hold the deposits in the anonymous reserve, as they were before named reserves existed.
+		for who in &[1, 2] {
+			let deposit = Balances::reserved_balance_named(&MULTISIG_ID, who);
+			Balances::unreserve_named(&MULTISIG_ID, who, deposit);
+			assert_ok!(Balances::reserve(who, deposit));
+		}
+
+		// The multisig deposit and the call deposit are named for their own depositors.
+		migration::migrate_to_named_reserves::<Test>();
+		assert_eq!(Balances::reserved_balance_named(&MULTISIG_ID, &1), 4);
+		assert_eq!(Balances::reserved_balance_named(&MULTISIG_ID, &2), 2);
+
+		assert_ok!(Multisig::cancel_as_multi(Origin::signed(1), 3, vec![2, 3], now(), hash));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
@@ -48,7 +48,8 @@ use sp_io::hashing::blake2_256;
 use sp_runtime::{DispatchResult, traits::{Dispatchable, Zero, Hash, Member, Saturating}};
 use frame_support::{
 	decl_module, decl_event, decl_error, decl_storage, Parameter, ensure, RuntimeDebug, traits::{
-		Get, ReservableCurrency, Currency, InstanceFilter, OriginTrait, IsType, IsSubType,
+		Get, NamedReservableCurrency, ReservableCurrency, ReserveIdentifier, Currency,
+		InstanceFilter, OriginTrait, IsType, IsSubType,
 	}, weights::{Weight, GetDispatchInfo}, dispatch::PostDispatchInfo, storage::IterableStorageMap,
 };
 use frame_system::{self as system, ensure_signed};
@@ -57,6 +58,9 @@ pub use weights::WeightInfo;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// The identifier under which the deposits of this module are reserved.
+const PROXY_ID: ReserveIdentifier = *b"proxy   ";
+
 /// Configuration trait.
 pub trait Config: frame_system::Config {
 	/// The overarching event type.
@@ -68,7 +72,7 @@ pub trait Config: frame_system::Config {
 		+ IsType<<Self as frame_system::Config>::Call>;
 
 	/// The currency mechanism.
-	type Currency: ReservableCurrency<Self::AccountId>;
+	type Currency: NamedReservableCurrency<Self::AccountId>;
 
 	/// A kind of proxy; specified with the proxy and passed in to the `IsProxyable` fitler.
 	/// The instance filter determines whether a given call may be proxied under this type.
@@ -149,6 +153,11 @@ decl_storage! {
 		/// The announcements made by the proxy (key).
 		pub Announcements get(fn announcements): map hasher(twox_64_concat) T::AccountId
 			=> (Vec<Announcement<T::AccountId, CallHashOf<T>, T::BlockNumber>>, BalanceOf<T>);
+
+		/// The deposits of anonymous proxies created before deposits were named, which their
+		/// spawner reserved without identifier. Released by `kill_anonymous`.
+		pub UnnamedAnonymousDeposits get(fn unnamed_anonymous_deposits):
+			map hasher(twox_64_concat) T::AccountId => BalanceOf<T>;
 	}
 }
 
@@ -308,7 +317,7 @@ decl_module! {
 		fn remove_proxies(origin) {
 			let who = ensure_signed(origin)?;
 			let (_, old_deposit) = Proxies::<T>::take(&who);
-			T::Currency::unreserve(&who, old_deposit);
+			T::Currency::unreserve_named_or_unnamed(&PROXY_ID, &who, old_deposit);
 		}
 
 		/// Spawn a fresh new account that is guaranteed to be otherwise inaccessible, and
@@ -341,7 +350,7 @@ decl_module! {
 			let anonymous = Self::anonymous_account(&who, &proxy_type, index, None);
 			ensure!(!Proxies::<T>::contains_key(&anonymous), Error::<T>::Duplicate);
 			let deposit = T::ProxyDepositBase::get() + T::ProxyDepositFactor::get();
-			T::Currency::reserve(&who, deposit)?;
+			T::Currency::reserve_named(&PROXY_ID, &who, deposit)?;
 			let proxy_def = ProxyDefinition {
 				delegate: who.clone(),
 				proxy_type: proxy_type.clone(),
@@ -386,7 +395,9 @@ decl_module! {
 			ensure!(proxy == who, Error::<T>::NoPermission);
 
 			let (_, deposit) = Proxies::<T>::take(&who);
-			T::Currency::unreserve(&spawner, deposit);
+			let unnamed = UnnamedAnonymousDeposits::<T>::take(&who).min(deposit);
+			T::Currency::unreserve_unnamed(&spawner, unnamed);
+			T::Currency::unreserve_named(&PROXY_ID, &spawner, deposit - unnamed);
 		}
 
 		/// Publish the hash of a proxy-call that will be made in the future.
@@ -577,9 +588,13 @@ impl<T: Config> Module<T> {
 			proxies.insert(i, proxy_def);
 			let new_deposit = Self::deposit(proxies.len() as u32);
 			if new_deposit > *deposit {
-				T::Currency::reserve(delegator, new_deposit - *deposit)?;
+				T::Currency::reserve_named(&PROXY_ID, delegator, new_deposit - *deposit)?;
 			} else if new_deposit < *deposit {
-				T::Currency::unreserve(delegator, *deposit - new_deposit);
+				T::Currency::unreserve_named_or_unnamed(
+					&PROXY_ID,
+					delegator,
+					*deposit - new_deposit,
+				);
 			}
 			*deposit = new_deposit;
 			Ok(())
@@ -607,9 +622,13 @@ impl<T: Config> Module<T> {
 			proxies.remove(i);
 			let new_deposit = Self::deposit(proxies.len() as u32);
 			if new_deposit > old_deposit {
-				T::Currency::reserve(delegator, new_deposit - old_deposit)?;
+				T::Currency::reserve_named(&PROXY_ID, delegator, new_deposit - old_deposit)?;
 			} else if new_deposit < old_deposit {
-				T::Currency::unreserve(delegator, old_deposit - new_deposit);
+				T::Currency::unreserve_named_or_unnamed(
+					&PROXY_ID,
+					delegator,
+					old_deposit - new_deposit,
+				);
 			}
 			if !proxies.is_empty() {
 				*x = Some((proxies, new_deposit))
@@ -639,9 +658,9 @@ impl<T: Config> Module<T> {
 			base + factor * (len as u32).into()
 		};
 		if new_deposit > old_deposit {
-			T::Currency::reserve(&who, new_deposit - old_deposit)?;
+			T::Currency::reserve_named(&PROXY_ID, &who, new_deposit - old_deposit)?;
 		} else if new_deposit < old_deposit {
-			T::Currency::unreserve(&who, old_deposit - new_deposit);
+			T::Currency::unreserve_named_or_unnamed(&PROXY_ID, &who, old_deposit - new_deposit);
 		}
 		Ok(if len == 0 {
 			None
@@ -732,4 +751,29 @@ pub mod migration {
 		);
 		T::BlockWeights::get().max_block
 	}
+
+	/// Names the deposits held by this module after `PROXY_ID`, see
+	/// [`NamedReservableCurrency::name_deposits`].
+	///
+	/// The deposit of an anonymous proxy is reserved by its spawner but recorded for the anonymous
+	/// account, which has nothing reserved, so it can't be named. It is recorded in
+	/// `UnnamedAnonymousDeposits` instead, and `kill_anonymous` releases it from the unnamed
+	/// reserve of the spawner.
+	pub fn migrate_to_named_reserves<T: Config>() -> Weight {
+		let mut reads: Weight = 0;
+		let proxies = Proxies::<T>::iter().map(|(who, (_, deposit))| (who, deposit));
+		let announcements = Announcements::<T>::iter().map(|(who, (_, deposit))| (who, deposit));
+		let deposits = proxies.chain(announcements).inspect(|_| reads += 1);
+		let accounts = T::Currency::name_deposits(&PROXY_ID, deposits) as Weight;
+
+		let mut anonymous: Weight = 0;
+		for (who, (_, deposit)) in Proxies::<T>::iter() {
+			reads += 2;
+			if !deposit.is_zero() && T::Currency::reserved_balance(&who).is_zero() {
+				UnnamedAnonymousDeposits::<T>::insert(&who, deposit);
+				anonymous += 1;
+			}
+		}
+		T::DbWeight::get().reads_writes(reads + accounts * 4, accounts * 2 + anonymous)
+	}
 }
@@ -22,7 +22,8 @@
 use super::*;
 
 use frame_support::{
-	assert_ok, assert_noop, parameter_types, RuntimeDebug, dispatch::DispatchError, traits::Filter,
+	assert_ok, assert_noop, parameter_types, RuntimeDebug, dispatch::DispatchError,
+	traits::{Filter, ReservableCurrency},
 };
 use codec::{Encode, Decode};
 use sp_core::H256;
@@ -76,9 +77,11 @@ impl frame_system::Config for Test {
 }
 parameter_types! {
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -466,3 +469,29 @@ fn anonymous_works() {
 		assert_noop!(Proxy::proxy(Origin::signed(1), anon, None, call.clone()), Error::<Test>::NotProxy);
 	});
 }
+
+#[test]
+fn kill_anonymous_releases_unnamed_deposit_of_spawner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::anonymous(Origin::signed(1), ProxyType::Any, 0, 0));
+		let anon = Proxy::anonymous_account(&1, &ProxyType::Any, 0, None);
+
+		// The spawner held the deposit in the anonymous reserve before named reserves existed.
+		Balances::unreserve_named(&PROXY_ID, &1, 2);
+		assert_ok!(Balances::reserve(&1, 2));
+		// Some other module's reserve must not be touched.
+		assert_ok!(Balances::reserve(&1, 1));
+
+		// It is recorded for the anonymous account, which has nothing reserved to name.
+		migration::migrate_to_named_reserves::<Test>();
+		assert_eq!(Proxy::unnamed_anonymous_deposits(anon), 2);
+		assert_eq!(Balances::reserved_balance_named(&PROXY_ID, &1), 0);
+		assert_eq!(Balances::reserved_balance(1), 3);
+
+		let call = Box::new(Call::Proxy(ProxyCall::kill_anonymous(1, ProxyType::Any, 0, 1, 0)));
+		assert_ok!(Proxy::proxy(Origin::signed(1), anon, None, call));
+		assert_eq!(Proxy::unnamed_anonymous_deposits(anon), 0);
+		assert_eq!(Balances::reserved_balance(1), 1);
+		assert_eq!(Balances::free_balance(1), 9);
+	});
+}
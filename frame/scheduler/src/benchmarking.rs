@@ -23,7 +23,8 @@ use super::*;
 use sp_std::{vec, prelude::*};
 use frame_system::RawOrigin;
 use frame_support::{ensure, traits::OnInitialize};
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_benchmarking::{benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use sp_runtime::traits::Bounded;
 
 use crate::Module as Scheduler;
 use frame_system::Module as System;
@@ -33,7 +34,7 @@ const BLOCK_NUMBER: u32 = 2;
 // Add `n` named items to the schedule
 fn fill_schedule<T: Config> (when: T::BlockNumber, n: u32) -> Result<(), &'static str> {
 	// Essentially a no-op call.
-	let call = frame_system::Call::set_storage(vec![]);
+	let call: <T as Config>::Call = frame_system::Call::set_storage(vec![]).into();
 	for i in 0..n {
 		// Named schedule is strictly heavier than anonymous
 		Scheduler::<T>::do_schedule_named(
@@ -58,7 +59,7 @@ benchmarks! {
 		let periodic = Some((T::BlockNumber::one(), 100));
 		let priority = 0;
 		// Essentially a no-op call.
-		let call = Box::new(frame_system::Call::set_storage(vec![]).into());
+		let call = Box::new(MaybeHashed::Value(frame_system::Call::set_storage(vec![]).into()));
 
 		fill_schedule::<T>(when, s)?;
 	}: _(RawOrigin::Root, when, periodic, priority, call)
@@ -95,7 +96,7 @@ benchmarks! {
 		let periodic = Some((T::BlockNumber::one(), 100));
 		let priority = 0;
 		// Essentially a no-op call.
-		let call = Box::new(frame_system::Call::set_storage(vec![]).into());
+		let call = Box::new(MaybeHashed::Value(frame_system::Call::set_storage(vec![]).into()));
 
 		fill_schedule::<T>(when, s)?;
 	}: _(RawOrigin::Root, id, when, periodic, priority, call)
@@ -124,6 +125,54 @@ benchmarks! {
 		);
 	}
 
+	set_retry {
+		let s in 1 .. T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		fill_schedule::<T>(when, s)?;
+	}: _(RawOrigin::Root, (when, 0), 10, T::BlockNumber::one())
+	verify {
+		ensure!(
+			Agenda::<T>::get(when)[0].as_ref().map_or(false, |s| s.maybe_retry.is_some()),
+			"didn't set retry"
+		);
+	}
+
+	set_retry_named {
+		let s in 1 .. T::MaxScheduledPerBlock::get();
+		let when = BLOCK_NUMBER.into();
+
+		fill_schedule::<T>(when, s)?;
+	}: _(RawOrigin::Root, 0.encode(), 10, T::BlockNumber::one())
+	verify {
+		ensure!(
+			Agenda::<T>::get(when)[0].as_ref().map_or(false, |s| s.maybe_retry.is_some()),
+			"didn't set retry"
+		);
+	}
+
+	note_preimage {
+		let s in 0 .. T::MaxPreimageSize::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let preimage = vec![1u8; s as usize];
+		let hash = T::Hashing::hash(&preimage[..]);
+	}: _(RawOrigin::Signed(caller), preimage)
+	verify {
+		ensure!(PreimageFor::<T>::contains_key(&hash), "didn't note preimage");
+	}
+
+	unnote_preimage {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let preimage = vec![1u8; T::MaxPreimageSize::get() as usize];
+		let hash = T::Hashing::hash(&preimage[..]);
+		Scheduler::<T>::note_preimage(RawOrigin::Signed(caller.clone()).into(), preimage)?;
+	}: _(RawOrigin::Signed(caller), hash)
+	verify {
+		ensure!(!PreimageFor::<T>::contains_key(&hash), "didn't remove preimage");
+	}
+
 	// TODO [#7141]: Make this more complex and flexible so it can be used in automation.
 	#[extra]
 	on_initialize {
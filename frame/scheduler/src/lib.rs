@@ -47,6 +47,26 @@
 //! * `schedule_named` - augments the `schedule` interface with an additional
 //!   `Vec<u8>` parameter that can be used for identification.
 //! * `cancel_named` - the named complement to the cancel function.
+//! * `schedule_after` and `schedule_named_after` - schedule a task relative to the
+//!   current block.
+//! * `set_retry` and `set_retry_named` - configure how often and how far apart a failed
+//!   dispatch of a task is retried.
+//! * `note_preimage` - store the encoded call for a task that was scheduled by its hash.
+//! * `unnote_preimage` - remove a stored encoded call which is not requested by any task.
+//!
+//! ### Scheduling by hash
+//!
+//! Large calls, e.g. runtime upgrades, can be scheduled by the hash of their encoding instead of
+//! the call itself so that the `Agenda` only contains a hash. The encoded call, its preimage, is
+//! then either noted beforehand or at any time before the task is due through `note_preimage`.
+//! Noting a preimage reserves a deposit from the provider unless it is requested by a scheduled
+//! task, in which case anyone can provide it for free. The preimage is looked up when the task is
+//! due and, if not yet available, the task is postponed by `NoPreimagePostponement` blocks.
+//!
+//! ### Retries
+//!
+//! A task can be given a retry policy. If its dispatch fails it is rescheduled as an anonymous,
+//! non-periodic task `period` blocks later, at most `retries` times.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -56,16 +76,22 @@ pub mod weights;
 
 use sp_std::{prelude::*, marker::PhantomData, borrow::Borrow};
 use codec::{Encode, Decode, Codec};
-use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating}};
+use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating, Hash}};
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap,
+	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap, ensure,
 	dispatch::{Dispatchable, DispatchError, DispatchResult, Parameter},
-	traits::{Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType},
+	traits::{
+		Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType, Currency,
+		ReservableCurrency,
+	},
 	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed};
 pub use weights::WeightInfo;
 
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 /// Our pallet's configuration trait. All our types and constants go in here. If the
 /// pallet is dependent on specific other pallets, then their configuration traits
 /// should be added to our implied traits list.
@@ -96,6 +122,23 @@ pub trait Config: system::Config {
 	/// Not strictly enforced, but used for weight estimation.
 	type MaxScheduledPerBlock: Get<u32>;
 
+	/// The currency used to reserve the deposit for noted preimages.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The base deposit for noting a preimage which is not requested.
+	type PreimageBaseDeposit: Get<BalanceOf<Self>>;
+
+	/// The deposit per byte for noting a preimage which is not requested.
+	type PreimageByteDeposit: Get<BalanceOf<Self>>;
+
+	/// The maximum size of a preimage in bytes.
+	type MaxPreimageSize: Get<u32>;
+
+	/// The number of blocks a task is postponed if its preimage is not available when it is due.
+	///
+	/// `None` drops such a task instead of postponing it.
+	type NoPreimagePostponement: Get<Option<Self::BlockNumber>>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -105,6 +148,78 @@ pub type PeriodicIndex = u32;
 /// The location of a scheduled task that can be used to remove it.
 pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
 
+/// Either a value or the hash of its encoding.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum MaybeHashed<T, Hash> {
+	/// The value itself.
+	Value(T),
+	/// The hash of the encoded value which this value represents.
+	Hash(Hash),
+}
+
+impl<T, H> From<T> for MaybeHashed<T, H> {
+	fn from(t: T) -> Self {
+		MaybeHashed::Value(t)
+	}
+}
+
+impl<T, H> MaybeHashed<T, H> {
+	/// Returns the value if it is not hashed.
+	pub fn as_value(&self) -> Option<&T> {
+		match self {
+			MaybeHashed::Value(v) => Some(v),
+			MaybeHashed::Hash(_) => None,
+		}
+	}
+
+	/// Returns the hash if the value is hashed.
+	pub fn as_hash(&self) -> Option<&H> {
+		match self {
+			MaybeHashed::Value(_) => None,
+			MaybeHashed::Hash(h) => Some(h),
+		}
+	}
+}
+
+/// A call of the runtime or the hash of its encoding.
+pub type CallOrHashOf<T> = MaybeHashed<<T as Config>::Call, <T as frame_system::Config>::Hash>;
+
+/// The reason why the call of a task that was scheduled by hash could not be looked up.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum LookupError {
+	/// The preimage is not known. The task is postponed or dropped depending on
+	/// `Config::NoPreimagePostponement`.
+	Unknown,
+	/// The preimage does not decode into a call. The task is dropped.
+	BadFormat,
+}
+
+/// The request status of a preimage.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum PreimageStatus<AccountId, Balance> {
+	/// The preimage is noted but no task requests it. The deposit of the provider is reserved.
+	Unrequested {
+		provider: AccountId,
+		deposit: Balance,
+	},
+	/// The preimage is requested by `count` scheduled tasks. It may or may not be noted yet.
+	///
+	/// A deposit is only reserved if the preimage was noted before it got requested.
+	Requested {
+		count: u32,
+		deposit: Option<(AccountId, Balance)>,
+	},
+}
+
+/// The retry policy of a task.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct RetryConfig<BlockNumber> {
+	/// The number of retries that are left.
+	pub remaining: u8,
+	/// The number of blocks between a failed dispatch and its retry.
+	pub period: BlockNumber,
+}
+
 #[cfg_attr(any(feature = "std", test), derive(PartialEq, Eq))]
 #[derive(Clone, RuntimeDebug, Encode, Decode)]
 struct ScheduledV1<Call, BlockNumber> {
@@ -131,8 +246,35 @@ pub struct ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId> {
 	_phantom: PhantomData<AccountId>,
 }
 
+/// Information regarding an item to be executed in the future.
+#[cfg_attr(any(feature = "std", test), derive(PartialEq, Eq))]
+#[derive(Clone, RuntimeDebug, Encode, Decode)]
+pub struct ScheduledV3<Call, BlockNumber, PalletsOrigin, AccountId> {
+	/// The unique identity for this task, if there is one.
+	maybe_id: Option<Vec<u8>>,
+	/// This task's priority.
+	priority: schedule::Priority,
+	/// The call to be dispatched or the hash of its encoding.
+	call: Call,
+	/// If the call is periodic, then this points to the information concerning that.
+	maybe_periodic: Option<schedule::Period<BlockNumber>>,
+	/// The origin to dispatch the call.
+	origin: PalletsOrigin,
+	/// If a failed dispatch is retried, then this points to the information concerning that.
+	maybe_retry: Option<RetryConfig<BlockNumber>>,
+	_phantom: PhantomData<AccountId>,
+}
+
 /// The current version of Scheduled struct.
-pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId>;
+pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV3<Call, BlockNumber, PalletsOrigin, AccountId>;
+
+/// The current version of Scheduled struct for the given config.
+pub type ScheduledOf<T> = Scheduled<
+	CallOrHashOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	<T as Config>::PalletsOrigin,
+	<T as frame_system::Config>::AccountId,
+>;
 
 // A value placed in storage that represents the current version of the Scheduler storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
@@ -141,6 +283,7 @@ pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Ca
 enum Releases {
 	V1,
 	V2,
+	V3,
 }
 
 impl Default for Releases {
@@ -152,27 +295,45 @@ impl Default for Releases {
 decl_storage! {
 	trait Store for Module<T: Config> as Scheduler {
 		/// Items to be executed, indexed by the block number that they should be executed on.
-		pub Agenda: map hasher(twox_64_concat) T::BlockNumber
-			=> Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>;
+		pub Agenda: map hasher(twox_64_concat) T::BlockNumber => Vec<Option<ScheduledOf<T>>>;
 
 		/// Lookup from identity to the block number and index of the task.
 		Lookup: map hasher(twox_64_concat) Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
 
+		/// The request status of known preimages of calls, keyed by the hash of the preimage.
+		pub StatusFor: map hasher(identity) T::Hash => Option<PreimageStatus<T::AccountId, BalanceOf<T>>>;
+
+		/// The preimages of calls that were noted, keyed by their hash.
+		pub PreimageFor: map hasher(identity) T::Hash => Option<Vec<u8>>;
+
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Releases::V2): Releases;
+		StorageVersion build(|_| Releases::V3): Releases;
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where <T as system::Config>::BlockNumber {
+	pub enum Event<T> where
+		<T as system::Config>::BlockNumber,
+		<T as system::Config>::Hash,
+	{
 		/// Scheduled some task. \[when, index\]
 		Scheduled(BlockNumber, u32),
 		/// Canceled some task. \[when, index\]
 		Canceled(BlockNumber, u32),
 		/// Dispatched some task. \[task, id, result\]
 		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, DispatchResult),
+		/// The call of a task that was scheduled by hash could not be looked up. \[task, id, error\]
+		CallLookupFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>, LookupError),
+		/// The retry policy of a task was set. \[task, id, retries, period\]
+		RetrySet(TaskAddress<BlockNumber>, Option<Vec<u8>>, u8, BlockNumber),
+		/// A preimage has been noted. \[hash\]
+		PreimageNoted(Hash),
+		/// A preimage has been requested by a scheduled task. \[hash\]
+		PreimageRequested(Hash),
+		/// A preimage has been removed. \[hash\]
+		PreimageCleared(Hash),
 	}
 );
 
@@ -186,6 +347,18 @@ decl_error! {
 		TargetBlockNumberInPast,
 		/// Reschedule failed because it does not change scheduled time.
 		RescheduleNoChange,
+		/// The preimage is larger than `MaxPreimageSize`.
+		PreimageTooLarge,
+		/// The preimage is already noted.
+		PreimageAlreadyNoted,
+		/// The preimage is not noted.
+		PreimageNotNoted,
+		/// The preimage is requested and can't be removed by its provider.
+		PreimageRequested,
+		/// The sender is not the provider of the preimage.
+		NotPreimageProvider,
+		/// The period between retries must not be zero.
+		InvalidRetryPeriod,
 	}
 }
 
@@ -197,20 +370,24 @@ decl_module! {
 
 		/// Anonymously schedule a task.
 		///
+		/// The task may be given by the hash of its call which is then looked up
+		/// when the task is due. See `note_preimage`.
+		///
 		/// # <weight>
 		/// - S = Number of already scheduled calls
 		/// - Base Weight: 22.29 + .126 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda
-		///     - Write: Agenda
+		///     - Read: Agenda, StatusFor if hashed
+		///     - Write: Agenda, StatusFor if hashed
 		/// - Will use base weight of 25 which should be good for up to 30 scheduled calls
 		/// # </weight>
-		#[weight = T::WeightInfo::schedule(T::MaxScheduledPerBlock::get())]
+		#[weight = T::WeightInfo::schedule(T::MaxScheduledPerBlock::get())
+			.saturating_add(Module::<T>::request_weight(call))]
 		fn schedule(origin,
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -223,11 +400,12 @@ decl_module! {
 		/// - S = Number of already scheduled calls
 		/// - Base Weight: 22.15 + 2.869 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda
-		///     - Write: Agenda, Lookup
+		///     - Read: Agenda, StatusFor
+		///     - Write: Agenda, Lookup, StatusFor
 		/// - Will use base weight of 100 which should be good for up to 30 scheduled calls
 		/// # </weight>
-		#[weight = T::WeightInfo::cancel(T::MaxScheduledPerBlock::get())]
+		#[weight = T::WeightInfo::cancel(T::MaxScheduledPerBlock::get())
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1))]
 		fn cancel(origin, when: T::BlockNumber, index: u32) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -240,17 +418,18 @@ decl_module! {
 		/// - S = Number of already scheduled calls
 		/// - Base Weight: 29.6 + .159 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda, Lookup
-		///     - Write: Agenda, Lookup
+		///     - Read: Agenda, Lookup, StatusFor if hashed
+		///     - Write: Agenda, Lookup, StatusFor if hashed
 		/// - Will use base weight of 35 which should be good for more than 30 scheduled calls
 		/// # </weight>
-		#[weight = T::WeightInfo::schedule_named(T::MaxScheduledPerBlock::get())]
+		#[weight = T::WeightInfo::schedule_named(T::MaxScheduledPerBlock::get())
+			.saturating_add(Module::<T>::request_weight(call))]
 		fn schedule_named(origin,
 			id: Vec<u8>,
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -265,11 +444,12 @@ decl_module! {
 		/// - S = Number of already scheduled calls
 		/// - Base Weight: 24.91 + 2.907 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda, Lookup
-		///     - Write: Agenda, Lookup
+		///     - Read: Agenda, Lookup, StatusFor
+		///     - Write: Agenda, Lookup, StatusFor
 		/// - Will use base weight of 100 which should be good for up to 30 scheduled calls
 		/// # </weight>
-		#[weight = T::WeightInfo::cancel_named(T::MaxScheduledPerBlock::get())]
+		#[weight = T::WeightInfo::cancel_named(T::MaxScheduledPerBlock::get())
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1))]
 		fn cancel_named(origin, id: Vec<u8>) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -281,12 +461,13 @@ decl_module! {
 		/// # <weight>
 		/// Same as [`schedule`].
 		/// # </weight>
-		#[weight = T::WeightInfo::schedule(T::MaxScheduledPerBlock::get())]
+		#[weight = T::WeightInfo::schedule(T::MaxScheduledPerBlock::get())
+			.saturating_add(Module::<T>::request_weight(call))]
 		fn schedule_after(origin,
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -300,13 +481,14 @@ decl_module! {
 		/// # <weight>
 		/// Same as [`schedule_named`].
 		/// # </weight>
-		#[weight = T::WeightInfo::schedule_named(T::MaxScheduledPerBlock::get())]
+		#[weight = T::WeightInfo::schedule_named(T::MaxScheduledPerBlock::get())
+			.saturating_add(Module::<T>::request_weight(call))]
 		fn schedule_named_after(origin,
 			id: Vec<u8>,
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -315,17 +497,95 @@ decl_module! {
 			)?;
 		}
 
+		/// Set the retry policy of an anonymously scheduled task.
+		///
+		/// If a dispatch of the task fails, it is retried `period` blocks later as an anonymous,
+		/// non-periodic task, at most `retries` times. Setting `retries` to zero removes the
+		/// retry policy.
+		///
+		/// # <weight>
+		/// - S = Number of already scheduled calls
+		/// - DB Weight:
+		///     - Read: Agenda
+		///     - Write: Agenda
+		/// # </weight>
+		#[weight = T::WeightInfo::set_retry(T::MaxScheduledPerBlock::get())]
+		fn set_retry(origin, task: TaskAddress<T::BlockNumber>, retries: u8, period: T::BlockNumber) {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::Origin::from(origin);
+			Self::do_set_retry(Some(origin.caller().clone()), task, retries, period)?;
+		}
+
+		/// Set the retry policy of a named task.
+		///
+		/// # <weight>
+		/// Same as [`set_retry`] with an additional read of `Lookup`.
+		/// # </weight>
+		#[weight = T::WeightInfo::set_retry_named(T::MaxScheduledPerBlock::get())]
+		fn set_retry_named(origin, id: Vec<u8>, retries: u8, period: T::BlockNumber) {
+			T::ScheduleOrigin::ensure_origin(origin.clone())?;
+			let origin = <T as Config>::Origin::from(origin);
+			let task = Lookup::<T>::get(&id).ok_or(Error::<T>::NotFound)?;
+			Self::do_set_retry(Some(origin.caller().clone()), task, retries, period)?;
+		}
+
+		/// Note the preimage of a call which is or will be scheduled by its hash.
+		///
+		/// If the preimage is requested by a scheduled task it is noted for free. Otherwise
+		/// a deposit of `PreimageBaseDeposit + PreimageByteDeposit * len` is reserved from the
+		/// sender until the preimage is unnoted.
+		///
+		/// # <weight>
+		/// - B = Size of the preimage in bytes
+		/// - DB Weight:
+		///     - Read: StatusFor, PreimageFor
+		///     - Write: StatusFor, PreimageFor
+		/// # </weight>
+		#[weight = T::WeightInfo::note_preimage(encoded_call.len() as u32)]
+		fn note_preimage(origin, encoded_call: Vec<u8>) {
+			let who = ensure_signed(origin)?;
+			Self::do_note_preimage(who, encoded_call)?;
+		}
+
+		/// Remove a preimage noted by the sender and unreserve its deposit.
+		///
+		/// If the preimage is requested by a scheduled task only the deposit is returned
+		/// and the preimage is kept until it is no longer requested.
+		///
+		/// # <weight>
+		/// - DB Weight:
+		///     - Read: StatusFor
+		///     - Write: StatusFor, PreimageFor
+		/// # </weight>
+		#[weight = T::WeightInfo::unnote_preimage()]
+		fn unnote_preimage(origin, hash: T::Hash) {
+			let who = ensure_signed(origin)?;
+			Self::do_unnote_preimage(who, hash)?;
+		}
+
 		/// Execute the scheduled calls
 		///
 		/// # <weight>
 		/// - S = Number of already scheduled calls
 		/// - N = Named scheduled calls
 		/// - P = Periodic Calls
+		/// - H = Calls scheduled by hash
+		/// - R = Calls with a retry policy
 		/// - Base Weight: 9.243 + 23.45 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda + Lookup * N + Agenda(Future) * P
-		///     - Write: Agenda + Lookup * N  + Agenda(future) * P
+		///     - Read: Agenda + Lookup * N + Agenda(Future) * (P + R) + (StatusFor + PreimageFor) * H
+		///     - Write: Agenda + Lookup * N  + Agenda(future) * (P + R) + (StatusFor + PreimageFor) * H
+		/// - The preimage of a call scheduled by hash is only read for tasks which are dispatched in
+		///   this block, deferred tasks keep the hash.
 		/// # </weight>
+		fn on_runtime_upgrade() -> Weight {
+			if Self::migrate_v1_to_v3() || Self::migrate_v2_to_v3() {
+				T::BlockWeights::get().max_block
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
 		fn on_initialize(now: T::BlockNumber) -> Weight {
 			let limit = T::MaximumWeight::get();
 			let mut queued = Agenda::<T>::take(now).into_iter()
@@ -340,76 +600,83 @@ decl_module! {
 				);
 			}
 			queued.sort_by_key(|(_, s)| s.priority);
-			let base_weight: Weight = T::DbWeight::get().reads_writes(1, 2); // Agenda + Agenda(next)
+			let next = now + One::one();
+			// Agenda + Agenda(next)
+			let mut cumulative_weight: Weight = T::DbWeight::get().reads_writes(1, 2);
 			let mut total_weight: Weight = 0;
-			queued.into_iter()
-				.enumerate()
-				.scan(base_weight, |cumulative_weight, (order, (index, s))| {
-					*cumulative_weight = cumulative_weight
-						.saturating_add(s.call.get_dispatch_info().weight);
-
-					let origin = <<T as Config>::Origin as From<T::PalletsOrigin>>::from(
-						s.origin.clone()
-					).into();
-
-					if ensure_signed(origin).is_ok() {
-						 // AccountData for inner call origin accountdata.
-						*cumulative_weight = cumulative_weight
-							.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-					}
-
-					if s.maybe_id.is_some() {
-						// Remove/Modify Lookup
-						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().writes(1));
-					}
-					if s.maybe_periodic.is_some() {
-						// Read/Write Agenda for future block
-						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-					}
+			// Once a task is deferred, all tasks of lower priority are deferred as well unless they
+			// are past their hard deadline.
+			let mut full = false;
+			for (order, (index, mut s)) in queued.into_iter().enumerate() {
+				// We allow a scheduled call if any is true:
+				// - It's priority is `HARD_DEADLINE`
+				// - It does not push the weight past the limit.
+				// - It is the first item in the schedule
+				let must_run = s.priority <= schedule::HARD_DEADLINE || order == 0;
+				let overhead = Self::task_overhead(&s);
+				if !must_run && (full || cumulative_weight.saturating_add(overhead) > limit) {
+					full = true;
+					Agenda::<T>::append(next, Some(s));
+					continue;
+				}
 
-					Some((order, index, *cumulative_weight, s))
-				})
-				.filter_map(|(order, index, cumulative_weight, mut s)| {
-					// We allow a scheduled call if any is true:
-					// - It's priority is `HARD_DEADLINE`
-					// - It does not push the weight past the limit.
-					// - It is the first item in the schedule
-					if s.priority <= schedule::HARD_DEADLINE || cumulative_weight <= limit || order == 0 {
-						let r = s.call.clone().dispatch(s.origin.clone().into());
-						let maybe_id = s.maybe_id.clone();
-						if let &Some((period, count)) = &s.maybe_periodic {
-							if count > 1 {
-								s.maybe_periodic = Some((period, count - 1));
-							} else {
-								s.maybe_periodic = None;
-							}
-							let next = now + period;
-							// If scheduled is named, place it's information in `Lookup`
-							if let Some(ref id) = s.maybe_id {
-								let next_index = Agenda::<T>::decode_len(now + period).unwrap_or(0);
-								Lookup::<T>::insert(id, (next, next_index as u32));
-							}
-							Agenda::<T>::append(next, Some(s));
-						} else {
-							if let Some(ref id) = s.maybe_id {
-								Lookup::<T>::remove(id);
-							}
-						}
-						Self::deposit_event(RawEvent::Dispatched(
+				// The call is only looked up for tasks which may be dispatched in this block and
+				// a task keeps requesting its preimage until it is done.
+				let call = match Self::lookup_call(&s.call) {
+					Ok(call) => call,
+					Err(error) => {
+						cumulative_weight = cumulative_weight.saturating_add(overhead);
+						total_weight = cumulative_weight;
+						Self::deposit_event(RawEvent::CallLookupFailed(
 							(now, index),
-							maybe_id,
-							r.map(|_| ()).map_err(|e| e.error)
+							s.maybe_id.clone(),
+							error,
 						));
-						total_weight = cumulative_weight;
-						None
+						Self::postpone_or_drop(now, s, error);
+						continue;
+					}
+				};
+				let weight = overhead.saturating_add(call.get_dispatch_info().weight);
+				if !must_run && cumulative_weight.saturating_add(weight) > limit {
+					full = true;
+					Agenda::<T>::append(next, Some(s));
+					continue;
+				}
+				cumulative_weight = cumulative_weight.saturating_add(weight);
+				total_weight = cumulative_weight;
+
+				let r = call.dispatch(s.origin.clone().into());
+				let maybe_id = s.maybe_id.clone();
+				if r.is_err() {
+					Self::schedule_retry(now, &s);
+				}
+				if let &Some((period, count)) = &s.maybe_periodic {
+					if count > 1 {
+						s.maybe_periodic = Some((period, count - 1));
 					} else {
-						Some(Some(s))
+						s.maybe_periodic = None;
+					}
+					let next = now + period;
+					// If scheduled is named, place it's information in `Lookup`
+					if let Some(ref id) = s.maybe_id {
+						let next_index = Agenda::<T>::decode_len(now + period).unwrap_or(0);
+						Lookup::<T>::insert(id, (next, next_index as u32));
+					}
+					Agenda::<T>::append(next, Some(s));
+				} else {
+					if let Some(ref id) = s.maybe_id {
+						Lookup::<T>::remove(id);
 					}
-				})
-				.for_each(|unused| {
-					let next = now + One::one();
-					Agenda::<T>::append(next, unused);
-				});
+					if let Some(hash) = s.call.as_hash() {
+						Self::unrequest_preimage(hash);
+					}
+				}
+				Self::deposit_event(RawEvent::Dispatched(
+					(now, index),
+					maybe_id,
+					r.map(|_| ()).map_err(|e| e.error)
+				));
+			}
 
 			total_weight
 		}
@@ -417,23 +684,53 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
-	/// Migrate storage format from V1 to V2.
+	/// Migrate storage format from V1 to V3.
 	/// Return true if migration is performed.
-	pub fn migrate_v1_to_t2() -> bool {
+	pub fn migrate_v1_to_v3() -> bool {
 		if StorageVersion::get() == Releases::V1 {
-			StorageVersion::put(Releases::V2);
+			StorageVersion::put(Releases::V3);
 
 			Agenda::<T>::translate::<
 				Vec<Option<ScheduledV1<<T as Config>::Call, T::BlockNumber>>>, _
 			>(|_, agenda| Some(
 				agenda
 					.into_iter()
-					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
+					.map(|schedule| schedule.map(|schedule| ScheduledV3 {
 						maybe_id: schedule.maybe_id,
 						priority: schedule.priority,
-						call: schedule.call,
+						call: schedule.call.into(),
 						maybe_periodic: schedule.maybe_periodic,
 						origin: system::RawOrigin::Root.into(),
+						maybe_retry: None,
+						_phantom: Default::default(),
+					}))
+					.collect::<Vec<_>>()
+			));
+
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Migrate storage format from V2 to V3.
+	/// Return true if migration is performed.
+	pub fn migrate_v2_to_v3() -> bool {
+		if StorageVersion::get() == Releases::V2 {
+			StorageVersion::put(Releases::V3);
+
+			Agenda::<T>::translate::<
+				Vec<Option<ScheduledV2<<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>, _
+			>(|_, agenda| Some(
+				agenda
+					.into_iter()
+					.map(|schedule| schedule.map(|schedule| ScheduledV3 {
+						maybe_id: schedule.maybe_id,
+						priority: schedule.priority,
+						call: schedule.call.into(),
+						maybe_periodic: schedule.maybe_periodic,
+						origin: schedule.origin,
+						maybe_retry: None,
 						_phantom: Default::default(),
 					}))
 					.collect::<Vec<_>>()
@@ -448,7 +745,7 @@ impl<T: Config> Module<T> {
 	/// Helper to migrate scheduler when the pallet origin type has changed.
 	pub fn migrate_origin<OldOrigin: Into<T::PalletsOrigin> + codec::Decode>() {
 		Agenda::<T>::translate::<
-			Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, OldOrigin, T::AccountId>>>, _
+			Vec<Option<Scheduled<CallOrHashOf<T>, T::BlockNumber, OldOrigin, T::AccountId>>>, _
 		>(|_, agenda| Some(
 			agenda
 				.into_iter()
@@ -458,12 +755,22 @@ impl<T: Config> Module<T> {
 					call: schedule.call,
 					maybe_periodic: schedule.maybe_periodic,
 					origin: schedule.origin.into(),
+					maybe_retry: schedule.maybe_retry,
 					_phantom: Default::default(),
 				}))
 				.collect::<Vec<_>>()
 		));
 	}
 
+	/// The weight of requesting the preimage of `call` when scheduling it.
+	fn request_weight(call: &CallOrHashOf<T>) -> Weight {
+		if call.as_hash().is_some() {
+			T::DbWeight::get().reads_writes(1, 1)
+		} else {
+			0
+		}
+	}
+
 	fn resolve_time(when: DispatchTime<T::BlockNumber>) -> Result<T::BlockNumber, DispatchError> {
 		let now = frame_system::Module::<T>::block_number();
 
@@ -481,12 +788,101 @@ impl<T: Config> Module<T> {
 		Ok(when)
 	}
 
+	/// The weight of the storage accesses of a due task, apart from looking up its call.
+	fn task_overhead(s: &ScheduledOf<T>) -> Weight {
+		let mut weight: Weight = 0;
+		let origin = <<T as Config>::Origin as From<T::PalletsOrigin>>::from(s.origin.clone()).into();
+		if ensure_signed(origin).is_ok() {
+			// AccountData for inner call origin accountdata.
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+		}
+		if s.maybe_id.is_some() {
+			// Remove/Modify Lookup
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+		}
+		if s.maybe_periodic.is_some() {
+			// Read/Write Agenda for future block
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+		}
+		if s.maybe_retry.is_some() {
+			// Read/Write Agenda for the block of the retry
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+		}
+		if s.call.as_hash().is_some() {
+			// Read PreimageFor, read/write StatusFor and maybe remove PreimageFor.
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+		}
+		weight
+	}
+
+	/// Look up the call of a task without changing the request of its preimage.
+	fn lookup_call(call: &CallOrHashOf<T>) -> Result<<T as Config>::Call, LookupError> {
+		match call {
+			MaybeHashed::Value(call) => Ok(call.clone()),
+			MaybeHashed::Hash(hash) => {
+				let preimage = PreimageFor::<T>::get(hash).ok_or(LookupError::Unknown)?;
+				<T as Config>::Call::decode(&mut &preimage[..]).map_err(|_| LookupError::BadFormat)
+			},
+		}
+	}
+
+	/// Postpone a due task whose call could not be looked up or drop it.
+	fn postpone_or_drop(now: T::BlockNumber, s: ScheduledOf<T>, error: LookupError) {
+		let maybe_delay = match error {
+			LookupError::Unknown => T::NoPreimagePostponement::get(),
+			LookupError::BadFormat => None,
+		};
+		match maybe_delay {
+			Some(delay) => {
+				let until = now.saturating_add(delay.max(One::one()));
+				if let Some(ref id) = s.maybe_id {
+					let index = Agenda::<T>::decode_len(until).unwrap_or(0);
+					Lookup::<T>::insert(id, (until, index as u32));
+				}
+				Agenda::<T>::append(until, Some(s));
+			},
+			None => {
+				if let Some(ref id) = s.maybe_id {
+					Lookup::<T>::remove(id);
+				}
+				if let Some(hash) = s.call.as_hash() {
+					Self::unrequest_preimage(hash);
+				}
+			},
+		}
+	}
+
+	/// Schedule an anonymous retry of a task whose dispatch failed if it has retries left.
+	fn schedule_retry(now: T::BlockNumber, s: &ScheduledOf<T>) {
+		let retry = match s.maybe_retry {
+			Some(retry) if retry.remaining > 0 => retry,
+			_ => return,
+		};
+		let when = now.saturating_add(retry.period);
+		// The retry requests the preimage on its own, the original task may be done with it.
+		if let Some(hash) = s.call.as_hash() {
+			Self::request_preimage(hash);
+		}
+		let task = Scheduled {
+			maybe_id: None,
+			priority: s.priority,
+			call: s.call.clone(),
+			maybe_periodic: None,
+			origin: s.origin.clone(),
+			maybe_retry: Some(RetryConfig { remaining: retry.remaining - 1, period: retry.period }),
+			_phantom: Default::default(),
+		};
+		Agenda::<T>::append(when, Some(task));
+		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
+		Self::deposit_event(RawEvent::Scheduled(when, index));
+	}
+
 	fn do_schedule(
 		when: DispatchTime<T::BlockNumber>,
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call
+		call: CallOrHashOf<T>
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		let when = Self::resolve_time(when)?;
 
@@ -495,8 +891,12 @@ impl<T: Config> Module<T> {
 			.filter(|p| p.1 > 1 && !p.0.is_zero())
 			// Remove one from the number of repetitions since we will schedule one now.
 			.map(|(p, c)| (p, c - 1));
+		if let Some(hash) = call.as_hash() {
+			Self::request_preimage(hash);
+		}
 		let s = Some(Scheduled {
-			maybe_id: None, priority, call, maybe_periodic, origin, _phantom: PhantomData::<T::AccountId>::default(),
+			maybe_id: None, priority, call, maybe_periodic, origin, maybe_retry: None,
+			_phantom: PhantomData::<T::AccountId>::default(),
 		});
 		Agenda::<T>::append(when, s);
 		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
@@ -520,7 +920,7 @@ impl<T: Config> Module<T> {
 			when,
 			|agenda| {
 				agenda.get_mut(index as usize)
-					.map_or(Ok(None), |s| -> Result<Option<Scheduled<_, _, _, _>>, DispatchError> {
+					.map_or(Ok(None), |s| -> Result<Option<ScheduledOf<T>>, DispatchError> {
 						if let (Some(ref o), Some(ref s)) = (origin, s.borrow()) {
 							if *o != s.origin {
 								return Err(BadOrigin.into());
@@ -534,6 +934,9 @@ impl<T: Config> Module<T> {
 			if let Some(id) = s.maybe_id {
 				Lookup::<T>::remove(id);
 			}
+			if let Some(hash) = s.call.as_hash() {
+				Self::unrequest_preimage(hash);
+			}
 			Self::deposit_event(RawEvent::Canceled(when, index));
 			Ok(())
 		} else {
@@ -571,7 +974,7 @@ impl<T: Config> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call,
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		// ensure id it is unique
 		if Lookup::<T>::contains_key(&id) {
@@ -586,8 +989,12 @@ impl<T: Config> Module<T> {
 			// Remove one from the number of repetitions since we will schedule one now.
 			.map(|(p, c)| (p, c - 1));
 
+		if let Some(hash) = call.as_hash() {
+			Self::request_preimage(hash);
+		}
 		let s = Scheduled {
-			maybe_id: Some(id.clone()), priority, call, maybe_periodic, origin, _phantom: Default::default()
+			maybe_id: Some(id.clone()), priority, call, maybe_periodic, origin, maybe_retry: None,
+			_phantom: Default::default()
 		};
 		Agenda::<T>::append(when, Some(s));
 		let index = Agenda::<T>::decode_len(when).unwrap_or(1) as u32 - 1;
@@ -616,6 +1023,9 @@ impl<T: Config> Module<T> {
 								return Err(BadOrigin.into());
 							}
 						}
+						if let Some(hash) = s.as_ref().and_then(|s| s.call.as_hash()) {
+							Self::unrequest_preimage(hash);
+						}
 						*s = None;
 					}
 					Ok(())
@@ -658,6 +1068,115 @@ impl<T: Config> Module<T> {
 			Ok((new_time, new_index))
 		})
 	}
+
+	fn do_set_retry(
+		origin: Option<T::PalletsOrigin>,
+		(when, index): TaskAddress<T::BlockNumber>,
+		retries: u8,
+		period: T::BlockNumber,
+	) -> DispatchResult {
+		ensure!(retries == 0 || !period.is_zero(), Error::<T>::InvalidRetryPeriod);
+		let maybe_id = Agenda::<T>::try_mutate(when, |agenda| -> Result<_, DispatchError> {
+			let task = agenda.get_mut(index as usize)
+				.and_then(|s| s.as_mut())
+				.ok_or(Error::<T>::NotFound)?;
+			if let Some(ref o) = origin {
+				if *o != task.origin {
+					return Err(BadOrigin.into());
+				}
+			}
+			task.maybe_retry = if retries > 0 {
+				Some(RetryConfig { remaining: retries, period })
+			} else {
+				None
+			};
+			Ok(task.maybe_id.clone())
+		})?;
+		Self::deposit_event(RawEvent::RetrySet((when, index), maybe_id, retries, period));
+		Ok(())
+	}
+
+	fn do_note_preimage(who: T::AccountId, preimage: Vec<u8>) -> DispatchResult {
+		ensure!(
+			preimage.len() as u32 <= T::MaxPreimageSize::get(),
+			Error::<T>::PreimageTooLarge,
+		);
+		let hash = T::Hashing::hash(&preimage[..]);
+		ensure!(!PreimageFor::<T>::contains_key(&hash), Error::<T>::PreimageAlreadyNoted);
+
+		// Requested preimages are noted for free.
+		let status = match StatusFor::<T>::get(&hash) {
+			Some(status @ PreimageStatus::Requested { .. }) => status,
+			_ => {
+				let deposit = T::PreimageBaseDeposit::get()
+					.saturating_add(T::PreimageByteDeposit::get().saturating_mul((preimage.len() as u32).into()));
+				T::Currency::reserve(&who, deposit)?;
+				PreimageStatus::Unrequested { provider: who, deposit }
+			},
+		};
+		StatusFor::<T>::insert(&hash, status);
+		PreimageFor::<T>::insert(&hash, preimage);
+		Self::deposit_event(RawEvent::PreimageNoted(hash));
+		Ok(())
+	}
+
+	fn do_unnote_preimage(who: T::AccountId, hash: T::Hash) -> DispatchResult {
+		StatusFor::<T>::try_mutate_exists(&hash, |maybe_status| -> DispatchResult {
+			match maybe_status.take() {
+				Some(PreimageStatus::Unrequested { provider, deposit }) => {
+					ensure!(provider == who, Error::<T>::NotPreimageProvider);
+					T::Currency::unreserve(&provider, deposit);
+					PreimageFor::<T>::remove(&hash);
+					Self::deposit_event(RawEvent::PreimageCleared(hash));
+					Ok(())
+				},
+				Some(PreimageStatus::Requested { count, deposit: Some((provider, deposit)) }) => {
+					ensure!(provider == who, Error::<T>::NotPreimageProvider);
+					T::Currency::unreserve(&provider, deposit);
+					*maybe_status = Some(PreimageStatus::Requested { count, deposit: None });
+					Ok(())
+				},
+				Some(PreimageStatus::Requested { .. }) => Err(Error::<T>::PreimageRequested.into()),
+				None => Err(Error::<T>::PreimageNotNoted.into()),
+			}
+		})
+	}
+
+	/// Mark the preimage of `hash` as requested by one more task.
+	fn request_preimage(hash: &T::Hash) {
+		StatusFor::<T>::mutate(hash, |maybe_status| {
+			let status = match maybe_status.take() {
+				Some(PreimageStatus::Unrequested { provider, deposit }) =>
+					PreimageStatus::Requested { count: 1, deposit: Some((provider, deposit)) },
+				Some(PreimageStatus::Requested { count, deposit }) =>
+					PreimageStatus::Requested { count: count.saturating_add(1), deposit },
+				None => PreimageStatus::Requested { count: 1, deposit: None },
+			};
+			*maybe_status = Some(status);
+		});
+		Self::deposit_event(RawEvent::PreimageRequested(*hash));
+	}
+
+	/// Mark the preimage of `hash` as requested by one task less.
+	///
+	/// A preimage which is no longer requested is removed unless a deposit is held for it.
+	fn unrequest_preimage(hash: &T::Hash) {
+		StatusFor::<T>::mutate_exists(hash, |maybe_status| {
+			match maybe_status.take() {
+				Some(PreimageStatus::Requested { count, deposit }) if count > 1 => {
+					*maybe_status = Some(PreimageStatus::Requested { count: count - 1, deposit });
+				},
+				Some(PreimageStatus::Requested { deposit: Some((provider, deposit)), .. }) => {
+					*maybe_status = Some(PreimageStatus::Unrequested { provider, deposit });
+				},
+				Some(PreimageStatus::Requested { deposit: None, .. }) => {
+					PreimageFor::<T>::remove(hash);
+					Self::deposit_event(RawEvent::PreimageCleared(*hash));
+				},
+				other => *maybe_status = other,
+			}
+		});
+	}
 }
 
 impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin> for Module<T> {
@@ -670,7 +1189,7 @@ impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOr
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call
 	) -> Result<Self::Address, DispatchError> {
-		Self::do_schedule(when, maybe_periodic, priority, origin, call)
+		Self::do_schedule(when, maybe_periodic, priority, origin, call.into())
 	}
 
 	fn cancel((when, index): Self::Address) -> Result<(), ()> {
@@ -700,7 +1219,7 @@ impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsO
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call,
 	) -> Result<Self::Address, ()> {
-		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call).map_err(|_| ())
+		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call.into()).map_err(|_| ())
 	}
 
 	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
@@ -726,7 +1245,7 @@ mod tests {
 	use frame_support::{
 		parameter_types, assert_ok, ord_parameter_types,
 		assert_noop, assert_err, Hashable,
-		traits::{OnInitialize, OnFinalize, Filter},
+		traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, Filter},
 		weights::constants::RocksDbWeight,
 	};
 	use sp_core::H256;
@@ -794,6 +1313,7 @@ mod tests {
 			UncheckedExtrinsic = UncheckedExtrinsic,
 		{
 			System: frame_system::{Module, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 			Logger: logger::{Module, Call, Event},
 			Scheduler: scheduler::{Module, Call, Storage, Event<T>},
 		}
@@ -830,18 +1350,35 @@ mod tests {
 		type BlockHashCount = BlockHashCount;
 		type Version = ();
 		type PalletInfo = PalletInfo;
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 		type SystemWeightInfo = ();
 		type SS58Prefix = ();
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+	impl pallet_balances::Config for Test {
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type WeightInfo = ();
+	}
 	impl logger::Config for Test {
 		type Event = Event;
 	}
 	parameter_types! {
 		pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
 		pub const MaxScheduledPerBlock: u32 = 10;
+		pub const PreimageBaseDeposit: u64 = 2;
+		pub const PreimageByteDeposit: u64 = 1;
+		pub const MaxPreimageSize: u32 = 1024;
+		pub const NoPreimagePostponement: Option<u64> = Some(10);
 	}
 	ord_parameter_types! {
 		pub const One: u64 = 1;
@@ -855,11 +1392,19 @@ mod tests {
 		type MaximumWeight = MaximumSchedulerWeight;
 		type ScheduleOrigin = EnsureOneOf<u64, EnsureRoot<u64>, EnsureSignedBy<One, u64>>;
 		type MaxScheduledPerBlock = MaxScheduledPerBlock;
+		type Currency = Balances;
+		type PreimageBaseDeposit = PreimageBaseDeposit;
+		type PreimageByteDeposit = PreimageByteDeposit;
+		type MaxPreimageSize = MaxPreimageSize;
+		type NoPreimagePostponement = NoPreimagePostponement;
 		type WeightInfo = ();
 	}
 
 	pub fn new_test_ext() -> sp_io::TestExternalities {
-		let t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: vec![(1, 100), (2, 100)],
+		}.assimilate_storage(&mut t).unwrap();
 		t.into()
 	}
 
@@ -880,7 +1425,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
@@ -897,7 +1442,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			// This will schedule the call 3 blocks after the next block... so block 3 + 3 = 6
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call.into()));
 			run_to_block(5);
 			assert!(logger::log().is_empty());
 			run_to_block(6);
@@ -913,7 +1458,7 @@ mod tests {
 			run_to_block(2);
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call.into()));
 			// Will trigger on the next block.
 			run_to_block(3);
 			assert_eq!(logger::log(), vec![(root(), 42u32)]);
@@ -927,7 +1472,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
 			assert_ok!(Scheduler::do_schedule(
-				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -951,7 +1496,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call).unwrap(), (4, 0));
+			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()).unwrap(), (4, 0));
 
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -977,7 +1522,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			assert_eq!(Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), call
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), call.into()
 			).unwrap(), (4, 0));
 
 			run_to_block(3);
@@ -1004,7 +1549,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			assert_eq!(Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), Some((3, 3)), 127, root(), call
+				1u32.encode(), DispatchTime::At(4), Some((3, 3)), 127, root(), call.into()
 			).unwrap(), (4, 0));
 
 			run_to_block(3);
@@ -1040,10 +1585,10 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4.
 			Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			let i = Scheduler::do_schedule(
-				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1064,7 +1609,7 @@ mod tests {
 				Some((3, 3)),
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, 1000))
+				Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			// same id results in error.
 			assert!(Scheduler::do_schedule_named(
@@ -1073,11 +1618,11 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, 1000))
+				Call::Logger(logger::Call::log(69, 1000)).into()
 			).is_err());
 			// different id is ok.
 			Scheduler::do_schedule_named(
-				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1098,13 +1643,13 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// 69 and 42 do not fit together
 			run_to_block(4);
@@ -1122,14 +1667,14 @@ mod tests {
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// With base weights, 69 and 42 should not fit together, but do because of hard deadlines
 			run_to_block(4);
@@ -1145,14 +1690,14 @@ mod tests {
 				None,
 				1,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32)]);
@@ -1166,19 +1711,19 @@ mod tests {
 				DispatchTime::At(4),
 				None,
 				255,
-				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				126,
-				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into()
 			));
 
 			// 2600 does not fit with 69 or 42, but has higher priority, so will go through
@@ -1202,7 +1747,7 @@ mod tests {
 			assert_ok!(
 				Scheduler::do_schedule_named(
 					1u32.encode(), DispatchTime::At(1), None, 255, root(),
-					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3))
+					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3)).into()
 				)
 			);
 			// Anon Periodic
@@ -1211,7 +1756,7 @@ mod tests {
 				Some((1000, 3)),
 				128,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			// Anon
 			assert_ok!(Scheduler::do_schedule(
@@ -1219,12 +1764,12 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// Named Periodic
 			assert_ok!(Scheduler::do_schedule_named(
 				2u32.encode(), DispatchTime::At(1), Some((1000, 3)), 126, root(),
-				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)))
+				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into())
 			);
 
 			// Will include the named periodic only
@@ -1256,8 +1801,8 @@ mod tests {
 	#[test]
 	fn root_calls_works() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(69, 1000))));
+			let call2 = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(42, 1000))));
 			assert_ok!(Scheduler::schedule_named(Origin::root(), 1u32.encode(), 4, None, 127, call));
 			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, call2));
 			run_to_block(3);
@@ -1277,8 +1822,8 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			run_to_block(3);
 
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(69, 1000))));
+			let call2 = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(42, 1000))));
 
 			assert_err!(
 				Scheduler::schedule_named(Origin::root(), 1u32.encode(), 2, None, 127, call),
@@ -1300,8 +1845,8 @@ mod tests {
 	#[test]
 	fn should_use_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(69, 1000))));
+			let call2 = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(42, 1000))));
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1321,8 +1866,8 @@ mod tests {
 	#[test]
 	fn should_check_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(69, 1000))));
+			let call2 = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log(42, 1000))));
 			assert_noop!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(2).into(), 1u32.encode(), 4, None, 127, call),
 				BadOrigin
@@ -1334,8 +1879,8 @@ mod tests {
 	#[test]
 	fn should_check_orign_for_cancel() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)));
+			let call = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log_without_filter(69, 1000))));
+			let call2 = Box::new(MaybeHashed::Value(Call::Logger(logger::Call::log_without_filter(42, 1000))));
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1357,7 +1902,234 @@ mod tests {
 	}
 
 	#[test]
-	fn migration_to_v2_works() {
+	fn scheduling_by_hash_works() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, Box::new(MaybeHashed::Hash(hash))));
+			assert_eq!(
+				StatusFor::<Test>::get(hash),
+				Some(PreimageStatus::Requested { count: 1, deposit: None }),
+			);
+
+			// Requested preimages are noted for free.
+			assert_ok!(Scheduler::note_preimage(Origin::signed(2), call.encode()));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_noop!(
+				Scheduler::note_preimage(Origin::signed(2), call.encode()),
+				Error::<Test>::PreimageAlreadyNoted,
+			);
+
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			// The preimage is removed once the task no longer requests it.
+			assert!(StatusFor::<Test>::get(hash).is_none());
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+		});
+	}
+
+	#[test]
+	fn noting_unrequested_preimage_reserves_deposit() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let encoded = call.encode();
+			let hash = <Test as frame_system::Config>::Hashing::hash(&encoded[..]);
+			let deposit = 2 + encoded.len() as u64;
+
+			assert_noop!(
+				Scheduler::note_preimage(Origin::signed(1), vec![0u8; 1025]),
+				Error::<Test>::PreimageTooLarge,
+			);
+			assert_ok!(Scheduler::note_preimage(Origin::signed(1), encoded));
+			assert_eq!(Balances::reserved_balance(1), deposit);
+
+			// The deposit is kept while the preimage is requested but can be returned.
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)));
+			assert_noop!(Scheduler::unnote_preimage(Origin::signed(2), hash), Error::<Test>::NotPreimageProvider);
+			assert_ok!(Scheduler::unnote_preimage(Origin::signed(1), hash));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert!(PreimageFor::<Test>::get(hash).is_some());
+			assert_noop!(Scheduler::unnote_preimage(Origin::signed(1), hash), Error::<Test>::PreimageRequested);
+
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+			assert_noop!(Scheduler::unnote_preimage(Origin::signed(1), hash), Error::<Test>::PreimageNotNoted);
+		});
+	}
+
+	#[test]
+	fn unnoting_unrequested_preimage_returns_deposit() {
+		new_test_ext().execute_with(|| {
+			let encoded = Call::Logger(logger::Call::log(42, 1000)).encode();
+			let hash = <Test as frame_system::Config>::Hashing::hash(&encoded[..]);
+			assert_ok!(Scheduler::note_preimage(Origin::signed(1), encoded));
+			assert_ok!(Scheduler::unnote_preimage(Origin::signed(1), hash));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert!(StatusFor::<Test>::get(hash).is_none());
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+		});
+	}
+
+	#[test]
+	fn missing_preimage_postpones_task() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert!(System::events().iter().any(|r| r.event == Event::scheduler(
+				RawEvent::CallLookupFailed((4, 0), Some(1u32.encode()), LookupError::Unknown)
+			)));
+			// Postponed by `NoPreimagePostponement` blocks.
+			assert_eq!(Lookup::<Test>::get(1u32.encode()), Some((14, 0)));
+
+			assert_ok!(Scheduler::note_preimage(Origin::signed(2), call.encode()));
+			run_to_block(14);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+		});
+	}
+
+	#[test]
+	fn undecodable_preimage_drops_task() {
+		new_test_ext().execute_with(|| {
+			let preimage = vec![255u8; 4];
+			let hash = <Test as frame_system::Config>::Hashing::hash(&preimage[..]);
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)));
+			assert_ok!(Scheduler::note_preimage(Origin::signed(2), preimage));
+
+			run_to_block(4);
+			assert!(System::events().iter().any(|r| r.event == Event::scheduler(
+				RawEvent::CallLookupFailed((4, 0), None, LookupError::BadFormat)
+			)));
+			assert!(StatusFor::<Test>::get(hash).is_none());
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+			run_to_block(100);
+			assert!(Agenda::<Test>::iter().all(|(_, agenda)| agenda.iter().all(|s| s.is_none())));
+		});
+	}
+
+	#[test]
+	fn cancel_hashed_task_unrequests_preimage() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, Box::new(MaybeHashed::Hash(hash))));
+			assert_ok!(Scheduler::schedule_named(
+				Origin::root(), 1u32.encode(), 4, None, 127, Box::new(MaybeHashed::Hash(hash))
+			));
+			assert_ok!(Scheduler::note_preimage(Origin::signed(2), call.encode()));
+			assert_eq!(
+				StatusFor::<Test>::get(hash),
+				Some(PreimageStatus::Requested { count: 2, deposit: None }),
+			);
+
+			assert_ok!(Scheduler::cancel(Origin::root(), 4, 0));
+			assert_eq!(
+				StatusFor::<Test>::get(hash),
+				Some(PreimageStatus::Requested { count: 1, deposit: None }),
+			);
+			assert_ok!(Scheduler::cancel_named(Origin::root(), 1u32.encode()));
+			assert!(StatusFor::<Test>::get(hash).is_none());
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+		});
+	}
+
+	#[test]
+	fn deferred_and_periodic_hashed_tasks_keep_the_hash() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4),
+				None,
+				127,
+				root(),
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
+			));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), Some((3, 2)), 127, root(), MaybeHashed::Hash(hash)));
+			assert_ok!(Scheduler::note_preimage(Origin::signed(2), call.encode()));
+
+			// The hashed task does not fit and is deferred with its hash.
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 69u32)]);
+			assert_eq!(Agenda::<Test>::get(5)[0].as_ref().unwrap().call, MaybeHashed::Hash(hash));
+			assert_eq!(
+				StatusFor::<Test>::get(hash),
+				Some(PreimageStatus::Requested { count: 1, deposit: None }),
+			);
+
+			// The next occurrence of the periodic task still requests the preimage.
+			run_to_block(5);
+			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32)]);
+			assert_eq!(Agenda::<Test>::get(8)[0].as_ref().unwrap().call, MaybeHashed::Hash(hash));
+			assert!(PreimageFor::<Test>::get(hash).is_some());
+
+			// The preimage is removed after the last occurrence.
+			run_to_block(8);
+			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32), (root(), 42u32)]);
+			assert!(StatusFor::<Test>::get(hash).is_none());
+			assert!(PreimageFor::<Test>::get(hash).is_none());
+		});
+	}
+
+	#[test]
+	fn retry_scheduling_works() {
+		new_test_ext().execute_with(|| {
+			// `set_heap_pages` requires root and fails when dispatched as signed.
+			let call = Call::System(frame_system::Call::set_heap_pages(8));
+			let origin: OriginCaller = system::RawOrigin::Signed(1).into();
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, origin.clone(), call.into()));
+			assert_noop!(
+				Scheduler::set_retry(system::RawOrigin::Signed(1).into(), (4, 0), 2, 0),
+				Error::<Test>::InvalidRetryPeriod,
+			);
+			assert_noop!(Scheduler::set_retry(Origin::root(), (4, 0), 2, 3), BadOrigin);
+			assert_noop!(
+				Scheduler::set_retry(system::RawOrigin::Signed(1).into(), (4, 1), 2, 3),
+				Error::<Test>::NotFound,
+			);
+			assert_ok!(Scheduler::set_retry(system::RawOrigin::Signed(1).into(), (4, 0), 2, 3));
+
+			run_to_block(4);
+			let retry = Agenda::<Test>::get(7)[0].clone().unwrap();
+			assert_eq!(retry.maybe_retry, Some(RetryConfig { remaining: 1, period: 3 }));
+			assert_eq!(retry.origin, origin);
+			run_to_block(7);
+			let retry = Agenda::<Test>::get(10)[0].clone().unwrap();
+			assert_eq!(retry.maybe_retry, Some(RetryConfig { remaining: 0, period: 3 }));
+			run_to_block(10);
+			// No retries are left.
+			assert!(Agenda::<Test>::get(13).is_empty());
+		});
+	}
+
+	#[test]
+	fn retry_is_not_scheduled_on_success() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(),
+				Call::Logger(logger::Call::log(42, 1000)).into(),
+			));
+			assert_ok!(Scheduler::set_retry_named(Origin::root(), 1u32.encode(), 2, 3));
+			assert!(System::events().iter().any(|r| r.event == Event::scheduler(
+				RawEvent::RetrySet((4, 0), Some(1u32.encode()), 2, 3)
+			)));
+
+			run_to_block(4);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(Agenda::<Test>::get(7).is_empty());
+		});
+	}
+
+	#[test]
+	fn migration_v1_to_v3_works() {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
@@ -1386,15 +2158,48 @@ mod tests {
 
 			assert_eq!(StorageVersion::get(), Releases::V1);
 
-			assert!(Scheduler::migrate_v1_to_t2());
+			assert!(Scheduler::migrate_v1_to_v3());
 
-			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
-				(
-					0,
-					vec![
+			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), (0..3u64).map(|i| (
+				i,
+				vec![
+					Some(ScheduledV3 {
+						maybe_id: None,
+						priority: i as u8 + 10,
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
+						maybe_periodic: None,
+						origin: root(),
+						maybe_retry: None,
+						_phantom: PhantomData::<u64>::default(),
+					}),
+					None,
+					Some(ScheduledV3 {
+						maybe_id: Some(b"test".to_vec()),
+						priority: 123,
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
+						maybe_periodic: Some((456u64, 10)),
+						origin: root(),
+						maybe_retry: None,
+						_phantom: PhantomData::<u64>::default(),
+					}),
+				],
+			)).collect::<Vec<_>>());
+
+			assert_eq!(StorageVersion::get(), Releases::V3);
+			assert!(!Scheduler::migrate_v2_to_v3());
+		});
+	}
+
+	#[test]
+	fn migration_v2_to_v3_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::put(Releases::V2);
+			for i in 0..3u64 {
+				let k = i.twox_64_concat();
+				let old = vec![
 					Some(ScheduledV2 {
 						maybe_id: None,
-						priority: 10,
+						priority: i as u8 + 10,
 						call: Call::Logger(logger::Call::log(96, 100)),
 						maybe_periodic: None,
 						origin: root(),
@@ -1406,57 +2211,49 @@ mod tests {
 						priority: 123,
 						call: Call::Logger(logger::Call::log(69, 1000)),
 						maybe_periodic: Some((456u64, 10)),
+						origin: system::RawOrigin::None.into(),
+						_phantom: PhantomData::<u64>::default(),
+					}),
+				];
+				frame_support::migration::put_storage_value(
+					b"Scheduler",
+					b"Agenda",
+					&k,
+					old,
+				);
+			}
+
+			assert_eq!(
+				Scheduler::on_runtime_upgrade(),
+				<Test as frame_system::Config>::BlockWeights::get().max_block,
+			);
+
+			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), (0..3u64).map(|i| (
+				i,
+				vec![
+					Some(ScheduledV3 {
+						maybe_id: None,
+						priority: i as u8 + 10,
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
+						maybe_periodic: None,
 						origin: root(),
+						maybe_retry: None,
 						_phantom: PhantomData::<u64>::default(),
 					}),
-				]),
-				(
-					1,
-					vec![
-						Some(ScheduledV2 {
-							maybe_id: None,
-							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
-							maybe_periodic: None,
-							origin: root(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-						None,
-						Some(ScheduledV2 {
-							maybe_id: Some(b"test".to_vec()),
-							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
-							maybe_periodic: Some((456u64, 10)),
-							origin: root(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-					]
-				),
-				(
-					2,
-					vec![
-						Some(ScheduledV2 {
-							maybe_id: None,
-							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
-							maybe_periodic: None,
-							origin: root(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-						None,
-						Some(ScheduledV2 {
-							maybe_id: Some(b"test".to_vec()),
-							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
-							maybe_periodic: Some((456u64, 10)),
-							origin: root(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-					]
-				)
-			]);
+					None,
+					Some(ScheduledV3 {
+						maybe_id: Some(b"test".to_vec()),
+						priority: 123,
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
+						maybe_periodic: Some((456u64, 10)),
+						origin: system::RawOrigin::None.into(),
+						maybe_retry: None,
+						_phantom: PhantomData::<u64>::default(),
+					}),
+				],
+			)).collect::<Vec<_>>());
 
-			assert_eq!(StorageVersion::get(), Releases::V2);
+			assert_eq!(StorageVersion::get(), Releases::V3);
 		});
 	}
 
@@ -1465,13 +2262,14 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
-				let old: Vec<Option<Scheduled<_, _, u32, u64>>> = vec![
+				let old: Vec<Option<Scheduled<CallOrHashOf<Test>, _, u32, u64>>> = vec![
 					Some(Scheduled {
 						maybe_id: None,
 						priority: i as u8 + 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						origin: 3u32,
 						maybe_periodic: None,
+						maybe_retry: None,
 						_phantom: Default::default(),
 					}),
 					None,
@@ -1479,8 +2277,9 @@ mod tests {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
 						origin: 2u32,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
+						maybe_retry: Some(RetryConfig { remaining: 2, period: 5 }),
 						_phantom: Default::default(),
 					}),
 				];
@@ -1504,73 +2303,30 @@ mod tests {
 
 			Scheduler::migrate_origin::<u32>();
 
-			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
-				(
-					0,
-					vec![
-					Some(ScheduledV2::<_, _, OriginCaller, u64> {
+			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), (0..3u64).map(|i| (
+				i,
+				vec![
+					Some(ScheduledV3::<_, _, OriginCaller, u64> {
 						maybe_id: None,
-						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						priority: i as u8 + 10,
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: system::RawOrigin::Root.into(),
+						maybe_retry: None,
 						_phantom: PhantomData::<u64>::default(),
 					}),
 					None,
-					Some(ScheduledV2 {
+					Some(ScheduledV3 {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: system::RawOrigin::None.into(),
+						maybe_retry: Some(RetryConfig { remaining: 2, period: 5 }),
 						_phantom: PhantomData::<u64>::default(),
 					}),
-				]),
-				(
-					1,
-					vec![
-						Some(ScheduledV2 {
-							maybe_id: None,
-							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
-							maybe_periodic: None,
-							origin: system::RawOrigin::Root.into(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-						None,
-						Some(ScheduledV2 {
-							maybe_id: Some(b"test".to_vec()),
-							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
-							maybe_periodic: Some((456u64, 10)),
-							origin: system::RawOrigin::None.into(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-					]
-				),
-				(
-					2,
-					vec![
-						Some(ScheduledV2 {
-							maybe_id: None,
-							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
-							maybe_periodic: None,
-							origin: system::RawOrigin::Root.into(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-						None,
-						Some(ScheduledV2 {
-							maybe_id: Some(b"test".to_vec()),
-							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
-							maybe_periodic: Some((456u64, 10)),
-							origin: system::RawOrigin::None.into(),
-							_phantom: PhantomData::<u64>::default(),
-						}),
-					]
-				)
-			]);
+				],
+			)).collect::<Vec<_>>());
 		});
 	}
 }
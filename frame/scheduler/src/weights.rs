@@ -47,6 +47,10 @@ pub trait WeightInfo {
 	fn cancel(s: u32, ) -> Weight;
 	fn schedule_named(s: u32, ) -> Weight;
 	fn cancel_named(s: u32, ) -> Weight;
+	fn set_retry(s: u32, ) -> Weight;
+	fn set_retry_named(s: u32, ) -> Weight;
+	fn note_preimage(s: u32, ) -> Weight;
+	fn unnote_preimage() -> Weight;
 	
 }
 
@@ -81,6 +85,29 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			
 	}
+	// The following weights are not benchmarked yet and are to be regenerated from the benchmarks
+	// of this pallet. Setting a retry accesses the agenda like scheduling a task does.
+	fn set_retry(s: u32, ) -> Weight {
+		Self::schedule(s)
+	}
+	fn set_retry_named(s: u32, ) -> Weight {
+		Self::schedule_named(s)
+	}
+	// Noting and removing a preimage do the same work as `note_preimage` and `reap_preimage` of
+	// `pallet_democracy`, the execution time is taken from there.
+	fn note_preimage(s: u32, ) -> Weight {
+		(60_073_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			
+	}
+	fn unnote_preimage() -> Weight {
+		(54_861_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			
+	}
 	
 }
 
@@ -114,5 +141,28 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 			
 	}
+	// The following weights are not benchmarked yet and are to be regenerated from the benchmarks
+	// of this pallet. Setting a retry accesses the agenda like scheduling a task does.
+	fn set_retry(s: u32, ) -> Weight {
+		Self::schedule(s)
+	}
+	fn set_retry_named(s: u32, ) -> Weight {
+		Self::schedule_named(s)
+	}
+	// Noting and removing a preimage do the same work as `note_preimage` and `reap_preimage` of
+	// `pallet_democracy`, the execution time is taken from there.
+	fn note_preimage(s: u32, ) -> Weight {
+		(60_073_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			
+	}
+	fn unnote_preimage() -> Weight {
+		(54_861_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			
+	}
 	
 }
@@ -174,6 +174,8 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 	);
 	let validate_unsigned = decl_validate_unsigned(&name, modules.iter(), &scrate);
 	let integrity_test = decl_integrity_test(&scrate);
+	let storage_info = decl_all_storage_info(&name, modules.iter(), &scrate);
+	let try_state = decl_all_try_state(&name, &system_module, &scrate);
 
 	let res = quote!(
 		#scrate_decl
@@ -212,6 +214,10 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 		#validate_unsigned
 
 		#integrity_test
+
+		#storage_info
+
+		#try_state
 	);
 
 	Ok(res)
@@ -519,6 +525,51 @@ fn decl_pallet_runtime_setup(
 	)
 }
 
+fn decl_all_storage_info<'a>(
+	runtime: &'a Ident,
+	module_declarations: impl Iterator<Item = &'a Module>,
+	scrate: &'a TokenStream2,
+) -> TokenStream2 {
+	let names = module_declarations
+		.filter(|module_declaration| module_declaration.exists_part("Storage"))
+		.map(|module_declaration| &module_declaration.name);
+
+	quote!(
+		impl #scrate::traits::StorageInfoTrait for #runtime {
+			fn storage_info() -> #scrate::sp_std::vec::Vec<#scrate::traits::StorageInfo> {
+				let mut res = #scrate::sp_std::vec::Vec::new();
+				#(
+					res.extend(<#names as #scrate::traits::StorageInfoTrait>::storage_info());
+				)*
+				res
+			}
+		}
+	)
+}
+
+fn decl_all_try_state(
+	runtime: &Ident,
+	system_module: &Module,
+	scrate: &TokenStream2,
+) -> TokenStream2 {
+	let system_path = &system_module.module;
+
+	quote!(
+		#[cfg(feature = "try-runtime")]
+		impl #scrate::traits::TryState<<#runtime as #system_path::Config>::BlockNumber> for #runtime {
+			fn try_state(
+				n: <#runtime as #system_path::Config>::BlockNumber,
+			) -> Result<(), &'static str> {
+				<
+					AllModulesWithSystem
+					as
+					#scrate::traits::TryState<<#runtime as #system_path::Config>::BlockNumber>
+				>::try_state(n)
+			}
+		}
+	)
+}
+
 fn decl_integrity_test(scrate: &TokenStream2) -> TokenStream2 {
 	quote!(
 		#[cfg(test)]
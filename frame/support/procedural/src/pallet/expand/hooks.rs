@@ -108,7 +108,7 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 
 			#[cfg(feature = "try-runtime")]
-			fn pre_upgrade() -> Result<(), &'static str> {
+			fn pre_upgrade() -> Result<#frame_support::sp_std::vec::Vec<u8>, &'static str> {
 				<
 					Self
 					as
@@ -117,12 +117,70 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 
 			#[cfg(feature = "try-runtime")]
-			fn post_upgrade() -> Result<(), &'static str> {
+			fn post_upgrade(
+				state: #frame_support::sp_std::vec::Vec<u8>,
+			) -> Result<(), &'static str> {
 				<
 					Self
 					as
 					#frame_support::traits::Hooks<<T as #frame_system::Config>::BlockNumber>
-				>::post_upgrade()
+				>::post_upgrade(state)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		impl<#type_impl_gen>
+			#frame_support::traits::TryState<<T as #frame_system::Config>::BlockNumber>
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn try_state(
+				n: <T as #frame_system::Config>::BlockNumber,
+			) -> Result<(), &'static str> {
+				<
+					Self as #frame_support::traits::Hooks<
+						<T as #frame_system::Config>::BlockNumber
+					>
+				>::try_state(n)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		impl<#type_impl_gen>
+			#frame_support::traits::TryRuntimeChecks<<T as #frame_system::Config>::BlockNumber>
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn check_on_runtime_upgrade(
+			) -> #frame_support::sp_std::vec::Vec<#frame_support::traits::PalletCheckOutcome> {
+				let pallet_name = <
+					<T as #frame_system::Config>::PalletInfo
+					as
+					#frame_support::traits::PalletInfo
+				>::name::<Self>().unwrap_or("<unknown pallet name>");
+				let mut outcomes = #frame_support::sp_std::vec::Vec::new();
+				outcomes.push(
+					#frame_support::traits::PalletCheckOutcome::on_runtime_upgrade::<Self>(
+						pallet_name,
+					),
+				);
+				outcomes
+			}
+
+			fn check_try_state(
+				n: <T as #frame_system::Config>::BlockNumber,
+			) -> #frame_support::sp_std::vec::Vec<#frame_support::traits::PalletCheckOutcome> {
+				let pallet_name = <
+					<T as #frame_system::Config>::PalletInfo
+					as
+					#frame_support::traits::PalletInfo
+				>::name::<Self>().unwrap_or("<unknown pallet name>");
+				let mut outcomes = #frame_support::sp_std::vec::Vec::new();
+				outcomes.push(
+					#frame_support::traits::PalletCheckOutcome::try_state::<_, Self>(
+						pallet_name,
+						n,
+					),
+				);
+				outcomes
 			}
 		}
 
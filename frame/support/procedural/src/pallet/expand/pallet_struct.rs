@@ -21,6 +21,7 @@ use crate::pallet::{Def, parse::helper::get_doc_literals};
 /// * Implement GetPalletVersion on Pallet
 /// * Implement OnGenesis on Pallet
 /// * Implement ModuleErrorMetadata on Pallet
+/// * Implement StorageInfoTrait on Pallet
 /// * declare Module type alias for construct_runtime
 /// * replace the first field type of `struct Pallet` with `PhantomData` if it is `_`
 pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
@@ -96,6 +97,52 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 		)
 	};
 
+	// Depending on the flag `generate_storage_info` we use partial or full storage info from
+	// storage.
+	let (storage_info_span, storage_info_trait, storage_info_method) =
+		if let Some(span) = def.pallet_struct.generate_storage_info {
+			(
+				span,
+				quote::quote_spanned!(span => StorageInfoTrait),
+				quote::quote_spanned!(span => storage_info),
+			)
+		} else {
+			let span = def.pallet_struct.attr_span;
+			(
+				span,
+				quote::quote_spanned!(span => PartialStorageInfoTrait),
+				quote::quote_spanned!(span => partial_storage_info),
+			)
+		};
+
+	let storage_names = &def.storages.iter().map(|storage| &storage.ident).collect::<Vec<_>>();
+
+	let mut storages_where_clauses = vec![&def.config.where_clause];
+	storages_where_clauses.extend(def.storages.iter().map(|storage| &storage.where_clause));
+	let storages_where_clauses = super::merge_where_clauses(&storages_where_clauses);
+
+	let storage_info = quote::quote_spanned!(storage_info_span =>
+		impl<#type_impl_gen> #frame_support::traits::StorageInfoTrait
+			for #pallet_ident<#type_use_gen>
+			#storages_where_clauses
+		{
+			fn storage_info()
+				-> #frame_support::sp_std::vec::Vec<#frame_support::traits::StorageInfo>
+			{
+				let mut res = #frame_support::sp_std::vec::Vec::new();
+
+				#(
+					res.extend(<
+						#storage_names<#type_use_gen>
+							as #frame_support::traits::#storage_info_trait
+					>::#storage_info_method());
+				)*
+
+				res
+			}
+		}
+	);
+
 	quote::quote_spanned!(def.pallet_struct.attr_span =>
 		#module_error_metadata
 
@@ -132,5 +179,7 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 					.put_into_storage::<<T as #frame_system::Config>::PalletInfo, Self>();
 			}
 		}
+
+		#storage_info
 	)
 }
@@ -25,6 +25,7 @@ mod metadata;
 mod instance_trait;
 mod genesis_config;
 mod print_pallet_upgrade;
+mod storage_info;
 
 pub(crate) use instance_trait::INHERENT_INSTANCE_NAME;
 
@@ -414,6 +415,7 @@ pub fn decl_storage_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 	let instance_trait = instance_trait::decl_and_impl(&scrate, &def_ext);
 	let genesis_config = genesis_config::genesis_config_and_build_storage(&scrate, &def_ext);
 	let storage_struct = storage_struct::decl_and_impl(&scrate, &def_ext);
+	let storage_info = storage_info::impl_storage_info(&scrate, &def_ext);
 
 	quote!(
 		use #scrate::{
@@ -432,5 +434,6 @@ pub fn decl_storage_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 		#instance_trait
 		#genesis_config
 		#storage_struct
+		#storage_info
 	).into()
 }
@@ -207,9 +207,49 @@ pub fn decl_and_impl(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			}
 		};
 
+		let storage_info_impl = {
+			let (prefix, max_values) = match &line.storage_type {
+				StorageLineTypeDef::Simple(_) => (
+					quote!(
+						<
+							#storage_struct as #scrate::#storage_generator_trait
+						>::storage_value_final_key()
+					),
+					quote!(Some(1)),
+				),
+				StorageLineTypeDef::Map(_) | StorageLineTypeDef::DoubleMap(_) => (
+					quote!(
+						<
+							#storage_struct as #scrate::storage::StoragePrefixedMap<#value_type>
+						>::final_prefix()
+					),
+					quote!(None),
+				),
+			};
+
+			quote!(
+				impl<#impl_trait> #scrate::traits::PartialStorageInfoTrait for #storage_struct
+				#optional_storage_where_clause
+				{
+					fn partial_storage_info()
+						-> #scrate::sp_std::vec::Vec<#scrate::traits::StorageInfo>
+					{
+						#scrate::sp_std::vec![
+							#scrate::traits::StorageInfo {
+								prefix: #prefix,
+								max_values: #max_values,
+								max_size: None,
+							}
+						]
+					}
+				}
+			)
+		};
+
 		impls.extend(quote!(
 			#struct_decl
 			#struct_impl
+			#storage_info_impl
 		))
 	}
 
@@ -1355,15 +1355,6 @@ macro_rules! decl_module {
 				result.saturating_add(additional_write)
 			}
 
-			#[cfg(feature = "try-runtime")]
-			fn pre_upgrade() -> Result<(), &'static str> {
-				Ok(())
-			}
-
-			#[cfg(feature = "try-runtime")]
-			fn post_upgrade() -> Result<(), &'static str> {
-				Ok(())
-			}
 		}
 	};
 
@@ -1398,15 +1389,6 @@ macro_rules! decl_module {
 				<<$trait_instance as $system::Config>::DbWeight as $crate::traits::Get<_>>::get().writes(1)
 			}
 
-			#[cfg(feature = "try-runtime")]
-			fn pre_upgrade() -> Result<(), &'static str> {
-				Ok(())
-			}
-
-			#[cfg(feature = "try-runtime")]
-			fn post_upgrade() -> Result<(), &'static str> {
-				Ok(())
-			}
 		}
 	};
 
@@ -1439,6 +1421,54 @@ macro_rules! decl_module {
 		{}
 	};
 
+	(@impl_try_state
+		{ $system:ident }
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		{ $( $other_where_bounds:tt )* }
+	) => {
+		#[cfg(feature = "try-runtime")]
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::traits::TryState<<$trait_instance as $system::Config>::BlockNumber>
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{
+			fn try_state(
+				_: <$trait_instance as $system::Config>::BlockNumber,
+			) -> Result<(), &'static str> {
+				Ok(())
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::traits::TryRuntimeChecks<<$trait_instance as $system::Config>::BlockNumber>
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{
+			fn check_on_runtime_upgrade() -> $crate::sp_std::vec::Vec<$crate::traits::PalletCheckOutcome> {
+				let pallet_name = <<
+					$trait_instance
+					as
+					$system::Config
+				>::PalletInfo as $crate::traits::PalletInfo>::name::<Self>().unwrap_or("<unknown pallet name>");
+				let mut outcomes = $crate::sp_std::vec::Vec::new();
+				outcomes.push($crate::traits::PalletCheckOutcome::on_runtime_upgrade::<Self>(pallet_name));
+				outcomes
+			}
+
+			fn check_try_state(
+				n: <$trait_instance as $system::Config>::BlockNumber,
+			) -> $crate::sp_std::vec::Vec<$crate::traits::PalletCheckOutcome> {
+				let pallet_name = <<
+					$trait_instance
+					as
+					$system::Config
+				>::PalletInfo as $crate::traits::PalletInfo>::name::<Self>().unwrap_or("<unknown pallet name>");
+				let mut outcomes = $crate::sp_std::vec::Vec::new();
+				outcomes.push($crate::traits::PalletCheckOutcome::try_state::<_, Self>(pallet_name, n));
+				outcomes
+			}
+		}
+	};
+
 	(@impl_on_finalize
 		{ $system:ident }
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
@@ -1763,6 +1793,13 @@ macro_rules! decl_module {
 			$( $integrity_test )*
 		}
 
+		$crate::decl_module! {
+			@impl_try_state
+			{ $system }
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			{ $( $other_where_bounds )* }
+		}
+
 		/// Can also be called using [`Call`].
 		///
 		/// [`Call`]: enum.Call.html
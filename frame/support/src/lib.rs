@@ -46,6 +46,8 @@ pub use sp_io::{storage::root as storage_root, self};
 pub use sp_runtime::RuntimeDebug;
 #[doc(hidden)]
 pub use log;
+#[doc(hidden)]
+pub use max_encoded_len;
 
 #[macro_use]
 mod origin;
@@ -74,8 +76,10 @@ pub use self::hash::{
 	StorageHasher, ReversibleStorageHasher
 };
 pub use self::storage::{
-	StorageValue, StorageMap, StorageDoubleMap, StoragePrefixedMap, IterableStorageMap,
-	IterableStorageDoubleMap, migration
+	StorageValue, StorageMap, StorageDoubleMap, StorageNMap, StoragePrefixedMap,
+	IterableStorageMap, IterableStorageDoubleMap, IterableStorageNMap, migration,
+	bounded_vec::BoundedVec, bounded_btree_map::BoundedBTreeMap,
+	bounded_btree_set::BoundedBTreeSet,
 };
 pub use self::dispatch::{Parameter, Callable};
 pub use sp_runtime::{self, ConsensusEngineId, print, traits::Printable};
@@ -1076,11 +1080,14 @@ pub mod pallet_prelude {
 	pub use frame_support::{
 		EqNoBound, PartialEqNoBound, RuntimeDebugNoBound, DebugNoBound, CloneNoBound, Twox256,
 		Twox128, Blake2_256, Blake2_128, Identity, Twox64Concat, Blake2_128Concat, ensure,
-		RuntimeDebug, storage,
-		traits::{Get, Hooks, IsType, GetPalletVersion, EnsureOrigin},
+		RuntimeDebug, storage, BoundedVec, BoundedBTreeMap, BoundedBTreeSet,
+		traits::{Get, Hooks, IsType, GetPalletVersion, EnsureOrigin, MaxEncodedLen},
 		dispatch::{DispatchResultWithPostInfo, Parameter, DispatchError, DispatchResult},
 		weights::{DispatchClass, Pays, Weight},
-		storage::types::{StorageValue, StorageMap, StorageDoubleMap, ValueQuery, OptionQuery},
+		storage::types::{
+			StorageValue, StorageMap, CountedStorageMap, StorageDoubleMap, StorageNMap,
+			Key as NMapKey, ValueQuery, OptionQuery,
+		},
 	};
 	pub use codec::{Encode, Decode};
 	pub use sp_inherents::{InherentData, InherentIdentifier, ProvideInherent};
@@ -1185,6 +1192,17 @@ pub mod pallet_prelude {
 /// Thus when defining a storage named `Foo`, it can later be accessed from `Pallet` using
 /// `<Pallet as Store>::Foo`.
 ///
+/// To generate the full storage info (used for PoV calculation) use the attribute
+/// `#[pallet::generate_storage_info]`, e.g.:
+/// ```ignore
+/// #[pallet::pallet]
+/// #[pallet::generate_storage_info]
+/// pub struct Pallet<T>(_);
+/// ```
+///
+/// This require all storage to implement the trait [`traits::StorageInfoTrait`], thus all keys
+/// and value types must bound [`traits::MaxEncodedLen`].
+///
 /// ### Macro expansion:
 ///
 /// The macro add this attribute to the struct definition:
@@ -1202,6 +1220,9 @@ pub mod pallet_prelude {
 /// * [`traits::GetPalletVersion`]
 /// * [`traits::OnGenesis`]: contains some logic to write pallet version into storage.
 /// * `ModuleErrorMetadata`: using error declared or no metadata.
+/// * [`traits::StorageInfoTrait`]: using the full storage info of each storage if the attribute
+///   `generate_storage_info` is used, or the partial storage info otherwise (see
+///   [`traits::PartialStorageInfoTrait`]).
 ///
 /// It declare `type Module` type alias for `Pallet`, used by [`construct_runtime`].
 ///
@@ -1400,7 +1421,8 @@ pub mod pallet_prelude {
 /// 	= $StorageType<_, $some_generics, ...>;
 /// ```
 /// I.e. it must be a type alias, with generics: `T` or `T: Config`, aliased type must be one
-/// of `StorageValue`, `StorageMap` or `StorageDoubleMap` (defined in frame_support).
+/// of `StorageValue`, `StorageMap`, `CountedStorageMap`, `StorageDoubleMap` or `StorageNMap`
+/// (defined in frame_support).
 /// Their first generic must be `_` as it is written by the macro itself.
 ///
 /// The Prefix generic written by the macro is generated using `PalletInfo::name::<Pallet<..>>()`
@@ -1700,7 +1722,8 @@ pub mod pallet_prelude {
 ///
 /// 	// Declare a storage item. Any amount of storage items can be declared (optional).
 /// 	//
-/// 	// Is expected either `StorageValue`, `StorageMap` or `StorageDoubleMap`.
+/// 	// Is expected either `StorageValue`, `StorageMap`, `CountedStorageMap`, `StorageDoubleMap`
+/// 	// or `StorageNMap`.
 /// 	// The macro generates the prefix type and replaces the first generic `_`.
 /// 	//
 /// 	// The macro expands the metadata for the storage item with the type used:
@@ -1708,6 +1731,10 @@ pub mod pallet_prelude {
 /// 	// * for a storage map the type of the values and the type of the key is copied into the metadata
 /// 	// * for a storage double map the types of the values and keys are copied into the
 /// 	//   metadata.
+/// 	// * for a storage n map the type of the values and the types of all keys are copied into
+/// 	//   the metadata.
+/// 	// * for a counted storage map the metadata is the one of a storage map, plus an additional
+/// 	//   `u32` storage value entry named `CounterFor$StorageName` for the counter.
 /// 	//
 /// 	// NOTE: The generic `Hasher` must implement the `StorageHasher` trait (or the type is not
 /// 	// usable at all). We use [`StorageHasher::METADATA`] for the metadata of the hasher of the
@@ -0,0 +1,798 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage counted map type. Implements the same methods as `StorageMap` but maintains a counter
+//! of the number of values stored in the map.
+
+use codec::{FullCodec, Decode, EncodeLike, Encode, Ref};
+use crate::{
+	storage::{
+		StorageAppend, StorageTryAppend, StorageDecodeLength, PrefixIteratorOnRemoval,
+		generator::StorageMap as _,
+		types::{
+			OptionQuery, QueryKindTrait, OnEmptyGetter, StorageMap, StorageValue, ValueQuery,
+		},
+	},
+	traits::{
+		GetDefault, StorageInstance, StorageInfo, StorageInfoTrait, PartialStorageInfoTrait,
+		MaxEncodedLen,
+	},
+	Never,
+};
+use frame_metadata::{DefaultByteGetter, StorageEntryModifier};
+use sp_std::prelude::*;
+
+/// A wrapper around a `StorageMap` and a `StorageValue<Value=u32>` to keep track of how many items
+/// are in a map, without needing to iterate all the values.
+///
+/// This storage item has additional storage read and write overhead when manipulating values
+/// compared to a regular storage map.
+///
+/// For functions where we only add or remove a value, a single storage read is needed to check if
+/// that value already exists. For mutate functions, two storage reads are used to check if the
+/// value existed before and after the mutation.
+///
+/// Whenever the counter needs to be updated, an additional read and write occurs to update that
+/// counter.
+pub struct CountedStorageMap<
+	Prefix, Hasher, Key, Value, QueryKind = OptionQuery, OnEmpty = GetDefault, MaxValues = GetDefault,
+>(core::marker::PhantomData<(Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues)>);
+
+/// The requirement for an instance of [`CountedStorageMap`].
+pub trait CountedStorageMapInstance: StorageInstance {
+	/// The prefix to use for the counter storage value.
+	type CounterPrefix: StorageInstance;
+}
+
+// Private helper trait to access map from counted storage map.
+trait MapWrapper {
+	type Map;
+}
+
+impl<P: CountedStorageMapInstance, H, K, V, Q, O, M> MapWrapper
+	for CountedStorageMap<P, H, K, V, Q, O, M>
+{
+	type Map = StorageMap<P, H, K, V, Q, O, M>;
+}
+
+type CounterFor<P> = StorageValue<<P as CountedStorageMapInstance>::CounterPrefix, u32, ValueQuery>;
+
+/// On removal logic for updating counter while draining upon some prefix with
+/// [`crate::storage::PrefixIterator`].
+pub struct OnRemovalCounterUpdate<Prefix>(core::marker::PhantomData<Prefix>);
+
+impl<Prefix: CountedStorageMapInstance> PrefixIteratorOnRemoval
+	for OnRemovalCounterUpdate<Prefix>
+{
+	fn on_removal(_key: &[u8], _value: &[u8]) {
+		CounterFor::<Prefix>::mutate(|value| *value = value.saturating_sub(1));
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+	CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+	MaxValues: crate::traits::Get<Option<u32>>,
+{
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	pub fn hashed_key_for<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Vec<u8> {
+		<Self as MapWrapper>::Map::hashed_key_for(key)
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn contains_key<KeyArg: EncodeLike<Key>>(key: KeyArg) -> bool {
+		<Self as MapWrapper>::Map::contains_key(key)
+	}
+
+	/// Load the value associated with the given key from the map.
+	pub fn get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		<Self as MapWrapper>::Map::get(key)
+	}
+
+	/// Try to get the value for the given key from the map.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Result<Value, ()> {
+		<Self as MapWrapper>::Map::try_get(key)
+	}
+
+	/// Swap the values of two keys.
+	pub fn swap<KeyArg1: EncodeLike<Key>, KeyArg2: EncodeLike<Key>>(key1: KeyArg1, key2: KeyArg2) {
+		<Self as MapWrapper>::Map::swap(key1, key2)
+	}
+
+	/// Store a value to be associated with the given key from the map.
+	pub fn insert<KeyArg: EncodeLike<Key>, ValArg: EncodeLike<Value>>(key: KeyArg, val: ValArg) {
+		if !<Self as MapWrapper>::Map::contains_key(Ref::from(&key)) {
+			CounterFor::<Prefix>::mutate(|value| *value = value.saturating_add(1));
+		}
+		<Self as MapWrapper>::Map::insert(key, val)
+	}
+
+	/// Remove the value under a key.
+	pub fn remove<KeyArg: EncodeLike<Key>>(key: KeyArg) {
+		if <Self as MapWrapper>::Map::contains_key(Ref::from(&key)) {
+			CounterFor::<Prefix>::mutate(|value| *value = value.saturating_sub(1));
+		}
+		<Self as MapWrapper>::Map::remove(key)
+	}
+
+	/// Mutate the value under a key.
+	pub fn mutate<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut QueryKind::Query) -> R>(
+		key: KeyArg,
+		f: F
+	) -> R {
+		Self::try_mutate(key, |v| Ok::<R, Never>(f(v)))
+			.expect("`Never` can not be constructed; qed")
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned.
+	pub fn try_mutate<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		Self::try_mutate_exists(key, |option_value_ref| {
+			let option_value = core::mem::replace(option_value_ref, None);
+			let mut query = <Self as MapWrapper>::Map::from_optional_value_to_query(option_value);
+			let res = f(&mut query);
+			let option_value = <Self as MapWrapper>::Map::from_query_to_optional_value(query);
+			let _ = core::mem::replace(option_value_ref, option_value);
+			res
+		})
+	}
+
+	/// Mutate the value under a key. Deletes the item if mutated to a `None`.
+	pub fn mutate_exists<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut Option<Value>) -> R>(
+		key: KeyArg,
+		f: F
+	) -> R {
+		Self::try_mutate_exists(key, |v| Ok::<R, Never>(f(v)))
+			.expect("`Never` can not be constructed; qed")
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned. Deletes the item if mutated to a `None`.
+	pub fn try_mutate_exists<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		<Self as MapWrapper>::Map::try_mutate_exists(key, |option_value| {
+			let existed = option_value.is_some();
+			let res = f(option_value);
+			let exist = option_value.is_some();
+
+			if res.is_ok() {
+				if existed && !exist {
+					// Value was deleted
+					CounterFor::<Prefix>::mutate(|value| *value = value.saturating_sub(1));
+				} else if !existed && exist {
+					// Value was added
+					CounterFor::<Prefix>::mutate(|value| *value = value.saturating_add(1));
+				}
+			}
+			res
+		})
+	}
+
+	/// Take the value under a key.
+	pub fn take<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		let removed_value = <Self as MapWrapper>::Map::mutate_exists(key, |value| value.take());
+		if removed_value.is_some() {
+			CounterFor::<Prefix>::mutate(|value| *value = value.saturating_sub(1));
+		}
+		<Self as MapWrapper>::Map::from_optional_value_to_query(removed_value)
+	}
+
+	/// Append the given items to the value in the storage.
+	///
+	/// `Value` is required to implement `codec::EncodeAppend`.
+	///
+	/// # Warning
+	///
+	/// If the storage item is not encoded properly, the storage will be overwritten
+	/// and set to `[item]`. Any default value set for the storage item will be ignored
+	/// on overwrite.
+	pub fn append<Item, EncodeLikeItem, EncodeLikeKey>(key: EncodeLikeKey, item: EncodeLikeItem)
+	where
+		EncodeLikeKey: EncodeLike<Key>,
+		Item: Encode,
+		EncodeLikeItem: EncodeLike<Item>,
+		Value: StorageAppend<Item>
+	{
+		if !<Self as MapWrapper>::Map::contains_key(Ref::from(&key)) {
+			CounterFor::<Prefix>::mutate(|value| *value = value.saturating_add(1));
+		}
+		<Self as MapWrapper>::Map::append(key, item)
+	}
+
+	/// Read the length of the storage value without decoding the entire value under the
+	/// given `key`.
+	///
+	/// `Value` is required to implement [`StorageDecodeLength`].
+	///
+	/// If the value does not exists or it fails to decode the length, `None` is returned.
+	/// Otherwise `Some(len)` is returned.
+	///
+	/// # Warning
+	///
+	/// `None` does not mean that `get()` does not return a value. The default value is completly
+	/// ignored by this function.
+	pub fn decode_len<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Option<usize>
+		where Value: StorageDecodeLength,
+	{
+		<Self as MapWrapper>::Map::decode_len(key)
+	}
+
+	/// Try and append the given item to the value in the storage.
+	///
+	/// Is only available if `Value` of the storage implements [`StorageTryAppend`].
+	pub fn try_append<KArg, Item, EncodeLikeItem>(key: KArg, item: EncodeLikeItem) -> Result<(), ()>
+	where
+		KArg: EncodeLike<Key> + Clone,
+		Item: Encode,
+		EncodeLikeItem: EncodeLike<Item>,
+		Value: StorageTryAppend<Item>,
+	{
+		let existed = <Self as MapWrapper>::Map::contains_key(Ref::from(&key));
+		<Self as MapWrapper>::Map::try_append(key, item).map(|()| {
+			if !existed {
+				CounterFor::<Prefix>::mutate(|value| *value = value.saturating_add(1));
+			}
+		})
+	}
+
+	/// Migrate an item with the given `key` from a defunct `OldHasher` to the current hasher.
+	///
+	/// If the key doesn't exist, then it's a no-op. If it does, then it returns its value.
+	pub fn migrate_key<OldHasher: crate::hash::StorageHasher, KeyArg: EncodeLike<Key>>(
+		key: KeyArg
+	) -> Option<Value> {
+		<Self as MapWrapper>::Map::migrate_key::<OldHasher, _>(key)
+	}
+
+	/// Remove all value of the storage, and reset the counter.
+	pub fn remove_all() {
+		CounterFor::<Prefix>::kill();
+		<Self as MapWrapper>::Map::remove_all()
+	}
+
+	/// Iter over all value of the storage.
+	///
+	/// NOTE: If a value failed to decode becaues storage is corrupted then it is skipped.
+	pub fn iter_values() -> crate::storage::PrefixIterator<Value, OnRemovalCounterUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter_values().convert_on_removal()
+	}
+
+	/// Translate the values of all elements by a function `f`, in the map in no particular order.
+	///
+	/// By returning `None` from `f` for an element, you'll remove it from the map.
+	///
+	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
+	///
+	/// # Warning
+	///
+	/// This function must be used with care, before being updated the storage still contains the
+	/// old type, thus other calls (such as `get`) will fail at decoding it.
+	///
+	/// # Usage
+	///
+	/// This would typically be called inside the module implementation of on_runtime_upgrade.
+	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(mut f: F) {
+		<Self as MapWrapper>::Map::translate_values(|old_value| {
+			let res = f(old_value);
+			if res.is_none() {
+				CounterFor::<Prefix>::mutate(|value| *value = value.saturating_sub(1));
+			}
+			res
+		})
+	}
+
+	/// Initialize the counter with the actual number of items in the map.
+	///
+	/// This function iterates through all the items in the map and sets the counter. This operation
+	/// can be very heavy, so use with caution.
+	///
+	/// Returns the number of items in the map which is used to set the counter.
+	pub fn initialize_counter() -> u32 {
+		let count = Self::iter_values().count() as u32;
+		CounterFor::<Prefix>::put(count);
+		count
+	}
+
+	/// Return the count.
+	pub fn count() -> u32 {
+		CounterFor::<Prefix>::get()
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+	CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+	MaxValues: crate::traits::Get<Option<u32>>,
+{
+	/// Enumerate all elements in the map in no particular order.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter() -> crate::storage::PrefixIterator<(Key, Value), OnRemovalCounterUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::iter().convert_on_removal()
+	}
+
+	/// Remove all elements from the map and iterate through them in no particular order.
+	///
+	/// If you add elements to the map while doing this, you'll get undefined results.
+	pub fn drain() -> crate::storage::PrefixIterator<(Key, Value), OnRemovalCounterUpdate<Prefix>> {
+		<Self as MapWrapper>::Map::drain().convert_on_removal()
+	}
+
+	/// Translate the values of all elements by a function `f`, in the map in no particular order.
+	///
+	/// By returning `None` from `f` for an element, you'll remove it from the map.
+	///
+	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
+	pub fn translate<O: Decode, F: FnMut(Key, O) -> Option<Value>>(mut f: F) {
+		<Self as MapWrapper>::Map::translate(|key, old_value| {
+			let res = f(key, old_value);
+			if res.is_none() {
+				CounterFor::<Prefix>::mutate(|value| *value = value.saturating_sub(1));
+			}
+			res
+		})
+	}
+}
+
+/// Part of storage metadata for a counted storage map.
+///
+/// The counted storage map is exposed as two entries: the map itself and its counter.
+pub trait CountedStorageMapMetadata {
+	const MODIFIER: StorageEntryModifier;
+	const NAME: &'static str;
+	const DEFAULT: DefaultByteGetter;
+	const HASHER: frame_metadata::StorageHasher;
+	const COUNTER_NAME: &'static str;
+	const COUNTER_MODIFIER: StorageEntryModifier;
+	const COUNTER_DEFAULT: DefaultByteGetter;
+	const COUNTER_DOC: &'static str;
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> CountedStorageMapMetadata
+	for CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+	MaxValues: crate::traits::Get<Option<u32>>,
+{
+	const MODIFIER: StorageEntryModifier = QueryKind::METADATA;
+	const HASHER: frame_metadata::StorageHasher = Hasher::METADATA;
+	const NAME: &'static str = Prefix::STORAGE_PREFIX;
+	const DEFAULT: DefaultByteGetter =
+		DefaultByteGetter(&OnEmptyGetter::<QueryKind::Query, OnEmpty>(core::marker::PhantomData));
+	const COUNTER_NAME: &'static str = <Prefix::CounterPrefix as StorageInstance>::STORAGE_PREFIX;
+	const COUNTER_MODIFIER: StorageEntryModifier = StorageEntryModifier::Default;
+	const COUNTER_DEFAULT: DefaultByteGetter =
+		DefaultByteGetter(&OnEmptyGetter::<u32, GetDefault>(core::marker::PhantomData));
+	const COUNTER_DOC: &'static str = "Counter for the related counted storage map";
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> StorageInfoTrait
+	for CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + MaxEncodedLen,
+	Value: FullCodec + MaxEncodedLen,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+	MaxValues: crate::traits::Get<Option<u32>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as MapWrapper>::Map::storage_info(),
+			CounterFor::<Prefix>::storage_info(),
+		].concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> PartialStorageInfoTrait
+	for CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+	MaxValues: crate::traits::Get<Option<u32>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as MapWrapper>::Map::partial_storage_info(),
+			CounterFor::<Prefix>::partial_storage_info(),
+		].concat()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use sp_io::{TestExternalities, hashing::twox_128};
+	use crate::hash::*;
+	use crate::storage::bounded_vec::BoundedVec;
+	use frame_metadata::StorageEntryModifier;
+	use sp_std::convert::TryInto;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str { "test" }
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+	impl CountedStorageMapInstance for Prefix {
+		type CounterPrefix = CounterPrefix;
+	}
+
+	struct CounterPrefix;
+	impl StorageInstance for CounterPrefix {
+		fn pallet_prefix() -> &'static str { "test" }
+		const STORAGE_PREFIX: &'static str = "counter_for_foo";
+	}
+
+	struct ADefault;
+	impl crate::traits::Get<u32> for ADefault {
+		fn get() -> u32 {
+			97
+		}
+	}
+
+	crate::parameter_types! {
+		pub const Two: u32 = 2;
+	}
+
+	#[test]
+	fn test_value_query() {
+		type A = CountedStorageMap<Prefix, Twox64Concat, u16, u32, ValueQuery, ADefault>;
+
+		TestExternalities::default().execute_with(|| {
+			let mut k: Vec<u8> = vec![];
+			k.extend(&twox_128(b"test"));
+			k.extend(&twox_128(b"foo"));
+			k.extend(&3u16.twox_64_concat());
+			assert_eq!(A::hashed_key_for(3).to_vec(), k);
+
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::get(3), ADefault::get());
+			assert_eq!(A::try_get(3), Err(()));
+			assert_eq!(A::count(), 0);
+
+			// Insert non-existing.
+			A::insert(3, 10);
+
+			assert_eq!(A::contains_key(3), true);
+			assert_eq!(A::get(3), 10);
+			assert_eq!(A::try_get(3), Ok(10));
+			assert_eq!(A::count(), 1);
+
+			// Swap non-existing with existing.
+			A::swap(4, 3);
+
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::get(3), ADefault::get());
+			assert_eq!(A::contains_key(4), true);
+			assert_eq!(A::get(4), 10);
+			assert_eq!(A::count(), 1);
+
+			// Swap existing with non-existing.
+			A::swap(4, 3);
+
+			assert_eq!(A::contains_key(3), true);
+			assert_eq!(A::get(3), 10);
+			assert_eq!(A::contains_key(4), false);
+			assert_eq!(A::count(), 1);
+
+			// Insert existing.
+			A::insert(3, 11);
+
+			assert_eq!(A::get(3), 11);
+			assert_eq!(A::count(), 1);
+
+			// Insert non-existing.
+			A::insert(2, 11);
+
+			assert_eq!(A::contains_key(2), true);
+			assert_eq!(A::get(2), 11);
+			assert_eq!(A::count(), 2);
+
+			// Remove existing.
+			A::remove(2);
+
+			assert_eq!(A::contains_key(2), false);
+			assert_eq!(A::count(), 1);
+
+			// Remove non-existing.
+			A::remove(2);
+
+			assert_eq!(A::contains_key(2), false);
+			assert_eq!(A::count(), 1);
+
+			// Mutate non-existing to existing.
+			A::mutate(2, |old| {
+				assert_eq!(*old, ADefault::get());
+				*old = 12;
+			});
+
+			assert_eq!(A::contains_key(2), true);
+			assert_eq!(A::get(2), 12);
+			assert_eq!(A::count(), 2);
+
+			// Mutate existing to existing.
+			A::mutate(2, |old| {
+				assert_eq!(*old, 12);
+				*old = 13;
+			});
+
+			assert_eq!(A::get(2), 13);
+			assert_eq!(A::count(), 2);
+
+			// Try fail mutate non-existing to existing.
+			A::try_mutate(1, |old| {
+				assert_eq!(*old, ADefault::get());
+				*old = 14;
+				Result::<(), ()>::Err(())
+			}).err().unwrap();
+
+			assert_eq!(A::contains_key(1), false);
+			assert_eq!(A::count(), 2);
+
+			// Try succeed mutate non-existing to existing.
+			A::try_mutate(1, |old| {
+				assert_eq!(*old, ADefault::get());
+				*old = 14;
+				Result::<(), ()>::Ok(())
+			}).unwrap();
+
+			assert_eq!(A::get(1), 14);
+			assert_eq!(A::count(), 3);
+
+			// Mutate exists non-existing to existing.
+			A::mutate_exists(4, |query| {
+				assert_eq!(*query, None);
+				*query = Some(16);
+			});
+
+			assert_eq!(A::get(4), 16);
+			assert_eq!(A::count(), 4);
+
+			// Mutate exists existing to non-existing.
+			A::mutate_exists(4, |query| {
+				assert_eq!(*query, Some(16));
+				*query = None;
+			});
+
+			assert_eq!(A::contains_key(4), false);
+			assert_eq!(A::count(), 3);
+
+			// Try fail mutate exists existing to non-existing.
+			A::try_mutate_exists(3, |query| {
+				assert_eq!(*query, Some(11));
+				*query = None;
+				Result::<(), ()>::Err(())
+			}).err().unwrap();
+
+			assert_eq!(A::get(3), 11);
+			assert_eq!(A::count(), 3);
+
+			// Try succeed mutate exists existing to non-existing.
+			A::try_mutate_exists(3, |query| {
+				assert_eq!(*query, Some(11));
+				*query = None;
+				Result::<(), ()>::Ok(())
+			}).unwrap();
+
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::count(), 2);
+
+			// Take existing.
+			assert_eq!(A::take(1), 14);
+
+			assert_eq!(A::contains_key(1), false);
+			assert_eq!(A::count(), 1);
+
+			// Take non-existing.
+			assert_eq!(A::take(1), ADefault::get());
+
+			assert_eq!(A::count(), 1);
+
+			// Remove all.
+			A::remove_all();
+
+			assert_eq!(A::count(), 0);
+			assert_eq!(A::initialize_counter(), 0);
+
+			A::insert(1, 1);
+			A::insert(2, 2);
+
+			// Iter values.
+			let mut values = A::iter_values().collect::<Vec<_>>();
+			values.sort();
+			assert_eq!(values, vec![1, 2]);
+
+			// Iter.
+			let mut items = A::iter().collect::<Vec<_>>();
+			items.sort();
+			assert_eq!(items, vec![(1, 1), (2, 2)]);
+
+			// Translate values.
+			A::translate_values::<u32, _>(|old| if old == 1 { None } else { Some(old * 2) });
+
+			assert_eq!(A::iter_values().collect::<Vec<_>>(), vec![4]);
+			assert_eq!(A::count(), 1);
+
+			// Translate.
+			A::insert(3, 3);
+			A::translate::<u32, _>(|key, old| if key == 3 { None } else { Some(old * 2) });
+
+			assert_eq!(A::iter().collect::<Vec<_>>(), vec![(2, 8)]);
+			assert_eq!(A::count(), 1);
+
+			// Drain.
+			A::insert(3, 3);
+			assert_eq!(A::count(), 2);
+			let mut drained = A::drain().collect::<Vec<_>>();
+			drained.sort();
+			assert_eq!(drained, vec![(2, 8), (3, 3)]);
+			assert_eq!(A::count(), 0);
+
+			// Initialize counter after the counter got out of sync.
+			A::insert(1, 1);
+			A::insert(2, 2);
+			CounterFor::<Prefix>::kill();
+			assert_eq!(A::count(), 0);
+			assert_eq!(A::initialize_counter(), 2);
+			assert_eq!(A::count(), 2);
+		})
+	}
+
+	#[test]
+	fn test_option_query() {
+		type B = CountedStorageMap<Prefix, Twox64Concat, u16, u32>;
+
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(B::get(3), None);
+			assert_eq!(B::count(), 0);
+
+			B::insert(3, 10);
+			assert_eq!(B::get(3), Some(10));
+			assert_eq!(B::count(), 1);
+
+			// Mutate existing to non-existing.
+			B::mutate(3, |old| {
+				assert_eq!(*old, Some(10));
+				*old = None;
+			});
+
+			assert_eq!(B::contains_key(3), false);
+			assert_eq!(B::count(), 0);
+
+			// Mutate non-existing to non-existing.
+			B::mutate(3, |old| {
+				assert_eq!(*old, None);
+			});
+
+			assert_eq!(B::count(), 0);
+
+			// Try succeed mutate non-existing to existing.
+			B::try_mutate(3, |old| {
+				*old = Some(12);
+				Result::<(), ()>::Ok(())
+			}).unwrap();
+
+			assert_eq!(B::get(3), Some(12));
+			assert_eq!(B::count(), 1);
+
+			assert_eq!(B::take(3), Some(12));
+			assert_eq!(B::take(3), None);
+			assert_eq!(B::count(), 0);
+		})
+	}
+
+	#[test]
+	fn append_decode_len_works() {
+		type B = CountedStorageMap<Prefix, Twox64Concat, u16, Vec<u32>>;
+
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(B::decode_len(0), None);
+			B::append(0, 3);
+			assert_eq!(B::decode_len(0), Some(1));
+			assert_eq!(B::count(), 1);
+			B::append(0, 4);
+			assert_eq!(B::decode_len(0), Some(2));
+			assert_eq!(B::count(), 1);
+		})
+	}
+
+	#[test]
+	fn try_append_works() {
+		type B = CountedStorageMap<Prefix, Twox64Concat, u16, BoundedVec<u32, Two>>;
+
+		TestExternalities::default().execute_with(|| {
+			let bounded: BoundedVec<u32, Two> = vec![1].try_into().unwrap();
+			B::insert(1, bounded);
+			assert_eq!(B::count(), 1);
+
+			assert!(B::try_append(0, 3).is_ok());
+			assert_eq!(B::count(), 2);
+			assert!(B::try_append(0, 4).is_ok());
+			assert!(B::try_append(0, 5).is_err());
+			assert_eq!(B::decode_len(0), Some(2));
+			assert_eq!(B::count(), 2);
+		})
+	}
+
+	#[test]
+	fn metadata_works() {
+		type A = CountedStorageMap<Prefix, Twox64Concat, u16, u32, ValueQuery, ADefault>;
+		type B = CountedStorageMap<Prefix, Twox64Concat, u16, u32>;
+
+		assert_eq!(A::MODIFIER, StorageEntryModifier::Default);
+		assert_eq!(B::MODIFIER, StorageEntryModifier::Optional);
+		assert_eq!(A::NAME, "foo");
+		assert_eq!(A::HASHER, frame_metadata::StorageHasher::Twox64Concat);
+		assert_eq!(A::DEFAULT.0.default_byte(), 97u32.encode());
+		assert_eq!(B::DEFAULT.0.default_byte(), Option::<u32>::None.encode());
+		assert_eq!(A::COUNTER_NAME, "counter_for_foo");
+		assert_eq!(A::COUNTER_MODIFIER, StorageEntryModifier::Default);
+		assert_eq!(A::COUNTER_DEFAULT.0.default_byte(), 0u32.encode());
+	}
+
+	#[test]
+	fn storage_info_works() {
+		type A = CountedStorageMap<Prefix, Twox64Concat, u16, u32>;
+
+		let prefix: [u8; 32] = [twox_128(b"test"), twox_128(b"foo")].concat().try_into().unwrap();
+		let counter_prefix: [u8; 32] =
+			[twox_128(b"test"), twox_128(b"counter_for_foo")].concat().try_into().unwrap();
+		assert_eq!(
+			A::storage_info(),
+			vec![
+				StorageInfo { prefix, max_values: None, max_size: Some(8 + 2 + 4) },
+				StorageInfo { prefix: counter_prefix, max_values: Some(1), max_size: Some(4) },
+			],
+		);
+		assert_eq!(
+			A::partial_storage_info(),
+			vec![
+				StorageInfo { prefix, max_values: None, max_size: None },
+				StorageInfo { prefix: counter_prefix, max_values: Some(1), max_size: None },
+			],
+		);
+	}
+}
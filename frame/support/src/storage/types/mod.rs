@@ -24,10 +24,22 @@ use frame_metadata::{DefaultByte, StorageEntryModifier};
 mod value;
 mod map;
 mod double_map;
+mod counted_map;
+mod key;
+mod nmap;
 
 pub use value::{StorageValue, StorageValueMetadata};
 pub use map::{StorageMap, StorageMapMetadata};
 pub use double_map::{StorageDoubleMap, StorageDoubleMapMetadata};
+pub use counted_map::{
+	CountedStorageMap, CountedStorageMapInstance, CountedStorageMapMetadata,
+	OnRemovalCounterUpdate,
+};
+pub use key::{
+	EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, Key, KeyGenerator,
+	KeyGeneratorMaxEncodedLen, ReversibleKeyGenerator, TupleToEncodedIter,
+};
+pub use nmap::{StorageNMap, StorageNMapMetadata};
 
 /// Trait implementing how the storage optional value is converted into the queried type.
 ///
@@ -42,6 +42,10 @@ use impl_trait_for_tuples::impl_for_tuples;
 #[doc(hidden)]
 pub use sp_std::{mem::{swap, take}, cell::RefCell, vec::Vec, boxed::Box};
 
+pub use max_encoded_len::MaxEncodedLen;
+
+pub mod tokens;
+
 /// A trait for online node inspection in a session.
 ///
 /// Something that can give information about the current validator set.
@@ -1189,6 +1193,154 @@ pub trait ReservableCurrency<AccountId>: Currency<AccountId> {
 	) -> result::Result<Self::Balance, DispatchError>;
 }
 
+/// An identifier for a reserve. Used for disambiguating different reserves so that
+/// they can be individually increased or removed.
+pub type ReserveIdentifier = [u8; 8];
+
+/// A currency whose reserved balance can be split into named reserves.
+///
+/// A named reserve is the part of the reserved balance of an account that was reserved under a
+/// given identifier. Operations on a named reserve never touch more than what was reserved
+/// under its identifier, so pallets using different identifiers can not move each other's
+/// reserved funds.
+pub trait NamedReservableCurrency<AccountId>: ReservableCurrency<AccountId> {
+	/// The amount of the balance of a given account that is reserved under the identifier `id`.
+	fn reserved_balance_named(id: &ReserveIdentifier, who: &AccountId) -> Self::Balance;
+
+	/// Moves `value` from balance to the reserved balance named `id`.
+	///
+	/// If the free balance is lower than `value` or the account has too many named reserves,
+	/// then no funds will be moved and an `Err` will be returned.
+	fn reserve_named(id: &ReserveIdentifier, who: &AccountId, value: Self::Balance) -> DispatchResult;
+
+	/// Moves up to `value` from the reserved balance named `id` to free balance. This function
+	/// cannot fail.
+	///
+	/// As much funds up to `value` will be moved as possible. If the reserve balance named `id`
+	/// of `who` is less than `value`, then the remaining amount will be returned.
+	fn unreserve_named(id: &ReserveIdentifier, who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+	/// Deducts up to `value` from the reserved balance named `id` of `who`. This function cannot
+	/// fail.
+	///
+	/// As much funds up to `value` will be deducted as possible. If the reserve balance named
+	/// `id` of `who` is less than `value`, then a non-zero second item will be returned.
+	fn slash_reserved_named(
+		id: &ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance
+	) -> (Self::NegativeImbalance, Self::Balance);
+
+	/// Moves up to `value` from the reserved balance named `id` of account `slashed` to balance
+	/// of account `beneficiary`. `beneficiary` must exist for this to succeed. If it does not,
+	/// `Err` will be returned. Funds will be placed in either the `free` balance or the reserved
+	/// balance named `id`, depending on the `status`.
+	///
+	/// As much funds up to `value` will be deducted as possible. If this is less than `value`,
+	/// then `Ok(non_zero)` will be returned.
+	fn repatriate_reserved_named(
+		id: &ReserveIdentifier,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError>;
+
+	/// Ensure the reserved balance named `id` is equal to `value`.
+	///
+	/// This will reserve the difference if the current named reserve is less than `value`, and
+	/// unreserve it if the current named reserve is greater than `value`.
+	fn ensure_reserved_named(
+		id: &ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> DispatchResult {
+		let current = Self::reserved_balance_named(id, who);
+		if current > value {
+			// we always have enough balance to unreserve here
+			Self::unreserve_named(id, who, current - value);
+			Ok(())
+		} else if value > current {
+			// we checked value > current
+			Self::reserve_named(id, who, value - current)
+		} else {
+			// current == value
+			Ok(())
+		}
+	}
+
+	/// Unreserve all the reserved balance named `id`, returning the unreserved amount.
+	///
+	/// Is a no-op if the named reserve does not exist.
+	fn unreserve_all_named(id: &ReserveIdentifier, who: &AccountId) -> Self::Balance {
+		let value = Self::reserved_balance_named(id, who);
+		let remaining = Self::unreserve_named(id, who, value);
+		value.saturating_sub(remaining)
+	}
+
+	/// Moves up to `value` from the part of the reserved balance of `who` which isn't reserved
+	/// under any identifier to the reserved balance named `id`. The free balance is untouched.
+	///
+	/// If less than `value` could be moved, because too little is reserved without identifier or
+	/// the account has too many named reserves, then the remaining amount will be returned. In the
+	/// latter case, the amount which stays reserved without identifier is recorded as a deposit
+	/// for `id`, which `unreserve_named_or_unnamed` can release.
+	fn name_reserved(
+		id: &ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> Self::Balance;
+
+	/// The amount of the deposits for `id` which `name_reserved` couldn't name and recorded.
+	fn unnamed_deposit(id: &ReserveIdentifier, who: &AccountId) -> Self::Balance;
+
+	/// Moves up to `value` from the part of the reserved balance of `who` which isn't reserved
+	/// under any identifier to free balance. This function cannot fail.
+	///
+	/// No named reserve is ever touched. If less than `value` is reserved without identifier,
+	/// then the remaining amount will be returned.
+	fn unreserve_unnamed(who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+	/// Like `unreserve_named`, but whatever isn't found in the reserved balance named `id` is
+	/// unreserved from the deposits which `name_reserved` recorded for `id` because it couldn't
+	/// name them. Anything else reserved without identifier is never touched.
+	///
+	/// This releases deposits which were reserved before they could be named, and couldn't be
+	/// named by `name_deposits`.
+	fn unreserve_named_or_unnamed(
+		id: &ReserveIdentifier,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> Self::Balance;
+
+	/// Names the deposits which were reserved before named reserves existed: the deposits of each
+	/// account are summed, and the part of that sum which isn't in the reserved balance named `id`
+	/// yet is moved there with `name_reserved`. Returns the number of distinct accounts.
+	///
+	/// Only the part of a deposit which isn't named yet is moved, so naming the same deposits more
+	/// than once is harmless. Whatever can't be named because the account has too many named
+	/// reserves stays reserved without identifier and is recorded; pallets calling this should
+	/// release their deposits with `unreserve_named_or_unnamed`.
+	fn name_deposits<D>(id: &ReserveIdentifier, deposits: D) -> u32 where
+		D: IntoIterator<Item = (AccountId, Self::Balance)>,
+		AccountId: Ord,
+	{
+		let mut totals = sp_std::collections::btree_map::BTreeMap::<_, Self::Balance>::new();
+		for (who, deposit) in deposits {
+			let total = totals.entry(who).or_default();
+			*total = total.saturating_add(deposit);
+		}
+		let accounts = totals.len() as u32;
+		for (who, total) in totals {
+			let known = Self::reserved_balance_named(id, &who)
+				.saturating_add(Self::unnamed_deposit(id, &who));
+			let missing = total.saturating_sub(known);
+			Self::name_reserved(id, &who, missing);
+		}
+		accounts
+	}
+}
+
 /// An identifier for a lock. Used for disambiguating different locks so that
 /// they can be individually replaced or removed.
 pub type LockIdentifier = [u8; 8];
@@ -1251,8 +1403,8 @@ pub trait VestingSchedule<AccountId> {
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
-	/// and nothing is updated.
+	/// If the account can not hold any more vesting schedules, an `Err` is returned and nothing
+	/// is updated.
 	///
 	/// Is a no-op if the amount to be vested is zero.
 	///
@@ -1264,10 +1416,18 @@ pub trait VestingSchedule<AccountId> {
 		starting_block: Self::Moment,
 	) -> DispatchResult;
 
-	/// Remove a vesting schedule for a given account.
+	/// Checks if `add_vesting_schedule` would work against `who`, without altering any state.
+	fn can_add_vesting_schedule(
+		who: &AccountId,
+		locked: <Self::Currency as Currency<AccountId>>::Balance,
+		per_block: <Self::Currency as Currency<AccountId>>::Balance,
+		starting_block: Self::Moment,
+	) -> DispatchResult;
+
+	/// Remove the vesting schedule at `schedule_index` of a given account.
 	///
 	/// NOTE: This doesn't alter the free balance of the account.
-	fn remove_vesting_schedule(who: &AccountId);
+	fn remove_vesting_schedule(who: &AccountId, schedule_index: u32) -> DispatchResult;
 }
 
 bitflags! {
@@ -1621,15 +1781,21 @@ pub trait OnRuntimeUpgrade {
 
 	/// Execute some pre-checks prior to a runtime upgrade.
 	///
+	/// The returned blob is opaque to the caller, and is handed back untouched to
+	/// [`Self::post_upgrade`]. This can be used to communicate data from the pre-upgrade to the
+	/// post-upgrade state, e.g. the number of items that are expected to be migrated.
+	///
 	/// This hook is never meant to be executed on-chain but is meant to be used by testing tools.
 	#[cfg(feature = "try-runtime")]
-	fn pre_upgrade() -> Result<(), &'static str> { Ok(()) }
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> { Ok(Vec::new()) }
 
 	/// Execute some post-checks after a runtime upgrade.
 	///
+	/// `state` is the blob previously returned by [`Self::pre_upgrade`].
+	///
 	/// This hook is never meant to be executed on-chain but is meant to be used by testing tools.
 	#[cfg(feature = "try-runtime")]
-	fn post_upgrade() -> Result<(), &'static str> { Ok(()) }
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> { Ok(()) }
 }
 
 #[impl_for_tuples(30)]
@@ -1640,21 +1806,134 @@ impl OnRuntimeUpgrade for Tuple {
 		weight
 	}
 
+	/// Execute the `pre_upgrade` of all elements, and return the encoded list of their states.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		let mut states: Vec<Vec<u8>> = Vec::new();
+		for_tuples!( #( states.push(Tuple::pre_upgrade()?); )* );
+		Ok(states.encode())
+	}
+
+	/// Execute the `post_upgrade` of all elements, each with the state its `pre_upgrade` returned.
 	#[cfg(feature = "try-runtime")]
-	fn pre_upgrade() -> Result<(), &'static str> {
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		let states = <Vec<Vec<u8>>>::decode(&mut &*state)
+			.map_err(|_| "failed to decode the state returned by `pre_upgrade`")?;
+		let mut states = states.into_iter();
 		let mut result = Ok(());
-		for_tuples!( #( result = result.and(Tuple::pre_upgrade()); )* );
+		for_tuples!( #(
+			result = result.and(Tuple::post_upgrade(states.next().unwrap_or_default()));
+		)* );
 		result
 	}
+}
 
-	#[cfg(feature = "try-runtime")]
-	fn post_upgrade() -> Result<(), &'static str> {
+/// Execute some checks to ensure the internal state of a pallet is consistent.
+///
+/// Usually, these checks should check all of the invariants that are expected to be held on all of
+/// the storage items of the pallet. They can be executed on top of the state of any block.
+///
+/// This is never meant to be executed on-chain but is meant to be used by testing tools.
+#[cfg(feature = "try-runtime")]
+pub trait TryState<BlockNumber> {
+	/// Execute the state checks.
+	fn try_state(n: BlockNumber) -> Result<(), &'static str>;
+}
+
+#[cfg(feature = "try-runtime")]
+#[impl_for_tuples(30)]
+impl<BlockNumber: Clone> TryState<BlockNumber> for Tuple {
+	fn try_state(n: BlockNumber) -> Result<(), &'static str> {
 		let mut result = Ok(());
-		for_tuples!( #( result = result.and(Tuple::post_upgrade()); )* );
+		for_tuples!( #( result = result.and(Tuple::try_state(n.clone())); )* );
 		result
 	}
 }
 
+/// The outcome of a try-runtime check of a single pallet.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PalletCheckOutcome {
+	/// The name of the pallet, as given in `construct_runtime!`.
+	pub pallet: Vec<u8>,
+	/// The error the check failed with, `None` if it passed.
+	pub error: Option<Vec<u8>>,
+	/// The weight consumed by the checked operation.
+	pub weight: Weight,
+}
+
+impl PalletCheckOutcome {
+	/// Whether the check passed.
+	pub fn is_ok(&self) -> bool {
+		self.error.is_none()
+	}
+
+	/// Run the `pre_upgrade`, `on_runtime_upgrade` and `post_upgrade` hooks of `U` in sequence and
+	/// record the outcome under the name `pallet`.
+	///
+	/// If `pre_upgrade` fails, the upgrade is not executed and the failure is reported right away.
+	#[cfg(feature = "try-runtime")]
+	pub fn on_runtime_upgrade<U: OnRuntimeUpgrade>(pallet: &str) -> Self {
+		let state = match U::pre_upgrade() {
+			Ok(state) => state,
+			Err(error) => return Self::new(pallet, Err(error), 0),
+		};
+		let weight = U::on_runtime_upgrade();
+		Self::new(pallet, U::post_upgrade(state), weight)
+	}
+
+	/// Run the `try_state` hook of `S` at block `n` and record the outcome under the name `pallet`.
+	#[cfg(feature = "try-runtime")]
+	pub fn try_state<BlockNumber, S: TryState<BlockNumber>>(pallet: &str, n: BlockNumber) -> Self {
+		Self::new(pallet, S::try_state(n), 0)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn new(pallet: &str, result: Result<(), &'static str>, weight: Weight) -> Self {
+		if let Err(error) = result {
+			crate::log::error!(
+				target: crate::LOG_TARGET,
+				"try-runtime check of {} failed: {}",
+				pallet,
+				error,
+			);
+		}
+		Self {
+			pallet: pallet.as_bytes().to_vec(),
+			error: result.err().map(|e| e.as_bytes().to_vec()),
+			weight,
+		}
+	}
+}
+
+/// Run the try-runtime checks of pallets one at a time, reporting on each of them individually.
+///
+/// This is implemented for each pallet by `decl_module` and `#[pallet::hooks]`, and for tuples of
+/// pallets, such as the `AllModules` type generated by `construct_runtime!`.
+#[cfg(feature = "try-runtime")]
+pub trait TryRuntimeChecks<BlockNumber> {
+	/// Run the upgrade hooks of every pallet, see [`PalletCheckOutcome::on_runtime_upgrade`].
+	fn check_on_runtime_upgrade() -> Vec<PalletCheckOutcome>;
+
+	/// Run the `try_state` hook of every pallet at block `n`.
+	fn check_try_state(n: BlockNumber) -> Vec<PalletCheckOutcome>;
+}
+
+#[cfg(feature = "try-runtime")]
+#[impl_for_tuples(30)]
+impl<BlockNumber: Clone> TryRuntimeChecks<BlockNumber> for Tuple {
+	fn check_on_runtime_upgrade() -> Vec<PalletCheckOutcome> {
+		let mut outcomes = Vec::new();
+		for_tuples!( #( outcomes.extend(Tuple::check_on_runtime_upgrade()); )* );
+		outcomes
+	}
+
+	fn check_try_state(n: BlockNumber) -> Vec<PalletCheckOutcome> {
+		let mut outcomes = Vec::new();
+		for_tuples!( #( outcomes.extend(Tuple::check_try_state(n.clone())); )* );
+		outcomes
+	}
+}
+
 /// Off-chain computation trait.
 ///
 /// Implementing this trait on a module allows you to perform long-running tasks
@@ -2058,17 +2337,30 @@ pub trait Hooks<BlockNumber> {
 
 	/// Execute some pre-checks prior to a runtime upgrade.
 	///
+	/// The returned blob is handed back to [`Self::post_upgrade`].
+	///
 	/// This hook is never meant to be executed on-chain but is meant to be used by testing tools.
 	#[cfg(feature = "try-runtime")]
-	fn pre_upgrade() -> Result<(), &'static str> {
-		Ok(())
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		Ok(Vec::new())
 	}
 
 	/// Execute some post-checks after a runtime upgrade.
 	///
+	/// `state` is the blob returned by [`Self::pre_upgrade`].
+	///
 	/// This hook is never meant to be executed on-chain but is meant to be used by testing tools.
 	#[cfg(feature = "try-runtime")]
-	fn post_upgrade() -> Result<(), &'static str> {
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	/// Execute some checks to ensure the internal state of the pallet is consistent, on top of
+	/// the state of block `n`.
+	///
+	/// This hook is never meant to be executed on-chain but is meant to be used by testing tools.
+	#[cfg(feature = "try-runtime")]
+	fn try_state(_n: BlockNumber) -> Result<(), &'static str> {
 		Ok(())
 	}
 
@@ -2243,6 +2535,44 @@ pub trait ExecuteBlock<Block: BlockT> {
 	fn execute_block(block: Block);
 }
 
+/// Metadata about storage from the runtime.
+#[derive(Encode, Decode, RuntimeDebug, Eq, PartialEq, Clone)]
+pub struct StorageInfo {
+	/// The prefix of the storage. All keys after the prefix are considered part of the storage
+	pub prefix: [u8; 32],
+	/// The maximum number of values in the storage, or none if no maximum specified.
+	pub max_values: Option<u32>,
+	/// The maximum size of key/values in the storage, or none if no maximum specified.
+	pub max_size: Option<u32>,
+}
+
+/// A trait to give information about storage.
+///
+/// It can be used to calculate PoV worst case size.
+pub trait StorageInfoTrait {
+	/// The information about every storage of the type.
+	fn storage_info() -> Vec<StorageInfo>;
+}
+
+#[impl_for_tuples(30)]
+impl StorageInfoTrait for Tuple {
+	fn storage_info() -> Vec<StorageInfo> {
+		let mut res = Vec::new();
+		for_tuples!( #( res.extend_from_slice(&Tuple::storage_info()); )* );
+		res
+	}
+}
+
+/// Similar to [`StorageInfoTrait`], a trait to give partial information about storage.
+///
+/// This is useful when a type can give some partial information with its generic parameter doesn't
+/// implement some bounds.
+pub trait PartialStorageInfoTrait {
+	/// The partial information about every storage of the type, i.e. without the bounds which
+	/// can't be computed.
+	fn partial_storage_info() -> Vec<StorageInfo>;
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -2278,4 +2608,47 @@ mod tests {
 		assert!(version > PalletVersion::new(2, 49, 51));
 		assert!(version < PalletVersion::new(3, 49, 51));
 	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn pre_upgrade_state_is_handed_to_post_upgrade() {
+		struct Counter;
+		impl OnRuntimeUpgrade for Counter {
+			fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+				Ok(7u32.encode())
+			}
+			fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+				match u32::decode(&mut &*state) {
+					Ok(7) => Ok(()),
+					_ => Err("unexpected state"),
+				}
+			}
+		}
+		struct Failing;
+		impl OnRuntimeUpgrade for Failing {
+			fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+				Err("failing")
+			}
+		}
+		impl TryState<u32> for Failing {
+			fn try_state(_: u32) -> Result<(), &'static str> {
+				Err("inconsistent")
+			}
+		}
+
+		type Tuple = (Counter, (), Counter);
+		let state = <Tuple as OnRuntimeUpgrade>::pre_upgrade().unwrap();
+		assert_eq!(<Tuple as OnRuntimeUpgrade>::post_upgrade(state), Ok(()));
+
+		type WithFailing = (Counter, Failing);
+		let state = <WithFailing as OnRuntimeUpgrade>::pre_upgrade().unwrap();
+		assert_eq!(<WithFailing as OnRuntimeUpgrade>::post_upgrade(state), Err("failing"));
+
+		let outcome = PalletCheckOutcome::on_runtime_upgrade::<Counter>("Counter");
+		assert!(outcome.is_ok());
+		let outcome = PalletCheckOutcome::on_runtime_upgrade::<Failing>("Failing");
+		assert_eq!(outcome.error, Some(b"failing".to_vec()));
+		let outcome = PalletCheckOutcome::try_state::<_, Failing>("Failing", 1);
+		assert_eq!(outcome.error, Some(b"inconsistent".to_vec()));
+	}
 }
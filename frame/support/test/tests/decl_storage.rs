@@ -387,6 +387,28 @@ mod tests {
 		pretty_assertions::assert_eq!(EXPECTED_METADATA, metadata);
 	}
 
+	#[test]
+	fn storage_info() {
+		use frame_support::traits::{StorageInfo, StorageInfoTrait};
+		use sp_io::hashing::twox_128;
+		use std::convert::TryInto;
+
+		let prefix = |storage_name: &[u8]| -> [u8; 32] {
+			[twox_128(b"TestStorage"), twox_128(storage_name)].concat().try_into().unwrap()
+		};
+
+		let storage_info = Module::<TraitImpl>::storage_info();
+		assert_eq!(storage_info.len(), 27);
+		assert_eq!(
+			storage_info[0],
+			StorageInfo { prefix: prefix(b"U32"), max_values: Some(1), max_size: None },
+		);
+		assert_eq!(
+			storage_info[16],
+			StorageInfo { prefix: prefix(b"MAPU32"), max_values: None, max_size: None },
+		);
+	}
+
 	#[test]
 	fn check_genesis_config() {
 		let config = GenesisConfig::default();
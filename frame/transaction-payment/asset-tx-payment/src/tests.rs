@@ -0,0 +1,487 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the asset transaction payment pallet.
+
+use super::*;
+use crate as pallet_asset_tx_payment;
+use frame_system as system;
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{Get, tokens::fungibles::Mutate},
+	weights::{
+		DispatchClass, DispatchInfo, PostDispatchInfo, Pays, Weight, WeightToFeeCoefficient,
+		WeightToFeeCoefficients, WeightToFeePolynomial,
+	},
+};
+use pallet_assets::BalanceToAssetBalance;
+use pallet_balances::Call as BalancesCall;
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	testing::Header,
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	transaction_validity::InvalidTransaction,
+};
+use smallvec::smallvec;
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		Assets: pallet_assets::{Module, Call, Storage, Event<T>},
+		AssetTxPayment: pallet_asset_tx_payment::{Module, Event<T>},
+	}
+);
+
+const CALL: &<Runtime as frame_system::Config>::Call =
+	&Call::Balances(BalancesCall::transfer(2, 69));
+
+thread_local! {
+	static EXTRINSIC_BASE_WEIGHT: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct BlockWeights;
+impl Get<frame_system::limits::BlockWeights> for BlockWeights {
+	fn get() -> frame_system::limits::BlockWeights {
+		frame_system::limits::BlockWeights::builder()
+			.base_block(0)
+			.for_class(DispatchClass::all(), |weights| {
+				weights.base_extrinsic = EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow()).into();
+			})
+			.for_class(DispatchClass::non_mandatory(), |weights| {
+				weights.max_total = 1024.into();
+			})
+			.build_or_panic()
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub static TransactionByteFee: u64 = 1;
+	pub static WeightToFee: u64 = 1;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 10;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type WeightInfo = ();
+}
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = u64;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			coeff_frac: Perbill::zero(),
+			coeff_integer: WEIGHT_TO_FEE.with(|v| *v.borrow()),
+			negative: false,
+		}]
+	}
+}
+
+impl pallet_transaction_payment::Config for Runtime {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = WeightToFee;
+	type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: u64 = 2;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type WeightInfo = ();
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction = FungiblesAdapter<
+		BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		(),
+	>;
+}
+
+pub struct ExtBuilder {
+	balance_factor: u64,
+	base_weight: u64,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balance_factor: 1,
+			base_weight: 0,
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn base_weight(mut self, base_weight: u64) -> Self {
+		self.base_weight = base_weight;
+		self
+	}
+	pub fn balance_factor(mut self, factor: u64) -> Self {
+		self.balance_factor = factor;
+		self
+	}
+	pub fn build(self) -> sp_io::TestExternalities {
+		EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow_mut() = self.base_weight);
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![
+				(1, 10 * self.balance_factor),
+				(2, 20 * self.balance_factor),
+				(3, 30 * self.balance_factor),
+				(42, 100),
+			],
+		}.assimilate_storage(&mut t).unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// create a transaction info struct from weight. Handy to avoid building the whole struct.
+pub fn info_from_weight(w: Weight) -> DispatchInfo {
+	// pays_fee: Pays::Yes -- class: DispatchClass::Normal
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: Some(w),
+		pays_fee: Default::default(),
+	}
+}
+
+fn default_post_info() -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: None,
+		pays_fee: Default::default(),
+	}
+}
+
+fn last_event() -> Event {
+	frame_system::Module::<Runtime>::events().pop().expect("Event expected").event
+}
+
+/// Create the sufficient asset `id` owned by account 42 and mint `balance` of it into `who`.
+fn create_asset(id: u32, min_balance: u64, who: u64, balance: u64) {
+	assert_ok!(Assets::force_create(Origin::root(), id, 42, true, min_balance));
+	assert_ok!(<Assets as Mutate<_>>::mint_into(id, &who, balance));
+	assert_eq!(Assets::balance(id, who), balance);
+}
+
+#[test]
+fn transaction_payment_in_native_possible() {
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None)
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), 100 - 5 - 5 - 10);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(1), 100 - 5 - 5 - 10);
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(5 /* tipped */, None)
+			.pre_dispatch(&2, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(2), 200 - 5 - 10 - 100 - 5);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(100),
+			&post_info_from_weight(50),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Balances::free_balance(2), 200 - 5 - 10 - 50 - 5);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_possible() {
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		// The asset is worth twice as many units as the native currency.
+		let asset_id = 1;
+		create_asset(asset_id, 20, 1, 1000);
+
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		let fee = (5 + 5 + 10) * 2;
+		// The native balance is not touched.
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(Assets::balance(asset_id, 1), 1000 - fee);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, 1), 1000 - fee);
+		// The fee is burned by the default credit handler.
+		assert_eq!(Assets::total_supply(asset_id), 1000 - fee);
+		assert_eq!(
+			last_event(),
+			Event::pallet_asset_tx_payment(crate::Event::AssetTxFeePaid(1, fee, 0, asset_id)),
+		);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_refunds_overpaid_fee() {
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		create_asset(asset_id, 20, 2, 1000);
+
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(5 /* tipped */, Some(asset_id))
+			.pre_dispatch(&2, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Assets::balance(asset_id, 2), 1000 - (5 + 10 + 100 + 5) * 2);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(100),
+			&post_info_from_weight(50),
+			len,
+			&Ok(()),
+		));
+		let fee = (5 + 10 + 50 + 5) * 2;
+		assert_eq!(Assets::balance(asset_id, 2), 1000 - fee);
+		assert_eq!(Balances::free_balance(2), 200);
+		assert_eq!(
+			last_event(),
+			Event::pallet_asset_tx_payment(crate::Event::AssetTxFeePaid(2, fee, 5 * 2, asset_id)),
+		);
+	});
+}
+
+#[test]
+fn transaction_payment_without_native_balance_possible() {
+	ExtBuilder::default()
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		// Account 333 only holds the sufficient asset.
+		let asset_id = 1;
+		create_asset(asset_id, 10, 333, 100);
+		assert_eq!(Balances::free_balance(333), 0);
+
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&333, CALL, &info_from_weight(5), len)
+			.unwrap();
+		assert_eq!(Assets::balance(asset_id, 333), 100 - 20);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, 333), 100 - 20);
+
+		// Paying in the native currency is not possible.
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, None)
+				.pre_dispatch(&333, CALL, &info_from_weight(5), len)
+				.unwrap_err(),
+			InvalidTransaction::Payment.into(),
+		);
+	});
+}
+
+#[test]
+fn converted_fee_is_never_zero_if_input_fee_is_not() {
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(1)
+		.build()
+		.execute_with(||
+	{
+		// The asset is worth a tenth of the units of the native currency.
+		let asset_id = 1;
+		create_asset(asset_id, 1, 1, 100);
+
+		let len = 1;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&1, CALL, &info_from_weight(0), len)
+			.unwrap();
+		// A fee of 2 rounds down to zero in the asset, but at least one unit is charged.
+		assert_eq!(Assets::balance(asset_id, 1), 100 - 1);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info_from_weight(0),
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, 1), 100 - 1);
+	});
+}
+
+#[test]
+fn payment_with_insufficient_asset_fails() {
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		assert_ok!(Assets::force_create(Origin::root(), asset_id, 42, false, 10));
+		assert_ok!(Assets::mint(Origin::signed(42), asset_id, 1, 1000));
+
+		let len = 10;
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+				.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+				.unwrap_err(),
+			InvalidTransaction::Payment.into(),
+		);
+		// An unknown asset can not be used either.
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(2))
+				.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+				.unwrap_err(),
+			InvalidTransaction::Payment.into(),
+		);
+		assert_eq!(Assets::balance(asset_id, 1), 1000);
+	});
+}
+
+#[test]
+fn payment_of_free_transaction_is_skipped() {
+	ExtBuilder::default()
+		.balance_factor(10)
+		.base_weight(5)
+		.build()
+		.execute_with(||
+	{
+		let asset_id = 1;
+		create_asset(asset_id, 20, 1, 1000);
+
+		let len = 10;
+		let info = DispatchInfo { pays_fee: Pays::No, ..info_from_weight(5) };
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(asset_id))
+			.pre_dispatch(&1, CALL, &info, len)
+			.unwrap();
+		assert_eq!(Assets::balance(asset_id, 1), 1000);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre,
+			&info,
+			&default_post_info(),
+			len,
+			&Ok(()),
+		));
+		assert_eq!(Assets::balance(asset_id, 1), 1000);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
@@ -0,0 +1,104 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for transaction-storage Pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use sp_std::prelude::*;
+use super::*;
+use sp_runtime::traits::{One, Bounded};
+use frame_system::{RawOrigin, Pallet as System, EventRecord};
+use frame_benchmarking::{benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::traits::{Currency, OnFinalize, OnInitialize};
+use sp_transaction_storage_proof::build_proof;
+
+use crate::Pallet as TransactionStorage;
+
+/// Storage period used in the benchmarks, to keep the number of simulated blocks low.
+const BENCHMARK_STORAGE_PERIOD: u32 = 10;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	let events = System::<T>::events();
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+pub fn run_to_block<T: Config>(n: T::BlockNumber) {
+	while frame_system::Pallet::<T>::block_number() < n {
+		crate::Pallet::<T>::on_finalize(frame_system::Pallet::<T>::block_number());
+		frame_system::Pallet::<T>::on_finalize(frame_system::Pallet::<T>::block_number());
+		frame_system::Pallet::<T>::set_block_number(frame_system::Pallet::<T>::block_number() + One::one());
+		frame_system::Pallet::<T>::on_initialize(frame_system::Pallet::<T>::block_number());
+		crate::Pallet::<T>::on_initialize(frame_system::Pallet::<T>::block_number());
+	}
+}
+
+benchmarks! {
+	store {
+		let l in 1 .. T::MaxTransactionSize::get();
+		run_to_block::<T>(1u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller.clone()), vec![0u8; l as usize])
+	verify {
+		assert!(!BlockTransactions::<T>::get().is_empty());
+		assert_last_event::<T>(Event::<T>::Stored(0).into());
+	}
+
+	renew {
+		run_to_block::<T>(1u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		TransactionStorage::<T>::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; T::MaxTransactionSize::get() as usize],
+		)?;
+		run_to_block::<T>(2u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), 1u32.into(), 0)
+	verify {
+		assert_last_event::<T>(Event::<T>::Renewed(0).into());
+	}
+
+	check_proof_max {
+		StoragePeriod::<T>::put(T::BlockNumber::from(BENCHMARK_STORAGE_PERIOD));
+		run_to_block::<T>(1u32.into());
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		// Fill the block with the maximum number of transactions, one of them of maximum size.
+		let mut transactions = vec![vec![0u8; 1]; T::MaxBlockTransactions::get() as usize - 1];
+		transactions.push(vec![0u8; T::MaxTransactionSize::get() as usize]);
+		for data in transactions.iter() {
+			TransactionStorage::<T>::store(RawOrigin::Signed(caller.clone()).into(), data.clone())?;
+		}
+		run_to_block::<T>((BENCHMARK_STORAGE_PERIOD + 1).into());
+		let parent_hash = frame_system::Pallet::<T>::parent_hash();
+		let proof = build_proof(parent_hash.as_ref(), transactions)
+			.expect("Proof is built from valid data")
+			.expect("There is data to prove");
+	}: check_proof(RawOrigin::None, proof)
+	verify {
+		assert_last_event::<T>(Event::<T>::ProofChecked.into());
+	}
+}
+
+impl_benchmark_test_suite!(
+	TransactionStorage,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
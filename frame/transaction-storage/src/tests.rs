@@ -0,0 +1,253 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for transaction-storage pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_ok, assert_noop, inherent::{InherentData, ProvideInherent}};
+use frame_system::RawOrigin;
+use sp_transaction_storage_proof::build_proof;
+
+fn last_event() -> Event {
+	frame_system::Pallet::<Test>::events().pop().expect("Event expected").event
+}
+
+#[test]
+fn discards_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; 2000 as usize]
+		));
+		assert_ok!(TransactionStorage::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; 2000 as usize]
+		));
+		let proof_provider = || {
+			let block_num = <frame_system::Pallet<Test>>::block_number();
+			if block_num == 11 {
+				let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+				Some(build_proof(
+					parent_hash.as_ref(),
+					vec![vec![0u8; 2000], vec![0u8; 2000]]
+				).unwrap().unwrap())
+			} else {
+				None
+			}
+		};
+		run_to_block(11, proof_provider);
+		assert!(Transactions::<Test>::get(1).is_some());
+		let transactions = Transactions::<Test>::get(1).unwrap();
+		assert_eq!(transactions.len(), 2);
+		assert_eq!(ChunkCount::<Test>::get(1), 16);
+		run_to_block(12, proof_provider);
+		assert!(Transactions::<Test>::get(1).is_none());
+		assert_eq!(ChunkCount::<Test>::get(1), 0);
+	});
+}
+
+#[test]
+fn burns_fee() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_noop!(TransactionStorage::store(
+				RawOrigin::Signed(5).into(),
+				vec![0u8; 2000 as usize]
+			),
+			Error::<Test>::InsufficientFunds,
+		);
+		assert_ok!(TransactionStorage::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; 2000 as usize]
+		));
+		assert_eq!(Balances::free_balance(1), 1_000_000_000 - 2000 * 2 - 200);
+		assert_eq!(last_event(), Event::pallet_transaction_storage(crate::Event::Stored(0)));
+	});
+}
+
+#[test]
+fn rejects_bad_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_noop!(
+			TransactionStorage::store(RawOrigin::Signed(caller).into(), Vec::new()),
+			Error::<Test>::EmptyTransaction,
+		);
+		assert_noop!(
+			TransactionStorage::store(
+				RawOrigin::Signed(caller).into(),
+				vec![0u8; MaxTransactionSize::get() as usize + 1],
+			),
+			Error::<Test>::TransactionTooLarge,
+		);
+		for _ in 0..MaxBlockTransactions::get() {
+			assert_ok!(TransactionStorage::store(RawOrigin::Signed(caller).into(), vec![0u8; 1]));
+		}
+		assert_noop!(
+			TransactionStorage::store(RawOrigin::Signed(caller).into(), vec![0u8; 1]),
+			Error::<Test>::TooManyTransactions,
+		);
+	});
+}
+
+#[test]
+fn checks_proof() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; MaxTransactionSize::get() as usize]
+		));
+		run_to_block(10, || None);
+		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+		let proof = build_proof(
+			parent_hash.as_ref(),
+			vec![vec![0u8; MaxTransactionSize::get() as usize]]
+		).unwrap().unwrap();
+		assert_noop!(
+			TransactionStorage::check_proof(Origin::none(), proof),
+			Error::<Test>::UnexpectedProof,
+		);
+		run_to_block(11, || None);
+		let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+
+		let invalid_proof = build_proof(
+			parent_hash.as_ref(),
+			vec![vec![1u8; MaxTransactionSize::get() as usize]]
+		).unwrap().unwrap();
+		assert_noop!(
+			TransactionStorage::check_proof(Origin::none(), invalid_proof),
+			Error::<Test>::InvalidProof,
+		);
+
+		let proof = build_proof(
+			parent_hash.as_ref(),
+			vec![vec![0u8; MaxTransactionSize::get() as usize]]
+		).unwrap().unwrap();
+		assert_ok!(TransactionStorage::check_proof(Origin::none(), proof.clone()));
+		assert_eq!(last_event(), Event::pallet_transaction_storage(crate::Event::ProofChecked));
+		assert_noop!(
+			TransactionStorage::check_proof(Origin::none(), proof),
+			Error::<Test>::DoubleCheck,
+		);
+	});
+}
+
+#[test]
+fn requires_proof_for_indexed_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; MaxTransactionSize::get() as usize]
+		));
+		let data = InherentData::new();
+		// Block 10 checks block 0, which has no indexed data.
+		run_to_block(9, || None);
+		assert!(TransactionStorage::is_inherent_required(&data).unwrap().is_none());
+		let proof = build_proof(
+			&[0u8; 32],
+			vec![vec![0u8; MaxTransactionSize::get() as usize]]
+		).unwrap().unwrap();
+		assert!(matches!(
+			TransactionStorage::check_inherent(&crate::Call::check_proof(proof.clone()), &data),
+			Err(InherentError::UnexpectedProof),
+		));
+		// Block 11 must check the data indexed in block 1.
+		run_to_block(10, || None);
+		assert!(matches!(
+			TransactionStorage::is_inherent_required(&data),
+			Ok(Some(InherentError::MissingProof)),
+		));
+		assert_ok!(TransactionStorage::check_inherent(&crate::Call::check_proof(proof), &data));
+	});
+}
+
+#[test]
+fn verifies_proof_for_any_transaction_in_block() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		let transactions: Vec<Vec<u8>> = (0..5u8)
+			.map(|i| vec![i; (i as usize + 1) * CHUNK_SIZE / 2])
+			.collect();
+		for data in &transactions {
+			assert_ok!(TransactionStorage::store(RawOrigin::Signed(caller).into(), data.clone()));
+		}
+		run_to_block(11, || None);
+		// Check the proof for every possible source of randomness in the proved block.
+		let total_chunks = ChunkCount::<Test>::get(1);
+		for i in 0..total_chunks as u64 {
+			let mut parent_hash = [0u8; 32];
+			parent_hash[..8].copy_from_slice(&i.to_be_bytes());
+			let parent_hash = sp_core::H256::from(parent_hash);
+			<frame_system::Pallet<Test>>::set_parent_hash(parent_hash);
+			ProofChecked::<Test>::kill();
+			let proof = build_proof(parent_hash.as_ref(), transactions.clone()).unwrap().unwrap();
+			assert_ok!(TransactionStorage::check_proof(Origin::none(), proof));
+		}
+	});
+}
+
+#[test]
+fn renews_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		let caller = 1;
+		assert_ok!(TransactionStorage::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; 2000]
+		));
+		let info = BlockTransactions::<Test>::get().last().unwrap().clone();
+		run_to_block(6, || None);
+		assert_noop!(
+			TransactionStorage::renew(RawOrigin::Signed(caller).into(), 2, 0),
+			Error::<Test>::RenewedNotFound,
+		);
+		assert_ok!(TransactionStorage::renew(
+			RawOrigin::Signed(caller.clone()).into(),
+			1, // block
+			0, // transaction
+		));
+		assert_eq!(Balances::free_balance(1), 1_000_000_000 - (2000 * 2 + 200) * 2);
+		assert_eq!(last_event(), Event::pallet_transaction_storage(crate::Event::Renewed(0)));
+		let proof_provider = || {
+			let block_num = <frame_system::Pallet<Test>>::block_number();
+			if block_num == 11 || block_num == 16 {
+				let parent_hash = <frame_system::Pallet<Test>>::parent_hash();
+				Some(build_proof(
+					parent_hash.as_ref(),
+					vec![vec![0u8; 2000]]
+				).unwrap().unwrap())
+			} else {
+				None
+			}
+		};
+		run_to_block(16, proof_provider);
+		assert!(Transactions::<Test>::get(1).is_none());
+		assert_eq!(Transactions::<Test>::get(6).unwrap().get(0), Some(info).as_ref());
+		run_to_block(17, proof_provider);
+		assert!(Transactions::<Test>::get(6).is_none());
+	});
+}
@@ -19,19 +19,58 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Encode, Decode};
 use sp_std::prelude::*;
+use sp_runtime::RuntimeDebug;
 use frame_support::weights::Weight;
 
+pub use frame_support::traits::PalletCheckOutcome;
+
+/// The outcome of a try-runtime check, across all pallets of the runtime.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Report {
+	/// The outcome of the check of each pallet, in the order they were executed.
+	pub outcomes: Vec<PalletCheckOutcome>,
+	/// The maximum weight of a block in the runtime, to put the consumed weight into perspective.
+	pub max_block_weight: Weight,
+}
+
+impl Report {
+	/// Whether the checks of all pallets passed.
+	pub fn is_ok(&self) -> bool {
+		self.outcomes.iter().all(|outcome| outcome.is_ok())
+	}
+
+	/// The total weight consumed by all pallets.
+	pub fn weight(&self) -> Weight {
+		self.outcomes.iter().fold(0, |acc, outcome| acc.saturating_add(outcome.weight))
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// Runtime api for testing the execution of a runtime upgrade.
 	pub trait TryRuntime {
-		/// dry-run runtime upgrades, returning the total weight consumed.
+		/// dry-run runtime upgrades, returning a report of the checks of each pallet.
 		///
 		/// This should do EXACTLY the same operations as the runtime would have done in the case of
 		/// a runtime upgrade (e.g. pallet ordering must be the same)
 		///
-		/// Returns the consumed weight of the migration in case of a successful one, combined with
-		/// the total allowed block weight of the runtime.
-		fn on_runtime_upgrade() -> Result<(Weight, Weight), sp_runtime::RuntimeString>;
+		/// The data returned by the `pre_upgrade` hook of each pallet is handed to its
+		/// `post_upgrade` hook. The report contains whether the checks of each pallet passed, along
+		/// with the weight consumed by its migration.
+		fn on_runtime_upgrade() -> Report;
+
+		/// Execute the given block, but don't check that its state root matches that of yours.
+		///
+		/// This is only sensible where the incoming block is from a different network, yet it has
+		/// the same block format as the runtime implementing this API, or when the runtime code
+		/// has been substituted before executing the block.
+		///
+		/// Returns the consumed weight of the block.
+		fn execute_block_no_check(block: Block) -> Weight;
+
+		/// Execute the `try_state` hook of all pallets on top of the current state, returning a
+		/// report of the checks of each pallet.
+		fn try_state() -> Report;
 	}
 }
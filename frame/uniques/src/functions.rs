@@ -0,0 +1,156 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Various pieces of common functionality.
+
+use super::*;
+use frame_support::{ensure, traits::Get};
+use sp_runtime::{DispatchError, DispatchResult};
+
+impl<T: Config> Pallet<T> {
+	/// Move `instance` of `class` to `dest`, clearing any approval.
+	///
+	/// `with_details` is called with the details of the class and the instance before anything
+	/// changes and may veto the transfer.
+	pub(crate) fn do_transfer(
+		class: T::ClassId,
+		instance: T::InstanceId,
+		dest: T::AccountId,
+		with_details: impl FnOnce(&ClassDetailsFor<T>, &mut InstanceDetailsFor<T>) -> DispatchResult,
+	) -> DispatchResult {
+		let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+		ensure!(!class_details.is_frozen, Error::<T>::Frozen);
+
+		let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+		ensure!(!details.is_frozen, Error::<T>::Frozen);
+		with_details(&class_details, &mut details)?;
+
+		Account::<T>::remove((&details.owner, &class, &instance));
+		Account::<T>::insert((&dest, &class, &instance), ());
+		let origin = sp_std::mem::replace(&mut details.owner, dest.clone());
+		details.approved = None;
+		Asset::<T>::insert(&class, &instance, &details);
+
+		Self::deposit_event(Event::Transferred(class, instance, origin, dest));
+		Ok(())
+	}
+
+	/// Create `class` owned by `owner` and managed by `admin`, reserving `deposit` from `owner`.
+	pub(crate) fn do_create_class(
+		class: T::ClassId,
+		owner: T::AccountId,
+		admin: T::AccountId,
+		deposit: DepositBalanceOf<T>,
+		free_holding: bool,
+		event: Event<T>,
+	) -> DispatchResult {
+		ensure!(!Class::<T>::contains_key(class), Error::<T>::InUse);
+
+		T::Currency::reserve(&owner, deposit)?;
+
+		Class::<T>::insert(class, ClassDetails {
+			owner,
+			issuer: admin.clone(),
+			admin: admin.clone(),
+			freezer: admin,
+			total_deposit: deposit,
+			free_holding,
+			instances: 0,
+			instance_metadatas: 0,
+			attributes: 0,
+			is_frozen: false,
+		});
+
+		Self::deposit_event(event);
+		Ok(())
+	}
+
+	/// Mint `instance` of `class` into the account of `owner`.
+	///
+	/// The instance deposit is reserved from the owner of the class, unless the class is
+	/// free-holding. `with_details` may veto the mint based on the details of the class.
+	pub(crate) fn do_mint(
+		class: T::ClassId,
+		instance: T::InstanceId,
+		owner: T::AccountId,
+		with_details: impl FnOnce(&ClassDetailsFor<T>) -> DispatchResult,
+	) -> DispatchResult {
+		ensure!(!Asset::<T>::contains_key(class, instance), Error::<T>::AlreadyExists);
+
+		Class::<T>::try_mutate(&class, |maybe_class_details| -> DispatchResult {
+			let class_details = maybe_class_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			with_details(class_details)?;
+
+			let instances = class_details.instances.checked_add(1)
+				.ok_or(ArithmeticError::Overflow)?;
+			class_details.instances = instances;
+
+			let deposit = match class_details.free_holding {
+				true => Zero::zero(),
+				false => T::InstanceDeposit::get(),
+			};
+			T::Currency::reserve(&class_details.owner, deposit)?;
+			class_details.total_deposit = class_details.total_deposit.saturating_add(deposit);
+
+			Account::<T>::insert((&owner, &class, &instance), ());
+			let details = InstanceDetails { owner: owner.clone(), approved: None, is_frozen: false, deposit };
+			Asset::<T>::insert(&class, &instance, details);
+			Ok(())
+		})?;
+
+		Self::deposit_event(Event::Issued(class, instance, owner));
+		Ok(())
+	}
+
+	/// Burn `instance` of `class`, along with its metadata.
+	///
+	/// The attributes of the instance are kept, as their number is unbounded. They are removed
+	/// with `clear_attribute` or when the class is destroyed.
+	///
+	/// The deposits of the instance and its metadata are returned to the owner of the class.
+	/// `with_details` may veto the burn based on the details of the class and the instance.
+	pub(crate) fn do_burn(
+		class: T::ClassId,
+		instance: T::InstanceId,
+		with_details: impl FnOnce(&ClassDetailsFor<T>, &InstanceDetailsFor<T>) -> DispatchResult,
+	) -> DispatchResult {
+		let owner = Class::<T>::try_mutate(&class, |maybe_class_details| -> Result<T::AccountId, DispatchError> {
+			let class_details = maybe_class_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			let details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			with_details(class_details, &details)?;
+
+			let mut deposit = details.deposit;
+			if let Some(metadata) = InstanceMetadataOf::<T>::take(&class, &instance) {
+				deposit = deposit.saturating_add(metadata.deposit);
+				class_details.instance_metadatas = class_details.instance_metadatas.saturating_sub(1);
+			}
+
+			// Return the deposits.
+			T::Currency::unreserve(&class_details.owner, deposit);
+			class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+			class_details.instances = class_details.instances.saturating_sub(1);
+			Ok(details.owner)
+		})?;
+
+		Asset::<T>::remove(&class, &instance);
+		Account::<T>::remove((&owner, &class, &instance));
+
+		Self::deposit_event(Event::Burned(class, instance, owner));
+		Ok(())
+	}
+}
@@ -0,0 +1,1167 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Unique (Assets) Module
+//!
+//! A simple, secure module for dealing with non-fungible assets.
+//!
+//! ## Overview
+//!
+//! The Uniques module provides functionality for asset management of non-fungible asset classes,
+//! including:
+//!
+//! * Asset Issuance
+//! * Asset Transfer
+//! * Asset Destruction
+//!
+//! To use it in your runtime, you need to implement the uniques [`Config`].
+//!
+//! The supported dispatchable functions are documented in the [`Call`] enum.
+//!
+//! ### Terminology
+//!
+//! * **Asset class**: A collection of asset instances, managed by the same team of accounts.
+//! * **Asset instance**: A single non-fungible asset of a class, identified by its class and
+//!   instance ID. There is exactly one instance of such an asset in existence and there is exactly
+//!   one owning account.
+//! * **Owner**: An account ID uniquely privileged to be able to destroy a particular asset class,
+//!   to set the Issuer, Freezer or Admin of that asset class and to set its metadata.
+//! * **Issuer**: An account ID uniquely privileged to be able to mint instances of an asset class.
+//! * **Admin**: An account ID uniquely privileged to be able to thaw instances and to transfer or
+//!   burn instances of an asset class regardless of their owner.
+//! * **Freezer**: An account ID uniquely privileged to be able to freeze instances of an asset
+//!   class.
+//! * **Approval**: The act of allowing an account the permission to transfer an asset instance on
+//!   behalf of its owner.
+//!
+//! Every storage item of a class, i.e. the class itself, its instances, metadata and attributes,
+//! is paid for by a deposit of the class Owner, unless the class was created as free-holding.
+//!
+//! ### Permissionless Functions
+//!
+//! * `create`: Creates a new asset class, taking the required deposit.
+//! * `transfer`: Transfer an asset instance to another account.
+//! * `approve_transfer`: Allow a delegate to transfer an asset instance.
+//! * `cancel_approval`: Rescind a previous approval.
+//!
+//! ### Permissioned Functions
+//!
+//! * `force_create`: Creates a new asset class without taking any deposit.
+//!
+//! ### Privileged Functions
+//!
+//! * `destroy`: Destroys an entire asset class; called by the asset class's Owner.
+//! * `mint`: Mints a new asset instance; called by the asset class's Issuer.
+//! * `burn`: Burns an asset instance; called by the asset class's Admin or the instance's owner.
+//! * `freeze`: Disallows further `transfer`s of an instance; called by the asset class's Freezer.
+//! * `thaw`: Allows further `transfer`s of an instance; called by the asset class's Admin.
+//! * `freeze_class`: Disallows further `transfer`s of all instances of a class; called by the
+//!   asset class's Freezer.
+//! * `thaw_class`: Allows further `transfer`s of all instances of a class; called by the asset
+//!   class's Admin.
+//! * `transfer_ownership`: Changes an asset class's Owner; called by the asset class's Owner.
+//! * `set_team`: Changes an asset class's Admin, Freezer and Issuer; called by the asset class's
+//!   Owner.
+//! * `set_attribute`, `clear_attribute`: Set or remove an attribute of a class or an instance;
+//!   called by the asset class's Owner or the `ForceOrigin`.
+//! * `set_metadata`, `clear_metadata`: Set or remove the metadata of an instance; called by the
+//!   asset class's Owner or the `ForceOrigin`.
+//! * `set_class_metadata`, `clear_class_metadata`: Set or remove the metadata of a class; called
+//!   by the asset class's Owner or the `ForceOrigin`.
+//!
+//! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
+//!
+//! The pallet also implements the `nonfungibles` traits of `frame_support::traits::tokens`, so
+//! that other pallets can deal with its asset instances generically.
+//!
+//! ## Related Modules
+//!
+//! * [`System`](../frame_system/index.html)
+//! * [`Support`](../frame_support/index.html)
+//! * [`Assets`](../pallet_assets/index.html)
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod weights;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+pub mod mock;
+#[cfg(test)]
+mod tests;
+
+mod types;
+mod functions;
+mod impl_nonfungibles;
+pub use types::*;
+
+use sp_std::prelude::*;
+use sp_runtime::{RuntimeDebug, ArithmeticError, traits::{Zero, StaticLookup, Saturating}};
+use codec::{Encode, Decode, HasCompact};
+use frame_support::traits::{Currency, ReservableCurrency, BalanceStatus::Reserved};
+pub use weights::WeightInfo;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+	};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	/// The module configuration trait.
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Identifier for the class of asset.
+		type ClassId: Member + Parameter + Default + Copy + HasCompact;
+
+		/// The type used to identify a unique asset within an asset class.
+		type InstanceId: Member + Parameter + Default + Copy + HasCompact;
+
+		/// The currency mechanism, used for paying for reserves.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The origin which may forcibly create or destroy an asset or otherwise alter privileged
+		/// attributes.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The basic amount of funds that must be reserved for an asset class.
+		type ClassDeposit: Get<DepositBalanceOf<Self>>;
+
+		/// The basic amount of funds that must be reserved for an asset instance.
+		type InstanceDeposit: Get<DepositBalanceOf<Self>>;
+
+		/// The basic amount of funds that must be reserved when adding metadata to your asset.
+		type MetadataDepositBase: Get<DepositBalanceOf<Self>>;
+
+		/// The basic amount of funds that must be reserved when adding an attribute to an asset.
+		type AttributeDepositBase: Get<DepositBalanceOf<Self>>;
+
+		/// The additional funds that must be reserved for the number of bytes store in metadata,
+		/// either "normal" metadata or attribute metadata.
+		type DepositPerByte: Get<DepositBalanceOf<Self>>;
+
+		/// The maximum length of data stored on-chain.
+		type StringLimit: Get<u32>;
+
+		/// The maximum length of an attribute key.
+		type KeyLimit: Get<u32>;
+
+		/// The maximum length of an attribute value.
+		type ValueLimit: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::storage]
+	/// Details of an asset class.
+	pub(super) type Class<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::ClassId,
+		ClassDetailsFor<T>,
+	>;
+
+	#[pallet::storage]
+	/// The asset instances held by any given account; set out this way so that instances owned by
+	/// a single account can be enumerated.
+	pub(super) type Account<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, T::ClassId>,
+			NMapKey<Blake2_128Concat, T::InstanceId>,
+		),
+		(),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// The asset instances in existence and their ownership details.
+	pub(super) type Asset<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::ClassId,
+		Blake2_128Concat,
+		T::InstanceId,
+		InstanceDetailsFor<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// Metadata of an asset class.
+	pub(super) type ClassMetadataOf<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::ClassId,
+		ClassMetadata<DepositBalanceOf<T>, T::StringLimit>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// Metadata of an asset instance.
+	pub(super) type InstanceMetadataOf<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::ClassId,
+		Blake2_128Concat,
+		T::InstanceId,
+		InstanceMetadata<DepositBalanceOf<T>, T::StringLimit>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// Attributes of an asset class or instance, along with the deposit paid for them.
+	pub(super) type Attribute<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::ClassId>,
+			NMapKey<Blake2_128Concat, Option<T::InstanceId>>,
+			NMapKey<Blake2_128Concat, BoundedVec<u8, T::KeyLimit>>,
+		),
+		(BoundedVec<u8, T::ValueLimit>, DepositBalanceOf<T>),
+		OptionQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", T::ClassId = "ClassId", T::InstanceId = "InstanceId")]
+	pub enum Event<T: Config> {
+		/// An asset class was created. \[class, creator, owner\]
+		Created(T::ClassId, T::AccountId, T::AccountId),
+		/// An asset class was force-created. \[class, owner\]
+		ForceCreated(T::ClassId, T::AccountId),
+		/// An asset `class` was destroyed. \[class\]
+		Destroyed(T::ClassId),
+		/// An asset `instance` was issued. \[class, instance, owner\]
+		Issued(T::ClassId, T::InstanceId, T::AccountId),
+		/// An asset `instance` was transferred. \[class, instance, from, to\]
+		Transferred(T::ClassId, T::InstanceId, T::AccountId, T::AccountId),
+		/// An asset `instance` was destroyed. \[class, instance, owner\]
+		Burned(T::ClassId, T::InstanceId, T::AccountId),
+		/// Some asset `instance` was frozen. \[class, instance\]
+		Frozen(T::ClassId, T::InstanceId),
+		/// Some asset `instance` was thawed. \[class, instance\]
+		Thawed(T::ClassId, T::InstanceId),
+		/// Some asset `class` was frozen. \[class\]
+		ClassFrozen(T::ClassId),
+		/// Some asset `class` was thawed. \[class\]
+		ClassThawed(T::ClassId),
+		/// The owner changed \[class, new_owner\]
+		OwnerChanged(T::ClassId, T::AccountId),
+		/// The management team changed \[class, issuer, admin, freezer\]
+		TeamChanged(T::ClassId, T::AccountId, T::AccountId, T::AccountId),
+		/// An `instance` of an asset `class` has been approved by the `owner` for transfer by a
+		/// `delegate`.
+		/// \[class, instance, owner, delegate\]
+		ApprovedTransfer(T::ClassId, T::InstanceId, T::AccountId, T::AccountId),
+		/// An approval for a `delegate` account to transfer the `instance` of an asset `class` was
+		/// cancelled by its `owner`.
+		/// \[class, instance, owner, delegate\]
+		ApprovalCancelled(T::ClassId, T::InstanceId, T::AccountId, T::AccountId),
+		/// New metadata has been set for an asset class. \[class, data, is_frozen\]
+		ClassMetadataSet(T::ClassId, BoundedVec<u8, T::StringLimit>, bool),
+		/// Metadata has been cleared for an asset class. \[class\]
+		ClassMetadataCleared(T::ClassId),
+		/// New metadata has been set for an asset instance.
+		/// \[class, instance, data, is_frozen\]
+		MetadataSet(T::ClassId, T::InstanceId, BoundedVec<u8, T::StringLimit>, bool),
+		/// Metadata has been cleared for an asset instance. \[class, instance\]
+		MetadataCleared(T::ClassId, T::InstanceId),
+		/// New attribute metadata has been set for an asset class or instance.
+		/// \[class, maybe_instance, key, value\]
+		AttributeSet(
+			T::ClassId,
+			Option<T::InstanceId>,
+			BoundedVec<u8, T::KeyLimit>,
+			BoundedVec<u8, T::ValueLimit>,
+		),
+		/// Attribute metadata has been cleared for an asset class or instance.
+		/// \[class, maybe_instance, key\]
+		AttributeCleared(T::ClassId, Option<T::InstanceId>, BoundedVec<u8, T::KeyLimit>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The signing account has no permission to do the operation.
+		NoPermission,
+		/// The given asset ID is unknown.
+		Unknown,
+		/// The asset instance ID has already been used for an asset.
+		AlreadyExists,
+		/// The owner turned out to be different to what was expected.
+		WrongOwner,
+		/// Invalid witness data given.
+		BadWitness,
+		/// The asset ID is already taken.
+		InUse,
+		/// The asset instance or class is frozen.
+		Frozen,
+		/// The delegate turned out to be different to what was expected.
+		WrongDelegate,
+		/// There is no delegate approved.
+		NoDelegate,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Issue a new class of non-fungible assets from a public origin.
+		///
+		/// This new asset class has no assets initially and its owner is the origin.
+		///
+		/// The origin must be Signed and the sender must have sufficient funds free.
+		///
+		/// `ClassDeposit` funds of sender are reserved.
+		///
+		/// Parameters:
+		/// - `class`: The identifier of the new asset class. This must not be currently in use.
+		/// - `admin`: The admin of this class of assets. The admin is the initial address of each
+		/// member of the asset class's admin team.
+		///
+		/// Emits `Created` event when successful.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::create())]
+		pub(super) fn create(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			admin: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
+
+			Self::do_create_class(
+				class,
+				owner.clone(),
+				admin.clone(),
+				T::ClassDeposit::get(),
+				false,
+				Event::Created(class, owner, admin),
+			)
+		}
+
+		/// Issue a new class of non-fungible assets from a privileged origin.
+		///
+		/// This new asset class has no assets initially.
+		///
+		/// The origin must conform to `ForceOrigin`.
+		///
+		/// Unlike `create`, no funds are reserved.
+		///
+		/// - `class`: The identifier of the new asset. This must not be currently in use.
+		/// - `owner`: The owner of this class of assets. The owner has full superuser permissions
+		/// over this asset, but may later change and configure the permissions using
+		/// `transfer_ownership` and `set_team`.
+		/// - `free_holding`: If `true`, no deposits are taken for the instances, metadata and
+		/// attributes of this class.
+		///
+		/// Emits `ForceCreated` event when successful.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::force_create())]
+		pub(super) fn force_create(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			free_holding: bool,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::do_create_class(
+				class,
+				owner.clone(),
+				owner.clone(),
+				Zero::zero(),
+				free_holding,
+				Event::ForceCreated(class, owner),
+			)
+		}
+
+		/// Destroy a class of non-fungible assets.
+		///
+		/// The origin must conform to `ForceOrigin` or must be Signed and the sender must be the
+		/// owner of the asset `class`.
+		///
+		/// - `class`: The identifier of the asset class to be destroyed.
+		/// - `witness`: Information on the instances minted in the asset class. This must be
+		/// correct.
+		///
+		/// All deposits of the class are returned to its owner.
+		///
+		/// Emits `Destroyed` event when successful.
+		///
+		/// Weight: `O(n + m + a)` where:
+		/// - `n = witness.instances`
+		/// - `m = witness.instance_metadatas`
+		/// - `a = witness.attributes`
+		#[pallet::weight(T::WeightInfo::destroy(
+			witness.instances,
+			witness.instance_metadatas,
+			witness.attributes,
+		))]
+		pub(super) fn destroy(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			witness: DestroyWitness,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+			Class::<T>::try_mutate_exists(class, |maybe_details| {
+				let class_details = maybe_details.take().ok_or(Error::<T>::Unknown)?;
+				if let Some(check_owner) = maybe_check_owner {
+					ensure!(class_details.owner == check_owner, Error::<T>::NoPermission);
+				}
+				ensure!(class_details.instances == witness.instances, Error::<T>::BadWitness);
+				ensure!(
+					class_details.instance_metadatas == witness.instance_metadatas,
+					Error::<T>::BadWitness,
+				);
+				ensure!(class_details.attributes == witness.attributes, Error::<T>::BadWitness);
+
+				for (instance, details) in Asset::<T>::drain_prefix(&class) {
+					Account::<T>::remove((&details.owner, &class, &instance));
+				}
+				InstanceMetadataOf::<T>::remove_prefix(&class);
+				ClassMetadataOf::<T>::remove(&class);
+				Attribute::<T>::remove_prefix((&class,));
+				T::Currency::unreserve(&class_details.owner, class_details.total_deposit);
+
+				Self::deposit_event(Event::Destroyed(class));
+
+				// NOTE: could use postinfo to reflect the actual number of instances/metadata/attributes
+				Ok(())
+			})
+		}
+
+		/// Mint an asset instance of a particular class.
+		///
+		/// The origin must be Signed and the sender must be the Issuer of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be minted.
+		/// - `instance`: The instance value of the asset to be minted. This must not be in use.
+		/// - `owner`: The initial owner of the minted asset.
+		///
+		/// `InstanceDeposit` funds of the class owner are reserved, unless the class is
+		/// free-holding.
+		///
+		/// Emits `Issued` event when successful.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::mint())]
+		pub(super) fn mint(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::do_mint(class, instance, owner, |class_details| {
+				ensure!(class_details.issuer == origin, Error::<T>::NoPermission);
+				Ok(())
+			})
+		}
+
+		/// Destroy a single asset instance.
+		///
+		/// Origin must be Signed and the sender should be the Admin of the asset `class` or the
+		/// owner of the asset `instance`.
+		///
+		/// - `class`: The class of the asset to be burned.
+		/// - `instance`: The instance of the asset to be burned.
+		/// - `check_owner`: If `Some` then the operation will fail with `WrongOwner` unless the
+		///   asset is owned by this value.
+		///
+		/// The deposits of the instance and its metadata are returned to the class owner. Its
+		/// attributes, and their deposits, are kept until they are cleared with `clear_attribute`
+		/// or the class is destroyed.
+		///
+		/// Emits `Burned` with the actual amount burned.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub(super) fn burn(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+			check_owner: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let check_owner = check_owner.map(T::Lookup::lookup).transpose()?;
+
+			Self::do_burn(class, instance, |class_details, details| {
+				let is_permitted = class_details.admin == origin || details.owner == origin;
+				ensure!(is_permitted, Error::<T>::NoPermission);
+				ensure!(check_owner.map_or(true, |o| o == details.owner), Error::<T>::WrongOwner);
+				Ok(())
+			})
+		}
+
+		/// Move an asset from the sender account to another.
+		///
+		/// Origin must be Signed and the signing account must be either:
+		/// - the Admin of the asset `class`;
+		/// - the Owner of the asset `instance`;
+		/// - the approved delegate for the asset `instance` (in this case, the approval is reset).
+		///
+		/// Arguments:
+		/// - `class`: The class of the asset to be transferred.
+		/// - `instance`: The instance of the asset to be transferred.
+		/// - `dest`: The account to receive ownership of the asset.
+		///
+		/// Emits `Transferred`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub(super) fn transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+			dest: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			Self::do_transfer(class, instance, dest, |class_details, details| {
+				if details.owner != origin && class_details.admin != origin {
+					let approved = details.approved.take().map_or(false, |i| i == origin);
+					ensure!(approved, Error::<T>::NoPermission);
+				}
+				Ok(())
+			})
+		}
+
+		/// Disallow further unprivileged transfer of an asset instance.
+		///
+		/// Origin must be Signed and the sender should be the Freezer of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be frozen.
+		/// - `instance`: The instance of the asset to be frozen.
+		///
+		/// Emits `Frozen`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::freeze())]
+		pub(super) fn freeze(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			ensure!(class_details.freezer == origin, Error::<T>::NoPermission);
+
+			details.is_frozen = true;
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(Event::<T>::Frozen(class, instance));
+			Ok(())
+		}
+
+		/// Re-allow unprivileged transfer of an asset instance.
+		///
+		/// Origin must be Signed and the sender should be the Admin of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be thawed.
+		/// - `instance`: The instance of the asset to be thawed.
+		///
+		/// Emits `Thawed`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::thaw())]
+		pub(super) fn thaw(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			ensure!(class_details.admin == origin, Error::<T>::NoPermission);
+
+			details.is_frozen = false;
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(Event::<T>::Thawed(class, instance));
+			Ok(())
+		}
+
+		/// Disallow further unprivileged transfers for a whole asset class.
+		///
+		/// Origin must be Signed and the sender should be the Freezer of the asset `class`.
+		///
+		/// - `class`: The asset class to be frozen.
+		///
+		/// Emits `ClassFrozen`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::freeze_class())]
+		pub(super) fn freeze_class(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.freezer, Error::<T>::NoPermission);
+
+				details.is_frozen = true;
+
+				Self::deposit_event(Event::<T>::ClassFrozen(class));
+				Ok(())
+			})
+		}
+
+		/// Re-allow unprivileged transfers for a whole asset class.
+		///
+		/// Origin must be Signed and the sender should be the Admin of the asset `class`.
+		///
+		/// - `class`: The class to be thawed.
+		///
+		/// Emits `ClassThawed`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::thaw_class())]
+		pub(super) fn thaw_class(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.admin, Error::<T>::NoPermission);
+
+				details.is_frozen = false;
+
+				Self::deposit_event(Event::<T>::ClassThawed(class));
+				Ok(())
+			})
+		}
+
+		/// Change the Owner of an asset class.
+		///
+		/// Origin must be Signed and the sender should be the Owner of the asset `class`.
+		///
+		/// - `class`: The asset class whose owner should be changed.
+		/// - `owner`: The new Owner of this asset class.
+		///
+		/// All deposits of the class are moved to the new owner.
+		///
+		/// Emits `OwnerChanged`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::transfer_ownership())]
+		pub(super) fn transfer_ownership(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+				if details.owner == owner { return Ok(()) }
+
+				// Move the deposit to the new owner.
+				T::Currency::repatriate_reserved(
+					&details.owner,
+					&owner,
+					details.total_deposit,
+					Reserved,
+				)?;
+				details.owner = owner.clone();
+
+				Self::deposit_event(Event::OwnerChanged(class, owner));
+				Ok(())
+			})
+		}
+
+		/// Change the Issuer, Admin and Freezer of an asset class.
+		///
+		/// Origin must be Signed and the sender should be the Owner of the asset `class`.
+		///
+		/// - `class`: The asset class whose team should be changed.
+		/// - `issuer`: The new Issuer of this asset class.
+		/// - `admin`: The new Admin of this asset class.
+		/// - `freezer`: The new Freezer of this asset class.
+		///
+		/// Emits `TeamChanged`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::set_team())]
+		pub(super) fn set_team(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let issuer = T::Lookup::lookup(issuer)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+
+				details.issuer = issuer.clone();
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+
+				Self::deposit_event(Event::TeamChanged(class, issuer, admin, freezer));
+				Ok(())
+			})
+		}
+
+		/// Approve an instance to be transferred by a delegated third-party account.
+		///
+		/// Origin must be Signed and must be the owner of the asset `instance` or the Admin of
+		/// the asset `class`.
+		///
+		/// - `class`: The class of the asset to be approved for delegated transfer.
+		/// - `instance`: The instance of the asset to be approved for delegated transfer.
+		/// - `delegate`: The account to delegate permission to transfer the asset.
+		///
+		/// Any previous approval of the instance is replaced.
+		///
+		/// Emits `ApprovedTransfer` on success.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::approve_transfer())]
+		pub(super) fn approve_transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let is_permitted = class_details.admin == origin || details.owner == origin;
+			ensure!(is_permitted, Error::<T>::NoPermission);
+
+			details.approved = Some(delegate.clone());
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(Event::ApprovedTransfer(class, instance, details.owner, delegate));
+			Ok(())
+		}
+
+		/// Cancel the prior approval for the transfer of an asset by a delegate.
+		///
+		/// Origin must be Signed and must be the owner of the asset `instance` or the Admin of
+		/// the asset `class`.
+		///
+		/// - `class`: The class of the asset of whose approval will be cancelled.
+		/// - `instance`: The instance of the asset of whose approval will be cancelled.
+		/// - `maybe_check_delegate`: If `Some` will ensure that the given account is the one to
+		///   which permission of transfer is delegated.
+		///
+		/// Emits `ApprovalCancelled` on success.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::cancel_approval())]
+		pub(super) fn cancel_approval(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+			maybe_check_delegate: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let maybe_check_delegate = maybe_check_delegate.map(T::Lookup::lookup).transpose()?;
+
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let is_permitted = class_details.admin == origin || details.owner == origin;
+			ensure!(is_permitted, Error::<T>::NoPermission);
+
+			let old = details.approved.take().ok_or(Error::<T>::NoDelegate)?;
+			if let Some(check_delegate) = maybe_check_delegate {
+				ensure!(check_delegate == old, Error::<T>::WrongDelegate);
+			}
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(Event::ApprovalCancelled(class, instance, details.owner, old));
+			Ok(())
+		}
+
+		/// Set an attribute for an asset class or instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// If the origin is Signed, then funds of the class owner are reserved according to the
+		/// formula: `AttributeDepositBase + DepositPerByte * (key.len + value.len)` taking into
+		/// account any already reserved funds, unless the class is free-holding.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to set.
+		/// - `maybe_instance`: The identifier of the asset instance whose metadata to set, or
+		/// `None` to set an attribute of the class itself.
+		/// - `key`: The key of the attribute.
+		/// - `value`: The value to which to set the attribute.
+		///
+		/// Emits `AttributeSet`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::set_attribute())]
+		pub(super) fn set_attribute(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			maybe_instance: Option<T::InstanceId>,
+			key: BoundedVec<u8, T::KeyLimit>,
+			value: BoundedVec<u8, T::ValueLimit>,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+			let is_frozen = match maybe_instance {
+				None => ClassMetadataOf::<T>::get(class).map(|m| m.is_frozen),
+				Some(instance) => {
+					ensure!(Asset::<T>::contains_key(&class, &instance), Error::<T>::Unknown);
+					InstanceMetadataOf::<T>::get(class, instance).map(|m| m.is_frozen)
+				},
+			};
+			ensure!(maybe_check_owner.is_none() || !is_frozen.unwrap_or(false), Error::<T>::Frozen);
+
+			let attribute = Attribute::<T>::get((class, maybe_instance, &key));
+			if attribute.is_none() {
+				class_details.attributes = class_details.attributes.checked_add(1)
+					.ok_or(ArithmeticError::Overflow)?;
+			}
+			let old_deposit = attribute.map_or(Zero::zero(), |m| m.1);
+			let mut deposit = Zero::zero();
+			if !class_details.free_holding && maybe_check_owner.is_some() {
+				deposit = T::DepositPerByte::get()
+					.saturating_mul(((key.len() + value.len()) as u32).into())
+					.saturating_add(T::AttributeDepositBase::get());
+			}
+			if deposit > old_deposit {
+				T::Currency::reserve(&class_details.owner, deposit - old_deposit)?;
+			} else {
+				T::Currency::unreserve(&class_details.owner, old_deposit - deposit);
+			}
+			class_details.total_deposit = class_details.total_deposit
+				.saturating_sub(old_deposit)
+				.saturating_add(deposit);
+
+			Attribute::<T>::insert((&class, maybe_instance, &key), (&value, deposit));
+			Class::<T>::insert(class, &class_details);
+			Self::deposit_event(Event::AttributeSet(class, maybe_instance, key, value));
+			Ok(())
+		}
+
+		/// Clear an attribute for an asset class or instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// Any deposit is freed for the asset class owner.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to clear.
+		/// - `maybe_instance`: The identifier of the asset instance whose metadata to clear, or
+		/// `None` to clear an attribute of the class itself.
+		/// - `key`: The key of the attribute.
+		///
+		/// Emits `AttributeCleared`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::clear_attribute())]
+		pub(super) fn clear_attribute(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			maybe_instance: Option<T::InstanceId>,
+			key: BoundedVec<u8, T::KeyLimit>,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+			let is_frozen = match maybe_instance {
+				None => ClassMetadataOf::<T>::get(class).map(|m| m.is_frozen),
+				Some(instance) => InstanceMetadataOf::<T>::get(class, instance).map(|m| m.is_frozen),
+			};
+			ensure!(maybe_check_owner.is_none() || !is_frozen.unwrap_or(false), Error::<T>::Frozen);
+
+			let (_, deposit) = Attribute::<T>::take((class, maybe_instance, &key))
+				.ok_or(Error::<T>::Unknown)?;
+			class_details.attributes = class_details.attributes.saturating_sub(1);
+			class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+			T::Currency::unreserve(&class_details.owner, deposit);
+
+			Class::<T>::insert(class, &class_details);
+			Self::deposit_event(Event::AttributeCleared(class, maybe_instance, key));
+			Ok(())
+		}
+
+		/// Set the metadata for an asset instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// If the origin is Signed, then funds of the class owner are reserved according to the
+		/// formula: `MetadataDepositBase + DepositPerByte * data.len` taking into account any
+		/// already reserved funds, unless the class is free-holding.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to set.
+		/// - `instance`: The identifier of the asset instance whose metadata to set.
+		/// - `data`: The general information of this asset. Limited in length by `StringLimit`.
+		/// - `is_frozen`: Whether the metadata should be frozen against further changes by a
+		/// Signed origin.
+		///
+		/// Emits `MetadataSet`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::set_metadata())]
+		pub(super) fn set_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+			data: BoundedVec<u8, T::StringLimit>,
+			is_frozen: bool,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+			ensure!(Asset::<T>::contains_key(&class, &instance), Error::<T>::Unknown);
+
+			InstanceMetadataOf::<T>::try_mutate_exists(class, instance, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				if metadata.is_none() {
+					class_details.instance_metadatas = class_details.instance_metadatas
+						.checked_add(1)
+						.ok_or(ArithmeticError::Overflow)?;
+				}
+				let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+				let mut deposit = Zero::zero();
+				if !class_details.free_holding && maybe_check_owner.is_some() {
+					deposit = T::DepositPerByte::get()
+						.saturating_mul((data.len() as u32).into())
+						.saturating_add(T::MetadataDepositBase::get());
+				}
+				if deposit > old_deposit {
+					T::Currency::reserve(&class_details.owner, deposit - old_deposit)?;
+				} else {
+					T::Currency::unreserve(&class_details.owner, old_deposit - deposit);
+				}
+				class_details.total_deposit = class_details.total_deposit
+					.saturating_sub(old_deposit)
+					.saturating_add(deposit);
+
+				*metadata = Some(InstanceMetadata { deposit, data: data.clone(), is_frozen });
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(Event::MetadataSet(class, instance, data, is_frozen));
+				Ok(())
+			})
+		}
+
+		/// Clear the metadata for an asset instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// Any deposit is freed for the asset class owner.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to clear.
+		/// - `instance`: The identifier of the asset instance whose metadata to clear.
+		///
+		/// Emits `MetadataCleared`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::clear_metadata())]
+		pub(super) fn clear_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			#[pallet::compact] instance: T::InstanceId,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			InstanceMetadataOf::<T>::try_mutate_exists(class, instance, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				let deposit = metadata.take().ok_or(Error::<T>::Unknown)?.deposit;
+				class_details.instance_metadatas = class_details.instance_metadatas.saturating_sub(1);
+				class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+				T::Currency::unreserve(&class_details.owner, deposit);
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(Event::MetadataCleared(class, instance));
+				Ok(())
+			})
+		}
+
+		/// Set the metadata for an asset class.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of
+		/// the asset `class`.
+		///
+		/// If the origin is `Signed`, then funds of the class owner are reserved according to the
+		/// formula: `MetadataDepositBase + DepositPerByte * data.len` taking into account any
+		/// already reserved funds, unless the class is free-holding.
+		///
+		/// - `class`: The identifier of the asset whose metadata to update.
+		/// - `data`: The general information of this asset. Limited in length by `StringLimit`.
+		/// - `is_frozen`: Whether the metadata should be frozen against further changes by a
+		/// Signed origin.
+		///
+		/// Emits `ClassMetadataSet`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::set_class_metadata())]
+		pub(super) fn set_class_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+			data: BoundedVec<u8, T::StringLimit>,
+			is_frozen: bool,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			ClassMetadataOf::<T>::try_mutate_exists(class, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+				let mut deposit = Zero::zero();
+				if !class_details.free_holding && maybe_check_owner.is_some() {
+					deposit = T::DepositPerByte::get()
+						.saturating_mul((data.len() as u32).into())
+						.saturating_add(T::MetadataDepositBase::get());
+				}
+				if deposit > old_deposit {
+					T::Currency::reserve(&class_details.owner, deposit - old_deposit)?;
+				} else {
+					T::Currency::unreserve(&class_details.owner, old_deposit - deposit);
+				}
+				class_details.total_deposit = class_details.total_deposit
+					.saturating_sub(old_deposit)
+					.saturating_add(deposit);
+
+				*metadata = Some(ClassMetadata { deposit, data: data.clone(), is_frozen });
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(Event::ClassMetadataSet(class, data, is_frozen));
+				Ok(())
+			})
+		}
+
+		/// Clear the metadata for an asset class.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of
+		/// the asset `class`.
+		///
+		/// Any deposit is freed for the asset class owner.
+		///
+		/// - `class`: The identifier of the asset class whose metadata to clear.
+		///
+		/// Emits `ClassMetadataCleared`.
+		///
+		/// Weight: `O(1)`
+		#[pallet::weight(T::WeightInfo::clear_class_metadata())]
+		pub(super) fn clear_class_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] class: T::ClassId,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			ClassMetadataOf::<T>::try_mutate_exists(class, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				let deposit = metadata.take().ok_or(Error::<T>::Unknown)?.deposit;
+				class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+				T::Currency::unreserve(&class_details.owner, deposit);
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(Event::ClassMetadataCleared(class));
+				Ok(())
+			})
+		}
+	}
+}
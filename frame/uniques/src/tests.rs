@@ -0,0 +1,483 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for Uniques pallet.
+
+use super::*;
+use crate::mock::*;
+use sp_std::convert::TryInto;
+use frame_support::{assert_ok, assert_noop, BoundedVec, traits::{Currency, Get}};
+use pallet_balances::Error as BalancesError;
+
+fn last_event() -> mock::Event {
+	frame_system::Module::<Test>::events().pop().expect("Event expected").event
+}
+
+fn bvec<S: Get<u32>>(v: &[u8]) -> BoundedVec<u8, S> {
+	v.to_vec().try_into().unwrap()
+}
+
+fn assets() -> Vec<(u64, u32, u32)> {
+	let mut r: Vec<_> = Account::<Test>::iter().map(|x| x.0).collect();
+	r.sort();
+	let mut s: Vec<_> = Asset::<Test>::iter().map(|x| (x.2.owner, x.0, x.1)).collect();
+	s.sort();
+	assert_eq!(r, s);
+	r
+}
+
+fn attributes(class: u32) -> Vec<(Option<u32>, Vec<u8>, Vec<u8>)> {
+	let mut s: Vec<_> = Attribute::<Test>::iter_prefix((class,))
+		.map(|((instance, key), (value, _))| (instance, key.into_inner(), value.into_inner()))
+		.collect();
+	s.sort();
+	s
+}
+
+#[test]
+fn basic_setup_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(assets(), vec![]);
+	});
+}
+
+#[test]
+fn basic_minting_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, true));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 1));
+		assert_eq!(assets(), vec![(1, 0, 42)]);
+		assert_eq!(last_event(), mock::Event::pallet_uniques(crate::Event::Issued(0, 42, 1)));
+
+		assert_ok!(Uniques::force_create(Origin::root(), 1, 2, true));
+		assert_ok!(Uniques::mint(Origin::signed(2), 1, 69, 1));
+		assert_eq!(assets(), vec![(1, 0, 42), (1, 1, 69)]);
+
+		assert_noop!(Uniques::mint(Origin::signed(2), 1, 69, 2), Error::<Test>::AlreadyExists);
+		assert_noop!(Uniques::mint(Origin::signed(2), 2, 69, 2), Error::<Test>::Unknown);
+	});
+}
+
+#[test]
+fn lifecycle_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Uniques::create(Origin::signed(1), 0, 1));
+		assert_eq!(Balances::reserved_balance(&1), 2);
+		assert!(Class::<Test>::contains_key(0));
+		assert_noop!(Uniques::create(Origin::signed(1), 0, 1), Error::<Test>::InUse);
+
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0, 0]), false));
+		assert_eq!(Balances::reserved_balance(&1), 5);
+		assert!(ClassMetadataOf::<Test>::contains_key(0));
+
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 10));
+		assert_eq!(Balances::reserved_balance(&1), 6);
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 69, 20));
+		assert_eq!(Balances::reserved_balance(&1), 7);
+		assert_eq!(assets(), vec![(10, 0, 42), (20, 0, 69)]);
+
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[42, 42]), false));
+		assert_eq!(Balances::reserved_balance(&1), 10);
+		assert!(InstanceMetadataOf::<Test>::contains_key(0, 42));
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 69, bvec(&[69, 69]), false));
+		assert_eq!(Balances::reserved_balance(&1), 13);
+		assert!(InstanceMetadataOf::<Test>::contains_key(0, 69));
+
+		let w = Class::<Test>::get(0).unwrap().destroy_witness();
+		assert_eq!(w.instances, 2);
+		assert_eq!(w.instance_metadatas, 2);
+		assert_ok!(Uniques::destroy(Origin::signed(1), 0, w));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+
+		assert!(!Class::<Test>::contains_key(0));
+		assert!(!Asset::<Test>::contains_key(0, 42));
+		assert!(!Asset::<Test>::contains_key(0, 69));
+		assert!(!ClassMetadataOf::<Test>::contains_key(0));
+		assert!(!InstanceMetadataOf::<Test>::contains_key(0, 42));
+		assert!(!InstanceMetadataOf::<Test>::contains_key(0, 69));
+		assert_eq!(assets(), vec![]);
+	});
+}
+
+#[test]
+fn destroy_with_bad_witness_should_not_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Uniques::create(Origin::signed(1), 0, 1));
+
+		let w = Class::<Test>::get(0).unwrap().destroy_witness();
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 1));
+		assert_noop!(Uniques::destroy(Origin::signed(1), 0, w), Error::<Test>::BadWitness);
+	});
+}
+
+#[test]
+fn transfer_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, true));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 2));
+
+		assert_ok!(Uniques::transfer(Origin::signed(2), 0, 42, 3));
+		assert_eq!(assets(), vec![(3, 0, 42)]);
+		assert_eq!(last_event(), mock::Event::pallet_uniques(crate::Event::Transferred(0, 42, 2, 3)));
+		assert_noop!(Uniques::transfer(Origin::signed(2), 0, 42, 4), Error::<Test>::NoPermission);
+
+		assert_ok!(Uniques::approve_transfer(Origin::signed(3), 0, 42, 2));
+		assert_ok!(Uniques::transfer(Origin::signed(2), 0, 42, 4));
+		assert_eq!(assets(), vec![(4, 0, 42)]);
+
+		// The admin may always transfer.
+		assert_ok!(Uniques::transfer(Origin::signed(1), 0, 42, 5));
+		assert_eq!(assets(), vec![(5, 0, 42)]);
+	});
+}
+
+#[test]
+fn freezing_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, true));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 1));
+		assert_ok!(Uniques::freeze(Origin::signed(1), 0, 42));
+		assert_noop!(Uniques::transfer(Origin::signed(1), 0, 42, 2), Error::<Test>::Frozen);
+
+		assert_ok!(Uniques::thaw(Origin::signed(1), 0, 42));
+		assert_ok!(Uniques::freeze_class(Origin::signed(1), 0));
+		assert_noop!(Uniques::transfer(Origin::signed(1), 0, 42, 2), Error::<Test>::Frozen);
+
+		assert_ok!(Uniques::thaw_class(Origin::signed(1), 0));
+		assert_ok!(Uniques::transfer(Origin::signed(1), 0, 42, 2));
+	});
+}
+
+#[test]
+fn origin_guards_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, true));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 1));
+		assert_noop!(Uniques::transfer_ownership(Origin::signed(2), 0, 2), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::set_team(Origin::signed(2), 0, 2, 2, 2), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::freeze(Origin::signed(2), 0, 42), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::thaw(Origin::signed(2), 0, 42), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::freeze_class(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::thaw_class(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::mint(Origin::signed(2), 0, 69, 2), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::burn(Origin::signed(2), 0, 42, None), Error::<Test>::NoPermission);
+		let w = Class::<Test>::get(0).unwrap().destroy_witness();
+		assert_noop!(Uniques::destroy(Origin::signed(2), 0, w), Error::<Test>::NoPermission);
+	});
+}
+
+#[test]
+fn transfer_owner_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		Balances::make_free_balance_be(&2, 100);
+		Balances::make_free_balance_be(&3, 100);
+		assert_ok!(Uniques::create(Origin::signed(1), 0, 1));
+		assert_ok!(Uniques::transfer_ownership(Origin::signed(1), 0, 2));
+		assert_eq!(Balances::total_balance(&1), 98);
+		assert_eq!(Balances::total_balance(&2), 102);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::reserved_balance(&2), 2);
+
+		assert_noop!(Uniques::transfer_ownership(Origin::signed(1), 0, 1), Error::<Test>::NoPermission);
+
+		// Mint and set metadata now and make sure that deposit gets transferred back.
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(2), 0, bvec(&[0u8; 20]), false));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 1));
+		assert_ok!(Uniques::set_metadata(Origin::signed(2), 0, 42, bvec(&[0u8; 20]), false));
+		assert_eq!(Balances::reserved_balance(&2), 45);
+
+		assert_ok!(Uniques::transfer_ownership(Origin::signed(2), 0, 3));
+		assert_eq!(Balances::total_balance(&2), 57);
+		assert_eq!(Balances::total_balance(&3), 145);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(Balances::reserved_balance(&3), 45);
+	});
+}
+
+#[test]
+fn set_team_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, true));
+		assert_ok!(Uniques::set_team(Origin::signed(1), 0, 2, 3, 4));
+
+		assert_ok!(Uniques::mint(Origin::signed(2), 0, 42, 2));
+		assert_ok!(Uniques::freeze(Origin::signed(4), 0, 42));
+		assert_ok!(Uniques::thaw(Origin::signed(3), 0, 42));
+		assert_ok!(Uniques::transfer(Origin::signed(3), 0, 42, 3));
+		assert_ok!(Uniques::burn(Origin::signed(3), 0, 42, None));
+	});
+}
+
+#[test]
+fn set_class_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		// Cannot add metadata to unknown asset
+		assert_noop!(
+			Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 20]), false),
+			Error::<Test>::Unknown,
+		);
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, false));
+		// Cannot add metadata to unowned asset
+		assert_noop!(
+			Uniques::set_class_metadata(Origin::signed(2), 0, bvec(&[0u8; 20]), false),
+			Error::<Test>::NoPermission,
+		);
+
+		// Successfully add metadata and take deposit
+		Balances::make_free_balance_be(&1, 30);
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 20]), false));
+		assert_eq!(Balances::free_balance(&1), 9);
+		assert!(ClassMetadataOf::<Test>::contains_key(0));
+
+		// Force origin works, too.
+		assert_ok!(Uniques::set_class_metadata(Origin::root(), 0, bvec(&[0u8; 18]), false));
+
+		// Update deposit
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 15]), false));
+		assert_eq!(Balances::free_balance(&1), 14);
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 25]), false));
+		assert_eq!(Balances::free_balance(&1), 4);
+
+		// Cannot over-reserve
+		assert_noop!(
+			Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 40]), false),
+			BalancesError::<Test>::InsufficientBalance,
+		);
+
+		// Can't set or clear frozen metadata, except with the force origin
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 15]), true));
+		assert_noop!(
+			Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[0u8; 15]), false),
+			Error::<Test>::Frozen,
+		);
+		assert_noop!(Uniques::clear_class_metadata(Origin::signed(1), 0), Error::<Test>::Frozen);
+
+		// Clear Metadata
+		assert_ok!(Uniques::set_class_metadata(Origin::root(), 0, bvec(&[0u8; 15]), false));
+		assert_noop!(Uniques::clear_class_metadata(Origin::signed(2), 0), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::clear_class_metadata(Origin::signed(1), 1), Error::<Test>::Unknown);
+		assert_ok!(Uniques::clear_class_metadata(Origin::signed(1), 0));
+		assert!(!ClassMetadataOf::<Test>::contains_key(0));
+		assert_eq!(Balances::free_balance(&1), 30);
+	});
+}
+
+#[test]
+fn set_instance_metadata_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 30);
+
+		// Cannot add metadata to unknown asset
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, false));
+		assert_noop!(
+			Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 20]), false),
+			Error::<Test>::Unknown,
+		);
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 1));
+		// Cannot add metadata to unowned asset
+		assert_noop!(
+			Uniques::set_metadata(Origin::signed(2), 0, 42, bvec(&[0u8; 20]), false),
+			Error::<Test>::NoPermission,
+		);
+
+		// Successfully add metadata and take deposit
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 20]), false));
+		assert_eq!(Balances::free_balance(&1), 8);
+		assert!(InstanceMetadataOf::<Test>::contains_key(0, 42));
+
+		// Update deposit
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 15]), false));
+		assert_eq!(Balances::free_balance(&1), 13);
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 25]), false));
+		assert_eq!(Balances::free_balance(&1), 3);
+
+		// Cannot over-reserve
+		assert_noop!(
+			Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 40]), false),
+			BalancesError::<Test>::InsufficientBalance,
+		);
+
+		// Can't set or clear frozen metadata, except with the force origin
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 15]), true));
+		assert_noop!(
+			Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0u8; 15]), false),
+			Error::<Test>::Frozen,
+		);
+		assert_noop!(Uniques::clear_metadata(Origin::signed(1), 0, 42), Error::<Test>::Frozen);
+
+		// Clear Metadata
+		assert_ok!(Uniques::clear_metadata(Origin::root(), 0, 42));
+		assert!(!InstanceMetadataOf::<Test>::contains_key(0, 42));
+		assert_eq!(Balances::free_balance(&1), 29);
+		assert_noop!(Uniques::clear_metadata(Origin::signed(1), 0, 42), Error::<Test>::Unknown);
+		assert_eq!(Class::<Test>::get(0).unwrap().instance_metadatas, 0);
+	});
+}
+
+#[test]
+fn set_attribute_should_work() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, false));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 0, 1));
+
+		assert_ok!(Uniques::set_attribute(Origin::signed(1), 0, None, bvec(&[0]), bvec(&[0])));
+		assert_ok!(Uniques::set_attribute(Origin::signed(1), 0, Some(0), bvec(&[0]), bvec(&[0])));
+		assert_ok!(Uniques::set_attribute(Origin::signed(1), 0, Some(0), bvec(&[1]), bvec(&[0])));
+		assert_eq!(attributes(0), vec![
+			(None, vec![0], vec![0]),
+			(Some(0), vec![0], vec![0]),
+			(Some(0), vec![1], vec![0]),
+		]);
+		assert_eq!(Balances::reserved_balance(&1), 10);
+
+		assert_ok!(Uniques::set_attribute(Origin::signed(1), 0, None, bvec(&[0]), bvec(&[0; 10])));
+		assert_eq!(attributes(0), vec![
+			(None, vec![0], vec![0; 10]),
+			(Some(0), vec![0], vec![0]),
+			(Some(0), vec![1], vec![0]),
+		]);
+		assert_eq!(Balances::reserved_balance(&1), 19);
+
+		assert_ok!(Uniques::clear_attribute(Origin::signed(1), 0, Some(0), bvec(&[1])));
+		assert_eq!(attributes(0), vec![
+			(None, vec![0], vec![0; 10]),
+			(Some(0), vec![0], vec![0]),
+		]);
+		assert_eq!(Balances::reserved_balance(&1), 16);
+		assert_noop!(
+			Uniques::clear_attribute(Origin::signed(1), 0, Some(0), bvec(&[1])),
+			Error::<Test>::Unknown,
+		);
+
+		let w = Class::<Test>::get(0).unwrap().destroy_witness();
+		assert_eq!(w.attributes, 2);
+		assert_ok!(Uniques::destroy(Origin::signed(1), 0, w));
+		assert_eq!(attributes(0), vec![]);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+	});
+}
+
+#[test]
+fn burn_works() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, false));
+		assert_ok!(Uniques::set_team(Origin::signed(1), 0, 2, 3, 4));
+
+		assert_noop!(Uniques::burn(Origin::signed(5), 0, 42, Some(5)), Error::<Test>::Unknown);
+
+		assert_ok!(Uniques::mint(Origin::signed(2), 0, 42, 5));
+		assert_ok!(Uniques::mint(Origin::signed(2), 0, 69, 5));
+		assert_eq!(Balances::reserved_balance(&1), 2);
+
+		assert_ok!(Uniques::set_metadata(Origin::signed(1), 0, 42, bvec(&[0, 0]), false));
+		assert_ok!(Uniques::set_attribute(Origin::signed(1), 0, Some(42), bvec(&[0]), bvec(&[0])));
+		assert_eq!(Balances::reserved_balance(&1), 8);
+
+		assert_noop!(Uniques::burn(Origin::signed(0), 0, 42, None), Error::<Test>::NoPermission);
+		assert_noop!(Uniques::burn(Origin::signed(5), 0, 42, Some(6)), Error::<Test>::WrongOwner);
+
+		// Burning returns the deposits of the instance and its metadata, attributes are kept.
+		assert_ok!(Uniques::burn(Origin::signed(5), 0, 42, Some(5)));
+		assert_eq!(Balances::reserved_balance(&1), 4);
+		assert!(!InstanceMetadataOf::<Test>::contains_key(0, 42));
+		assert_eq!(attributes(0), vec![(Some(42), vec![0], vec![0])]);
+		let w = Class::<Test>::get(0).unwrap().destroy_witness();
+		assert_eq!((w.instances, w.instance_metadatas, w.attributes), (1, 0, 1));
+
+		// The attributes of a burned instance can still be cleared.
+		assert_ok!(Uniques::clear_attribute(Origin::signed(1), 0, Some(42), bvec(&[0])));
+		assert_eq!(Balances::reserved_balance(&1), 1);
+		assert_eq!(attributes(0), vec![]);
+
+		assert_ok!(Uniques::burn(Origin::signed(3), 0, 69, Some(5)));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(assets(), vec![]);
+	});
+}
+
+#[test]
+fn cancel_approval_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Uniques::force_create(Origin::root(), 0, 1, true));
+		assert_ok!(Uniques::mint(Origin::signed(1), 0, 42, 2));
+
+		assert_ok!(Uniques::approve_transfer(Origin::signed(2), 0, 42, 3));
+		assert_noop!(Uniques::cancel_approval(Origin::signed(2), 1, 42, None), Error::<Test>::Unknown);
+		assert_noop!(Uniques::cancel_approval(Origin::signed(2), 0, 43, None), Error::<Test>::Unknown);
+		assert_noop!(
+			Uniques::cancel_approval(Origin::signed(3), 0, 42, None),
+			Error::<Test>::NoPermission,
+		);
+		assert_noop!(
+			Uniques::cancel_approval(Origin::signed(2), 0, 42, Some(4)),
+			Error::<Test>::WrongDelegate,
+		);
+
+		assert_ok!(Uniques::cancel_approval(Origin::signed(2), 0, 42, Some(3)));
+		assert_noop!(Uniques::cancel_approval(Origin::signed(2), 0, 42, None), Error::<Test>::NoDelegate);
+		assert_noop!(Uniques::transfer(Origin::signed(3), 0, 42, 3), Error::<Test>::NoPermission);
+	});
+}
+
+#[test]
+fn nonfungibles_traits_work() {
+	use frame_support::traits::tokens::nonfungibles::{
+		Inspect, InspectEnumerable, Create, Mutate, Transfer,
+	};
+
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&1, 100);
+		assert_ok!(<Uniques as Create<u64>>::create_class(&0, &1, &1));
+		assert_ok!(<Uniques as Mutate<u64>>::mint_into(&0, &42, &2));
+		assert_ok!(<Uniques as Mutate<u64>>::mint_into(&0, &43, &2));
+		assert_eq!(<Uniques as Inspect<u64>>::owner(&0, &42), Some(2));
+		assert_eq!(<Uniques as Inspect<u64>>::class_owner(&0), Some(1));
+		assert_eq!(<Uniques as Inspect<u64>>::owner(&0, &44), None);
+
+		assert_ok!(Uniques::set_class_metadata(Origin::signed(1), 0, bvec(&[1, 2]), false));
+		assert_ok!(Uniques::set_attribute(Origin::signed(1), 0, Some(42), bvec(&[3]), bvec(&[4])));
+		assert_eq!(<Uniques as Inspect<u64>>::class_attribute(&0, &[]), Some(vec![1, 2]));
+		assert_eq!(<Uniques as Inspect<u64>>::attribute(&0, &42, &[3]), Some(vec![4]));
+		assert_eq!(<Uniques as Inspect<u64>>::attribute(&0, &43, &[3]), None);
+		assert_eq!(<Uniques as Inspect<u64>>::attribute(&0, &42, &[3; 51]), None);
+
+		assert_ok!(<Uniques as Transfer<u64>>::transfer(&0, &42, &3));
+		assert_eq!(<Uniques as InspectEnumerable<u64>>::owned(&3).collect::<Vec<_>>(), vec![(0, 42)]);
+		assert_eq!(
+			<Uniques as InspectEnumerable<u64>>::owned_in_class(&0, &2).collect::<Vec<_>>(),
+			vec![43],
+		);
+		let mut instances = <Uniques as InspectEnumerable<u64>>::instances(&0).collect::<Vec<_>>();
+		instances.sort();
+		assert_eq!(instances, vec![42, 43]);
+		assert_eq!(<Uniques as InspectEnumerable<u64>>::classes().collect::<Vec<_>>(), vec![0]);
+
+		assert_ok!(Uniques::freeze(Origin::signed(1), 0, 43));
+		assert!(<Uniques as Inspect<u64>>::can_transfer(&0, &42));
+		assert!(!<Uniques as Inspect<u64>>::can_transfer(&0, &43));
+
+		assert_ok!(<Uniques as Mutate<u64>>::burn_from(&0, &42));
+		assert_eq!(<Uniques as Inspect<u64>>::owner(&0, &42), None);
+		assert_eq!(assets(), vec![(2, 0, 43)]);
+	});
+}
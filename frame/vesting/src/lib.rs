@@ -32,6 +32,9 @@
 //! either `vest` (in typical case where the sender is calling on their own behalf) or `vest_other`
 //! in case the sender is calling on another account's behalf.
 //!
+//! An account can have up to `MaxVestingSchedules` vesting schedules at the same time. All of them
+//! share a single lock, which holds the sum of the amounts still locked by each schedule.
+//!
 //! ## Interface
 //!
 //! This module implements the `VestingSchedule` trait.
@@ -41,6 +44,9 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to an account and add a vesting schedule for them.
+//! - `merge_schedules` - Merge two vesting schedules of the sender into a single one.
+//! - `force_remove_vesting_schedule` - Remove a vesting schedule of an account.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -48,15 +54,16 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 use sp_std::prelude::*;
-use sp_std::fmt::Debug;
+use sp_std::{convert::TryInto, fmt::Debug};
 use codec::{Encode, Decode};
-use sp_runtime::{DispatchResult, RuntimeDebug, traits::{
-	StaticLookup, Zero, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert
+use sp_runtime::{DispatchResult, DispatchError, RuntimeDebug, traits::{
+	StaticLookup, Zero, One, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert, Saturating,
 }};
-use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure};
+use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure, BoundedVec};
 use frame_support::traits::{
 	Currency, LockableCurrency, VestingSchedule, WithdrawReasons, LockIdentifier,
 	ExistenceRequirement, Get,
@@ -66,6 +73,7 @@ pub use weights::WeightInfo;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type MaxLocksOf<T> = <<T as Config>::Currency as LockableCurrency<<T as frame_system::Config>::AccountId>>::MaxLocks;
+type VestingInfoOf<T> = VestingInfo<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
 
 pub trait Config: frame_system::Config {
 	/// The overarching event type.
@@ -80,12 +88,52 @@ pub trait Config: frame_system::Config {
 	/// The minimum amount transferred to call `vested_transfer`.
 	type MinVestedTransfer: Get<BalanceOf<Self>>;
 
+	/// The maximum number of vesting schedules an account can have at the same time.
+	type MaxVestingSchedules: Get<u32>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
 
+/// Storage layout version of the module.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// A single vesting schedule per account.
+	V0,
+	/// A bounded list of vesting schedules per account.
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
+/// Actions that can be performed on the vesting schedules of an account.
+#[derive(Clone, Copy)]
+enum VestingAction {
+	/// Only remove the completed schedules.
+	Passive,
+	/// Also remove the schedule at the given index.
+	Remove(usize),
+	/// Also remove the two schedules at the given indices, in order to merge them.
+	Merge(usize, usize),
+}
+
+impl VestingAction {
+	/// Whether the schedule at `index` is removed by this action.
+	fn should_remove(&self, index: usize) -> bool {
+		match *self {
+			VestingAction::Passive => false,
+			VestingAction::Remove(remove) => remove == index,
+			VestingAction::Merge(first, second) => first == index || second == index,
+		}
+	}
+}
+
 /// Struct to encode the vesting schedule of an individual account.
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct VestingInfo<Balance, BlockNumber> {
@@ -101,6 +149,18 @@ impl<
 	Balance: AtLeast32BitUnsigned + Copy,
 	BlockNumber: AtLeast32BitUnsigned + Copy,
 > VestingInfo<Balance, BlockNumber> {
+	/// Create a new vesting schedule.
+	pub fn new(locked: Balance, per_block: Balance, starting_block: BlockNumber) -> Self {
+		VestingInfo { locked, per_block, starting_block }
+	}
+
+	/// Whether the schedule ever unlocks anything.
+	///
+	/// This does not check the amount against `MinVestedTransfer`.
+	pub fn is_valid(&self) -> bool {
+		!self.locked.is_zero() && !self.per_block.is_zero()
+	}
+
 	/// Amount locked at block `n`.
 	pub fn locked_at<
 		BlockNumberToBalance: Convert<BlockNumber, Balance>
@@ -117,6 +177,26 @@ impl<
 			Zero::zero()
 		}
 	}
+
+	/// First block at which nothing is locked anymore, as a balance.
+	pub fn ending_block_as_balance<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self) -> Balance {
+		let starting_block = BlockNumberToBalance::convert(self.starting_block);
+		let per_block = self.per_block.max(One::one());
+		let duration = if per_block >= self.locked {
+			// Everything is unlocked in the first block.
+			One::one()
+		} else {
+			let remainder: Balance = if (self.locked % per_block).is_zero() {
+				Zero::zero()
+			} else {
+				One::one()
+			};
+			self.locked / per_block + remainder
+		};
+		duration.saturating_add(starting_block)
+	}
 }
 
 decl_storage! {
@@ -124,12 +204,17 @@ decl_storage! {
 		/// Information regarding the vesting of a given account.
 		pub Vesting get(fn vesting):
 			map hasher(blake2_128_concat) T::AccountId
-			=> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+			=> Option<BoundedVec<VestingInfo<BalanceOf<T>, T::BlockNumber>, T::MaxVestingSchedules>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the latest version, as determined by the genesis build.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V1): Releases;
 	}
 	add_extra_genesis {
 		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
 		build(|config: &GenesisConfig<T>| {
-			use sp_runtime::traits::Saturating;
+			use frame_support::storage::TryAppendMap;
 			// Generate initial vesting configuration
 			// * who - Account which we are generating vesting configuration for
 			// * begin - Block when the account will start to vest
@@ -142,12 +227,11 @@ decl_storage! {
 				let locked = balance.saturating_sub(liquid);
 				let length_as_balance = T::BlockNumberToBalance::convert(length);
 				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
+				let vesting_info = VestingInfo::new(locked, per_block, begin);
+				assert!(vesting_info.is_valid(), "Invalid vesting schedule at genesis");
 
-				Vesting::<T>::insert(who, VestingInfo {
-					locked: locked,
-					per_block: per_block,
-					starting_block: begin
-				});
+				Vesting::<T>::try_append(who, vesting_info)
+					.expect("Too many vesting schedules at genesis");
 				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
 				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
 			}
@@ -171,10 +255,15 @@ decl_error! {
 	pub enum Error for Module<T: Config> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
+		/// The account already has `MaxVestingSchedules` vesting schedules.
+		AtMaxVestingSchedules,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// An index was out of bounds of the vesting schedules.
+		ScheduleIndexOutOfBounds,
+		/// The vesting schedule never unlocks anything, because its `locked` or `per_block` is
+		/// zero.
+		InvalidScheduleParams,
 	}
 }
 
@@ -186,6 +275,9 @@ decl_module! {
 		/// The minimum amount to be transferred to create a new vesting schedule.
 		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
 
+		/// The maximum number of vesting schedules an account can have at the same time.
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
 		fn deposit_event() = default;
 
 		/// Unlock any vested funds of the sender account.
@@ -196,17 +288,17 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the sender.
 		/// - DbWeight: 2 Reads, 2 Writes
 		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
 		/// # </weight>
-		#[weight = T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
+		#[weight = T::WeightInfo::vest_locked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
 		]
 		fn vest(origin) -> DispatchResult {
 			let who = ensure_signed(origin)?;
-			Self::update_lock(who)
+			Self::do_vest(who)
 		}
 
 		/// Unlock any vested funds of a `target` account.
@@ -219,17 +311,17 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account
 		/// # </weight>
-		#[weight = T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
+		#[weight = T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
 		]
 		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
 			ensure_signed(origin)?;
-			Self::update_lock(T::Lookup::lookup(target)?)
+			Self::do_vest(T::Lookup::lookup(target)?)
 		}
 
 		/// Create a vested transfer.
@@ -237,35 +329,26 @@ decl_module! {
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// The schedule is added next to the existing schedules of `target`, if any.
+		///
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		/// # </weight>
-		#[weight = T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get())]
+		#[weight = T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())]
 		pub fn vested_transfer(
 			origin,
 			target: <T::Lookup as StaticLookup>::Source,
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
-
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
-
-			Ok(())
+			Self::do_vested_transfer(transactor, target, schedule)
 		}
 
 		/// Force a vested transfer.
@@ -274,18 +357,19 @@ decl_module! {
 		///
 		/// - `source`: The account whose funds should be transferred.
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// The schedule is added next to the existing schedules of `target`, if any.
+		///
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 4 Reads, 4 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, Source Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
 		/// # </weight>
-		#[weight = T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get())]
+		#[weight = T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())]
 		pub fn force_vested_transfer(
 			origin,
 			source: <T::Lookup as StaticLookup>::Source,
@@ -293,39 +377,232 @@ decl_module! {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let target = T::Lookup::lookup(target)?;
 			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
+			Self::do_vested_transfer(source, target, schedule)
+		}
 
-			T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+		/// Merge two vesting schedules of the sender into a single one.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `schedule1_index`: The index of the first schedule to merge.
+		/// - `schedule2_index`: The index of the second schedule to merge.
+		///
+		/// Any funds vested up to the current block are unlocked first. The merged schedule never
+		/// unlocks funds earlier than the two schedules would have:
+		/// - `locked`: the sum of the amounts still locked by both schedules.
+		/// - `starting_block`: the latest of both starting blocks and the current block.
+		/// - ending block: the latest of both ending blocks, or later because of rounding.
+		///
+		/// If one of the schedules has ended, the other one is kept unchanged. If both have ended,
+		/// they are both removed. Merging a schedule with itself is a no-op.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules of the sender.
+		/// - DbWeight: 2 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
+		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
+		/// # </weight>
+		#[weight = T::WeightInfo::not_unlocking_merge_schedules(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::unlocking_merge_schedules(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
+		]
+		fn merge_schedules(origin, schedule1_index: u32, schedule2_index: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if schedule1_index == schedule2_index {
+				return Ok(());
+			}
 
-			Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+			let schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let action = VestingAction::Merge(schedule1_index as usize, schedule2_index as usize);
+			let (schedules, locked_now) = Self::exec_action(schedules.into_inner(), action)?;
 
+			Self::write_vesting(&who, schedules)?;
+			Self::write_lock(&who, locked_now);
 			Ok(())
 		}
+
+		/// Remove a vesting schedule of an account, unlocking the funds it still locks.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		///
+		/// - `target`: The account whose vesting schedule should be removed.
+		/// - `schedule_index`: The index of the schedule to remove.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
+		/// - DbWeight: 3 Reads, 3 Writes
+		///     - Reads: Vesting Storage, Balances Locks, Target Account
+		///     - Writes: Vesting Storage, Balances Locks, Target Account
+		/// # </weight>
+		#[weight = T::WeightInfo::force_remove_vesting_schedule(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())]
+		fn force_remove_vesting_schedule(
+			origin,
+			target: <T::Lookup as StaticLookup>::Source,
+			schedule_index: u32,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let who = T::Lookup::lookup(target)?;
+			<Self as VestingSchedule<_>>::remove_vesting_schedule(&who, schedule_index)
+		}
 	}
 }
 
 impl<T: Config> Module<T> {
-	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
-	/// current unvested amount.
-	fn update_lock(who: T::AccountId) -> DispatchResult {
-		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+	/// Merge two vesting schedules into one that unlocks between the latest of both starting
+	/// blocks and `now`, and the latest of both ending blocks.
+	///
+	/// Returns the schedule that did not end yet if the other one did, and `None` if both ended.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		schedule1: VestingInfoOf<T>,
+		schedule2: VestingInfoOf<T>,
+	) -> Option<VestingInfoOf<T>> {
+		let schedule1_ending_block = schedule1.ending_block_as_balance::<T::BlockNumberToBalance>();
+		let schedule2_ending_block = schedule2.ending_block_as_balance::<T::BlockNumberToBalance>();
+		let now_as_balance = T::BlockNumberToBalance::convert(now);
+
+		match (schedule1_ending_block <= now_as_balance, schedule2_ending_block <= now_as_balance) {
+			(true, true) => return None,
+			(true, false) => return Some(schedule2),
+			(false, true) => return Some(schedule1),
+			(false, false) => {},
+		}
+
+		let locked = schedule1.locked_at::<T::BlockNumberToBalance>(now)
+			.saturating_add(schedule2.locked_at::<T::BlockNumberToBalance>(now));
+		let ending_block = schedule1_ending_block.max(schedule2_ending_block);
+		let starting_block = now.max(schedule1.starting_block).max(schedule2.starting_block);
+
+		// Rounding down `per_block` can only postpone the ending block.
+		let duration = ending_block
+			.saturating_sub(T::BlockNumberToBalance::convert(starting_block))
+			.max(One::one());
+		let per_block = (locked / duration).max(One::one());
+
+		Some(VestingInfo::new(locked, per_block, starting_block))
+	}
+
+	/// Transfer `schedule.locked` from `source` to `target` and add `schedule` to the vesting
+	/// schedules of `target`.
+	fn do_vested_transfer(
+		source: T::AccountId,
+		target: <T::Lookup as StaticLookup>::Source,
+		schedule: VestingInfoOf<T>,
+	) -> DispatchResult {
+		ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+		ensure!(schedule.is_valid(), Error::<T>::InvalidScheduleParams);
+
+		let target = T::Lookup::lookup(target)?;
+		// Check the schedule can be added before moving any funds.
+		Self::can_add_vesting_schedule(
+			&target,
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+		)?;
+
+		T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+		Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
+			.expect("schedule was checked to be addable; q.e.d.");
+
+		Ok(())
+	}
+
+	/// Remove the completed schedules and those removed by `action` from `schedules`.
+	///
+	/// Returns the remaining schedules and the amount they lock at the current block.
+	fn report_schedule_updates(
+		schedules: Vec<VestingInfoOf<T>>,
+		action: VestingAction,
+	) -> (Vec<VestingInfoOf<T>>, BalanceOf<T>) {
 		let now = <frame_system::Module<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		let mut total_locked_now: BalanceOf<T> = Zero::zero();
+		let schedules = schedules
+			.into_iter()
+			.enumerate()
+			.filter_map(|(index, schedule)| {
+				let locked_now = schedule.locked_at::<T::BlockNumberToBalance>(now);
+				if locked_now.is_zero() || action.should_remove(index) {
+					None
+				} else {
+					total_locked_now = total_locked_now.saturating_add(locked_now);
+					Some(schedule)
+				}
+			})
+			.collect();
+		(schedules, total_locked_now)
+	}
+
+	/// Apply `action` to `schedules` and remove the completed schedules.
+	///
+	/// Returns the updated schedules and the amount they lock at the current block.
+	fn exec_action(
+		schedules: Vec<VestingInfoOf<T>>,
+		action: VestingAction,
+	) -> Result<(Vec<VestingInfoOf<T>>, BalanceOf<T>), DispatchError> {
+		match action {
+			VestingAction::Passive => Ok(Self::report_schedule_updates(schedules, action)),
+			VestingAction::Remove(index) => {
+				ensure!(index < schedules.len(), Error::<T>::ScheduleIndexOutOfBounds);
+				Ok(Self::report_schedule_updates(schedules, action))
+			},
+			VestingAction::Merge(index1, index2) => {
+				// Indices refer to the schedules before the completed ones are removed.
+				let schedule1 = *schedules.get(index1).ok_or(Error::<T>::ScheduleIndexOutOfBounds)?;
+				let schedule2 = *schedules.get(index2).ok_or(Error::<T>::ScheduleIndexOutOfBounds)?;
+
+				// Two schedules are removed here, so there is room for the merged one.
+				let (mut schedules, mut locked_now) = Self::report_schedule_updates(schedules, action);
+
+				let now = <frame_system::Module<T>>::block_number();
+				if let Some(merged) = Self::merge_vesting_info(now, schedule1, schedule2) {
+					locked_now = locked_now.saturating_add(merged.locked_at::<T::BlockNumberToBalance>(now));
+					schedules.push(merged);
+				}
+				Ok((schedules, locked_now))
+			},
+		}
+	}
 
-		if locked_now.is_zero() {
-			T::Currency::remove_lock(VESTING_ID, &who);
-			Vesting::<T>::remove(&who);
-			Self::deposit_event(RawEvent::VestingCompleted(who));
+	/// Write the vesting schedules of `who`, removing the entry if there are none.
+	fn write_vesting(who: &T::AccountId, schedules: Vec<VestingInfoOf<T>>) -> DispatchResult {
+		let schedules: BoundedVec<_, T::MaxVestingSchedules> = schedules
+			.try_into()
+			.map_err(|_| Error::<T>::AtMaxVestingSchedules)?;
+		if schedules.is_empty() {
+			Vesting::<T>::remove(who);
+		} else {
+			Vesting::<T>::insert(who, schedules);
+		}
+		Ok(())
+	}
+
+	/// (Re)set or remove the module's currency lock on `who`'s account to `total_locked_now`.
+	fn write_lock(who: &T::AccountId, total_locked_now: BalanceOf<T>) {
+		if total_locked_now.is_zero() {
+			T::Currency::remove_lock(VESTING_ID, who);
+			Self::deposit_event(RawEvent::VestingCompleted(who.clone()));
 		} else {
 			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
-			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
+			T::Currency::set_lock(VESTING_ID, who, total_locked_now, reasons);
+			Self::deposit_event(RawEvent::VestingUpdated(who.clone(), total_locked_now));
 		}
+	}
+
+	/// Update the lock of `who` in accordance with their current unvested amount, removing the
+	/// completed schedules.
+	fn do_vest(who: T::AccountId) -> DispatchResult {
+		let schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		let (schedules, locked_now) =
+			Self::exec_action(schedules.into_inner(), VestingAction::Passive)?;
+
+		Self::write_vesting(&who, schedules)?;
+		Self::write_lock(&who, locked_now);
 		Ok(())
 	}
 }
@@ -338,9 +615,11 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
+		if let Some(schedules) = Self::vesting(who) {
 			let now = <frame_system::Module<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			let locked_now = schedules.iter().fold(Zero::zero(), |total: BalanceOf<T>, schedule| {
+				total.saturating_add(schedule.locked_at::<T::BlockNumberToBalance>(now))
+			});
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
 			None
@@ -349,7 +628,7 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// If the account already has `MaxVestingSchedules` vesting schedules, an `Err` is returned
 	/// and nothing is updated.
 	///
 	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
@@ -364,25 +643,48 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
-		}
-		let vesting_schedule = VestingInfo {
-			locked,
-			per_block,
-			starting_block
-		};
-		Vesting::<T>::insert(who, vesting_schedule);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let _ = Self::update_lock(who.clone());
+
+		let vesting_schedule = VestingInfo::new(locked, per_block, starting_block);
+		ensure!(vesting_schedule.is_valid(), Error::<T>::InvalidScheduleParams);
+
+		let mut schedules = Self::vesting(who).unwrap_or_default();
+		// The new schedule must be in the list for the lock to account for it.
+		schedules.try_push(vesting_schedule).map_err(|_| Error::<T>::AtMaxVestingSchedules)?;
+		let (schedules, locked_now) =
+			Self::exec_action(schedules.into_inner(), VestingAction::Passive)?;
+
+		Self::write_vesting(who, schedules)?;
+		Self::write_lock(who, locked_now);
+		Ok(())
+	}
+
+	/// Checks whether a vesting schedule can be added to a given account, without adding it.
+	fn can_add_vesting_schedule(
+		who: &T::AccountId,
+		locked: BalanceOf<T>,
+		per_block: BalanceOf<T>,
+		starting_block: T::BlockNumber
+	) -> DispatchResult {
+		ensure!(
+			VestingInfo::new(locked, per_block, starting_block).is_valid(),
+			Error::<T>::InvalidScheduleParams,
+		);
+		ensure!(
+			(Vesting::<T>::decode_len(who).unwrap_or_default() as u32) < T::MaxVestingSchedules::get(),
+			Error::<T>::AtMaxVestingSchedules,
+		);
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
-	fn remove_vesting_schedule(who: &T::AccountId) {
-		Vesting::<T>::remove(who);
-		// it can't fail, but even if somehow it did, we don't really care.
-		let _ = Self::update_lock(who.clone());
+	/// Remove the vesting schedule at `schedule_index` of a given account.
+	fn remove_vesting_schedule(who: &T::AccountId, schedule_index: u32) -> DispatchResult {
+		let schedules = Self::vesting(who).ok_or(Error::<T>::NotVesting)?;
+		let action = VestingAction::Remove(schedule_index as usize);
+		let (schedules, locked_now) = Self::exec_action(schedules.into_inner(), action)?;
+
+		Self::write_vesting(who, schedules)?;
+		Self::write_lock(who, locked_now);
+		Ok(())
 	}
 }
 
@@ -453,10 +755,12 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = MaxLocks;
+		type MaxReserves = ();
 		type WeightInfo = ();
 	}
 	parameter_types! {
 		pub const MinVestedTransfer: u64 = 256 * 2;
+		pub const MaxVestingSchedules: u32 = 3;
 		pub static ExistentialDeposit: u64 = 0;
 	}
 	impl Config for Test {
@@ -464,6 +768,7 @@ mod tests {
 		type Currency = Balances;
 		type BlockNumberToBalance = Identity;
 		type MinVestedTransfer = MinVestedTransfer;
+		type MaxVestingSchedules = MaxVestingSchedules;
 		type WeightInfo = ();
 	}
 
@@ -534,9 +839,9 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+				assert_eq!(Vesting::vesting(&1).unwrap(), vec![user1_vesting_schedule]); // Account 1 has a vesting schedule
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]); // Account 2 has a vesting schedule
+				assert_eq!(Vesting::vesting(&12).unwrap(), vec![user12_vesting_schedule]); // Account 12 has a vesting schedule
 
 				// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
 				assert_eq!(Vesting::vesting_balance(&1), Some(128 * 9));
@@ -657,7 +962,7 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+				assert_eq!(Vesting::vesting(&12).unwrap(), vec![user12_vesting_schedule]);
 
 				// Account 12 can still send liquid funds
 				assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
@@ -684,7 +989,7 @@ mod tests {
 				};
 				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4).unwrap(), vec![new_vesting_schedule]);
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -723,17 +1028,17 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]);
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// Fails due to a schedule that never unlocks anything.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
-					per_block: 64, // Vesting over 20 blocks
+					per_block: 0,
 					starting_block: 10,
 				};
 				assert_noop!(
 					Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::InvalidScheduleParams,
 				);
 
 				// Fails due to too low transfer amount.
@@ -774,7 +1079,7 @@ mod tests {
 				assert_noop!(Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule), BadOrigin);
 				assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4).unwrap(), vec![new_vesting_schedule]);
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -813,17 +1118,17 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]);
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// Fails due to a schedule that never unlocks anything.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
-					per_block: 64, // Vesting over 20 blocks
+					per_block: 0,
 					starting_block: 10,
 				};
 				assert_noop!(
 					Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::InvalidScheduleParams,
 				);
 
 				// Fails due to too low transfer amount.
@@ -842,4 +1147,219 @@ mod tests {
 				assert_eq!(user4_free_balance, 256 * 40);
 			});
 	}
+
+	#[test]
+	fn vested_transfer_adds_schedule_to_vesting_account() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo::new(256 * 20, 256, 10);
+				let new_vesting_schedule = VestingInfo::new(256 * 10, 128, 20);
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+
+				assert_eq!(
+					Vesting::vesting(&2).unwrap(),
+					vec![user2_vesting_schedule, new_vesting_schedule],
+				);
+				assert_eq!(Balances::free_balance(&2), 256 * 30);
+				// Both schedules are covered by the lock.
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 30));
+
+				System::set_block_number(25);
+				// 5 * 256 locked by the first schedule, 15 * 128 by the second one.
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 5 + 128 * 15));
+				assert_ok!(Vesting::vest(Some(2).into()));
+				assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 30 - 256 * 5 - 128 * 15));
+			});
+	}
+
+	#[test]
+	fn vested_transfer_respects_max_vesting_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let new_vesting_schedule = VestingInfo::new(256 * 2, 64, 10);
+				for _ in 0..MaxVestingSchedules::get() {
+					assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
+				}
+				assert_eq!(Vesting::vesting(&4).unwrap().len(), 3);
+				assert_eq!(Vesting::vesting_balance(&4), Some(256 * 2 * 3));
+
+				// No funds are transferred if the schedule can not be added.
+				assert_noop!(
+					Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule),
+					Error::<Test>::AtMaxVestingSchedules,
+				);
+				assert_noop!(
+					Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, new_vesting_schedule),
+					Error::<Test>::AtMaxVestingSchedules,
+				);
+				assert_noop!(
+					Vesting::add_vesting_schedule(&4, 256 * 2, 64, 10),
+					Error::<Test>::AtMaxVestingSchedules,
+				);
+			});
+	}
+
+	#[test]
+	fn vest_removes_completed_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user1_vesting_schedule = VestingInfo::new(256 * 5, 128, 0);
+				let new_vesting_schedule = VestingInfo::new(256 * 4, 64, 10);
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 1, new_vesting_schedule));
+				assert_eq!(
+					Vesting::vesting(&1).unwrap(),
+					vec![user1_vesting_schedule, new_vesting_schedule],
+				);
+
+				System::set_block_number(15);
+				assert_ok!(Vesting::vest(Some(1).into()));
+				assert_eq!(Vesting::vesting(&1).unwrap(), vec![new_vesting_schedule]);
+				assert_eq!(Vesting::vesting_balance(&1), Some(256 * 4 - 64 * 5));
+
+				System::set_block_number(26);
+				assert_ok!(Vesting::vest(Some(1).into()));
+				assert_eq!(Vesting::vesting(&1), None);
+				assert_eq!(Vesting::vesting_balance(&1), None);
+			});
+	}
+
+	#[test]
+	fn merge_ongoing_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo::new(256 * 20, 256, 10);
+				let new_vesting_schedule = VestingInfo::new(256 * 10, 128, 20);
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+				assert_eq!(user2_vesting_schedule.ending_block_as_balance::<Identity>(), 30);
+				assert_eq!(new_vesting_schedule.ending_block_as_balance::<Identity>(), 40);
+
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 1));
+
+				// Unlocks from the latest starting block until the latest ending block.
+				let merged_schedule = VestingInfo::new(256 * 30, 256 * 30 / 20, 20);
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![merged_schedule]);
+				assert_eq!(merged_schedule.ending_block_as_balance::<Identity>(), 40);
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 30));
+
+				// The merged schedule never unlocks more than the two original ones.
+				for n in 0..45 {
+					assert!(
+						merged_schedule.locked_at::<Identity>(n) >=
+							user2_vesting_schedule.locked_at::<Identity>(n) +
+								new_vesting_schedule.locked_at::<Identity>(n)
+					);
+				}
+			});
+	}
+
+	#[test]
+	fn merge_with_ended_schedules() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let new_vesting_schedule = VestingInfo::new(256 * 4, 64, 10);
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 1, new_vesting_schedule));
+
+				// The genesis schedule of account 1 ended at block 10, the other one is kept as is.
+				System::set_block_number(12);
+				assert_ok!(Vesting::merge_schedules(Some(1).into(), 1, 0));
+				assert_eq!(Vesting::vesting(&1).unwrap(), vec![new_vesting_schedule]);
+				assert_eq!(Vesting::vesting_balance(&1), Some(256 * 4 - 64 * 2));
+
+				// Both schedules ended, the vesting is completed.
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 1, new_vesting_schedule));
+				System::set_block_number(30);
+				assert_ok!(Vesting::merge_schedules(Some(1).into(), 0, 1));
+				assert_eq!(Vesting::vesting(&1), None);
+				assert_eq!(Vesting::vesting_balance(&1), None);
+				assert_eq!(
+					System::events().last().unwrap().event,
+					Event::pallet_vesting(RawEvent::VestingCompleted(1)),
+				);
+			});
+	}
+
+	#[test]
+	fn merge_schedules_correctly_fails() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo::new(256 * 20, 256, 10);
+
+				// Merging a schedule with itself is a no-op.
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 0, 0));
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![user2_vesting_schedule]);
+
+				assert_noop!(
+					Vesting::merge_schedules(Some(2).into(), 0, 1),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+				assert_noop!(
+					Vesting::merge_schedules(Some(4).into(), 0, 1),
+					Error::<Test>::NotVesting,
+				);
+			});
+	}
+
+	#[test]
+	fn force_remove_vesting_schedule_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let new_vesting_schedule = VestingInfo::new(256 * 10, 128, 20);
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+
+				assert_noop!(Vesting::force_remove_vesting_schedule(Some(2).into(), 2, 0), BadOrigin);
+				assert_noop!(
+					Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 2),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+
+				assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 0));
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![new_vesting_schedule]);
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 10));
+
+				assert_ok!(Vesting::force_remove_vesting_schedule(RawOrigin::Root.into(), 2, 0));
+				assert_eq!(Vesting::vesting(&2), None);
+				assert_ok!(Balances::transfer(Some(2).into(), 3, 256 * 30));
+			});
+	}
+
+	#[test]
+	fn migration_to_v1_works() {
+		use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade};
+
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				assert_eq!(StorageVersion::get(), Releases::V1);
+
+				// Write a single schedule for account 4, as in the old layout.
+				let schedule = VestingInfo::new(256 * 5, 64, 10);
+				unhashed::put(&super::Vesting::<Test>::hashed_key_for(&4), &schedule);
+				StorageVersion::put(Releases::V0);
+
+				migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+				assert_eq!(StorageVersion::get(), Releases::V1);
+				assert_eq!(Vesting::vesting(&4).unwrap(), vec![schedule]);
+				assert_eq!(Vesting::vesting(&2).unwrap(), vec![VestingInfo::new(256 * 20, 256, 10)]);
+
+				// Running it again does nothing.
+				migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+				assert_eq!(Vesting::vesting(&4).unwrap(), vec![schedule]);
+			});
+	}
 }
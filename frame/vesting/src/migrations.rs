@@ -0,0 +1,96 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the vesting module.
+
+use super::*;
+
+/// Migration from a single vesting schedule per account (`V0`) to a bounded list of vesting
+/// schedules per account (`V1`).
+pub mod v1 {
+	use super::*;
+	use frame_support::{
+		storage::{IterableStorageMap, migration::StorageIterator},
+		traits::OnRuntimeUpgrade,
+		weights::Weight,
+	};
+	use sp_std::marker::PhantomData;
+
+	/// Wraps the vesting schedule of every account into a list.
+	///
+	/// Does nothing if the storage is already at `V1`, so it can be kept in the runtime after it
+	/// ran.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() != Releases::V0 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let mut migrated: Weight = 0;
+			Vesting::<T>::translate::<VestingInfoOf<T>, _>(|_who, schedule| {
+				migrated += 1;
+				// A single schedule fits unless `MaxVestingSchedules` is zero.
+				let mut schedules = BoundedVec::default();
+				schedules.try_push(schedule).ok()?;
+				Some(schedules)
+			});
+			StorageVersion::put(Releases::V1);
+
+			T::DbWeight::get().reads_writes(migrated + 1, migrated + 1)
+		}
+
+		/// Records the number of vesting accounts and the total amount they lock.
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			if StorageVersion::get() != Releases::V0 {
+				return Ok(Vec::new());
+			}
+
+			let mut accounts = 0u32;
+			let mut locked: BalanceOf<T> = Zero::zero();
+			for (_, schedule) in StorageIterator::<VestingInfoOf<T>>::new(b"Vesting", b"Vesting") {
+				accounts += 1;
+				locked = locked.saturating_add(schedule.locked);
+			}
+			Ok((accounts, locked).encode())
+		}
+
+		/// Checks that every account still vests the same amount, with a single schedule.
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(StorageVersion::get() == Releases::V1, "storage version was not updated");
+			if state.is_empty() {
+				return Ok(());
+			}
+
+			let (expected_accounts, expected_locked) = <(u32, BalanceOf<T>)>::decode(&mut &*state)
+				.map_err(|_| "invalid state returned by `pre_upgrade`")?;
+			let mut accounts = 0u32;
+			let mut locked: BalanceOf<T> = Zero::zero();
+			for (_, schedules) in Vesting::<T>::iter() {
+				ensure!(schedules.len() == 1, "an account has more than one vesting schedule");
+				accounts += 1;
+				locked = locked.saturating_add(schedules[0].locked);
+			}
+			ensure!(accounts == expected_accounts, "vesting accounts were lost");
+			ensure!(locked == expected_locked, "vested amount changed");
+			Ok(())
+		}
+	}
+}
@@ -16,9 +16,11 @@
 // limitations under the License.
 
 //! Weights for pallet_vesting
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.0
-//! DATE: 2020-10-27, STEPS: `[50, ]`, REPEAT: 20, LOW RANGE: [], HIGH RANGE: []
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//!
+//! The base weights and the weights per lock were generated with the benchmark CLI version 2.0.0
+//! on 2020-10-27 (steps 50, repeat 20, wasm execution, dev chain), when accounts had a single
+//! vesting schedule. The weights per schedule and of the calls added since are not benchmarked
+//! yet and are estimated from them until this file is regenerated with the command below.
 
 // Executed Command:
 // target/release/substrate
@@ -43,106 +45,140 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_vesting.
 pub trait WeightInfo {
-	fn vest_locked(l: u32, ) -> Weight;
-	fn vest_unlocked(l: u32, ) -> Weight;
-	fn vest_other_locked(l: u32, ) -> Weight;
-	fn vest_other_unlocked(l: u32, ) -> Weight;
-	fn vested_transfer(l: u32, ) -> Weight;
-	fn force_vested_transfer(l: u32, ) -> Weight;
-
+	fn vest_locked(l: u32, s: u32, ) -> Weight;
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight;
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight;
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight;
+	fn vested_transfer(l: u32, s: u32, ) -> Weight;
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight;
+	fn not_unlocking_merge_schedules(l: u32, s: u32, ) -> Weight;
+	fn unlocking_merge_schedules(l: u32, s: u32, ) -> Weight;
+	fn force_remove_vesting_schedule(l: u32, s: u32, ) -> Weight;
 }
 
 /// Weights for pallet_vesting using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn vest_locked(l: u32, ) -> Weight {
+	fn vest_locked(l: u32, s: u32, ) -> Weight {
 		(57_472_000 as Weight)
 			.saturating_add((155_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((155_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
-
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
-	fn vest_unlocked(l: u32, ) -> Weight {
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_681_000 as Weight)
 			.saturating_add((138_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((138_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-
 	}
-	fn vest_other_locked(l: u32, ) -> Weight {
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight {
 		(56_910_000 as Weight)
 			.saturating_add((160_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((160_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
-
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_319_000 as Weight)
 			.saturating_add((144_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((144_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
-
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
+	fn vested_transfer(l: u32, s: u32, ) -> Weight {
 		(124_996_000 as Weight)
 			.saturating_add((209_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((209_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(3 as Weight))
-
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight {
 		(123_911_000 as Weight)
 			.saturating_add((213_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((213_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
-
 	}
-
+	// Not benchmarked yet. Merging vests the schedules and writes them back like vesting does,
+	// removing a schedule does the same for another account.
+	fn not_unlocking_merge_schedules(l: u32, s: u32, ) -> Weight {
+		Self::vest_locked(l, s)
+	}
+	fn unlocking_merge_schedules(l: u32, s: u32, ) -> Weight {
+		Self::vest_unlocked(l, s)
+	}
+	fn force_remove_vesting_schedule(l: u32, s: u32, ) -> Weight {
+		Self::vest_other_unlocked(l, s)
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn vest_locked(l: u32, ) -> Weight {
+	fn vest_locked(l: u32, s: u32, ) -> Weight {
 		(57_472_000 as Weight)
 			.saturating_add((155_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((155_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
-
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
-	fn vest_unlocked(l: u32, ) -> Weight {
+	fn vest_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_681_000 as Weight)
 			.saturating_add((138_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((138_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
-
 	}
-	fn vest_other_locked(l: u32, ) -> Weight {
+	fn vest_other_locked(l: u32, s: u32, ) -> Weight {
 		(56_910_000 as Weight)
 			.saturating_add((160_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((160_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
-
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
-	fn vest_other_unlocked(l: u32, ) -> Weight {
+	fn vest_other_unlocked(l: u32, s: u32, ) -> Weight {
 		(61_319_000 as Weight)
 			.saturating_add((144_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((144_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
-
 	}
-	fn vested_transfer(l: u32, ) -> Weight {
+	fn vested_transfer(l: u32, s: u32, ) -> Weight {
 		(124_996_000 as Weight)
 			.saturating_add((209_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((209_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
-
 	}
-	fn force_vested_transfer(l: u32, ) -> Weight {
+	fn force_vested_transfer(l: u32, s: u32, ) -> Weight {
 		(123_911_000 as Weight)
 			.saturating_add((213_000 as Weight).saturating_mul(l as Weight))
+			// Not benchmarked yet, charged like a lock.
+			.saturating_add((213_000 as Weight).saturating_mul(s as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
-
 	}
-
+	// Not benchmarked yet. Merging vests the schedules and writes them back like vesting does,
+	// removing a schedule does the same for another account.
+	fn not_unlocking_merge_schedules(l: u32, s: u32, ) -> Weight {
+		Self::vest_locked(l, s)
+	}
+	fn unlocking_merge_schedules(l: u32, s: u32, ) -> Weight {
+		Self::vest_unlocked(l, s)
+	}
+	fn force_remove_vesting_schedule(l: u32, s: u32, ) -> Weight {
+		Self::vest_other_unlocked(l, s)
+	}
 }
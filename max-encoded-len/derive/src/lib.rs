@@ -0,0 +1,180 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Derive macro for `MaxEncodedLen`.
+
+use proc_macro2::{Span, TokenStream};
+use proc_macro_crate::crate_name;
+use quote::{quote, quote_spanned};
+use syn::{
+	Data, DeriveInput, Fields, GenericParam, Generics, Ident, parse_quote, spanned::Spanned,
+};
+
+/// Derive `MaxEncodedLen`.
+#[proc_macro_derive(MaxEncodedLen)]
+pub fn derive_max_encoded_len(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input: DeriveInput = match syn::parse(input) {
+		Ok(input) => input,
+		Err(e) => return e.to_compile_error().into(),
+	};
+
+	let mel_crate = match max_encoded_len_crate() {
+		Ok(mel_crate) => mel_crate,
+		Err(e) => return e.to_compile_error().into(),
+	};
+
+	let name = &input.ident;
+	let generics = add_trait_bounds(input.generics, &mel_crate);
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+	let data_expr = match data_length_expr(&input.data, &mel_crate) {
+		Ok(data_expr) => data_expr,
+		Err(e) => return e.to_compile_error().into(),
+	};
+
+	quote!(
+		const _: () = {
+			impl #impl_generics #mel_crate::MaxEncodedLen for #name #ty_generics #where_clause {
+				fn max_encoded_len() -> usize {
+					#data_expr
+				}
+			}
+		};
+	)
+	.into()
+}
+
+/// Find the path to the crate defining `MaxEncodedLen`.
+///
+/// Either `max-encoded-len` is a direct dependency, or it is accessed through its re-export in
+/// `frame-support`.
+fn max_encoded_len_crate() -> syn::Result<TokenStream> {
+	const MEL_CRATE: &str = "max-encoded-len";
+	const FRAME_SUPPORT_CRATE: &str = "frame-support";
+
+	let pkg_name = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+	if pkg_name == MEL_CRATE {
+		return Ok(quote!(max_encoded_len));
+	}
+	if let Ok(name) = crate_name(MEL_CRATE) {
+		let name = Ident::new(&name, Span::call_site());
+		return Ok(quote!(#name));
+	}
+	if pkg_name == FRAME_SUPPORT_CRATE {
+		return Ok(quote!(frame_support::max_encoded_len));
+	}
+	match crate_name(FRAME_SUPPORT_CRATE) {
+		Ok(name) => {
+			let name = Ident::new(&name, Span::call_site());
+			Ok(quote!(#name::max_encoded_len))
+		},
+		Err(_) => Err(syn::Error::new(
+			Span::call_site(),
+			"MaxEncodedLen derive requires either `max-encoded-len` or `frame-support` as a \
+			dependency",
+		)),
+	}
+}
+
+/// Add a bound `T: MaxEncodedLen` to every type parameter `T`.
+fn add_trait_bounds(mut generics: Generics, mel_crate: &TokenStream) -> Generics {
+	for param in &mut generics.params {
+		if let GenericParam::Type(ref mut type_param) = *param {
+			type_param.bounds.push(parse_quote!(#mel_crate::MaxEncodedLen));
+		}
+	}
+	generics
+}
+
+/// Generate an expression to sum up the max encoded length from several fields.
+fn fields_length_expr(fields: &Fields, mel_crate: &TokenStream) -> TokenStream {
+	let type_iter: Box<dyn Iterator<Item = &syn::Field>> = match fields {
+		Fields::Named(ref fields) => Box::new(fields.named.iter()),
+		Fields::Unnamed(ref fields) => Box::new(fields.unnamed.iter()),
+		Fields::Unit => Box::new(std::iter::empty()),
+	};
+	// expands to an expression like
+	//
+	//   0
+	//     .saturating_add(<type of first field>::max_encoded_len())
+	//     .saturating_add(<type of second field>::max_encoded_len())
+	//
+	// We match the span of each field to the span of the corresponding
+	// `max_encoded_len` call. This way, if one field's type doesn't implement
+	// `MaxEncodedLen`, the compiler's error message will underline which field
+	// caused the issue.
+	let expansion = type_iter.map(|field| {
+		let ty = &field.ty;
+		if is_compact(field) {
+			quote_spanned! {
+				ty.span() => .saturating_add(
+					<#mel_crate::__private::Compact<#ty> as #mel_crate::MaxEncodedLen>::max_encoded_len()
+				)
+			}
+		} else {
+			quote_spanned! {
+				ty.span() => .saturating_add(<#ty as #mel_crate::MaxEncodedLen>::max_encoded_len())
+			}
+		}
+	});
+	quote! {
+		0_usize #( #expansion )*
+	}
+}
+
+/// Whether the field is annotated with `#[codec(compact)]`.
+fn is_compact(field: &syn::Field) -> bool {
+	field.attrs.iter().any(|attr| {
+		attr.path.is_ident("codec")
+			&& attr.parse_args::<Ident>().map_or(false, |ident| ident == "compact")
+	})
+}
+
+// generate an expression to sum up the max encoded length of each field
+fn data_length_expr(data: &Data, mel_crate: &TokenStream) -> syn::Result<TokenStream> {
+	match *data {
+		Data::Struct(ref data) => Ok(fields_length_expr(&data.fields, mel_crate)),
+		Data::Enum(ref data) => {
+			// We need an expression expanded for each variant like
+			//
+			//   0
+			//     .max(<variant expression>)
+			//     .max(<variant expression>)
+			//     .saturating_add(1)
+			//
+			// The 1 derives from the discriminant, which `parity-scale-codec` encodes as a single
+			// byte before the fields of the variant.
+			//
+			// Each variant expression's sum is computed the way an equivalent struct's would be.
+
+			let expansion = data.variants.iter().map(|variant| {
+				let variant_expression = fields_length_expr(&variant.fields, mel_crate);
+				quote! {
+					.max(#variant_expression)
+				}
+			});
+
+			Ok(quote! {
+				0_usize #( #expansion )* .saturating_add(1)
+			})
+		},
+		Data::Union(ref data) => {
+			// `parity-scale-codec` doesn't support encoding unions either.
+			Err(syn::Error::new(data.union_token.span(), "Union types are not supported"))
+		},
+	}
+}
@@ -0,0 +1,172 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `trait MaxEncodedLen` bounds the max encoded length of items.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Allows the derive macro to refer to this crate as `max_encoded_len` from within its own tests.
+extern crate self as max_encoded_len;
+
+use codec::{Compact, Encode};
+use impl_trait_for_tuples::impl_for_tuples;
+use core::{mem, marker::PhantomData};
+use primitive_types::{H160, H256, H512, U256};
+
+/// Derive macro for [`MaxEncodedLen`][max_encoded_len::MaxEncodedLen].
+///
+/// ```
+/// # use max_encoded_len::MaxEncodedLen;
+/// # use codec::Encode;
+/// #[derive(Encode, MaxEncodedLen)]
+/// struct Example;
+/// ```
+///
+/// The generated implementation sums the maximum encoded length of all fields of a struct, and
+/// takes the largest variant plus one byte for the discriminant of an enum. Every generic type
+/// parameter is bounded by `MaxEncodedLen`.
+#[cfg(feature = "derive")]
+pub use max_encoded_len_derive::MaxEncodedLen;
+
+#[doc(hidden)]
+pub mod __private {
+	pub use codec::Compact;
+}
+
+/// Items implementing `MaxEncodedLen` have a statically known maximum encoded size.
+///
+/// Some containers, such as `BoundedVec`, have enforced size limits and this trait
+/// can be implemented accurately. Other containers, such as `StorageMap`, do not have enforced size
+/// limits. For those containers, it is necessary to make a documented assumption about the maximum
+/// usage, and compute the max encoded length based on that assumption.
+pub trait MaxEncodedLen: Encode {
+	/// Upper bound, in bytes, of the maximum encoded size of this item.
+	fn max_encoded_len() -> usize;
+}
+
+macro_rules! impl_primitives {
+	( $($t:ty),+ ) => {
+		$(
+			impl MaxEncodedLen for $t {
+				fn max_encoded_len() -> usize {
+					mem::size_of::<$t>()
+				}
+			}
+		)+
+	};
+}
+
+impl_primitives!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, H160, H256, H512, U256);
+
+macro_rules! impl_compact {
+	($( $t:ty => $e:expr; )*) => {
+		$(
+			impl MaxEncodedLen for Compact<$t> {
+				fn max_encoded_len() -> usize {
+					$e
+				}
+			}
+		)*
+	};
+}
+
+impl_compact!(
+	// Single byte prefix for up to 2^6, two bytes for up to 2^14.
+	u8 => 2;
+	// Up to 2^30 fits in four bytes.
+	u16 => 4;
+	// One byte prefix followed by the four bytes of the value.
+	u32 => 5;
+	// One byte prefix followed by the eight bytes of the value.
+	u64 => 9;
+	// One byte prefix followed by the sixteen bytes of the value.
+	u128 => 17;
+);
+
+// Impl for tuples; the max encoded length of a tuple is the sum of the max encoded lengths of
+// its elements.
+#[impl_for_tuples(18)]
+impl MaxEncodedLen for Tuple {
+	fn max_encoded_len() -> usize {
+		let mut len: usize = 0;
+		for_tuples!( #( len = len.saturating_add(Tuple::max_encoded_len()); )* );
+		len
+	}
+}
+
+impl<T: MaxEncodedLen, const N: usize> MaxEncodedLen for [T; N]
+where
+	[T; N]: Encode,
+{
+	fn max_encoded_len() -> usize {
+		T::max_encoded_len().saturating_mul(N)
+	}
+}
+
+impl<T: MaxEncodedLen> MaxEncodedLen for Option<T> {
+	fn max_encoded_len() -> usize {
+		T::max_encoded_len().saturating_add(1)
+	}
+}
+
+impl<T, E> MaxEncodedLen for Result<T, E>
+where
+	T: MaxEncodedLen,
+	E: MaxEncodedLen,
+{
+	fn max_encoded_len() -> usize {
+		T::max_encoded_len().max(E::max_encoded_len()).saturating_add(1)
+	}
+}
+
+impl<T> MaxEncodedLen for PhantomData<T> {
+	fn max_encoded_len() -> usize {
+		0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	macro_rules! test_compact_length {
+		($(fn $name:ident($t:ty);)*) => {
+			$(
+				#[test]
+				fn $name() {
+					assert_eq!(Compact(<$t>::MAX).encode().len(), Compact::<$t>::max_encoded_len());
+				}
+			)*
+		};
+	}
+
+	test_compact_length!(
+		fn compact_u8(u8);
+		fn compact_u16(u16);
+		fn compact_u32(u32);
+		fn compact_u64(u64);
+		fn compact_u128(u128);
+	);
+
+	#[test]
+	fn tuples_and_arrays_work() {
+		assert_eq!(<(u8, u32, Option<u16>)>::max_encoded_len(), 1 + 4 + 3);
+		assert_eq!(<[u32; 4]>::max_encoded_len(), 16);
+		assert_eq!(<Result<u8, u64>>::max_encoded_len(), 9);
+		assert_eq!(<()>::max_encoded_len(), 0);
+	}
+}
@@ -19,6 +19,7 @@
 
 use sp_std::{ops::{self, Add, Sub, Mul, Div}, fmt::Debug, prelude::*, convert::{TryInto, TryFrom}};
 use codec::{Encode, Decode, CompactAs};
+use max_encoded_len::MaxEncodedLen;
 use crate::{
 	helpers_128bit::multiply_by_rational, PerThing,
 	traits::{
@@ -339,7 +340,10 @@ macro_rules! implement_fixed {
 		/// A fixed point number representation in the range.
 		///
 		#[doc = $title]
-		#[derive(Encode, Decode, CompactAs, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+		#[derive(
+			Encode, Decode, CompactAs, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+			MaxEncodedLen,
+		)]
 		pub struct $name($inner_type);
 
 		impl From<$inner_type> for $name {
@@ -362,11 +362,13 @@ pub struct Epoch {
 	pub authorities: Vec<(AuthorityId, BabeAuthorityWeight)>,
 	/// Randomness for this epoch.
 	pub randomness: [u8; VRF_OUTPUT_LENGTH],
+	/// Configuration of this epoch, `None` if it is still the genesis configuration.
+	pub config: Option<BabeEpochConfiguration>,
 }
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for block authorship with BABE.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait BabeApi {
 		/// Return the genesis configuration for BABE. The configuration is only read on genesis.
 		fn configuration() -> BabeGenesisConfiguration;
@@ -378,7 +380,8 @@ sp_api::decl_runtime_apis! {
 		/// Returns the slot that started the current epoch.
 		fn current_epoch_start() -> Slot;
 
-		/// Returns information regarding the current epoch.
+		/// Returns information regarding the current epoch. The configuration of the epoch is
+		/// only reported since version 3.
 		fn current_epoch() -> Epoch;
 
 		/// Returns information regarding the next epoch (which was already
@@ -34,7 +34,7 @@ use crate::{
 	error::Error as ConsensusError,
 	block_import::{
 		BlockImport, BlockOrigin, BlockImportParams, ImportedAux, JustificationImport, ImportResult,
-		BlockCheckParams,
+		BlockCheckParams, ImportedState, ForkChoiceStrategy,
 	},
 	metrics::Metrics,
 };
@@ -76,6 +76,8 @@ pub struct IncomingBlock<B: BlockT> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// Complete state of the block, downloaded by state sync.
+	pub state: Option<ImportedState<B>>,
 }
 
 /// Type of keys in the blockchain cache that consensus module could use for its needs.
@@ -231,6 +233,7 @@ pub(crate) fn import_single_block_metered<B: BlockT, V: Verifier<B>, Transaction
 		number,
 		parent_hash,
 		allow_missing_state: block.allow_missing_state,
+		allow_missing_parent: block.state.is_some(),
 		import_existing: block.import_existing,
 	}))? {
 		BlockImportResult::ImportedUnknown { .. } => (),
@@ -238,18 +241,28 @@ pub(crate) fn import_single_block_metered<B: BlockT, V: Verifier<B>, Transaction
 	}
 
 	let started = wasm_timer::Instant::now();
-	let (mut import_block, maybe_keys) = verifier.verify(block_origin, header, justification, block.body)
-		.map_err(|msg| {
-			if let Some(ref peer) = peer {
-				trace!(target: "sync", "Verifying {}({}) from {} failed: {}", number, hash, peer, msg);
-			} else {
-				trace!(target: "sync", "Verifying {}({}) failed: {}", number, hash, msg);
-			}
-			if let Some(metrics) = metrics.as_ref() {
-				metrics.report_verification(false, started.elapsed());
-			}
-			BlockImportError::VerificationFailed(peer.clone(), msg)
-		})?;
+	let (mut import_block, maybe_keys) = if block.state.is_some() {
+		// A block that comes with its state has been verified while syncing, either as part of
+		// the downloaded header chain or by a finality proof. Its parent may not be known.
+		let mut import_block = BlockImportParams::new(block_origin, header);
+		import_block.body = block.body;
+		import_block.justification = justification;
+		import_block.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+		(import_block, None)
+	} else {
+		verifier.verify(block_origin, header, justification, block.body)
+			.map_err(|msg| {
+				if let Some(ref peer) = peer {
+					trace!(target: "sync", "Verifying {}({}) from {} failed: {}", number, hash, peer, msg);
+				} else {
+					trace!(target: "sync", "Verifying {}({}) failed: {}", number, hash, msg);
+				}
+				if let Some(metrics) = metrics.as_ref() {
+					metrics.report_verification(false, started.elapsed());
+				}
+				BlockImportError::VerificationFailed(peer.clone(), msg)
+			})?
+	};
 
 	if let Some(metrics) = metrics.as_ref() {
 		metrics.report_verification(true, started.elapsed());
@@ -260,6 +273,8 @@ pub(crate) fn import_single_block_metered<B: BlockT, V: Verifier<B>, Transaction
 		cache.extend(keys.into_iter());
 	}
 	import_block.allow_missing_state = block.allow_missing_state;
+	import_block.import_existing = block.import_existing;
+	import_block.imported_state = block.state;
 
 	let imported = import_handle.import_block(import_block.convert_transaction(), cache);
 	if let Some(metrics) = metrics.as_ref() {
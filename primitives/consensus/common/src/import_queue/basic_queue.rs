@@ -545,6 +545,7 @@ mod tests {
 					origin: None,
 					allow_missing_state: false,
 					import_existing: false,
+					state: None,
 				}],
 			)))
 			.unwrap();
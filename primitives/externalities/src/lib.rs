@@ -205,6 +205,21 @@ pub trait Externalities: ExtensionStore {
 	/// The returned hash is defined by the `Block` and is SCALE encoded.
 	fn storage_changes_root(&mut self, parent: &[u8]) -> Result<Option<Vec<u8>>, ()>;
 
+	/// Index the last `size` bytes of the extrinsic with the given `index` in the current
+	/// block under the content `hash`, so that the client stores them separately from
+	/// the block body.
+	fn storage_index_transaction(&mut self, _index: u32, _hash: &[u8], _size: u32) {
+		unimplemented!("storage_index_transaction");
+	}
+
+	/// Renew existing piece of transaction storage.
+	///
+	/// The extrinsic with the given `index` in the current block references data that was
+	/// previously indexed under the content `hash`.
+	fn storage_renew_transaction_index(&mut self, _index: u32, _hash: &[u8]) {
+		unimplemented!("storage_renew_transaction_index");
+	}
+
 	/// Start a new nested transaction.
 	///
 	/// This allows to either commit or roll back all changes made after this call to the
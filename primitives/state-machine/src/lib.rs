@@ -46,7 +46,9 @@ pub use std_reexport::*;
 #[cfg(feature = "std")]
 pub use execution::*;
 #[cfg(feature = "std")]
-pub use log::{debug, warn, trace, error as log_error};
+pub use log::{debug, warn, error as log_error};
+#[cfg(feature = "std")]
+pub use tracing::trace;
 
 /// In no_std we skip logs for state_machine, this macro
 /// is a noops.
@@ -121,6 +123,7 @@ pub use crate::overlayed_changes::{
 	StorageChanges, StorageTransactionCache,
 	OffchainChangesCollection,
 	OffchainOverlayedChanges,
+	IndexOperation,
 };
 pub use crate::backend::Backend;
 pub use crate::trie_backend_essence::{TrieBackendStorage, Storage};
@@ -762,6 +765,51 @@ mod execution {
 		Ok(proving_backend.extract_proof())
 	}
 
+	/// Generate a proof for a range of the main trie, or of the given child trie, starting at
+	/// `start_at` (inclusive).
+	///
+	/// Entries are added until their accumulated size exceeds `size_limit` bytes, so the
+	/// proof always covers at least one entry when the range is not empty. Returns the
+	/// proof and the number of entries it covers.
+	pub fn prove_range_read_with_size<B, H>(
+		mut backend: B,
+		child_info: Option<&ChildInfo>,
+		size_limit: usize,
+		start_at: Option<&[u8]>,
+	) -> Result<(StorageProof, u32), Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let trie_backend = backend.as_trie_backend()
+			.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+		prove_range_read_with_size_on_trie_backend(trie_backend, child_info, size_limit, start_at)
+	}
+
+	/// Generate range storage read proof on pre-created trie backend.
+	pub fn prove_range_read_with_size_on_trie_backend<S, H>(
+		trie_backend: &TrieBackend<S, H>,
+		child_info: Option<&ChildInfo>,
+		size_limit: usize,
+		start_at: Option<&[u8]>,
+	) -> Result<(StorageProof, u32), Box<dyn Error>>
+	where
+		S: trie_backend_essence::TrieBackendStorage<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+		let mut count = 0;
+		let mut size = 0;
+		proving_backend.apply_to_key_values_while(child_info, start_at, |key, value| {
+			size += key.len() + value.len();
+			count += 1;
+			size < size_limit
+		}).map_err(|e| Box::new(e) as Box<dyn Error>)?;
+		Ok((proving_backend.extract_proof(), count))
+	}
+
 	/// Generate storage read proof on pre-created trie backend.
 	pub fn prove_child_read_on_trie_backend<S, H, I>(
 		trie_backend: &TrieBackend<S, H>,
@@ -831,6 +879,35 @@ mod execution {
 		Ok(result)
 	}
 
+	/// Check storage range proof, generated by `prove_range_read_with_size` call.
+	///
+	/// `root` is the root of the main trie, also for a range of a child trie. Returns all the
+	/// entries covered by the proof, starting at `start_at`, and whether the proof reaches the
+	/// end of the trie.
+	pub fn read_range_proof_check<H>(
+		root: H::Out,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_at: Option<&[u8]>,
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+		let mut entries = Vec::new();
+		let completed = proving_backend.essence().apply_to_key_values_while(
+			child_info,
+			start_at,
+			|key, value| {
+				entries.push((key, value));
+				true
+			},
+			true,
+		).map_err(|e| Box::new(e) as Box<dyn Error>)?;
+		Ok((entries, completed))
+	}
+
 	/// Check storage read proof on pre-created proving backend.
 	pub fn read_proof_check_on_proving_backend<H>(
 		proving_backend: &TrieBackend<MemoryDB<H>, H>,
@@ -1458,6 +1535,60 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn prove_range_read_with_size_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let child_info = ChildInfo::new_default(b"sub1");
+
+		let collect = |child_info: Option<&ChildInfo>| {
+			let mut collected = Vec::new();
+			let mut start_at: Option<Vec<u8>> = None;
+			loop {
+				let (proof, count) = prove_range_read_with_size(
+					trie_backend::tests::test_trie(),
+					child_info,
+					10,
+					start_at.as_deref(),
+				).unwrap();
+				assert!(count > 0);
+				let (entries, completed) = read_range_proof_check::<BlakeTwo256>(
+					remote_root,
+					proof,
+					child_info,
+					start_at.as_deref(),
+				).unwrap();
+				assert!(entries.len() >= count as usize);
+				if completed {
+					collected.extend(entries);
+					break;
+				}
+				// Continue right after the last received key.
+				let mut next = entries.last().unwrap().0.clone();
+				next.push(0);
+				start_at = Some(next);
+				collected.extend(entries);
+			}
+			collected
+		};
+		assert_eq!(collect(None), remote_backend.pairs());
+		assert_eq!(
+			collect(Some(&child_info)),
+			vec![(b"value3".to_vec(), vec![142]), (b"value4".to_vec(), vec![124])],
+		);
+
+		// A proof generated against another root is rejected.
+		let (proof, _) = prove_range_read_with_size(
+			trie_backend::tests::test_trie(),
+			None,
+			10,
+			None,
+		).unwrap();
+		assert!(
+			read_range_proof_check::<BlakeTwo256>(Default::default(), proof, None, None).is_err()
+		);
+	}
+
 	#[test]
 	fn child_storage_uuid() {
 
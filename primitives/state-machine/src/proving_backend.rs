@@ -148,6 +148,18 @@ impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> ProvingBackend<'a, S, H>
 		ProvingBackend(TrieBackend::new(recorder, root))
 	}
 
+	/// Retrieve all entries of the main trie, or of the given child trie, starting at `start_at`,
+	/// and call `f` for each of them while recording the accessed trie nodes. Aborts as soon as
+	/// `f` returns false.
+	pub fn apply_to_key_values_while<F: FnMut(Vec<u8>, Vec<u8>) -> bool>(
+		&self,
+		child_info: Option<&ChildInfo>,
+		start_at: Option<&[u8]>,
+		f: F,
+	) -> Result<bool, String> {
+		self.0.essence().apply_to_key_values_while(child_info, start_at, f, false)
+	}
+
 	/// Extracting the gathered unordered proof.
 	pub fn extract_proof(&self) -> StorageProof {
 		let trie_nodes = self.0.essence().backend_storage().proof_recorder
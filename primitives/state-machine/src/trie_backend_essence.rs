@@ -99,13 +99,8 @@ impl<S: TrieBackendStorage<H>, H: Hasher> TrieBackendEssence<S, H> where H::Out:
 		self.storage(child_info.prefixed_storage_key().as_slice())
 	}
 
-	/// Return the next key in the child trie i.e. the minimum key that is strictly superior to
-	/// `key` in lexicographic order.
-	pub fn next_child_storage_key(
-		&self,
-		child_info: &ChildInfo,
-		key: &[u8],
-	) -> Result<Option<StorageKey>> {
+	/// Access the root of the child storage in its parent trie, as a hash.
+	fn child_root_hash(&self, child_info: &ChildInfo) -> Result<Option<H::Out>> {
 		let child_root = match self.child_root(child_info)? {
 			Some(child_root) => child_root,
 			None => return Ok(None),
@@ -119,7 +114,20 @@ impl<S: TrieBackendStorage<H>, H: Hasher> TrieBackendEssence<S, H> where H::Out:
 		// note: child_root and hash must be same size, panics otherwise.
 		hash.as_mut().copy_from_slice(&child_root[..]);
 
-		self.next_storage_key_from_root(&hash, Some(child_info), key)
+		Ok(Some(hash))
+	}
+
+	/// Return the next key in the child trie i.e. the minimum key that is strictly superior to
+	/// `key` in lexicographic order.
+	pub fn next_child_storage_key(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<StorageKey>> {
+		match self.child_root_hash(child_info)? {
+			Some(hash) => self.next_storage_key_from_root(&hash, Some(child_info), key),
+			None => Ok(None),
+		}
 	}
 
 	/// Return next key from main trie or child trie by providing corresponding root.
@@ -214,6 +222,62 @@ impl<S: TrieBackendStorage<H>, H: Hasher> TrieBackendEssence<S, H> where H::Out:
 		}
 	}
 
+	/// Retrieve all entries of the main trie, or of the given child trie, starting at `start_at`
+	/// (inclusive), and call `f` for each of them. Aborts as soon as `f` returns false.
+	///
+	/// If `allow_missing_nodes` is set, the iteration stops without error at the first trie
+	/// node that is missing from the storage, which is what happens when iterating over a
+	/// partial proof. Returns `true` if the end of the trie has been reached.
+	pub fn apply_to_key_values_while<F: FnMut(Vec<u8>, Vec<u8>) -> bool>(
+		&self,
+		child_info: Option<&ChildInfo>,
+		start_at: Option<&[u8]>,
+		mut f: F,
+		allow_missing_nodes: bool,
+	) -> Result<bool> {
+		let root = match child_info {
+			Some(child_info) => match self.child_root_hash(child_info)? {
+				Some(hash) => hash,
+				// A child trie which doesn't exist is empty.
+				None => return Ok(true),
+			},
+			None => self.root,
+		};
+		let dyn_eph: &dyn hash_db::HashDBRef<_, _>;
+		let keyspace_eph;
+		if let Some(child_info) = child_info {
+			keyspace_eph = KeySpacedDB::new(self, child_info.keyspace());
+			dyn_eph = &keyspace_eph;
+		} else {
+			dyn_eph = self;
+		}
+
+		let mut iter = || -> sp_std::result::Result<bool, Box<TrieError<H::Out>>> {
+			let trie = TrieDB::<H>::new(dyn_eph, &root)?;
+			let mut iter = trie.iter()?;
+			if let Some(start_at) = start_at {
+				iter.seek(start_at)?;
+			}
+
+			for x in iter {
+				let (key, value) = x?;
+				if !f(key, value) {
+					return Ok(false);
+				}
+			}
+
+			Ok(true)
+		};
+
+		match iter() {
+			Ok(completed) => Ok(completed),
+			Err(e) => match *e {
+				TrieError::IncompleteDatabase(_) if allow_missing_nodes => Ok(false),
+				e => Err(format!("TrieDB iteration error: {}", e)),
+			},
+		}
+	}
+
 	/// Execute given closure for all keys starting with prefix.
 	pub fn for_child_keys_with_prefix<F: FnMut(&[u8])>(
 		&self,
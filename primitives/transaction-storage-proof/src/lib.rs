@@ -0,0 +1,293 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage proof primitives. Contains types and basic code to extract storage
+//! proofs for indexed transactions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::{result::Result, prelude::*};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use codec::{Encode, Decode};
+use sp_inherents::{InherentIdentifier, InherentData, IsFatalError};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+
+pub use sp_inherents::Error;
+
+/// The identifier for the proof inherent.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"tx_proof";
+/// Storage period for data.
+pub const DEFAULT_STORAGE_PERIOD: u32 = 100800;
+/// Proof trie value size.
+pub const CHUNK_SIZE: usize = 256;
+
+/// Errors that can occur while checking the storage proof.
+#[derive(Encode, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode))]
+pub enum InherentError {
+	/// The proof does not match the stored chunk root.
+	InvalidProof,
+	/// Proof trie could not be decoded.
+	TrieError,
+	/// The block must check a storage proof, but no proof was included.
+	MissingProof,
+	/// The block includes a proof, but there is no indexed data to check.
+	UnexpectedProof,
+}
+
+impl IsFatalError for InherentError {
+	fn is_fatal_error(&self) -> bool {
+		true
+	}
+}
+
+/// Holds a chunk of data retrieved from storage along with
+/// a proof that the data was stored at that location in the trie.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub struct TransactionStorageProof {
+	/// Data chunk that is proved to exist.
+	pub chunk: Vec<u8>,
+	/// Trie nodes that compose the proof.
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// Auxiliary trait to extract storage proof.
+pub trait TransactionStorageProofInherentData {
+	/// Get the proof.
+	fn storage_proof(&self) -> Result<Option<TransactionStorageProof>, Error>;
+}
+
+impl TransactionStorageProofInherentData for InherentData {
+	fn storage_proof(&self) -> Result<Option<TransactionStorageProof>, Error> {
+		Ok(self.get_data(&INHERENT_IDENTIFIER)?)
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the transaction storage configuration of the runtime.
+	pub trait TransactionStorageApi {
+		/// Returns the number of blocks indexed data is kept for.
+		fn storage_period() -> NumberFor<Block>;
+	}
+}
+
+/// Provider for inherent data.
+///
+/// Blocks are authored on top of the best block, so the proof is built for the block
+/// following the best block, with the storage period read from the runtime at the best block.
+#[cfg(feature = "std")]
+pub struct InherentDataProvider<B, C> {
+	client: Arc<C>,
+	_phantom: std::marker::PhantomData<B>,
+}
+
+#[cfg(feature = "std")]
+impl<B, C> InherentDataProvider<B, C> {
+	/// Create a new instance that builds the proofs from the data indexed by `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		InherentDataProvider { client, _phantom: Default::default() }
+	}
+}
+
+#[cfg(feature = "std")]
+impl<B, C> sp_inherents::ProvideInherentData for InherentDataProvider<B, C>
+where
+	B: BlockT,
+	C: IndexedBody<B> + sp_blockchain::HeaderBackend<B> + sp_api::ProvideRuntimeApi<B>,
+	C::Api: TransactionStorageApi<B>,
+{
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
+		let parent = self.client.info().best_hash;
+		let storage_period = self.client.runtime_api()
+			.storage_period(&sp_runtime::generic::BlockId::Hash(parent))
+			.map_err(|e| Error::from(format!("Error reading the storage period: {}", e)))?;
+		match registration::new_proof(&*self.client, parent, storage_period)? {
+			Some(proof) => inherent_data.put_data(INHERENT_IDENTIFIER, &proof),
+			None => Ok(()),
+		}
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		let error = InherentError::decode(&mut &error[..]).ok()?;
+		Some(format!("{:?}", error))
+	}
+}
+
+/// An utility function to extract chunk index from the source of randomness.
+pub fn random_chunk(random_hash: &[u8], total_chunks: u32) -> u32 {
+	let mut buf = [0u8; 8];
+	buf.copy_from_slice(&random_hash[0..8]);
+	let random_u64 = u64::from_be_bytes(buf);
+	(random_u64 % total_chunks as u64) as u32
+}
+
+/// An utility function to encode transaction index as trie key.
+pub fn encode_index(input: u32) -> Vec<u8> {
+	codec::Encode::encode(&codec::Compact(input))
+}
+
+/// Number of chunks `size` bytes of data are split into.
+pub fn num_chunks(size: u32) -> u32 {
+	((size as u64 + CHUNK_SIZE as u64 - 1) / CHUNK_SIZE as u64) as u32
+}
+
+/// An interface to request indexed data from the client.
+pub trait IndexedBody<B: BlockT> {
+	/// Get all indexed transactions for a block,
+	/// including renewed transactions.
+	///
+	/// Note that this will only fetch transactions
+	/// that are indexed by the runtime with `storage_index_transaction`.
+	fn block_indexed_body(
+		&self,
+		number: NumberFor<B>,
+	) -> Result<Option<Vec<Vec<u8>>>, Error>;
+
+	/// Get block number for a block hash.
+	fn number(
+		&self,
+		hash: B::Hash,
+	) -> Result<Option<NumberFor<B>>, Error>;
+}
+
+/// Build a proof for a given source of randomness and indexed transactions.
+///
+/// Returns `None` if there is no indexed data to prove.
+pub fn build_proof(random_hash: &[u8], transactions: Vec<Vec<u8>>)
+	-> Result<Option<TransactionStorageProof>, Error>
+{
+	use sp_trie::TrieMut;
+
+	type TrieLayout = sp_trie::Layout<sp_runtime::traits::BlakeTwo256>;
+
+	let total_chunks: u32 = transactions.iter().map(|t| num_chunks(t.len() as u32)).sum();
+	if total_chunks == 0 {
+		return Ok(None);
+	}
+	let target_chunk_index = random_chunk(random_hash, total_chunks);
+
+	// Only the trie of the transaction that holds the target chunk needs to be built.
+	let mut chunk_index = 0;
+	for transaction in transactions {
+		let transaction_chunks = num_chunks(transaction.len() as u32);
+		if target_chunk_index >= chunk_index + transaction_chunks {
+			chunk_index += transaction_chunks;
+			continue;
+		}
+		let target_chunk_key = encode_index(target_chunk_index - chunk_index);
+		let mut db = sp_trie::MemoryDB::<sp_runtime::traits::BlakeTwo256>::default();
+		let mut transaction_root = sp_trie::empty_trie_root::<TrieLayout>();
+		let mut target_chunk = None;
+		{
+			let mut trie = sp_trie::TrieDBMut::<TrieLayout>::new(&mut db, &mut transaction_root);
+			for (index, chunk) in transaction.chunks(CHUNK_SIZE).enumerate() {
+				let key = encode_index(index as u32);
+				trie.insert(&key, chunk).map_err(|_| Error::from("Trie error"))?;
+				if key == target_chunk_key {
+					target_chunk = Some(chunk.to_vec());
+				}
+			}
+		}
+		let proof = sp_trie::generate_trie_proof::<TrieLayout, _, _, _>(
+			&db,
+			transaction_root,
+			&[target_chunk_key],
+		).map_err(|_| Error::from("Trie error"))?;
+		return Ok(target_chunk.map(|chunk| TransactionStorageProof { chunk, proof }));
+	}
+	Ok(None)
+}
+
+#[cfg(feature = "std")]
+pub mod registration {
+	use sp_runtime::traits::{Saturating, Zero, One};
+	use super::*;
+
+	/// Build the storage proof for the block built on top of `parent`.
+	///
+	/// Data indexed `storage_period` blocks before the new block is checked. The period must
+	/// match the `StoragePeriod` of the runtime at `parent`.
+	pub fn new_proof<B, C>(
+		client: &C,
+		parent: B::Hash,
+		storage_period: NumberFor<B>,
+	) -> Result<Option<TransactionStorageProof>, Error>
+		where
+			B: BlockT,
+			C: IndexedBody<B>,
+	{
+		let parent_number = client.number(parent.clone())?.unwrap_or(Zero::zero());
+		let number = parent_number
+			.saturating_add(One::one())
+			.saturating_sub(storage_period);
+		if number.is_zero() {
+			// Too early to collect proofs.
+			return Ok(None);
+		}
+
+		match client.block_indexed_body(number)? {
+			Some(transactions) => build_proof(parent.as_ref(), transactions),
+			None => {
+				// Nothing was indexed in that block.
+				Ok(None)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_trie::TrieConfiguration;
+
+	type TrieLayout = sp_trie::Layout<sp_runtime::traits::BlakeTwo256>;
+
+	#[test]
+	fn build_proof_check() {
+		let random = [1u8; 32];
+		let transactions = vec![vec![1u8; 10], vec![42u8; CHUNK_SIZE * 2 + 1]];
+		let total_chunks = transactions.iter().map(|t| num_chunks(t.len() as u32)).sum();
+		let chunk_index = random_chunk(&random, total_chunks);
+		let proof = build_proof(&random, transactions.clone()).unwrap().unwrap();
+
+		// The first transaction has a single chunk.
+		let (transaction, index) = if chunk_index == 0 {
+			(&transactions[0], 0)
+		} else {
+			(&transactions[1], chunk_index - 1)
+		};
+		let chunks: Vec<Vec<u8>> = transaction.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
+		let root = TrieLayout::ordered_trie_root(chunks.clone());
+		assert_eq!(proof.chunk, chunks[index as usize]);
+		sp_trie::verify_trie_proof::<TrieLayout, _, _, _>(
+			&root,
+			&proof.proof,
+			&[(encode_index(index), Some(proof.chunk))],
+		).unwrap();
+	}
+
+	#[test]
+	fn build_proof_empty() {
+		assert_eq!(build_proof(&[0u8; 32], vec![Vec::new()]).unwrap(), None);
+	}
+}
@@ -91,6 +91,7 @@ where
 			DatabaseConfig::Custom(sp_database::as_database(db))
 		},
 		keystore_remote: Default::default(),
+		keystore_remote_token: Default::default(),
 		keystore: KeystoreConfig::InMemory,
 		default_heap_pages: Default::default(),
 		dev_key_seed: Default::default(),
@@ -102,7 +102,9 @@
 //! ```
 
 use std::{
+	fmt::Debug,
 	fs,
+	future::Future,
 	path::{Path, PathBuf},
 };
 use log::*;
@@ -110,22 +112,69 @@ use sp_core::{hashing::twox_128};
 pub use sp_io::TestExternalities;
 use sp_core::{
 	hexdisplay::HexDisplay,
-	storage::{StorageKey, StorageData},
+	storage::{
+		ChildInfo, ChildType, PrefixedStorageKey, StorageChangeSet, StorageKey, StorageData,
+		well_known_keys,
+	},
 };
+use sp_version::RuntimeVersion;
 use codec::{Encode, Decode};
+use futures::future::join_all;
 use jsonrpsee_http_client::{HttpClient, HttpConfig};
 
+pub mod rpc_api;
+
 type KeyPair = (StorageKey, StorageData);
+type ChildKeyPairs = (StorageKey, Vec<KeyPair>);
 type Hash = sp_core::H256;
 // TODO: make these two generic.
 
 const LOG_TARGET: &str = "remote-ext";
 const TARGET: &str = "http://localhost:9933";
+/// The number of keys fetched per `state_getKeysPaged` request.
+const PAGE_SIZE: u32 = 512;
+/// The number of keys whose values are fetched per `state_queryStorageAt` request.
+const BATCH_SIZE: usize = 256;
+/// The number of requests that are sent to the node concurrently.
+const PARALLEL_REQUESTS: usize = 8;
+/// The number of times a failed request is retried before giving up.
+const MAX_RETRIES: usize = 5;
+
+/// The version of the snapshot file format. Must be bumped on every change of [`Snapshot`].
+pub const SNAPSHOT_VERSION: u16 = 2;
+
+/// The version of the state layout the key-value pairs of a snapshot are read from and inserted
+/// into. There is a single trie layout for now, so this is always `0`.
+pub const STATE_VERSION: u8 = 0;
 
 jsonrpsee_proc_macros::rpc_client_api! {
 	RpcApi {
-		#[rpc(method = "state_getPairs", positional_params)]
-		fn storage_pairs(prefix: StorageKey, hash: Option<Hash>) -> Vec<(StorageKey, StorageData)>;
+		#[rpc(method = "state_getKeysPaged", positional_params)]
+		fn storage_keys_paged(
+			prefix: Option<StorageKey>,
+			count: u32,
+			start_key: Option<StorageKey>,
+			hash: Option<Hash>,
+		) -> Vec<StorageKey>;
+		#[rpc(method = "state_queryStorageAt", positional_params)]
+		fn query_storage_at(
+			keys: Vec<StorageKey>,
+			hash: Option<Hash>,
+		) -> Vec<StorageChangeSet<Hash>>;
+		#[rpc(method = "childstate_getKeys", positional_params)]
+		fn child_storage_keys(
+			child_storage_key: PrefixedStorageKey,
+			prefix: StorageKey,
+			hash: Option<Hash>,
+		) -> Vec<StorageKey>;
+		#[rpc(method = "childstate_queryStorageAt", positional_params)]
+		fn child_query_storage_at(
+			child_storage_key: PrefixedStorageKey,
+			keys: Vec<StorageKey>,
+			hash: Option<Hash>,
+		) -> Vec<StorageChangeSet<Hash>>;
+		#[rpc(method = "state_getRuntimeVersion", positional_params)]
+		fn runtime_version(hash: Option<Hash>) -> RuntimeVersion;
 		#[rpc(method = "chain_getFinalizedHead")]
 		fn finalized_head() -> Hash;
 	}
@@ -162,11 +211,22 @@ pub struct OnlineConfig {
 	pub cache: Option<CacheConfig>,
 	/// The modules to scrape. If empty, entire chain state will be scraped.
 	pub modules: Vec<String>,
+	/// Scrape the child tries as well.
+	///
+	/// Child tries can't be attributed to a module, so all of them are scraped regardless of
+	/// `modules`. Off by default, since this can be a lot of data.
+	pub scrape_children: bool,
 }
 
 impl Default for OnlineConfig {
 	fn default() -> Self {
-		Self { uri: TARGET.to_owned(), at: None, cache: None, modules: Default::default() }
+		Self {
+			uri: TARGET.to_owned(),
+			at: None,
+			cache: None,
+			modules: Default::default(),
+			scrape_children: false,
+		}
 	}
 }
 
@@ -201,6 +261,70 @@ impl CacheConfig {
 	}
 }
 
+/// The content of a snapshot file.
+///
+/// The file starts with [`SNAPSHOT_VERSION`], so that snapshots written by a different version of
+/// this crate are rejected rather than silently misinterpreted.
+#[derive(Encode, Decode)]
+pub struct Snapshot {
+	/// The version of the format the snapshot was written with.
+	pub snapshot_version: u16,
+	/// The hash of the block the state was scraped at.
+	pub block_hash: Hash,
+	/// The runtime version of the chain at `block_hash`.
+	pub runtime_version: RuntimeVersion,
+	/// The version of the state layout, see [`STATE_VERSION`].
+	pub state_version: u8,
+	/// The key-value pairs of the top trie.
+	pub top: Vec<KeyPair>,
+	/// The key-value pairs of each child trie, along with the prefixed storage key of the child
+	/// trie.
+	pub children: Vec<ChildKeyPairs>,
+}
+
+impl Snapshot {
+	/// Read a snapshot from the file at `path`.
+	pub fn load(path: &Path) -> Result<Self, &'static str> {
+		info!(target: LOG_TARGET, "scraping keypairs from cache {:?}", path);
+		let bytes = fs::read(path).map_err(|_| "fs::read failed.")?;
+		let version = u16::decode(&mut &*bytes).map_err(|_| "decode failed")?;
+		if version != SNAPSHOT_VERSION {
+			error!(
+				target: LOG_TARGET,
+				"snapshot {:?} has version {}, expected {}",
+				path,
+				version,
+				SNAPSHOT_VERSION,
+			);
+			return Err("unsupported snapshot version");
+		}
+		let snapshot = Self::decode(&mut &*bytes).map_err(|_| "decode failed")?;
+		if snapshot.state_version != STATE_VERSION {
+			error!(
+				target: LOG_TARGET,
+				"snapshot {:?} has state version {}, expected {}",
+				path,
+				snapshot.state_version,
+				STATE_VERSION,
+			);
+			return Err("unsupported state version");
+		}
+		info!(
+			target: LOG_TARGET,
+			"loaded snapshot of block {:?}, runtime {}",
+			snapshot.block_hash,
+			snapshot.runtime_version,
+		);
+		Ok(snapshot)
+	}
+
+	/// Write the snapshot to the file at `path`.
+	pub fn save(&self, path: &Path) -> Result<(), &'static str> {
+		info!(target: LOG_TARGET, "writing to cache file {:?}", path);
+		fs::write(path, self.encode()).map_err(|_| "fs::write failed.")
+	}
+}
+
 /// Builder for remote-externalities.
 pub struct Builder {
 	inject: Vec<KeyPair>,
@@ -233,50 +357,153 @@ impl Builder {
 	}
 }
 
+/// Run the request created by `request` until it succeeds, at most `MAX_RETRIES` times.
+async fn with_retries<F, Fut, T, E>(what: &'static str, request: F) -> Result<T, &'static str>
+where
+	F: Fn() -> Fut,
+	Fut: Future<Output = Result<T, E>>,
+	E: Debug,
+{
+	let mut attempt = 0;
+	loop {
+		match request().await {
+			Ok(result) => return Ok(result),
+			Err(e) if attempt < MAX_RETRIES => {
+				attempt += 1;
+				warn!(target: LOG_TARGET, "rpc {} failed ({:?}), retry {}", what, e, attempt);
+			},
+			Err(e) => {
+				error!(target: LOG_TARGET, "Error = {:?}", e);
+				return Err(what);
+			},
+		}
+	}
+}
+
 // RPC methods
 impl Builder {
 	async fn rpc_get_head(&self) -> Result<Hash, &'static str> {
 		trace!(target: LOG_TARGET, "rpc: finalized_head");
-		RpcApi::finalized_head(&self.as_online().rpc()).await.map_err(|e| {
-			error!("Error = {:?}", e);
-			"rpc finalized_head failed."
-			})
+		let client = self.as_online().rpc();
+		with_retries("finalized_head", || RpcApi::finalized_head(&client)).await
 	}
 
-	/// Relay the request to `state_getPairs` rpc endpoint.
+	async fn rpc_get_runtime_version(&self, at: Hash) -> Result<RuntimeVersion, &'static str> {
+		trace!(target: LOG_TARGET, "rpc: runtime_version @ {:?}", at);
+		let client = self.as_online().rpc();
+		with_retries("runtime_version", || RpcApi::runtime_version(&client, Some(at))).await
+	}
+
+	/// Get all the keys with the given `prefix`, page by page.
 	///
-	/// Note that this is an unsafe RPC.
-	async fn rpc_get_pairs(
+	/// Each page is retried on failure, so that a flaky connection does not restart the whole
+	/// download.
+	async fn rpc_get_keys_paged(
 		&self,
+		client: &HttpClient,
 		prefix: StorageKey,
 		at: Hash,
-	) -> Result<Vec<KeyPair>, &'static str> {
-		trace!(target: LOG_TARGET, "rpc: storage_pairs: {:?} / {:?}", prefix, at);
-		RpcApi::storage_pairs(&self.as_online().rpc(), prefix, Some(at)).await.map_err(|e| {
-			error!("Error = {:?}", e);
-			"rpc storage_pairs failed"
+	) -> Result<Vec<StorageKey>, &'static str> {
+		let mut last_key: Option<StorageKey> = None;
+		let mut all_keys: Vec<StorageKey> = vec![];
+		loop {
+			let page = with_retries("storage_keys_paged", || {
+				RpcApi::storage_keys_paged(
+					client,
+					Some(prefix.clone()),
+					PAGE_SIZE,
+					last_key.clone(),
+					Some(at),
+				)
 			})
+			.await?;
+			let page_len = page.len();
+			all_keys.extend(page);
+
+			if page_len < PAGE_SIZE as usize {
+				debug!(target: LOG_TARGET, "last page received: {}", page_len);
+				break;
+			} else {
+				let new_last_key =
+					all_keys.last().expect("all_keys is populated; has .last(); qed");
+				debug!(
+					target: LOG_TARGET,
+					"new total = {}, full page received: {:?}",
+					all_keys.len(),
+					HexDisplay::from(new_last_key),
+				);
+				last_key = Some(new_last_key.clone());
+			}
+		}
+
+		Ok(all_keys)
 	}
-}
 
-// Internal methods
-impl Builder {
-	/// Save the given data as cache.
-	fn save_cache(&self, data: &[KeyPair], path: &Path) -> Result<(), &'static str> {
-		info!(target: LOG_TARGET, "writing to cache file {:?}", path);
-		fs::write(path, data.encode()).map_err(|_| "fs::write failed.")?;
-		Ok(())
+	/// Fetch the values of `keys` with `state_queryStorageAt`, or `childstate_queryStorageAt` if
+	/// `child_key` is given, in batches of `BATCH_SIZE` keys, of which `PARALLEL_REQUESTS` are in
+	/// flight at any time.
+	async fn rpc_get_storage_batched(
+		&self,
+		client: &HttpClient,
+		child_key: Option<&PrefixedStorageKey>,
+		keys: Vec<StorageKey>,
+		at: Hash,
+	) -> Result<Vec<KeyPair>, &'static str> {
+		let batches = keys.chunks(BATCH_SIZE).map(|c| c.to_vec()).collect::<Vec<_>>();
+		let mut key_values = Vec::with_capacity(keys.len());
+
+		for (i, parallel) in batches.chunks(PARALLEL_REQUESTS).enumerate() {
+			let requests = parallel.iter().map(|batch| async move {
+				match child_key {
+					Some(child_key) => with_retries("child_query_storage_at", || {
+						RpcApi::child_query_storage_at(
+							client,
+							child_key.clone(),
+							batch.clone(),
+							Some(at),
+						)
+					}).await,
+					None => with_retries("query_storage_at", || {
+						RpcApi::query_storage_at(client, batch.clone(), Some(at))
+					}).await,
+				}
+			});
+			for change_sets in join_all(requests).await {
+				let changes = change_sets?.into_iter().flat_map(|set| set.changes);
+				key_values.extend(changes.filter_map(|(k, v)| v.map(|v| (k, v))));
+			}
+			debug!(
+				target: LOG_TARGET,
+				"fetched values of {} / {} batches",
+				((i + 1) * PARALLEL_REQUESTS).min(batches.len()),
+				batches.len(),
+			);
+		}
+
+		Ok(key_values)
 	}
 
-	/// initialize `Self` from cache. Panics if the file does not exist.
-	fn load_cache(&self, path: &Path) -> Result<Vec<KeyPair>, &'static str> {
-		info!(target: LOG_TARGET, "scraping keypairs from cache {:?}", path,);
-		let bytes = fs::read(path).map_err(|_| "fs::read failed.")?;
-		Decode::decode(&mut &*bytes).map_err(|_| "decode failed")
+	/// Fetch all the key-value pairs of the child trie with the given `prefixed_key`.
+	async fn rpc_get_child_pairs(
+		&self,
+		client: &HttpClient,
+		prefixed_key: &StorageKey,
+		at: Hash,
+	) -> Result<Vec<KeyPair>, &'static str> {
+		let child_key = PrefixedStorageKey::new(prefixed_key.0.clone());
+		let keys = with_retries("child_storage_keys", || {
+			RpcApi::child_storage_keys(client, child_key.clone(), StorageKey(vec![]), Some(at))
+		})
+		.await?;
+
+		self.rpc_get_storage_batched(client, Some(&child_key), keys, at).await
 	}
+}
 
+// Internal methods
+impl Builder {
 	/// Build `Self` from a network node denoted by `uri`.
-	async fn load_remote(&self) -> Result<Vec<KeyPair>, &'static str> {
+	async fn load_remote(&self) -> Result<Snapshot, &'static str> {
 		let config = self.as_online();
 		let at = self
 			.as_online()
@@ -284,28 +511,59 @@ impl Builder {
 			.expect("online config must be initialized by this point; qed.")
 			.clone();
 		info!(target: LOG_TARGET, "scraping keypairs from remote node {} @ {:?}", config.uri, at);
+		let client = config.rpc();
 
-		let keys_and_values = if config.modules.len() > 0 {
-			let mut filtered_kv = vec![];
-			for f in config.modules.iter() {
-				let hashed_prefix = StorageKey(twox_128(f.as_bytes()).to_vec());
-				let module_kv = self.rpc_get_pairs(hashed_prefix.clone(), at).await?;
-				info!(
-					target: LOG_TARGET,
-					"downloaded data for module {} (count: {} / prefix: {:?}).",
-					f,
-					module_kv.len(),
-					HexDisplay::from(&hashed_prefix),
-				);
-				filtered_kv.extend(module_kv);
-			}
-			filtered_kv
+		let prefixes = if config.modules.len() > 0 {
+			config.modules.iter().map(|f| (f.clone(), twox_128(f.as_bytes()).to_vec())).collect()
 		} else {
 			info!(target: LOG_TARGET, "downloading data for all modules.");
-			self.rpc_get_pairs(StorageKey(vec![]), at).await?.into_iter().collect::<Vec<_>>()
+			vec![("all modules".to_string(), vec![])]
 		};
 
-		Ok(keys_and_values)
+		let mut top = vec![];
+		for (name, prefix) in prefixes {
+			let hashed_prefix = StorageKey(prefix);
+			let keys = self.rpc_get_keys_paged(&client, hashed_prefix.clone(), at).await?;
+			let module_kv = self.rpc_get_storage_batched(&client, None, keys, at).await?;
+			info!(
+				target: LOG_TARGET,
+				"downloaded data for {} (count: {} / prefix: {:?}).",
+				name,
+				module_kv.len(),
+				HexDisplay::from(&hashed_prefix),
+			);
+			top.extend(module_kv);
+		}
+
+		let mut children = vec![];
+		if config.scrape_children {
+			let child_prefix = StorageKey(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec());
+			let child_roots = self.rpc_get_keys_paged(&client, child_prefix, at).await?;
+			info!(target: LOG_TARGET, "downloading data of {} child tries.", child_roots.len());
+			for prefixed_key in child_roots {
+				let child_kv = self.rpc_get_child_pairs(&client, &prefixed_key, at).await?;
+				debug!(
+					target: LOG_TARGET,
+					"downloaded data for child trie {:?} (count: {}).",
+					HexDisplay::from(&prefixed_key),
+					child_kv.len(),
+				);
+				children.push((prefixed_key, child_kv));
+			}
+		}
+		// the roots of the child tries are recomputed when the child tries are inserted.
+		top.retain(|(k, _)| !well_known_keys::is_child_storage_key(&k.0));
+
+		let runtime_version = self.rpc_get_runtime_version(at).await?;
+
+		Ok(Snapshot {
+			snapshot_version: SNAPSHOT_VERSION,
+			block_hash: at,
+			runtime_version,
+			state_version: STATE_VERSION,
+			top,
+			children,
+		})
 	}
 
 	async fn init_remote_client(&mut self) -> Result<(), &'static str> {
@@ -317,16 +575,16 @@ impl Builder {
 		Ok(())
 	}
 
-	async fn pre_build(mut self) -> Result<Vec<KeyPair>, &'static str> {
-		let mut base_kv = match self.mode.clone() {
-			Mode::Offline(config) => self.load_cache(&config.cache.path())?,
+	async fn pre_build(mut self) -> Result<Snapshot, &'static str> {
+		let mut snapshot = match self.mode.clone() {
+			Mode::Offline(config) => Snapshot::load(&config.cache.path())?,
 			Mode::Online(config) => {
 				self.init_remote_client().await?;
-				let kp = self.load_remote().await?;
+				let snapshot = self.load_remote().await?;
 				if let Some(c) = config.cache {
-					self.save_cache(&kp, &c.path())?;
+					snapshot.save(&c.path())?;
 				}
-				kp
+				snapshot
 			}
 		};
 
@@ -335,8 +593,8 @@ impl Builder {
 			"extending externalities with {} manually injected keys",
 			self.inject.len()
 		);
-		base_kv.extend(self.inject.clone());
-		Ok(base_kv)
+		snapshot.top.extend(self.inject.clone());
+		Ok(snapshot)
 	}
 }
 
@@ -355,6 +613,12 @@ impl Builder {
 		self
 	}
 
+	/// Overwrite the runtime code in the built externalities with `code`.
+	pub fn overwrite_code(mut self, code: Vec<u8>) -> Self {
+		self.inject.push((StorageKey(well_known_keys::CODE.to_vec()), StorageData(code)));
+		self
+	}
+
 	/// Configure a cache to be used.
 	pub fn mode(mut self, mode: Mode) -> Self {
 		self.mode = mode;
@@ -363,18 +627,65 @@ impl Builder {
 
 	/// Build the test externalities.
 	pub async fn build(self) -> Result<TestExternalities, &'static str> {
-		let kv = self.pre_build().await?;
+		let snapshot = self.pre_build().await?;
 		let mut ext = TestExternalities::new_empty();
 
-		info!(target: LOG_TARGET, "injecting a total of {} keys", kv.len());
-		for (k, v) in kv {
+		info!(target: LOG_TARGET, "injecting a total of {} top keys", snapshot.top.len());
+		for (k, v) in snapshot.top {
 			let (k, v) = (k.0, v.0);
 			ext.insert(k, v);
 		}
+
+		info!(target: LOG_TARGET, "injecting a total of {} child tries", snapshot.children.len());
+		for (prefixed_key, kv) in snapshot.children {
+			let prefixed_key = PrefixedStorageKey::new(prefixed_key.0);
+			let child_info = match ChildType::from_prefixed_key(&prefixed_key) {
+				Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
+				None => return Err("invalid child storage key in snapshot"),
+			};
+			let kv = kv.into_iter().map(|(k, v)| (k.0, Some(v.0))).collect::<Vec<_>>();
+			ext.backend.insert(vec![(Some(child_info), kv)]);
+		}
+
 		Ok(ext)
 	}
 }
 
+#[cfg(test)]
+mod test_snapshot {
+	use super::*;
+
+	#[test]
+	fn snapshot_roundtrip_and_version_check() {
+		let path = std::env::temp_dir().join("remote_ext_snapshot_roundtrip_test.bin");
+		let snapshot = Snapshot {
+			snapshot_version: SNAPSHOT_VERSION,
+			block_hash: Hash::repeat_byte(1),
+			runtime_version: Default::default(),
+			state_version: STATE_VERSION,
+			top: vec![(StorageKey(vec![1]), StorageData(vec![2]))],
+			children: vec![],
+		};
+		snapshot.save(&path).unwrap();
+		let loaded = Snapshot::load(&path).unwrap();
+		assert_eq!(loaded.block_hash, snapshot.block_hash);
+		assert_eq!(loaded.top, snapshot.top);
+
+		// a snapshot of another version is rejected.
+		let mut encoded = snapshot.encode();
+		encoded[..2].copy_from_slice(&(SNAPSHOT_VERSION + 1).encode());
+		fs::write(&path, encoded).unwrap();
+		assert_eq!(Snapshot::load(&path).err(), Some("unsupported snapshot version"));
+
+		// and so is a snapshot of another state version.
+		let other_state = Snapshot { state_version: STATE_VERSION + 1, ..snapshot };
+		other_state.save(&path).unwrap();
+		assert_eq!(Snapshot::load(&path).err(), Some("unsupported state version"));
+
+		fs::remove_file(&path).unwrap();
+	}
+}
+
 #[cfg(feature = "remote-test")]
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! One-off RPC calls to a substrate node, outside of the [`crate::Builder`].
+//!
+//! These are mostly useful to fetch the blocks and headers that should be executed on top of the
+//! state built by the builder.
+
+use log::*;
+use jsonrpsee_http_client::{HttpClient, HttpConfig};
+use serde::de::DeserializeOwned;
+use sp_runtime::{generic::SignedBlock, traits::Block as BlockT};
+use crate::LOG_TARGET;
+
+jsonrpsee_proc_macros::rpc_client_api! {
+	ChainApi<B: BlockT> {
+		#[rpc(method = "chain_getHeader", positional_params)]
+		fn header(hash: Option<B::Hash>) -> Option<B::Header>;
+		#[rpc(method = "chain_getBlock", positional_params)]
+		fn block(hash: Option<B::Hash>) -> Option<SignedBlock<B>>;
+		#[rpc(method = "chain_getFinalizedHead")]
+		fn finalized_head() -> B::Hash;
+	}
+}
+
+fn build_client<S: AsRef<str>>(from: S) -> Result<HttpClient, &'static str> {
+	HttpClient::new(from.as_ref(), HttpConfig { max_request_body_size: u32::MAX }).map_err(|e| {
+		error!(target: LOG_TARGET, "Error = {:?}", e);
+		"failed to build http client"
+	})
+}
+
+/// Get the header of the block identified by `at`.
+pub async fn get_header<B, S>(from: S, at: B::Hash) -> Result<B::Header, &'static str>
+where
+	B: BlockT,
+	B::Header: DeserializeOwned,
+	S: AsRef<str>,
+{
+	trace!(target: LOG_TARGET, "rpc: chain_getHeader: {:?}", at);
+	let client = build_client(from)?;
+	ChainApi::<B>::header(&client, Some(at))
+		.await
+		.map_err(|e| {
+			error!(target: LOG_TARGET, "Error = {:?}", e);
+			"rpc chain_getHeader failed"
+		})?
+		.ok_or("rpc chain_getHeader returned no header")
+}
+
+/// Get the finalized head.
+pub async fn get_finalized_head<B, S>(from: S) -> Result<B::Hash, &'static str>
+where
+	B: BlockT,
+	S: AsRef<str>,
+{
+	trace!(target: LOG_TARGET, "rpc: chain_getFinalizedHead");
+	let client = build_client(from)?;
+	ChainApi::<B>::finalized_head(&client).await.map_err(|e| {
+		error!(target: LOG_TARGET, "Error = {:?}", e);
+		"rpc chain_getFinalizedHead failed"
+	})
+}
+
+/// Get the signed block identified by `at`.
+pub async fn get_block<B, S>(from: S, at: B::Hash) -> Result<B, &'static str>
+where
+	B: BlockT + DeserializeOwned,
+	B::Header: DeserializeOwned,
+	S: AsRef<str>,
+{
+	trace!(target: LOG_TARGET, "rpc: chain_getBlock: {:?}", at);
+	let client = build_client(from)?;
+	let signed_block = ChainApi::<B>::block(&client, Some(at))
+		.await
+		.map_err(|e| {
+			error!(target: LOG_TARGET, "Error = {:?}", e);
+			"rpc chain_getBlock failed"
+		})?
+		.ok_or("rpc chain_getBlock returned no block")?;
+
+	Ok(signed_block.block)
+}
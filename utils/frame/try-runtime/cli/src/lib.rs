@@ -15,21 +15,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! `Structopt`-ready struct for `try-runtime`.
+//! `Structopt`-ready structs for `try-runtime`.
 
-use parity_scale_codec::Decode;
-use std::{fmt::Debug, str::FromStr};
+use parity_scale_codec::{Decode, Encode};
+use std::{fmt::Debug, path::{Path, PathBuf}, sync::Arc};
 use sc_service::Configuration;
 use sc_cli::{CliConfiguration, ExecutionStrategy, WasmExecutionMethod};
 use sc_executor::NativeExecutor;
 use sc_service::NativeExecutionDispatch;
 use sp_state_machine::StateMachine;
-use sp_runtime::traits::{Block as BlockT, NumberFor};
-use sp_core::storage::{StorageData, StorageKey, well_known_keys};
+use sp_runtime::traits::{Block as BlockT, NumberFor, Header as HeaderT};
+use sp_core::{
+	H256,
+	offchain::{
+		OffchainExt, TransactionPoolExt,
+		testing::{TestOffchainExt, TestTransactionPoolExt},
+	},
+	storage::{StorageData, StorageKey, well_known_keys},
+};
+use sp_keystore::{KeystoreExt, testing::KeyStore};
+use remote_externalities::{
+	Builder, Mode, CacheConfig, OfflineConfig, OnlineConfig, TestExternalities, rpc_api,
+};
 
-/// Various commands to try out the new runtime, over configurable states.
-///
-/// For now this only assumes running the `on_runtime_upgrade` hooks.
+/// Various commands to try out against runtime state at a specific block.
 #[derive(Debug, structopt::StructOpt)]
 pub struct TryRuntimeCmd {
 	/// The shared parameters
@@ -37,11 +46,7 @@ pub struct TryRuntimeCmd {
 	#[structopt(flatten)]
 	pub shared_params: sc_cli::SharedParams,
 
-	/// The state to use to run the migration. Should be a valid FILE or HTTP URI.
-	#[structopt(short, long, default_value = "http://localhost:9933")]
-	pub state: State,
-
-	/// The execution strategy that should be used for benchmarks
+	/// The execution strategy that should be used.
 	#[structopt(
 		long = "execution",
 		value_name = "STRATEGY",
@@ -60,106 +65,523 @@ pub struct TryRuntimeCmd {
 		default_value = "Interpreted"
 	)]
 	pub wasm_method: WasmExecutionMethod,
+
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub command: Command,
+}
+
+/// The different types of operations that `try-runtime` can execute.
+#[derive(Debug, structopt::StructOpt)]
+pub enum Command {
+	/// Execute "TryRuntime_on_runtime_upgrade" against the given state, with the runtime of the
+	/// local chain spec.
+	OnRuntimeUpgrade(OnRuntimeUpgradeCmd),
+
+	/// Execute a given block on top of the given state, with the on-chain runtime unless
+	/// `--overwrite-wasm-code` is provided.
+	ExecuteBlock(ExecuteBlockCmd),
+
+	/// Execute "OffchainWorkerApi_offchain_worker" on top of the given state, for a given header.
+	OffchainWorker(OffchainWorkerCmd),
+
+	/// Execute "TryRuntime_try_state" on top of the given state, checking the invariants of all
+	/// pallets.
+	TryState(TryStateCmd),
+}
+
+#[derive(Debug, Clone, structopt::StructOpt)]
+pub struct OnRuntimeUpgradeCmd {
+	#[structopt(subcommand)]
+	pub state: State,
+}
+
+#[derive(Debug, Clone, structopt::StructOpt)]
+pub struct TryStateCmd {
+	/// Overwrite the wasm code in state or not.
+	#[structopt(long)]
+	pub overwrite_wasm_code: bool,
+
+	#[structopt(subcommand)]
+	pub state: State,
 }
 
-/// The state to use for a migration dry-run.
-#[derive(Debug)]
+#[derive(Debug, Clone, structopt::StructOpt)]
+pub struct ExecuteBlockCmd {
+	/// Overwrite the wasm code in state or not.
+	#[structopt(long)]
+	pub overwrite_wasm_code: bool,
+
+	/// Execute the block with "TryRuntime_execute_block_no_check" rather than
+	/// "Core_execute_block", skipping the state root check.
+	///
+	/// Signatures are still checked. A runtime upgrade triggered by the block is executed
+	/// without its pre and post upgrade checks; use the `on-runtime-upgrade` command for those.
+	#[structopt(long)]
+	pub no_check: bool,
+
+	/// The block hash at which to fetch the block.
+	///
+	/// If the `live` state type is being used, then this can be omitted, and is equal to whatever
+	/// the `state::at` is. Only use this (with care) when combined with a snapshot.
+	#[structopt(long, multiple = false, parse(try_from_str = parse_hash))]
+	pub block_at: Option<String>,
+
+	/// The url to fetch the block from.
+	///
+	/// If the `live` state type is being used, then this can be omitted, and is equal to whatever
+	/// the `state::url` is. Only use this (with care) when combined with a snapshot.
+	#[structopt(long, multiple = false, parse(try_from_str = parse_url))]
+	pub block_url: Option<String>,
+
+	/// Read the block to execute from a file containing its SCALE encoding, rather than fetching
+	/// it from a node.
+	#[structopt(long, multiple = false, conflicts_with_all = &["block-at", "block-url"])]
+	pub block_file: Option<PathBuf>,
+
+	#[structopt(subcommand)]
+	pub state: State,
+}
+
+#[derive(Debug, Clone, structopt::StructOpt)]
+pub struct OffchainWorkerCmd {
+	/// Overwrite the wasm code in state or not.
+	#[structopt(long)]
+	pub overwrite_wasm_code: bool,
+
+	/// The block hash at which to fetch the header.
+	///
+	/// If the `live` state type is being used, then this can be omitted, and is equal to whatever
+	/// the `state::at` is. Only use this (with care) when combined with a snapshot.
+	#[structopt(long, multiple = false, parse(try_from_str = parse_hash))]
+	pub header_at: Option<String>,
+
+	/// The url to fetch the header from.
+	///
+	/// If the `live` state type is being used, then this can be omitted, and is equal to whatever
+	/// the `state::url` is. Only use this (with care) when combined with a snapshot.
+	#[structopt(long, multiple = false, parse(try_from_str = parse_url))]
+	pub header_url: Option<String>,
+
+	#[structopt(subcommand)]
+	pub state: State,
+}
+
+/// The source of runtime state to try operations against.
+#[derive(Debug, Clone, structopt::StructOpt)]
 pub enum State {
-	/// A snapshot. Inner value is a file path.
-	Snap(String),
-
-	/// A live chain. Inner value is the HTTP uri.
-	Live(String),
-}
-
-impl FromStr for State {
-	type Err = &'static str;
-	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		match s.get(..7) {
-			// could use Url crate as well, but lets keep it simple for now.
-			Some("http://") => Ok(State::Live(s.to_string())),
-			Some("file://") => s
-				.split("//")
-				.collect::<Vec<_>>()
-				.get(1)
-				.map(|s| State::Snap(s.to_string()))
-				.ok_or("invalid file URI"),
-			_ => Err("invalid format. Must be a valid HTTP or File URI"),
+	/// Use a state snapshot as the source of runtime state. NOTE: for the offchain-worker and
+	/// execute-block command this is only partially supported and requires a archive node url.
+	Snap {
+		#[structopt(short, long)]
+		snapshot_path: PathBuf,
+	},
+
+	/// Use a live chain as the source of runtime state.
+	Live {
+		/// The url to connect to.
+		#[structopt(
+			short,
+			long,
+			default_value = "http://localhost:9933",
+			parse(try_from_str = parse_url)
+		)]
+		url: String,
+
+		/// The block hash at which to get the runtime state. Will be latest finalized head if not
+		/// provided.
+		#[structopt(short, long, multiple = false, parse(try_from_str = parse_hash))]
+		at: Option<String>,
+
+		/// An optional state snapshot file to WRITE to. Not written if set to `None`.
+		#[structopt(short, long)]
+		snapshot_path: Option<PathBuf>,
+
+		/// The modules to scrape. If empty, entire chain state will be scraped.
+		#[structopt(short, long, require_delimiter = true)]
+		modules: Option<Vec<String>>,
+	},
+}
+
+fn parse_hash(block_hash: &str) -> Result<String, String> {
+	let block_hash = if block_hash.starts_with("0x") {
+		&block_hash[2..]
+	} else {
+		block_hash
+	};
+
+	if let Some(pos) = block_hash.chars().position(|c| !c.is_ascii_hexdigit()) {
+		Err(format!(
+			"Expected block hash, found illegal hex character at position: {}",
+			2 + pos,
+		))
+	} else {
+		Ok(block_hash.into())
+	}
+}
+
+fn parse_url(s: &str) -> Result<String, &'static str> {
+	if s.starts_with("http://") {
+		// could use Url crate as well, but lets keep it simple for now.
+		Ok(s.to_string())
+	} else {
+		Err("not a valid HTTP url: must start with 'http://'")
+	}
+}
+
+/// Decode a hash, as previously sanitised by [`parse_hash`].
+fn hash_of(hash_str: &str) -> sc_cli::Result<H256> {
+	hash_str
+		.parse::<H256>()
+		.map_err(|e| format!("Could not parse block hash: {:?}", e).into())
+}
+
+/// The cache config pointing at the given snapshot file.
+fn cache_config(snapshot_path: &Path) -> CacheConfig {
+	let directory = snapshot_path
+		.parent()
+		.filter(|p| !p.as_os_str().is_empty())
+		.map(|p| p.to_string_lossy().into_owned())
+		.unwrap_or_else(|| ".".into());
+	let name = snapshot_path
+		.file_name()
+		.map(|n| n.to_string_lossy().into_owned())
+		.unwrap_or_default();
+	CacheConfig { name, directory }
+}
+
+/// Build the remote externalities for the given `state`.
+///
+/// If `at` is given, it overwrites the block at which a live state is scraped.
+async fn build_ext(
+	state: &State,
+	at: Option<H256>,
+) -> sc_cli::Result<TestExternalities> {
+	let builder = match state {
+		State::Snap { snapshot_path } => Builder::new().mode(Mode::Offline(OfflineConfig {
+			cache: cache_config(snapshot_path),
+		})),
+		State::Live { url, snapshot_path, modules, at: state_at } => {
+			let at = match (at, state_at) {
+				(Some(at), _) => Some(at),
+				(None, Some(state_at)) => Some(hash_of(state_at)?),
+				(None, None) => None,
+			};
+			Builder::new().mode(Mode::Online(OnlineConfig {
+				uri: url.clone(),
+				at,
+				cache: snapshot_path.as_deref().map(cache_config),
+				modules: modules.clone().unwrap_or_default(),
+				..Default::default()
+			}))
+		},
+	};
+
+	builder.build().await.map_err(Into::into)
+}
+
+/// Log the outcome of the checks of each pallet in `report`.
+fn log_report(report: &frame_try_runtime::Report) {
+	for outcome in &report.outcomes {
+		let pallet = String::from_utf8_lossy(&outcome.pallet);
+		match outcome.error {
+			None => log::info!("✅ {}: passed, consumed weight = {}", pallet, outcome.weight),
+			Some(ref error) => log::error!(
+				"❌ {}: failed with '{}', consumed weight = {}",
+				pallet,
+				String::from_utf8_lossy(error),
+				outcome.weight,
+			),
 		}
 	}
 }
 
+/// The local runtime code of the chain spec, along with the storage key under which it is stored.
+fn local_code(config: &Configuration) -> sc_cli::Result<(StorageKey, StorageData)> {
+	let genesis_storage = config.chain_spec.build_storage()?;
+	let code = StorageData(
+		genesis_storage
+			.top
+			.get(well_known_keys::CODE)
+			.expect("code key must exist in genesis storage; qed")
+			.to_vec(),
+	);
+	Ok((StorageKey(well_known_keys::CODE.to_vec()), code))
+}
+
 impl TryRuntimeCmd {
 	pub async fn run<B, ExecDispatch>(&self, config: Configuration) -> sc_cli::Result<()>
 	where
-		B: BlockT,
+		B: BlockT<Hash = H256> + serde::de::DeserializeOwned,
+		B::Header: serde::de::DeserializeOwned,
 		ExecDispatch: NativeExecutionDispatch + 'static,
 	{
-		let spec = config.chain_spec;
-		let genesis_storage = spec.build_storage()?;
-
-		let code = StorageData(
-			genesis_storage
-				.top
-				.get(well_known_keys::CODE)
-				.expect("code key must exist in genesis storage; qed")
-				.to_vec(),
-		);
-		let code_key = StorageKey(well_known_keys::CODE.to_vec());
-
-		let wasm_method = self.wasm_method;
-		let execution = self.execution;
+		match &self.command {
+			Command::OnRuntimeUpgrade(cmd) => {
+				self.on_runtime_upgrade::<B, ExecDispatch>(cmd, config).await
+			}
+			Command::ExecuteBlock(cmd) => {
+				self.execute_block::<B, ExecDispatch>(cmd, config).await
+			}
+			Command::OffchainWorker(cmd) => {
+				self.offchain_worker::<B, ExecDispatch>(cmd, config).await
+			}
+			Command::TryState(cmd) => {
+				self.try_state::<B, ExecDispatch>(cmd, config).await
+			}
+		}
+	}
 
-		let mut changes = Default::default();
+	fn executor<ExecDispatch>(&self, config: &Configuration) -> NativeExecutor<ExecDispatch>
+	where
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
 		// don't really care about these -- use the default values.
 		let max_runtime_instances = config.max_runtime_instances;
 		let heap_pages = config.default_heap_pages;
-		let executor = NativeExecutor::<ExecDispatch>::new(
-			wasm_method.into(),
+		NativeExecutor::<ExecDispatch>::new(
+			self.wasm_method.into(),
 			heap_pages,
 			max_runtime_instances,
-		);
-
-		let ext = {
-			use remote_externalities::{Builder, Mode, CacheConfig, OfflineConfig, OnlineConfig};
-			let builder = match &self.state {
-				State::Snap(file_path) => Builder::new().mode(Mode::Offline(OfflineConfig {
-					cache: CacheConfig { name: file_path.into(), ..Default::default() },
-				})),
-				State::Live(http_uri) => Builder::new().mode(Mode::Online(OnlineConfig {
-					uri: http_uri.into(),
-					..Default::default()
-				})),
-			};
-
-			// inject the code into this ext.
-			builder.inject(&[(code_key, code)]).build().await?
-		};
+		)
+	}
 
-		let encoded_result = StateMachine::<_, _, NumberFor<B>, _>::new(
+	/// Call `method` with `data` against the state of `ext`, returning the encoded result.
+	fn execute<B, ExecDispatch>(
+		&self,
+		ext: &TestExternalities,
+		extensions: sp_externalities::Extensions,
+		executor: &NativeExecutor<ExecDispatch>,
+		method: &'static str,
+		data: &[u8],
+	) -> sc_cli::Result<Vec<u8>>
+	where
+		B: BlockT,
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		let mut changes = Default::default();
+		StateMachine::<_, _, NumberFor<B>, _>::new(
 			&ext.backend,
 			None,
 			&mut changes,
-			&executor,
-			"TryRuntime_on_runtime_upgrade",
-			&[],
-			ext.extensions,
+			executor,
+			method,
+			data,
+			extensions,
 			&sp_state_machine::backend::BackendRuntimeCode::new(&ext.backend)
 				.runtime_code()?,
 			sp_core::testing::TaskExecutor::new(),
 		)
-		.execute(execution.into())
-		.map_err(|e| format!("failed to execute 'TryRuntime_on_runtime_upgrade' due to {:?}", e))?;
+		.execute(self.execution.into())
+		.map_err(|e| format!("failed to execute '{}' due to {:?}", method, e).into())
+	}
 
-		let (weight, total_weight) = <(u64, u64) as Decode>::decode(&mut &*encoded_result)
+	async fn on_runtime_upgrade<B, ExecDispatch>(
+		&self,
+		command: &OnRuntimeUpgradeCmd,
+		config: Configuration,
+	) -> sc_cli::Result<()>
+	where
+		B: BlockT<Hash = H256>,
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		let executor = self.executor::<ExecDispatch>(&config);
+		let (code_key, code) = local_code(&config)?;
+
+		let mut ext = build_ext(&command.state, None).await?;
+		ext.insert(code_key.0, code.0);
+		let extensions = std::mem::take(&mut ext.extensions);
+
+		let encoded_result = self.execute::<B, _>(
+			&ext,
+			extensions,
+			&executor,
+			"TryRuntime_on_runtime_upgrade",
+			&[],
+		)?;
+
+		let report = <frame_try_runtime::Report as Decode>::decode(&mut &*encoded_result)
 			.map_err(|e| format!("failed to decode output due to {:?}", e))?;
+		log_report(&report);
+		if !report.is_ok() {
+			return Err("try-runtime on_runtime_upgrade checks failed".into())
+		}
+
 		log::info!(
 			"try-runtime executed without errors. Consumed weight = {}, total weight = {} ({})",
-			weight,
-			total_weight,
-			weight as f64 / total_weight as f64
+			report.weight(),
+			report.max_block_weight,
+			report.weight() as f64 / report.max_block_weight as f64
+		);
+
+		Ok(())
+	}
+
+	async fn try_state<B, ExecDispatch>(
+		&self,
+		command: &TryStateCmd,
+		config: Configuration,
+	) -> sc_cli::Result<()>
+	where
+		B: BlockT<Hash = H256>,
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		let executor = self.executor::<ExecDispatch>(&config);
+
+		let mut ext = build_ext(&command.state, None).await?;
+		if command.overwrite_wasm_code {
+			let (code_key, code) = local_code(&config)?;
+			ext.insert(code_key.0, code.0);
+		}
+		let extensions = std::mem::take(&mut ext.extensions);
+
+		let encoded_result = self.execute::<B, _>(
+			&ext,
+			extensions,
+			&executor,
+			"TryRuntime_try_state",
+			&[],
+		)?;
+
+		let report = <frame_try_runtime::Report as Decode>::decode(&mut &*encoded_result)
+			.map_err(|e| format!("failed to decode output due to {:?}", e))?;
+		log_report(&report);
+		if !report.is_ok() {
+			return Err("try-runtime try_state checks failed".into())
+		}
+
+		log::info!("try-runtime try_state checks of all pallets passed.");
+
+		Ok(())
+	}
+
+	async fn execute_block<B, ExecDispatch>(
+		&self,
+		command: &ExecuteBlockCmd,
+		config: Configuration,
+	) -> sc_cli::Result<()>
+	where
+		B: BlockT<Hash = H256> + serde::de::DeserializeOwned,
+		B::Header: serde::de::DeserializeOwned,
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		let executor = self.executor::<ExecDispatch>(&config);
+
+		let block: B = match (&command.block_file, &command.state) {
+			(Some(path), _) => {
+				let encoded = std::fs::read(path)
+					.map_err(|e| format!("failed to read block file {:?}: {:?}", path, e))?;
+				B::decode(&mut &*encoded)
+					.map_err(|e| format!("failed to decode block file {:?}: {:?}", path, e))?
+			},
+			(None, State::Live { url, at, .. }) => {
+				let block_url = command.block_url.as_ref().unwrap_or(url);
+				let block_at = match command.block_at.as_ref().or(at.as_ref()) {
+					Some(block_at) => hash_of(block_at)?,
+					None => rpc_api::get_finalized_head::<B, _>(block_url).await?,
+				};
+				rpc_api::get_block::<B, _>(block_url, block_at).await?
+			},
+			(None, State::Snap { .. }) => {
+				let block_url = command
+					.block_url
+					.as_ref()
+					.ok_or("block-url must be provided when using a snapshot")?;
+				let block_at = command
+					.block_at
+					.as_ref()
+					.ok_or("block-at must be provided when using a snapshot")?;
+				rpc_api::get_block::<B, _>(block_url, hash_of(block_at)?).await?
+			},
+		};
+
+		let header = block.header().clone();
+		log::info!(
+			"executing block #{:?} ({:?}) on top of the state of its parent {:?}",
+			header.number(),
+			header.hash(),
+			header.parent_hash(),
 		);
 
+		// the block must be executed on top of the state of its parent.
+		let mut ext = build_ext(&command.state, Some(*header.parent_hash())).await?;
+		if command.overwrite_wasm_code {
+			let (code_key, code) = local_code(&config)?;
+			ext.insert(code_key.0, code.0);
+		}
+		let extensions = std::mem::take(&mut ext.extensions);
+
+		let method = if command.no_check {
+			"TryRuntime_execute_block_no_check"
+		} else {
+			"Core_execute_block"
+		};
+		let _ = self.execute::<B, _>(&ext, extensions, &executor, method, &block.encode())?;
+
+		log::info!("try-runtime executed block {:?} without errors.", header.hash());
+
+		Ok(())
+	}
+
+	async fn offchain_worker<B, ExecDispatch>(
+		&self,
+		command: &OffchainWorkerCmd,
+		config: Configuration,
+	) -> sc_cli::Result<()>
+	where
+		B: BlockT<Hash = H256>,
+		B::Header: serde::de::DeserializeOwned,
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		let executor = self.executor::<ExecDispatch>(&config);
+
+		let (header_url, header_at) = match &command.state {
+			State::Live { url, at, .. } => {
+				let header_url = command.header_url.as_ref().unwrap_or(url).clone();
+				let header_at = match command.header_at.as_ref().or(at.as_ref()) {
+					Some(header_at) => hash_of(header_at)?,
+					None => rpc_api::get_finalized_head::<B, _>(&header_url).await?,
+				};
+				(header_url, header_at)
+			},
+			State::Snap { .. } => {
+				let header_url = command
+					.header_url
+					.clone()
+					.ok_or("header-url must be provided when using a snapshot")?;
+				let header_at = command
+					.header_at
+					.as_ref()
+					.ok_or("header-at must be provided when using a snapshot")?;
+				(header_url, hash_of(header_at)?)
+			},
+		};
+		let header = rpc_api::get_header::<B, _>(&header_url, header_at).await?;
+
+		// the offchain worker runs on top of the state of the header it is given.
+		let mut ext = build_ext(&command.state, Some(header_at)).await?;
+		if command.overwrite_wasm_code {
+			let (code_key, code) = local_code(&config)?;
+			ext.insert(code_key.0, code.0);
+		}
+
+		let mut extensions = std::mem::take(&mut ext.extensions);
+		let (offchain, _offchain_state) = TestOffchainExt::new();
+		let (pool, _pool_state) = TestTransactionPoolExt::new();
+		extensions.register(OffchainExt::new(offchain));
+		extensions.register(TransactionPoolExt::new(pool));
+		extensions.register(KeystoreExt(Arc::new(KeyStore::new())));
+
+		let _ = self.execute::<B, _>(
+			&ext,
+			extensions,
+			&executor,
+			"OffchainWorkerApi_offchain_worker",
+			&header.encode(),
+		)?;
+
+		log::info!("try-runtime offchain worker executed at {:?} without errors.", header_at);
+
 		Ok(())
 	}
 }